@@ -17,16 +17,30 @@
 //! Stock, pure Aura collators.
 //!
 //! This includes the [`basic`] collator, which only builds on top of the most recently
-//! included parachain block, as well as the [`lookahead`] collator, which prospectively
-//! builds on parachain blocks which have not yet been included in the relay chain.
+//! included parachain block, the [`lookahead`] collator, which prospectively
+//! builds on parachain blocks which have not yet been included in the relay chain, as well as
+//! the [`slot_based`] collator, which authors blocks on its own slot timer and submits them on
+//! all cores assigned to the para.
 
+use crate::collator::SlotClaim;
+use codec::Codec;
+use cumulus_client_consensus_common::load_abridged_host_configuration;
+use cumulus_primitives_aura::AuraUnincludedSegmentApi;
 use cumulus_relay_chain_interface::RelayChainInterface;
 use polkadot_primitives::{
-	Hash as RHash, Id as ParaId, OccupiedCoreAssumption, ValidationCodeHash,
+	AsyncBackingParams, Hash as RHash, Id as ParaId, OccupiedCoreAssumption, ValidationCodeHash,
 };
+use sc_consensus_aura::standalone as aura_internal;
+use sp_api::ProvideRuntimeApi;
+use sp_consensus_aura::{AuraApi, Slot};
+use sp_core::crypto::Pair;
+use sp_keystore::KeystorePtr;
+use sp_runtime::traits::Block as BlockT;
+use sp_timestamp::Timestamp;
 
 pub mod basic;
 pub mod lookahead;
+pub mod slot_based;
 
 /// Check the `local_validation_code_hash` against the validation code hash in the relay chain
 /// state.
@@ -77,3 +91,62 @@ async fn check_validation_code_or_log(
 		},
 	}
 }
+
+/// Checks if we own the slot at the given block and whether there
+/// is space in the unincluded segment.
+async fn can_build_upon<Block: BlockT, Client, P>(
+	slot: Slot,
+	timestamp: Timestamp,
+	parent_hash: Block::Hash,
+	included_block: Block::Hash,
+	client: &Client,
+	keystore: &KeystorePtr,
+) -> Option<SlotClaim<P::Public>>
+where
+	Client: ProvideRuntimeApi<Block>,
+	Client::Api: AuraApi<Block, P::Public> + AuraUnincludedSegmentApi<Block>,
+	P: Pair,
+	P::Public: Codec,
+	P::Signature: Codec,
+{
+	let runtime_api = client.runtime_api();
+	let authorities = runtime_api.authorities(parent_hash).ok()?;
+	let author_pub = aura_internal::claim_slot::<P>(slot, &authorities, keystore).await?;
+
+	// Here we lean on the property that building on an empty unincluded segment must always
+	// be legal. Skipping the runtime API query here allows us to seamlessly run this
+	// collator against chains which have not yet upgraded their runtime.
+	if parent_hash != included_block {
+		if !runtime_api.can_build_upon(parent_hash, included_block, slot).ok()? {
+			return None
+		}
+	}
+
+	Some(SlotClaim::unchecked::<P>(author_pub, slot, timestamp))
+}
+
+/// Reads async backing parameters from the relay chain storage at the given relay parent.
+async fn async_backing_params(
+	relay_parent: RHash,
+	relay_client: &impl RelayChainInterface,
+) -> Option<AsyncBackingParams> {
+	match load_abridged_host_configuration(relay_parent, relay_client).await {
+		Ok(Some(config)) => Some(config.async_backing_params),
+		Ok(None) => {
+			tracing::error!(
+				target: crate::LOG_TARGET,
+				"Active config is missing in relay chain storage",
+			);
+			None
+		},
+		Err(err) => {
+			tracing::error!(
+				target: crate::LOG_TARGET,
+				?err,
+				?relay_parent,
+				"Failed to read active config from relay chain client",
+			);
+			None
+		},
+	}
+}
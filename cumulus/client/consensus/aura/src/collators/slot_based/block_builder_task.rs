@@ -0,0 +1,581 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+use codec::{Codec, Encode};
+use cumulus_client_collator::service::ServiceInterface as CollatorServiceInterface;
+use cumulus_client_consensus_common::{
+	self as consensus_common, ParachainBlockImportMarker, ParentSearchParams,
+};
+use cumulus_client_consensus_proposer::ProposerInterface;
+use cumulus_primitives_aura::AuraUnincludedSegmentApi;
+use cumulus_primitives_core::{
+	relay_chain::{BlockId as RBlockId, Hash as PHash},
+	CollectCollationInfo, PersistedValidationData,
+};
+use cumulus_relay_chain_interface::RelayChainInterface;
+
+use polkadot_node_subsystem::messages::{RuntimeApiMessage, RuntimeApiRequest};
+use polkadot_overseer::Handle as OverseerHandle;
+use polkadot_primitives::{CoreIndex, Id as ParaId, OccupiedCoreAssumption};
+
+use futures::{
+	channel::{mpsc::UnboundedSender, oneshot},
+	prelude::*,
+};
+use sc_client_api::{backend::AuxStore, BlockBackend, BlockOf};
+use sc_consensus::BlockImport;
+use sp_api::ProvideRuntimeApi;
+use sp_application_crypto::AppPublic;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::SyncOracle;
+use sp_consensus_aura::{AuraApi, Slot, SlotDuration};
+use sp_core::crypto::Pair;
+use sp_inherents::CreateInherentDataProviders;
+use sp_keystore::KeystorePtr;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Member};
+use sp_timestamp::Timestamp;
+use std::{
+	collections::{BTreeMap, VecDeque},
+	sync::Arc,
+	time::Duration,
+};
+
+use super::CollatorMessage;
+use crate::collator as collator_util;
+
+/// Parameters for [`run_block_builder`].
+pub struct BuilderTaskParams<
+	Block: BlockT,
+	BI,
+	CIDP,
+	Client,
+	Backend,
+	RClient,
+	CHP,
+	SO,
+	Proposer,
+	CS,
+> {
+	/// Inherent data providers. Only non-consensus inherent data should be provided, i.e.
+	/// the timestamp, slot, and paras inherents should be omitted, as they are set by this
+	/// collator.
+	pub create_inherent_data_providers: CIDP,
+	/// Used to actually import blocks.
+	pub block_import: BI,
+	/// The underlying para client.
+	pub para_client: Arc<Client>,
+	/// The para client's backend, used to access the database.
+	pub para_backend: Arc<Backend>,
+	/// A handle to the relay-chain client.
+	pub relay_client: RClient,
+	/// A validation code hash provider, used to get the current validation code hash.
+	pub code_hash_provider: CHP,
+	/// A chain synchronization oracle.
+	pub sync_oracle: SO,
+	/// The underlying keystore, which should contain Aura consensus keys.
+	pub keystore: KeystorePtr,
+	/// The para's ID.
+	pub para_id: ParaId,
+	/// A handle to the relay-chain client's "Overseer" or task orchestrator.
+	pub overseer_handle: OverseerHandle,
+	/// The underlying block proposer this should call into.
+	pub proposer: Proposer,
+	/// The generic collator service used to plug into this consensus engine.
+	pub collator_service: CS,
+	/// The length of slots in the relay chain.
+	pub relay_chain_slot_duration: Duration,
+	/// The maximum amount of time to spend authoring each block.
+	pub authoring_duration: Duration,
+	/// Offset applied to the slot timer.
+	pub slot_drift: Duration,
+	/// Channel to send built blocks to the collation task.
+	pub collator_sender: UnboundedSender<CollatorMessage<Block>>,
+}
+
+/// Information about the parachain slot the block-builder task woke up for.
+#[derive(Debug)]
+struct SlotInfo {
+	/// The timestamp at the beginning of the slot.
+	timestamp: Timestamp,
+	/// The slot number.
+	slot: Slot,
+}
+
+/// The interval in milliseconds between two attempts to build a block.
+///
+/// Blocks are built at the beginning of parachain slots, but not more often than they can be
+/// backed: the relay chain backs at most one block per core in each of its slots.
+fn block_production_interval(
+	slot_duration: SlotDuration,
+	relay_chain_slot_duration: Duration,
+	core_count: usize,
+) -> u64 {
+	let relay_chain_slot_millis = relay_chain_slot_duration.as_millis() as u64;
+	slot_duration
+		.as_millis()
+		.max(relay_chain_slot_millis / core_count.max(1) as u64)
+		.max(1)
+}
+
+/// Compute the next time a block should be built at, given the current time in milliseconds.
+fn next_slot_info(now: u64, slot_duration: SlotDuration, interval: u64) -> SlotInfo {
+	let next_attempt = (now / interval + 1) * interval;
+	let timestamp = Timestamp::new(next_attempt);
+	SlotInfo { timestamp, slot: Slot::from_timestamp(timestamp, slot_duration) }
+}
+
+/// Wait until the next time a block should be built, shifted by `drift`.
+async fn wait_until_next_slot(
+	slot_duration: SlotDuration,
+	relay_chain_slot_duration: Duration,
+	core_count: usize,
+	drift: Duration,
+) -> SlotInfo {
+	let interval = block_production_interval(slot_duration, relay_chain_slot_duration, core_count);
+	let now = Timestamp::current().as_millis().saturating_sub(drift.as_millis() as u64);
+	let slot_info = next_slot_info(now, slot_duration, interval);
+
+	futures_timer::Delay::new(Duration::from_millis(slot_info.timestamp.as_millis() - now)).await;
+
+	slot_info
+}
+
+/// Run the block-builder task.
+///
+/// At the beginning of every parachain slot this builds a block on top of the longest
+/// parachain fork with space in the unincluded segment, using the best relay-chain block as
+/// relay parent. At most one block is built per core assigned to the para at that relay
+/// parent. Built blocks are imported, announced and forwarded to the collation task.
+pub async fn run_block_builder<
+	Block,
+	P,
+	BI,
+	CIDP,
+	Client,
+	Backend,
+	RClient,
+	CHP,
+	SO,
+	Proposer,
+	CS,
+>(
+	params: BuilderTaskParams<Block, BI, CIDP, Client, Backend, RClient, CHP, SO, Proposer, CS>,
+) where
+	Block: BlockT,
+	Client: ProvideRuntimeApi<Block>
+		+ BlockOf
+		+ AuxStore
+		+ HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api:
+		AuraApi<Block, P::Public> + CollectCollationInfo<Block> + AuraUnincludedSegmentApi<Block>,
+	Backend: sc_client_api::Backend<Block> + 'static,
+	RClient: RelayChainInterface + Clone + 'static,
+	CIDP: CreateInherentDataProviders<Block, ()> + 'static,
+	CIDP::InherentDataProviders: Send,
+	BI: BlockImport<Block> + ParachainBlockImportMarker + Send + Sync + 'static,
+	SO: SyncOracle + Send + Sync + Clone + 'static,
+	Proposer: ProposerInterface<Block> + Send + Sync + 'static,
+	CS: CollatorServiceInterface<Block> + Send + Sync + 'static,
+	CHP: consensus_common::ValidationCodeHashProvider<Block::Hash> + Send + 'static,
+	P: Pair,
+	P::Public: AppPublic + Member + Codec,
+	P::Signature: TryFrom<Vec<u8>> + Member + Codec,
+{
+	// This is an arbitrary value which is likely guaranteed to exceed any reasonable
+	// limit, as it would correspond to 10 non-included blocks.
+	//
+	// Since we only search for parent blocks which have already been imported,
+	// we can guarantee that all imported blocks respect the unincluded segment
+	// rules specified by the parachain's runtime and thus will never be too deep.
+	const PARENT_SEARCH_DEPTH: usize = 10;
+
+	let BuilderTaskParams {
+		create_inherent_data_providers,
+		block_import,
+		para_client,
+		para_backend,
+		relay_client,
+		code_hash_provider,
+		sync_oracle,
+		keystore,
+		para_id,
+		mut overseer_handle,
+		proposer,
+		collator_service,
+		relay_chain_slot_duration,
+		authoring_duration,
+		slot_drift,
+		collator_sender,
+	} = params;
+
+	let mut collator = {
+		let params = collator_util::Params {
+			create_inherent_data_providers,
+			block_import,
+			relay_client: relay_client.clone(),
+			keystore: keystore.clone(),
+			para_id,
+			proposer,
+			collator_service,
+		};
+
+		collator_util::Collator::<Block, P, _, _, _, _, _>::new(params)
+	};
+
+	// The relay parent we last built on and the number of blocks built on top of it.
+	let mut last_relay_parent: Option<(PHash, usize)> = None;
+	// The number of cores assigned to the para at the last relay parent.
+	let mut core_count = 1;
+
+	loop {
+		let best_hash = para_client.info().best_hash;
+		let slot_duration = match crate::slot_duration_at(&*para_client, best_hash) {
+			Ok(sd) => sd,
+			Err(err) => {
+				tracing::error!(
+					target: crate::LOG_TARGET,
+					?err,
+					"Failed to acquire parachain slot duration, stopping block-builder task."
+				);
+				return
+			},
+		};
+
+		let para_slot =
+			wait_until_next_slot(slot_duration, relay_chain_slot_duration, core_count, slot_drift)
+				.await;
+
+		if sync_oracle.is_major_syncing() {
+			tracing::trace!(target: crate::LOG_TARGET, "Skipping slot, node is major syncing.");
+			continue
+		}
+
+		let relay_parent = match relay_client.best_block_hash().await {
+			Ok(hash) => hash,
+			Err(err) => {
+				tracing::error!(target: crate::LOG_TARGET, ?err, "Failed to fetch best relay-chain block");
+				continue
+			},
+		};
+
+		let relay_parent_header = match relay_client.header(RBlockId::hash(relay_parent)).await {
+			Ok(Some(header)) => header,
+			Ok(None) => {
+				tracing::error!(target: crate::LOG_TARGET, ?relay_parent, "Relay-chain header not found");
+				continue
+			},
+			Err(err) => {
+				tracing::error!(target: crate::LOG_TARGET, ?err, "Failed to fetch relay-chain header");
+				continue
+			},
+		};
+
+		let blocks_built = match last_relay_parent {
+			Some((hash, n)) if hash == relay_parent => n,
+			_ => 0,
+		};
+
+		let scheduled_cores =
+			cores_scheduled_for_para(relay_parent, para_id, &mut overseer_handle).await;
+		core_count = scheduled_cores.len().max(1);
+		let Some(core_index) = scheduled_cores.get(blocks_built).copied() else {
+			tracing::trace!(
+				target: crate::LOG_TARGET,
+				?relay_parent,
+				?para_id,
+				blocks_built,
+				scheduled_cores = scheduled_cores.len(),
+				"No free core for the para at this relay parent, skipping slot",
+			);
+			continue
+		};
+
+		let max_pov_size = match relay_client
+			.persisted_validation_data(relay_parent, para_id, OccupiedCoreAssumption::Included)
+			.await
+		{
+			Ok(None) => continue,
+			Ok(Some(pvd)) => pvd.max_pov_size,
+			Err(err) => {
+				tracing::error!(target: crate::LOG_TARGET, ?err, "Failed to gather information from relay-client");
+				continue
+			},
+		};
+
+		let parent_search_params = ParentSearchParams {
+			relay_parent,
+			para_id,
+			ancestry_lookback: crate::collators::async_backing_params(relay_parent, &relay_client)
+				.await
+				.map(|c| c.allowed_ancestry_len as usize)
+				.unwrap_or(0),
+			max_depth: PARENT_SEARCH_DEPTH,
+			ignore_alternative_branches: true,
+		};
+
+		let potential_parents = cumulus_client_consensus_common::find_potential_parents::<Block>(
+			parent_search_params,
+			&*para_backend,
+			&relay_client,
+		)
+		.await;
+
+		let mut potential_parents = match potential_parents {
+			Err(e) => {
+				tracing::error!(
+					target: crate::LOG_TARGET,
+					?relay_parent,
+					err = ?e,
+					"Could not fetch potential parents to build upon"
+				);
+
+				continue
+			},
+			Ok(x) => x,
+		};
+
+		let included_block = match potential_parents.iter().find(|x| x.depth == 0) {
+			None => continue, // also serves as an `is_empty` check.
+			Some(b) => b.hash,
+		};
+
+		// Sort by depth, ascending, to choose the longest chain.
+		potential_parents.sort_by_key(|a| a.depth);
+		let Some(parent) = potential_parents.pop() else { continue };
+		let parent_hash = parent.hash;
+		let parent_header = parent.header;
+
+		// Do not try to build upon an unknown, pruned or bad block
+		if !collator.collator_service().check_block_status(parent_hash, &parent_header) {
+			continue
+		}
+
+		let Some(slot_claim) = crate::collators::can_build_upon::<_, _, P>(
+			para_slot.slot,
+			para_slot.timestamp,
+			parent_hash,
+			included_block,
+			&*para_client,
+			&keystore,
+		)
+		.await
+		else {
+			tracing::trace!(
+				target: crate::LOG_TARGET,
+				?para_slot,
+				?parent_hash,
+				"Not eligible to claim slot or unincluded segment is full."
+			);
+			continue
+		};
+
+		tracing::debug!(
+			target: crate::LOG_TARGET,
+			?relay_parent,
+			?core_index,
+			slot = ?para_slot.slot,
+			unincluded_segment_len = parent.depth,
+			"Slot claimed. Building"
+		);
+
+		let validation_data = PersistedValidationData {
+			parent_head: parent_header.encode().into(),
+			relay_parent_number: *relay_parent_header.number(),
+			relay_parent_storage_root: *relay_parent_header.state_root(),
+			max_pov_size,
+		};
+
+		let (parachain_inherent_data, other_inherent_data) = match collator
+			.create_inherent_data(
+				relay_parent,
+				&validation_data,
+				parent_hash,
+				slot_claim.timestamp(),
+			)
+			.await
+		{
+			Err(err) => {
+				tracing::error!(target: crate::LOG_TARGET, ?err);
+				continue
+			},
+			Ok(x) => x,
+		};
+
+		let Some(validation_code_hash) = code_hash_provider.code_hash_at(parent_hash) else {
+			tracing::error!(target: crate::LOG_TARGET, ?parent_hash, "Could not fetch validation code hash");
+			continue
+		};
+
+		crate::collators::check_validation_code_or_log(
+			&validation_code_hash,
+			para_id,
+			&relay_client,
+			relay_parent,
+		)
+		.await;
+
+		let candidate = match collator
+			.build_block_and_import(
+				&parent_header,
+				&slot_claim,
+				None,
+				(parachain_inherent_data, other_inherent_data),
+				authoring_duration.min(slot_duration.as_duration()),
+				// Set the block limit to 50% of the maximum PoV size.
+				//
+				// TODO: If we got benchmarking that includes the proof size,
+				// we should be able to use the maximum pov size.
+				(validation_data.max_pov_size / 2) as usize,
+			)
+			.await
+		{
+			Ok(Some(candidate)) => candidate,
+			Ok(None) => {
+				tracing::debug!(target: crate::LOG_TARGET, "No block proposal");
+				continue
+			},
+			Err(err) => {
+				tracing::error!(target: crate::LOG_TARGET, ?err);
+				continue
+			},
+		};
+
+		let new_block_hash = candidate.block.header().hash();
+
+		// Here we are assuming that the import logic protects against equivocations
+		// and provides sybil-resistance, as it should.
+		collator.collator_service().announce_block(new_block_hash, None);
+
+		last_relay_parent = Some((relay_parent, blocks_built + 1));
+
+		if let Err(err) = collator_sender.unbounded_send(CollatorMessage {
+			relay_parent,
+			parent_header,
+			parachain_candidate: candidate,
+			validation_code_hash,
+			core_index,
+		}) {
+			tracing::error!(
+				target: crate::LOG_TARGET,
+				?err,
+				"Unable to send block to collation task, stopping block-builder task."
+			);
+			return
+		}
+	}
+}
+
+/// Return the cores assigned to the para at the provided relay parent, as listed at the head
+/// of the claim queue.
+async fn cores_scheduled_for_para(
+	relay_parent: PHash,
+	para_id: ParaId,
+	overseer_handle: &mut OverseerHandle,
+) -> Vec<CoreIndex> {
+	let (tx, rx) = oneshot::channel();
+	let request = RuntimeApiRequest::ClaimQueue(tx);
+	overseer_handle
+		.send_msg(RuntimeApiMessage::Request(relay_parent, request), "SlotBasedCollator")
+		.await;
+
+	let claim_queue = match rx.await {
+		Ok(Ok(claim_queue)) => claim_queue,
+		Ok(Err(error)) => {
+			tracing::error!(
+				target: crate::LOG_TARGET,
+				?error,
+				?relay_parent,
+				"Failed to query claim queue runtime API",
+			);
+			return Vec::new()
+		},
+		Err(oneshot::Canceled) => {
+			tracing::error!(
+				target: crate::LOG_TARGET,
+				?relay_parent,
+				"Sender for claim queue runtime request dropped",
+			);
+			return Vec::new()
+		},
+	};
+
+	cores_at_head_of_claim_queue(claim_queue, para_id)
+}
+
+/// Return the cores whose claim queue starts with `para_id`.
+fn cores_at_head_of_claim_queue(
+	claim_queue: BTreeMap<CoreIndex, VecDeque<ParaId>>,
+	para_id: ParaId,
+) -> Vec<CoreIndex> {
+	claim_queue
+		.into_iter()
+		.filter_map(|(core_index, queue)| (queue.front() == Some(&para_id)).then_some(core_index))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn block_production_interval_is_bounded_by_cores() {
+		let para_slot = SlotDuration::from_millis(2000);
+		let relay_slot = Duration::from_secs(6);
+
+		// A single core can back a block every relay-chain slot.
+		assert_eq!(block_production_interval(para_slot, relay_slot, 1), 6000);
+		// Three cores can back a block every parachain slot.
+		assert_eq!(block_production_interval(para_slot, relay_slot, 3), 2000);
+		// More cores don't make the parachain slots shorter.
+		assert_eq!(block_production_interval(para_slot, relay_slot, 6), 2000);
+		// No cores is handled as a single one.
+		assert_eq!(block_production_interval(para_slot, relay_slot, 0), 6000);
+	}
+
+	#[test]
+	fn next_slot_info_uses_parachain_slot_of_next_attempt() {
+		let para_slot = SlotDuration::from_millis(2000);
+
+		let info = next_slot_info(13_000, para_slot, 2000);
+		assert_eq!(info.timestamp, Timestamp::new(14_000));
+		assert_eq!(info.slot, Slot::from(7));
+
+		let info = next_slot_info(13_000, para_slot, 6000);
+		assert_eq!(info.timestamp, Timestamp::new(18_000));
+		assert_eq!(info.slot, Slot::from(9));
+	}
+
+	#[test]
+	fn only_cores_with_para_at_head_of_claim_queue_are_scheduled() {
+		let para_id = ParaId::from(2000);
+		let other = ParaId::from(3000);
+		let claim_queue = BTreeMap::from([
+			(CoreIndex(0), VecDeque::from([para_id, other])),
+			(CoreIndex(1), VecDeque::from([other, para_id])),
+			(CoreIndex(2), VecDeque::new()),
+			(CoreIndex(3), VecDeque::from([para_id])),
+		]);
+
+		assert_eq!(
+			cores_at_head_of_claim_queue(claim_queue, para_id),
+			vec![CoreIndex(0), CoreIndex(3)],
+		);
+	}
+}
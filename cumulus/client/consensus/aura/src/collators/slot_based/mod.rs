@@ -0,0 +1,194 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A collator for Aura that authors parachain blocks on its own slot timer and submits
+//! collations from a separate task.
+//!
+//! Unlike the [`lookahead`](super::lookahead) collator, block authoring is not driven by
+//! relay-chain block imports. Instead, [`run`] returns two futures which should be spawned
+//! as separate tasks:
+//!
+//! 1. The block-builder task wakes up at the beginning of parachain slots, picks the best
+//!    relay-chain block as relay parent and builds on the longest chain of parachain blocks which
+//!    still has space in the unincluded segment. The number of blocks built for a single relay
+//!    parent is bounded by the number of cores assigned to the para in the claim queue, and each
+//!    built block is assigned to one of those cores. It doesn't wake up more often than those cores
+//!    can back blocks, i.e. once per relay-chain slot and core.
+//! 2. The collation task receives the built blocks, packages them into collations and submits them
+//!    to the collation-generation subsystem for the core chosen by the block builder.
+//!
+//! This allows a parachain to make use of multiple cores (elastic scaling) by using a
+//! parachain slot duration shorter than the relay-chain slot duration, e.g. 2 seconds
+//! for three cores.
+
+use codec::Codec;
+use cumulus_client_collator::service::ServiceInterface as CollatorServiceInterface;
+use cumulus_client_consensus_common::{
+	self as consensus_common, ParachainBlockImportMarker, ParachainCandidate,
+};
+use cumulus_client_consensus_proposer::ProposerInterface;
+use cumulus_primitives_aura::AuraUnincludedSegmentApi;
+use cumulus_primitives_core::CollectCollationInfo;
+use cumulus_relay_chain_interface::RelayChainInterface;
+
+use polkadot_overseer::Handle as OverseerHandle;
+use polkadot_primitives::{
+	CollatorPair, CoreIndex, Hash as RHash, Id as ParaId, ValidationCodeHash,
+};
+
+use futures::{channel::mpsc, prelude::*};
+use sc_client_api::{backend::AuxStore, BlockBackend, BlockOf};
+use sc_consensus::BlockImport;
+use sp_api::ProvideRuntimeApi;
+use sp_application_crypto::AppPublic;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::SyncOracle;
+use sp_consensus_aura::AuraApi;
+use sp_core::crypto::Pair;
+use sp_inherents::CreateInherentDataProviders;
+use sp_keystore::KeystorePtr;
+use sp_runtime::traits::{Block as BlockT, Member};
+use std::{sync::Arc, time::Duration};
+
+mod block_builder_task;
+mod collation_task;
+
+/// Parameters for [`run`].
+pub struct Params<BI, CIDP, Client, Backend, RClient, CHP, SO, Proposer, CS> {
+	/// Inherent data providers. Only non-consensus inherent data should be provided, i.e.
+	/// the timestamp, slot, and paras inherents should be omitted, as they are set by this
+	/// collator.
+	pub create_inherent_data_providers: CIDP,
+	/// Used to actually import blocks.
+	pub block_import: BI,
+	/// The underlying para client.
+	pub para_client: Arc<Client>,
+	/// The para client's backend, used to access the database.
+	pub para_backend: Arc<Backend>,
+	/// A handle to the relay-chain client.
+	pub relay_client: RClient,
+	/// A validation code hash provider, used to get the current validation code hash.
+	pub code_hash_provider: CHP,
+	/// A chain synchronization oracle.
+	pub sync_oracle: SO,
+	/// The underlying keystore, which should contain Aura consensus keys.
+	pub keystore: KeystorePtr,
+	/// The collator key used to sign collations before submitting to validators.
+	pub collator_key: CollatorPair,
+	/// The para's ID.
+	pub para_id: ParaId,
+	/// A handle to the relay-chain client's "Overseer" or task orchestrator.
+	pub overseer_handle: OverseerHandle,
+	/// The length of slots in the relay chain. Together with the number of cores assigned to the
+	/// para, it bounds how often blocks are built.
+	pub relay_chain_slot_duration: Duration,
+	/// The underlying block proposer this should call into.
+	pub proposer: Proposer,
+	/// The generic collator service used to plug into this consensus engine.
+	pub collator_service: CS,
+	/// The maximum amount of time to spend authoring each block. The time actually spent is
+	/// additionally capped by the parachain slot duration.
+	pub authoring_duration: Duration,
+	/// Whether we should reinitialize the collator config (i.e. we are transitioning to aura).
+	pub reinitialize: bool,
+	/// Offset applied to the slot timer, i.e. how long after the start of a parachain slot
+	/// the block-builder task wakes up.
+	pub slot_drift: Duration,
+}
+
+/// Run the slot-based Aura collator.
+///
+/// Returns the collation task and the block-builder task, in that order. Both futures need
+/// to be polled for the collator to make progress.
+pub fn run<Block, P, BI, CIDP, Client, Backend, RClient, CHP, SO, Proposer, CS>(
+	params: Params<BI, CIDP, Client, Backend, RClient, CHP, SO, Proposer, CS>,
+) -> (impl Future<Output = ()> + Send + 'static, impl Future<Output = ()> + Send + 'static)
+where
+	Block: BlockT,
+	Client: ProvideRuntimeApi<Block>
+		+ BlockOf
+		+ AuxStore
+		+ HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api:
+		AuraApi<Block, P::Public> + CollectCollationInfo<Block> + AuraUnincludedSegmentApi<Block>,
+	Backend: sc_client_api::Backend<Block> + 'static,
+	RClient: RelayChainInterface + Clone + 'static,
+	CIDP: CreateInherentDataProviders<Block, ()> + 'static,
+	CIDP::InherentDataProviders: Send,
+	BI: BlockImport<Block> + ParachainBlockImportMarker + Send + Sync + 'static,
+	SO: SyncOracle + Send + Sync + Clone + 'static,
+	Proposer: ProposerInterface<Block> + Send + Sync + 'static,
+	CS: CollatorServiceInterface<Block> + Send + Sync + Clone + 'static,
+	CHP: consensus_common::ValidationCodeHashProvider<Block::Hash> + Send + 'static,
+	P: Pair,
+	P::Public: AppPublic + Member + Codec,
+	P::Signature: TryFrom<Vec<u8>> + Member + Codec,
+{
+	let (collator_sender, collator_receiver) = mpsc::unbounded();
+
+	let collation_task_params = collation_task::Params {
+		collator_key: params.collator_key,
+		para_id: params.para_id,
+		reinitialize: params.reinitialize,
+		collator_service: params.collator_service.clone(),
+		overseer_handle: params.overseer_handle.clone(),
+		collator_receiver,
+	};
+	let collation_future = collation_task::run_collation_task::<Block, _>(collation_task_params);
+
+	let block_builder_params = block_builder_task::BuilderTaskParams {
+		create_inherent_data_providers: params.create_inherent_data_providers,
+		block_import: params.block_import,
+		para_client: params.para_client,
+		para_backend: params.para_backend,
+		relay_client: params.relay_client,
+		code_hash_provider: params.code_hash_provider,
+		sync_oracle: params.sync_oracle,
+		keystore: params.keystore,
+		para_id: params.para_id,
+		overseer_handle: params.overseer_handle,
+		proposer: params.proposer,
+		collator_service: params.collator_service,
+		relay_chain_slot_duration: params.relay_chain_slot_duration,
+		authoring_duration: params.authoring_duration,
+		slot_drift: params.slot_drift,
+		collator_sender,
+	};
+	let block_builder_future =
+		block_builder_task::run_block_builder::<Block, P, _, _, _, _, _, _, _, _, _>(
+			block_builder_params,
+		);
+
+	(collation_future, block_builder_future)
+}
+
+/// Message sent from the block-builder task to the collation task.
+struct CollatorMessage<Block: BlockT> {
+	/// The hash of the relay-chain block that provides the context for the parachain block.
+	relay_parent: RHash,
+	/// The header of the parent block.
+	parent_header: Block::Header,
+	/// The parachain block candidate.
+	parachain_candidate: ParachainCandidate<Block>,
+	/// The validation code hash at the parent block.
+	validation_code_hash: ValidationCodeHash,
+	/// Core index that this block should be submitted on.
+	core_index: CoreIndex,
+}
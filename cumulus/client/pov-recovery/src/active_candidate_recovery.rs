@@ -18,41 +18,105 @@ use sp_runtime::traits::Block as BlockT;
 
 use polkadot_node_primitives::PoV;
 use polkadot_node_subsystem::messages::AvailabilityRecoveryMessage;
+use polkadot_primitives::{CandidateReceipt, SessionIndex};
 
 use futures::{channel::oneshot, stream::FuturesUnordered, Future, FutureExt, StreamExt};
 
 use std::{pin::Pin, sync::Arc};
 
-use crate::RecoveryHandle;
+use crate::{PeerRecoveryHandle, RecoveryHandle};
+
+/// The data of a recovered candidate.
+pub(crate) enum RecoveredData<Block> {
+	/// The [`PoV`] recovered through availability recovery.
+	PoV(Arc<PoV>),
+	/// The block recovered from parachain peers.
+	Block(Block),
+}
+
+/// The result of a single recovery.
+enum RecoveryResult<Block: BlockT> {
+	/// The recovery finished. `None` means that the recovery failed.
+	Done(Block::Hash, Option<RecoveredData<Block>>),
+	/// No peer could provide the block, it needs to be recovered through availability recovery.
+	PeersFailed(Block::Hash, CandidateReceipt, SessionIndex),
+}
 
 /// The active candidate recovery.
 ///
 /// This handles the candidate recovery and tracks the activate recoveries.
 pub(crate) struct ActiveCandidateRecovery<Block: BlockT> {
 	/// The recoveries that are currently being executed.
-	recoveries:
-		FuturesUnordered<Pin<Box<dyn Future<Output = (Block::Hash, Option<Arc<PoV>>)> + Send>>>,
+	recoveries: FuturesUnordered<Pin<Box<dyn Future<Output = RecoveryResult<Block>> + Send>>>,
 	recovery_handle: Box<dyn RecoveryHandle>,
+	/// If set, blocks are first requested from parachain peers.
+	peer_recovery_handle: Option<Arc<dyn PeerRecoveryHandle<Block>>>,
 }
 
 impl<Block: BlockT> ActiveCandidateRecovery<Block> {
-	pub fn new(recovery_handle: Box<dyn RecoveryHandle>) -> Self {
-		Self { recoveries: Default::default(), recovery_handle }
+	pub fn new(
+		recovery_handle: Box<dyn RecoveryHandle>,
+		peer_recovery_handle: Option<Arc<dyn PeerRecoveryHandle<Block>>>,
+	) -> Self {
+		Self { recoveries: Default::default(), recovery_handle, peer_recovery_handle }
 	}
 
 	/// Recover the given `candidate`.
+	///
+	/// The block is first requested from parachain peers, falling back to availability recovery
+	/// if none of them is able to provide it.
 	pub async fn recover_candidate(
 		&mut self,
 		block_hash: Block::Hash,
 		candidate: &crate::Candidate<Block>,
+	) {
+		let Some(peer_recovery_handle) = self.peer_recovery_handle.clone() else {
+			self.recover_available_data(
+				block_hash,
+				candidate.receipt.clone(),
+				candidate.session_index,
+			)
+			.await;
+			return
+		};
+
+		let block_number = candidate.block_number;
+		let receipt = candidate.receipt.clone();
+		let session_index = candidate.session_index;
+
+		self.recoveries.push(
+			async move {
+				match peer_recovery_handle.recover_block(block_hash, block_number).await {
+					Some(block) =>
+						RecoveryResult::Done(block_hash, Some(RecoveredData::Block(block))),
+					None => {
+						tracing::debug!(
+							target: crate::LOG_TARGET,
+							?block_hash,
+							"Peer recovery failed, falling back to availability recovery",
+						);
+						RecoveryResult::PeersFailed(block_hash, receipt, session_index)
+					},
+				}
+			}
+			.boxed(),
+		);
+	}
+
+	/// Recover the available data of the given candidate from the relay chain.
+	async fn recover_available_data(
+		&mut self,
+		block_hash: Block::Hash,
+		receipt: CandidateReceipt,
+		session_index: SessionIndex,
 	) {
 		let (tx, rx) = oneshot::channel();
 
 		self.recovery_handle
 			.send_recovery_msg(
 				AvailabilityRecoveryMessage::RecoverAvailableData(
-					candidate.receipt.clone(),
-					candidate.session_index,
+					receipt,
+					session_index,
 					None,
 					None,
 					tx,
@@ -64,7 +128,8 @@ impl<Block: BlockT> ActiveCandidateRecovery<Block> {
 		self.recoveries.push(
 			async move {
 				match rx.await {
-					Ok(Ok(res)) => (block_hash, Some(res.pov)),
+					Ok(Ok(res)) =>
+						RecoveryResult::Done(block_hash, Some(RecoveredData::PoV(res.pov))),
 					Ok(Err(error)) => {
 						tracing::debug!(
 							target: crate::LOG_TARGET,
@@ -72,14 +137,14 @@ impl<Block: BlockT> ActiveCandidateRecovery<Block> {
 							?block_hash,
 							"Availability recovery failed",
 						);
-						(block_hash, None)
+						RecoveryResult::Done(block_hash, None)
 					},
 					Err(_) => {
 						tracing::debug!(
 							target: crate::LOG_TARGET,
 							"Availability recovery oneshot channel closed",
 						);
-						(block_hash, None)
+						RecoveryResult::Done(block_hash, None)
 					},
 				}
 			}
@@ -89,13 +154,14 @@ impl<Block: BlockT> ActiveCandidateRecovery<Block> {
 
 	/// Waits for the next recovery.
 	///
-	/// If the returned [`PoV`] is `None`, it means that the recovery failed.
-	pub async fn wait_for_recovery(&mut self) -> (Block::Hash, Option<Arc<PoV>>) {
+	/// If the returned [`RecoveredData`] is `None`, it means that the recovery failed.
+	pub async fn wait_for_recovery(&mut self) -> (Block::Hash, Option<RecoveredData<Block>>) {
 		loop {
-			if let Some(res) = self.recoveries.next().await {
-				return res
-			} else {
-				futures::pending!()
+			match self.recoveries.next().await {
+				Some(RecoveryResult::Done(block_hash, data)) => return (block_hash, data),
+				Some(RecoveryResult::PeersFailed(block_hash, receipt, session_index)) =>
+					self.recover_available_data(block_hash, receipt, session_index).await,
+				None => futures::pending!(),
 			}
 		}
 	}
@@ -36,7 +36,9 @@
 //! 2. If between starting and firing the timer the block is imported, we skip the recovery of the
 //!    PoV.
 //!
-//! 3. If the timer fired we recover the PoV using the relay chain PoV recovery protocol.
+//! 3. If the timer fired we first request the block from parachain peers that claim to know it, see
+//!    [`PeerRecoveryHandle`]. Only if none of them is able to provide the block, we recover the PoV
+//!    using the relay chain PoV recovery protocol.
 //!
 //! 4a. After it is recovered, we restore the block and import it.
 //!
@@ -80,7 +82,13 @@ use std::{
 mod tests;
 
 mod active_candidate_recovery;
-use active_candidate_recovery::ActiveCandidateRecovery;
+use active_candidate_recovery::{ActiveCandidateRecovery, RecoveredData};
+
+mod peer_recovery;
+pub use peer_recovery::{
+	protocol_name as peer_recovery_protocol_name, NetworkPeerRecovery, PeerRecoveryHandle,
+	PeerRecoveryRequestHandler,
+};
 
 const LOG_TARGET: &str = "cumulus-pov-recovery";
 
@@ -242,6 +250,9 @@ where
 	RCInterface: RelayChainInterface + Clone,
 {
 	/// Create a new instance.
+	///
+	/// If `peer_recovery_handle` is given, blocks are first requested from parachain peers before
+	/// falling back to availability recovery.
 	pub fn new(
 		recovery_handle: Box<dyn RecoveryHandle>,
 		recovery_delay_range: RecoveryDelayRange,
@@ -251,11 +262,15 @@ where
 		para_id: ParaId,
 		recovery_chan_rx: Receiver<RecoveryRequest<Block>>,
 		parachain_sync_service: Arc<dyn SyncOracle + Sync + Send>,
+		peer_recovery_handle: Option<Arc<dyn PeerRecoveryHandle<Block>>>,
 	) -> Self {
 		Self {
 			candidates: HashMap::new(),
 			candidate_recovery_queue: RecoveryQueue::new(recovery_delay_range),
-			active_candidate_recovery: ActiveCandidateRecovery::new(recovery_handle),
+			active_candidate_recovery: ActiveCandidateRecovery::new(
+				recovery_handle,
+				peer_recovery_handle,
+			),
 			waiting_for_parent: HashMap::new(),
 			parachain_client,
 			parachain_import_queue,
@@ -350,11 +365,15 @@ where
 	}
 
 	/// Handle a recovered candidate.
-	async fn handle_candidate_recovered(&mut self, block_hash: Block::Hash, pov: Option<&PoV>) {
-		let pov = match pov {
-			Some(pov) => {
+	async fn handle_candidate_recovered(
+		&mut self,
+		block_hash: Block::Hash,
+		data: Option<RecoveredData<Block>>,
+	) {
+		let data = match data {
+			Some(data) => {
 				self.candidates_in_retry.remove(&block_hash);
-				pov
+				data
 			},
 			None =>
 				if self.candidates_in_retry.insert(block_hash) {
@@ -373,33 +392,17 @@ where
 				},
 		};
 
-		let raw_block_data =
-			match sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT) {
-				Ok(r) => r,
-				Err(error) => {
-					tracing::debug!(target: LOG_TARGET, ?error, "Failed to decompress PoV");
-
+		let block = match data {
+			RecoveredData::Block(block) => block,
+			RecoveredData::PoV(pov) => match block_from_pov(&pov) {
+				Some(block) => block,
+				None => {
 					self.reset_candidate(block_hash);
 					return
 				},
-			};
-
-		let block_data = match ParachainBlockData::<Block>::decode(&mut &raw_block_data[..]) {
-			Ok(d) => d,
-			Err(error) => {
-				tracing::warn!(
-					target: LOG_TARGET,
-					?error,
-					"Failed to decode parachain block data from recovered PoV",
-				);
-
-				self.reset_candidate(block_hash);
-				return
 			},
 		};
 
-		let block = block_data.into_block();
-
 		let parent = *block.header().parent_hash();
 
 		match self.parachain_client.block_status(parent) {
@@ -606,16 +609,40 @@ where
 				next_to_recover = self.candidate_recovery_queue.next_recovery().fuse() => {
 						self.recover_candidate(next_to_recover).await;
 				},
-				(block_hash, pov) =
+				(block_hash, data) =
 					self.active_candidate_recovery.wait_for_recovery().fuse() =>
 				{
-					self.handle_candidate_recovered(block_hash, pov.as_deref()).await;
+					self.handle_candidate_recovered(block_hash, data).await;
 				},
 			}
 		}
 	}
 }
 
+/// Extract the block from a [`PoV`] recovered through availability recovery.
+fn block_from_pov<Block: BlockT>(pov: &PoV) -> Option<Block> {
+	let raw_block_data =
+		match sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT) {
+			Ok(r) => r,
+			Err(error) => {
+				tracing::debug!(target: LOG_TARGET, ?error, "Failed to decompress PoV");
+				return None
+			},
+		};
+
+	match ParachainBlockData::<Block>::decode(&mut &raw_block_data[..]) {
+		Ok(block_data) => Some(block_data.into_block()),
+		Err(error) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?error,
+				"Failed to decode parachain block data from recovered PoV",
+			);
+			None
+		},
+	}
+}
+
 /// Returns a stream over pending candidates for the parachain corresponding to `para_id`.
 async fn pending_candidates(
 	relay_chain_client: impl RelayChainInterface + Clone,
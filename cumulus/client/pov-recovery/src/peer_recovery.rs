@@ -0,0 +1,251 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Recovery of parachain blocks from parachain peers.
+//!
+//! Recovering a block through the availability recovery of the relay chain is slow and puts
+//! load on the relay chain validators. Before falling back to it, we ask parachain peers that
+//! claim to know the block for it. Peers answer these requests through a dedicated
+//! request-response protocol, served by [`PeerRecoveryRequestHandler`].
+
+use sc_client_api::BlockBackend;
+use sc_network::{
+	request_responses::{IncomingRequest, OutgoingResponse},
+	service::traits::{NetworkRequest, NetworkService},
+	IfDisconnected, NetworkBackend, ProtocolName,
+};
+use sc_network_sync::SyncingService;
+use sp_runtime::{
+	traits::{Block as BlockT, Hash as HashT, Header as HeaderT, NumberFor, Zero},
+	StateVersion,
+};
+
+use codec::{Decode, Encode};
+use futures::StreamExt;
+use rand::{seq::SliceRandom, thread_rng};
+
+use std::{marker::PhantomData, sync::Arc, time::Duration};
+
+use crate::LOG_TARGET;
+
+/// The maximum number of peers that are asked for a block before giving up.
+const MAX_PEERS_TO_ASK: usize = 3;
+
+/// The maximum size of a response, big enough to hold any parachain block.
+const MAX_RESPONSE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// The time we wait for a peer to answer a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Generate the name of the peer recovery protocol from the genesis hash of the parachain.
+pub fn protocol_name<Hash: AsRef<[u8]>>(genesis_hash: Hash) -> ProtocolName {
+	format!("/{}/pov-recovery/1", array_bytes::bytes2hex("", genesis_hash.as_ref())).into()
+}
+
+/// Something that can recover blocks from parachain peers.
+#[async_trait::async_trait]
+pub trait PeerRecoveryHandle<Block: BlockT>: Send + Sync {
+	/// Recover the block with the given `block_hash` and `block_number` from peers.
+	///
+	/// Returns `None` if no peer was able to provide the block.
+	async fn recover_block(
+		&self,
+		block_hash: Block::Hash,
+		block_number: NumberFor<Block>,
+	) -> Option<Block>;
+}
+
+/// A [`PeerRecoveryHandle`] that requests blocks from the peers of the parachain network.
+pub struct NetworkPeerRecovery<Block: BlockT> {
+	network: Arc<dyn NetworkService>,
+	sync_service: Arc<SyncingService<Block>>,
+	protocol_name: ProtocolName,
+}
+
+impl<Block: BlockT> NetworkPeerRecovery<Block> {
+	/// Create a new instance.
+	pub fn new(
+		network: Arc<dyn NetworkService>,
+		sync_service: Arc<SyncingService<Block>>,
+		genesis_hash: Block::Hash,
+	) -> Self {
+		Self { network, sync_service, protocol_name: protocol_name(genesis_hash) }
+	}
+}
+
+#[async_trait::async_trait]
+impl<Block: BlockT> PeerRecoveryHandle<Block> for NetworkPeerRecovery<Block> {
+	async fn recover_block(
+		&self,
+		block_hash: Block::Hash,
+		block_number: NumberFor<Block>,
+	) -> Option<Block> {
+		let mut peers = match self.sync_service.peers_info().await {
+			Ok(peers) => peers
+				.into_iter()
+				.filter(|(_, info)| info.best_number >= block_number)
+				.map(|(peer, _)| peer)
+				.collect::<Vec<_>>(),
+			Err(_) => {
+				tracing::debug!(target: LOG_TARGET, "Failed to fetch the peers from the sync service");
+				return None
+			},
+		};
+		peers.shuffle(&mut thread_rng());
+
+		for peer in peers.into_iter().take(MAX_PEERS_TO_ASK) {
+			let response = match self
+				.network
+				.request(
+					peer,
+					self.protocol_name.clone(),
+					block_hash.encode(),
+					None,
+					IfDisconnected::ImmediateError,
+				)
+				.await
+			{
+				Ok((response, _)) => response,
+				Err(error) => {
+					tracing::debug!(
+						target: LOG_TARGET,
+						?error,
+						?peer,
+						?block_hash,
+						"Failed to request block from peer",
+					);
+					continue
+				},
+			};
+
+			match Option::<Block>::decode(&mut &response[..]) {
+				Ok(Some(block)) if is_requested_block(&block, block_hash) => return Some(block),
+				Ok(Some(_)) => tracing::debug!(
+					target: LOG_TARGET,
+					?peer,
+					?block_hash,
+					"Peer answered with a block that doesn't match the request",
+				),
+				Ok(None) => tracing::trace!(
+					target: LOG_TARGET,
+					?peer,
+					?block_hash,
+					"Peer doesn't know the requested block",
+				),
+				Err(error) => tracing::debug!(
+					target: LOG_TARGET,
+					?error,
+					?peer,
+					"Failed to decode peer recovery response",
+				),
+			}
+		}
+
+		None
+	}
+}
+
+/// Returns `true` if `block` has the given `block_hash` and a body that matches its header.
+fn is_requested_block<Block: BlockT>(block: &Block, block_hash: Block::Hash) -> bool {
+	let extrinsics_root = <<Block::Header as HeaderT>::Hashing as HashT>::ordered_trie_root(
+		block.extrinsics().iter().map(Encode::encode).collect(),
+		StateVersion::V0,
+	);
+
+	block.header().hash() == block_hash && *block.header().extrinsics_root() == extrinsics_root
+}
+
+/// Handler for the peer recovery requests of other parachain nodes.
+pub struct PeerRecoveryRequestHandler<Block, Client> {
+	client: Arc<Client>,
+	request_receiver: async_channel::Receiver<IncomingRequest>,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, Client> PeerRecoveryRequestHandler<Block, Client>
+where
+	Block: BlockT,
+	Client: BlockBackend<Block> + Send + Sync + 'static,
+{
+	/// Create a new [`PeerRecoveryRequestHandler`], alongside the configuration of the protocol
+	/// it serves.
+	pub fn new<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
+		client: Arc<Client>,
+		num_peer_hint: usize,
+	) -> (Self, N::RequestResponseProtocolConfig) {
+		// Reserve enough request slots for one request per peer when we are at the maximum
+		// number of peers.
+		let (tx, request_receiver) = async_channel::bounded(num_peer_hint.max(1));
+
+		let genesis_hash = client
+			.block_hash(Zero::zero())
+			.ok()
+			.flatten()
+			.expect("Genesis block exists; qed");
+		let protocol_config = N::request_response_config(
+			protocol_name(genesis_hash),
+			Vec::new(),
+			1024,
+			MAX_RESPONSE_SIZE,
+			REQUEST_TIMEOUT,
+			Some(tx),
+		);
+
+		(Self { client, request_receiver, _phantom: PhantomData }, protocol_config)
+	}
+
+	/// Run [`PeerRecoveryRequestHandler`].
+	pub async fn run(mut self) {
+		while let Some(request) = self.request_receiver.next().await {
+			let IncomingRequest { peer, payload, pending_response } = request;
+
+			let result = match Block::Hash::decode(&mut &payload[..]) {
+				Ok(block_hash) => Ok(self.block(block_hash).encode()),
+				Err(error) => {
+					tracing::debug!(
+						target: LOG_TARGET,
+						?error,
+						?peer,
+						"Failed to decode peer recovery request",
+					);
+					Err(())
+				},
+			};
+
+			let _ = pending_response.send(OutgoingResponse {
+				result,
+				reputation_changes: Vec::new(),
+				sent_feedback: None,
+			});
+		}
+	}
+
+	/// Returns the block with the given `block_hash`, if we know it.
+	fn block(&self, block_hash: Block::Hash) -> Option<Block> {
+		match self.client.block(block_hash) {
+			Ok(block) => block.map(|signed_block| signed_block.block),
+			Err(error) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					?error,
+					?block_hash,
+					"Failed to fetch block requested by peer",
+				);
+				None
+			},
+		}
+	}
+}
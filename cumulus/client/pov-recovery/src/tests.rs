@@ -73,6 +73,23 @@ impl RecoveryHandle for AvailabilityRecoverySubsystemHandle {
 	}
 }
 
+/// Peer recovery handle that knows a fixed set of blocks.
+#[derive(Default)]
+struct MockPeerRecovery {
+	blocks: HashMap<<Block as BlockT>::Hash, Block>,
+}
+
+#[async_trait::async_trait]
+impl PeerRecoveryHandle<Block> for MockPeerRecovery {
+	async fn recover_block(
+		&self,
+		block_hash: <Block as BlockT>::Hash,
+		_: NumberFor<Block>,
+	) -> Option<Block> {
+		self.blocks.get(&block_hash).cloned()
+	}
+}
+
 struct ParachainClientInner<Block: BlockT> {
 	import_notifications_rx: Option<TracingUnboundedReceiver<BlockImportNotification<Block>>>,
 	finality_notifications_rx: Option<TracingUnboundedReceiver<FinalityNotification<Block>>>,
@@ -585,6 +602,7 @@ async fn pending_candidate_height_lower_than_latest_finalized() {
 			ParaId::new(1000),
 			explicit_recovery_chan_rx,
 			Arc::new(DummySyncOracle::default()),
+			None,
 		);
 
 		task::spawn(pov_recovery.run());
@@ -643,6 +661,7 @@ async fn single_pending_candidate_recovery_success(#[case] runtime_version: u32)
 		ParaId::new(1000),
 		explicit_recovery_chan_rx,
 		Arc::new(DummySyncOracle::default()),
+		None,
 	);
 
 	task::spawn(pov_recovery.run());
@@ -688,6 +707,142 @@ async fn single_pending_candidate_recovery_success(#[case] runtime_version: u32)
 	assert_matches!(import_requests_rx.next().timeout(Duration::from_millis(100)).await, None);
 }
 
+#[tokio::test]
+async fn single_pending_candidate_recovered_from_peers() {
+	sp_tracing::init_for_tests();
+
+	let (recovery_subsystem_tx, mut recovery_subsystem_rx) =
+		AvailabilityRecoverySubsystemHandle::new();
+	let recovery_delay_range =
+		RecoveryDelayRange { min: Duration::from_millis(0), max: Duration::from_millis(10) };
+	let (_explicit_recovery_chan_tx, explicit_recovery_chan_rx) = mpsc::channel(10);
+	let candidates = make_candidate_chain(1..2);
+	let header = Header::decode(&mut &candidates[0].commitments.head_data.0[..]).unwrap();
+
+	let relay_chain_client = Relaychain::new(vec![(
+		PHeader {
+			parent_hash: PHash::from_low_u64_be(0),
+			number: 1,
+			state_root: PHash::random(),
+			extrinsics_root: PHash::random(),
+			digest: Default::default(),
+		},
+		candidates,
+	)]);
+
+	let mut known_blocks = HashMap::new();
+	known_blocks.insert(GENESIS_HASH, BlockStatus::InChainWithState);
+	let (parachain_client, _import_notifications_tx, _finality_notifications_tx) =
+		ParachainClient::new(vec![dummy_usage_info(0)], Arc::new(Mutex::new(known_blocks)));
+	let (parachain_import_queue, mut import_requests_rx) = ParachainImportQueue::new();
+
+	let mut peer_recovery = MockPeerRecovery::default();
+	peer_recovery.blocks.insert(header.hash(), Block::new(header.clone(), vec![]));
+
+	let pov_recovery = PoVRecovery::<Block, _, _>::new(
+		Box::new(recovery_subsystem_tx),
+		recovery_delay_range,
+		Arc::new(parachain_client),
+		Box::new(parachain_import_queue),
+		relay_chain_client,
+		ParaId::new(1000),
+		explicit_recovery_chan_rx,
+		Arc::new(DummySyncOracle::default()),
+		Some(Arc::new(peer_recovery)),
+	);
+
+	task::spawn(pov_recovery.run());
+
+	// Received import request for the block recovered from peers.
+	assert_matches!(import_requests_rx.next().await, Some(incoming_blocks) => {
+		assert_eq!(incoming_blocks.len(), 1);
+		assert_eq!(incoming_blocks[0].header, Some(header));
+	});
+
+	// Availability recovery was never used.
+	assert_matches!(recovery_subsystem_rx.next().timeout(Duration::from_millis(100)).await, None);
+}
+
+#[tokio::test]
+async fn single_pending_candidate_peer_recovery_falls_back_to_availability() {
+	sp_tracing::init_for_tests();
+
+	let (recovery_subsystem_tx, mut recovery_subsystem_rx) =
+		AvailabilityRecoverySubsystemHandle::new();
+	let recovery_delay_range =
+		RecoveryDelayRange { min: Duration::from_millis(0), max: Duration::from_millis(10) };
+	let (_explicit_recovery_chan_tx, explicit_recovery_chan_rx) = mpsc::channel(10);
+	let candidates = make_candidate_chain(1..2);
+	let header = Header::decode(&mut &candidates[0].commitments.head_data.0[..]).unwrap();
+	let candidate_hash = candidates[0].hash();
+
+	let relay_chain_client = Relaychain::new(vec![(
+		PHeader {
+			parent_hash: PHash::from_low_u64_be(0),
+			number: 1,
+			state_root: PHash::random(),
+			extrinsics_root: PHash::random(),
+			digest: Default::default(),
+		},
+		candidates,
+	)]);
+
+	let mut known_blocks = HashMap::new();
+	known_blocks.insert(GENESIS_HASH, BlockStatus::InChainWithState);
+	let (parachain_client, _import_notifications_tx, _finality_notifications_tx) =
+		ParachainClient::new(vec![dummy_usage_info(0)], Arc::new(Mutex::new(known_blocks)));
+	let (parachain_import_queue, mut import_requests_rx) = ParachainImportQueue::new();
+
+	// No peer knows the block.
+	let pov_recovery = PoVRecovery::<Block, _, _>::new(
+		Box::new(recovery_subsystem_tx),
+		recovery_delay_range,
+		Arc::new(parachain_client),
+		Box::new(parachain_import_queue),
+		relay_chain_client,
+		ParaId::new(1000),
+		explicit_recovery_chan_rx,
+		Arc::new(DummySyncOracle::default()),
+		Some(Arc::new(MockPeerRecovery::default())),
+	);
+
+	task::spawn(pov_recovery.run());
+
+	assert_matches!(
+		recovery_subsystem_rx.next().await,
+		Some(AvailabilityRecoveryMessage::RecoverAvailableData(
+			receipt,
+			session_index,
+			None,
+			None,
+			response_tx
+		)) => {
+			assert_eq!(receipt.hash(), candidate_hash);
+			assert_eq!(session_index, TEST_SESSION_INDEX);
+			response_tx.send(
+				Ok(
+					AvailableData {
+						pov: Arc::new(PoV {
+							block_data: ParachainBlockData::<Block>::new(
+								header.clone(),
+								vec![],
+								CompactProof {encoded_nodes: vec![]}
+							).encode().into()
+						}),
+						validation_data: dummy_pvd(),
+					}
+				)
+			).unwrap()
+		}
+	);
+
+	// Received import request for the recovered candidate
+	assert_matches!(import_requests_rx.next().await, Some(incoming_blocks) => {
+		assert_eq!(incoming_blocks.len(), 1);
+		assert_eq!(incoming_blocks[0].header, Some(header));
+	});
+}
+
 #[tokio::test]
 async fn single_pending_candidate_recovery_retry_succeeds() {
 	sp_tracing::init_for_tests();
@@ -726,6 +881,7 @@ async fn single_pending_candidate_recovery_retry_succeeds() {
 		ParaId::new(1000),
 		explicit_recovery_chan_rx,
 		Arc::new(DummySyncOracle::default()),
+		None,
 	);
 
 	task::spawn(pov_recovery.run());
@@ -829,6 +985,7 @@ async fn single_pending_candidate_recovery_retry_fails() {
 		ParaId::new(1000),
 		explicit_recovery_chan_rx,
 		Arc::new(DummySyncOracle::default()),
+		None,
 	);
 
 	task::spawn(pov_recovery.run());
@@ -915,6 +1072,7 @@ async fn single_pending_candidate_recovery_irrecoverable_error() {
 		ParaId::new(1000),
 		explicit_recovery_chan_rx,
 		Arc::new(DummySyncOracle::default()),
+		None,
 	);
 
 	task::spawn(pov_recovery.run());
@@ -987,6 +1145,7 @@ async fn pending_candidates_recovery_skipped_while_syncing() {
 		ParaId::new(1000),
 		explicit_recovery_chan_rx,
 		Arc::new(DummySyncOracle::new(true)),
+		None,
 	);
 
 	task::spawn(pov_recovery.run());
@@ -1036,6 +1195,7 @@ async fn candidate_is_imported_while_awaiting_recovery() {
 		ParaId::new(1000),
 		explicit_recovery_chan_rx,
 		Arc::new(DummySyncOracle::default()),
+		None,
 	);
 
 	task::spawn(pov_recovery.run());
@@ -1137,6 +1297,7 @@ async fn candidate_is_finalized_while_awaiting_recovery() {
 		ParaId::new(1000),
 		explicit_recovery_chan_rx,
 		Arc::new(DummySyncOracle::default()),
+		None,
 	);
 
 	task::spawn(pov_recovery.run());
@@ -1239,6 +1400,7 @@ async fn chained_recovery_success() {
 		ParaId::new(1000),
 		explicit_recovery_chan_rx,
 		Arc::new(DummySyncOracle::default()),
+		None,
 	);
 
 	task::spawn(pov_recovery.run());
@@ -1346,6 +1508,7 @@ async fn chained_recovery_child_succeeds_before_parent() {
 		ParaId::new(1000),
 		explicit_recovery_chan_rx,
 		Arc::new(DummySyncOracle::default()),
+		None,
 	);
 
 	task::spawn(pov_recovery.run());
@@ -18,10 +18,20 @@
 //!
 //! Provides functions for starting a collator node or a normal full node.
 
+use codec::Codec;
 use cumulus_client_cli::CollatorOptions;
-use cumulus_client_consensus_common::ParachainConsensus;
+use cumulus_client_collator::service::ServiceInterface as CollatorServiceInterface;
+use cumulus_client_consensus_aura::collators::slot_based::{self, Params as SlotBasedParams};
+use cumulus_client_consensus_common::{
+	ParachainBlockImportMarker, ParachainConsensus, ValidationCodeHashProvider,
+};
+use cumulus_client_consensus_proposer::ProposerInterface;
 use cumulus_client_network::{AssumeSybilResistance, RequireSecondedInBlockAnnounce};
-use cumulus_client_pov_recovery::{PoVRecovery, RecoveryDelayRange, RecoveryHandle};
+use cumulus_client_pov_recovery::{
+	NetworkPeerRecovery, PeerRecoveryHandle, PeerRecoveryRequestHandler, PoVRecovery,
+	RecoveryDelayRange, RecoveryHandle,
+};
+use cumulus_primitives_aura::AuraUnincludedSegmentApi;
 use cumulus_primitives_core::{CollectCollationInfo, ParaId};
 use cumulus_relay_chain_inprocess_interface::build_inprocess_relay_chain;
 use cumulus_relay_chain_interface::{RelayChainInterface, RelayChainResult};
@@ -34,7 +44,8 @@ use futures::{
 };
 use polkadot_primitives::{CollatorPair, OccupiedCoreAssumption};
 use sc_client_api::{
-	Backend as BackendT, BlockBackend, BlockchainEvents, Finalizer, ProofProvider, UsageProvider,
+	backend::AuxStore, Backend as BackendT, BlockBackend, BlockOf, BlockchainEvents, Finalizer,
+	ProofProvider, UsageProvider,
 };
 use sc_consensus::{
 	import_queue::{ImportQueue, ImportQueueService},
@@ -47,9 +58,13 @@ use sc_service::{Configuration, NetworkStarter, SpawnTaskHandle, TaskManager, Wa
 use sc_telemetry::{log, TelemetryWorkerHandle};
 use sc_utils::mpsc::TracingUnboundedSender;
 use sp_api::ProvideRuntimeApi;
+use sp_application_crypto::AppPublic;
 use sp_blockchain::{HeaderBackend, HeaderMetadata};
-use sp_core::{traits::SpawnNamed, Decode};
-use sp_runtime::traits::{Block as BlockT, BlockIdTo, Header};
+use sp_consensus::SyncOracle;
+use sp_consensus_aura::AuraApi;
+use sp_core::{crypto::Pair, traits::SpawnNamed, Decode};
+use sp_inherents::CreateInherentDataProviders;
+use sp_runtime::traits::{Block as BlockT, BlockIdTo, Header, Member, Zero};
 use std::{sync::Arc, time::Duration};
 
 pub use cumulus_primitives_proof_size_hostfunction::storage_proof_size;
@@ -109,6 +124,10 @@ pub struct StartRelayChainTasksParams<'a, Block: BlockT, Client, RCInterface> {
 	pub relay_chain_slot_duration: Duration,
 	pub recovery_handle: Box<dyn RecoveryHandle>,
 	pub sync_service: Arc<SyncingService<Block>>,
+	/// The parachain network, used to recover blocks from parachain peers before falling back
+	/// to availability recovery. If `None`, blocks are only recovered through availability
+	/// recovery.
+	pub network: Option<Arc<dyn NetworkService>>,
 }
 
 /// Parameters given to [`start_full_node`].
@@ -180,6 +199,7 @@ where
 		relay_chain_slot_duration,
 		recovery_handle,
 		sync_service,
+		network: None,
 	})?;
 
 	#[allow(deprecated)]
@@ -217,6 +237,7 @@ pub fn start_relay_chain_tasks<Block, Client, Backend, RCInterface>(
 		relay_chain_slot_duration,
 		recovery_handle,
 		sync_service,
+		network,
 	}: StartRelayChainTasksParams<Block, Client, RCInterface>,
 ) -> sc_service::error::Result<()>
 where
@@ -270,6 +291,13 @@ where
 		DARecoveryProfile::Other(profile) => profile,
 	};
 
+	let peer_recovery_handle = match (network, client.block_hash(Zero::zero())) {
+		(Some(network), Ok(Some(genesis_hash))) =>
+			Some(Arc::new(NetworkPeerRecovery::new(network, sync_service.clone(), genesis_hash))
+				as Arc<dyn PeerRecoveryHandle<Block>>),
+		_ => None,
+	};
+
 	let pov_recovery = PoVRecovery::new(
 		recovery_handle,
 		da_recovery_profile,
@@ -279,6 +307,7 @@ where
 		para_id,
 		recovery_chan_rx,
 		sync_service,
+		peer_recovery_handle,
 	);
 
 	task_manager
@@ -330,6 +359,7 @@ where
 		recovery_handle,
 		sync_service,
 		da_recovery_profile: DARecoveryProfile::FullNode,
+		network: None,
 	})
 }
 
@@ -341,6 +371,58 @@ pub mod old_consensus {
 	pub use cumulus_client_collator::{start_collator, start_collator_sync, StartCollatorParams};
 }
 
+/// Start the [slot-based](cumulus_client_consensus_aura::collators::slot_based) Aura collator.
+///
+/// The collation task and the block-builder task are spawned as essential tasks of the given
+/// [`TaskManager`], so the node shuts down if either of them stops.
+pub fn start_slot_based_aura_collator<
+	Block,
+	P,
+	BI,
+	CIDP,
+	Client,
+	Backend,
+	RClient,
+	CHP,
+	SO,
+	Proposer,
+	CS,
+>(
+	params: SlotBasedParams<BI, CIDP, Client, Backend, RClient, CHP, SO, Proposer, CS>,
+	task_manager: &TaskManager,
+) where
+	Block: BlockT,
+	Client: ProvideRuntimeApi<Block>
+		+ BlockOf
+		+ AuxStore
+		+ HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api:
+		AuraApi<Block, P::Public> + CollectCollationInfo<Block> + AuraUnincludedSegmentApi<Block>,
+	Backend: BackendT<Block> + 'static,
+	RClient: RelayChainInterface + Clone + 'static,
+	CIDP: CreateInherentDataProviders<Block, ()> + 'static,
+	CIDP::InherentDataProviders: Send,
+	BI: BlockImport<Block> + ParachainBlockImportMarker + Send + Sync + 'static,
+	SO: SyncOracle + Send + Sync + Clone + 'static,
+	Proposer: ProposerInterface<Block> + Send + Sync + 'static,
+	CS: CollatorServiceInterface<Block> + Send + Sync + Clone + 'static,
+	CHP: ValidationCodeHashProvider<Block::Hash> + Send + 'static,
+	P: Pair,
+	P::Public: AppPublic + Member + Codec,
+	P::Signature: TryFrom<Vec<u8>> + Member + Codec,
+{
+	let (collation_future, block_builder_future) =
+		slot_based::run::<Block, P, _, _, _, _, _, _, _, _, _>(params);
+
+	let spawner = task_manager.spawn_essential_handle();
+	spawner.spawn("collation-task", Some("parachain-block-authoring"), collation_future);
+	spawner.spawn("block-builder-task", Some("parachain-block-authoring"), block_builder_future);
+}
+
 /// Prepare the parachain's node configuration
 ///
 /// This function will disable the default announcement of Substrate for the parachain in favor
@@ -428,7 +510,7 @@ pub struct BuildNetworkParams<
 pub async fn build_network<'a, Block, Client, RCInterface, IQ, Network>(
 	BuildNetworkParams {
 		parachain_config,
-		net_config,
+		mut net_config,
 		client,
 		transaction_pool,
 		para_id,
@@ -492,6 +574,18 @@ where
 		parachain_config.prometheus_config.as_ref().map(|cfg| &cfg.registry),
 	);
 
+	// Serve the blocks requested by parachain peers that are recovering them.
+	let num_peer_hint = net_config.network_config.default_peers_set_num_full as usize +
+		net_config.network_config.default_peers_set.reserved_nodes.len();
+	let (peer_recovery_handler, peer_recovery_protocol_config) =
+		PeerRecoveryRequestHandler::new::<Network>(client.clone(), num_peer_hint);
+	net_config.add_request_response_protocol(peer_recovery_protocol_config);
+	spawn_handle.spawn(
+		"pov-recovery-request-handler",
+		Some("networking"),
+		peer_recovery_handler.run(),
+	);
+
 	sc_service::build_network(sc_service::BuildNetworkParams {
 		config: parachain_config,
 		net_config,
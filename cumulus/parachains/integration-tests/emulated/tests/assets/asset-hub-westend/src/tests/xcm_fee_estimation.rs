@@ -37,7 +37,7 @@ fn teleport_relay_system_para_works() {
 
 	// We get them from the Westend closure.
 	let mut delivery_fees_amount = 0;
-	let mut remote_message = VersionedXcm::V4(Xcm(Vec::new()));
+	let mut remote_message = VersionedXcm::V5(Xcm(Vec::new()));
 	<Westend as TestExt>::new_ext().execute_with(|| {
 		type Runtime = <Westend as Chain>::Runtime;
 		type RuntimeCall = <Westend as Chain>::RuntimeCall;
@@ -137,7 +137,7 @@ fn multi_hop_works() {
 
 	// We get them from the PenpalA closure.
 	let mut delivery_fees_amount = 0;
-	let mut remote_message = VersionedXcm::V4(Xcm(Vec::new()));
+	let mut remote_message = VersionedXcm::V5(Xcm(Vec::new()));
 	<PenpalA as TestExt>::execute_with(|| {
 		type Runtime = <PenpalA as Chain>::Runtime;
 		type RuntimeCall = <PenpalA as Chain>::RuntimeCall;
@@ -165,7 +165,7 @@ fn multi_hop_works() {
 	// This is set in the Westend closure.
 	let mut intermediate_execution_fees = 0;
 	let mut intermediate_delivery_fees_amount = 0;
-	let mut intermediate_remote_message = VersionedXcm::V4(Xcm::<()>(Vec::new()));
+	let mut intermediate_remote_message = VersionedXcm::V5(Xcm::<()>(Vec::new()));
 	<Westend as TestExt>::execute_with(|| {
 		type Runtime = <Westend as Chain>::Runtime;
 		type RuntimeCall = <Westend as Chain>::RuntimeCall;
@@ -177,7 +177,7 @@ fn multi_hop_works() {
 
 		// We have to do this to turn `VersionedXcm<()>` into `VersionedXcm<RuntimeCall>`.
 		let xcm_program =
-			VersionedXcm::V4(Xcm::<RuntimeCall>::from(remote_message.clone().try_into().unwrap()));
+			VersionedXcm::V5(Xcm::<RuntimeCall>::from(remote_message.clone().try_into().unwrap()));
 
 		// Now we get the delivery fees to the final destination.
 		let result =
@@ -70,6 +70,12 @@ impl<T: frame_system::Config> pallet_asset_conversion::WeightInfo for WeightInfo
 			.saturating_add(T::DbWeight::get().reads(7))
 			.saturating_add(T::DbWeight::get().writes(7))
 	}
+	/// Not benchmarked yet: `create_pool` plus the write of `AssetConversion::PoolCurves`.
+	/// The range of component `n` is `[0, 2]`.
+	fn create_pool_with_curve(_n: u32, ) -> Weight {
+		<Self as pallet_asset_conversion::WeightInfo>::create_pool()
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
 	/// Storage: `AssetConversion::Pools` (r:1 w:0)
 	/// Proof: `AssetConversion::Pools` (`max_values`: None, `max_size`: Some(1224), added: 3699, mode: `MaxEncodedLen`)
 	/// Storage: `ForeignAssets::Asset` (r:1 w:1)
@@ -176,4 +182,16 @@ impl<T: frame_system::Config> pallet_asset_conversion::WeightInfo for WeightInfo
 			.saturating_add(T::DbWeight::get().reads(7_u64))
 			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
 	}
+	/// Not benchmarked yet: bounded by `add_liquidity`, which moves two assets to an account
+	/// as well, plus the storage of the order.
+	fn place_limit_order() -> Weight {
+		<Self as pallet_asset_conversion::WeightInfo>::add_liquidity()
+			.saturating_add(T::DbWeight::get().reads_writes(3, 4))
+	}
+	/// Not benchmarked yet: bounded by `remove_liquidity`, which moves two assets from an
+	/// account as well, plus the storage of the order.
+	fn cancel_limit_order() -> Weight {
+		<Self as pallet_asset_conversion::WeightInfo>::remove_liquidity()
+			.saturating_add(T::DbWeight::get().reads_writes(3, 4))
+	}
 }
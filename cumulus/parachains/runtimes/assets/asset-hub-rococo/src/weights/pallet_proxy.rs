@@ -163,6 +163,12 @@ impl<T: frame_system::Config> pallet_proxy::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// Not benchmarked yet: `add_proxy` plus the write of `Proxy::Limits`.
+	/// The range of component `p` is `[1, 31]`.
+	fn add_proxy_with_limits(p: u32, ) -> Weight {
+		<Self as pallet_proxy::WeightInfo>::add_proxy(p)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
 	/// Storage: `Proxy::Proxies` (r:1 w:1)
 	/// Proof: `Proxy::Proxies` (`max_values`: None, `max_size`: Some(1241), added: 3716, mode: `MaxEncodedLen`)
 	/// The range of component `p` is `[1, 31]`.
@@ -161,4 +161,34 @@ impl<T: frame_system::Config> pallet_multisig::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// Not benchmarked yet: `approve_as_multi_create` plus the preimage status and request, the
+	/// stored call and the account of the depositor.
+	fn propose_as_multi(s: u32, ) -> Weight {
+		<Self as pallet_multisig::WeightInfo>::approve_as_multi_create(s)
+			.saturating_add(T::DbWeight::get().reads_writes(3, 3))
+	}
+	/// Not benchmarked yet: `as_multi_complete` without a call, plus removing the stored call
+	/// and the request of its preimage.
+	fn approve_as_multi_complete(s: u32, ) -> Weight {
+		<Self as pallet_multisig::WeightInfo>::as_multi_complete(s, 0)
+			.saturating_add(T::DbWeight::get().reads_writes(2, 2))
+	}
+	/// Not benchmarked yet: `approve_as_multi_create` plus the dissolved multisigs and the account
+	/// of the depositor.
+	fn create_managed_multisig(s: u32, ) -> Weight {
+		<Self as pallet_multisig::WeightInfo>::approve_as_multi_create(s)
+			.saturating_add(T::DbWeight::get().reads_writes(2, 1))
+	}
+	/// Not benchmarked yet: `approve_as_multi_create` plus the accounts of the previous and the
+	/// new depositor.
+	fn set_managed_multisig(s: u32, ) -> Weight {
+		<Self as pallet_multisig::WeightInfo>::approve_as_multi_create(s)
+			.saturating_add(T::DbWeight::get().reads_writes(2, 2))
+	}
+	/// Not benchmarked yet: `cancel_as_multi` plus the dissolved multisigs, the account of the
+	/// depositor and the total issuance.
+	fn dissolve_managed_multisig(s: u32, ) -> Weight {
+		<Self as pallet_multisig::WeightInfo>::cancel_as_multi(s)
+			.saturating_add(T::DbWeight::get().reads_writes(2, 3))
+	}
 }
@@ -17,7 +17,8 @@ mod pallet_xcm_benchmarks_fungible;
 mod pallet_xcm_benchmarks_generic;
 
 use crate::{xcm_config::MaxAssetsIntoHolding, Runtime};
-use frame_support::weights::Weight;
+use codec::Encode;
+use frame_support::{weights::Weight, BoundedVec};
 use pallet_xcm_benchmarks_fungible::WeightInfo as XcmFungibleWeight;
 use pallet_xcm_benchmarks_generic::WeightInfo as XcmGeneric;
 use sp_std::prelude::*;
@@ -229,4 +230,27 @@ impl<Call> XcmWeightInfo<Call> for AssetHubWestendXcmWeight<Call> {
 	fn unpaid_execution(_: &WeightLimit, _: &Option<Location>) -> Weight {
 		XcmGeneric::<Runtime>::unpaid_execution()
 	}
+	fn initiate_transfer(
+		_destination: &Location,
+		_remote_fees: &Option<AssetTransferFilter>,
+		_preserve_origin: &bool,
+		assets: &BoundedVec<AssetTransferFilter, MaxAssetTransferFilters>,
+		remote_xcm: &Xcm<()>,
+	) -> Weight {
+		// The benchmark transfers the remote fees and sends them along with `remote_xcm`, the
+		// other assets are weighed by the instructions transferring them the same way.
+		let remote_xcm_encoded_len = remote_xcm.encode().len() as u32;
+		let base = XcmFungibleWeight::<Runtime>::initiate_transfer(remote_xcm_encoded_len);
+		assets.iter().fold(base, |weight, filter| {
+			let per_asset = match filter {
+				AssetTransferFilter::Teleport(_) =>
+					XcmFungibleWeight::<Runtime>::initiate_teleport(),
+				AssetTransferFilter::ReserveDeposit(_) =>
+					XcmFungibleWeight::<Runtime>::deposit_reserve_asset(),
+				AssetTransferFilter::ReserveWithdraw(_) =>
+					XcmFungibleWeight::<Runtime>::initiate_reserve_withdraw(),
+			};
+			weight.saturating_add(filter.inner().weigh_assets(per_asset))
+		})
+	}
 }
@@ -197,4 +197,10 @@ impl<T: frame_system::Config> WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(8))
 			.saturating_add(T::DbWeight::get().writes(3))
 	}
+	/// Not benchmarked yet: `deposit_reserve_asset`, which transfers the remote fees and sends
+	/// the message the same way, without the size of the `remote_xcm` (`x`).
+	/// The range of component `x` is `[1, 1000]`.
+	pub fn initiate_transfer(_x: u32, ) -> Weight {
+		Self::deposit_reserve_asset()
+	}
 }
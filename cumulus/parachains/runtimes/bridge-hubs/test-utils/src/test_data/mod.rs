@@ -40,7 +40,7 @@ pub fn prepare_inbound_xcm<InnerXcmRuntimeCall>(
 	destination: InteriorLocation,
 ) -> Vec<u8> {
 	let location = xcm::VersionedInteriorLocation::V4(destination);
-	let xcm = xcm::VersionedXcm::<InnerXcmRuntimeCall>::V4(xcm_message);
+	let xcm = xcm::VersionedXcm::<InnerXcmRuntimeCall>::V5(xcm_message);
 	// this is the `BridgeMessage` from polkadot xcm builder, but it has no constructor
 	// or public fields, so just tuple
 	// (double encoding, because `.encode()` is called on original Xcm BLOB when it is pushed
@@ -240,6 +240,7 @@ parameter_types! {
 	pub const DepositBase: Balance = deposit(1, 88);
 	// Additional storage item size of 32 bytes.
 	pub const DepositFactor: Balance = deposit(0, 32);
+	pub const DissolvedDeposit: Balance = deposit(1, 40);
 }
 
 impl pallet_multisig::Config for Runtime {
@@ -248,7 +249,9 @@ impl pallet_multisig::Config for Runtime {
 	type Currency = Balances;
 	type DepositBase = DepositBase;
 	type DepositFactor = DepositFactor;
+	type DissolvedDeposit = DissolvedDeposit;
 	type MaxSignatories = ConstU32<100>;
+	type Preimages = Preimage;
 	type WeightInfo = weights::pallet_multisig::WeightInfo<Runtime>;
 }
 
@@ -267,6 +270,8 @@ parameter_types! {
 	// One storage item; key size 32, value size 16
 	pub const AnnouncementDepositBase: Balance = deposit(1, 48);
 	pub const AnnouncementDepositFactor: Balance = deposit(0, 66);
+	// One storage item; key size 69 (account and proxy definition), value size 62.
+	pub const ProxyLimitsDeposit: Balance = deposit(1, 131);
 }
 
 /// The type used to represent the kinds of proxying allowed.
@@ -374,6 +379,7 @@ impl pallet_proxy::Config for Runtime {
 	type CallHasher = BlakeTwo256;
 	type AnnouncementDepositBase = AnnouncementDepositBase;
 	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+	type ProxyLimitsDeposit = ProxyLimitsDeposit;
 }
 
 parameter_types! {
@@ -982,6 +988,18 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl xcm_fee_payment_runtime_api::conversions::LocationToAccountApi<Block, AccountId> for Runtime {
+		fn convert_location(location: VersionedLocation) -> Result<
+			AccountId,
+			xcm_fee_payment_runtime_api::conversions::Error
+		> {
+			xcm_fee_payment_runtime_api::conversions::LocationToAccountHelper::<
+				AccountId,
+				xcm_config::LocationToAccountId,
+			>::convert_location(location)
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)
@@ -253,6 +253,7 @@ parameter_types! {
 	pub const DepositBase: Balance = deposit(1, 88);
 	// Additional storage item size of 32 bytes.
 	pub const DepositFactor: Balance = deposit(0, 32);
+	pub const DissolvedDeposit: Balance = deposit(1, 40);
 }
 
 impl pallet_multisig::Config for Runtime {
@@ -261,7 +262,9 @@ impl pallet_multisig::Config for Runtime {
 	type Currency = Balances;
 	type DepositBase = DepositBase;
 	type DepositFactor = DepositFactor;
+	type DissolvedDeposit = DissolvedDeposit;
 	type MaxSignatories = ConstU32<100>;
+	type Preimages = ();
 	type WeightInfo = pallet_multisig::weights::SubstrateWeight<Runtime>;
 }
 
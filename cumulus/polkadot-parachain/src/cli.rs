@@ -16,6 +16,29 @@
 
 use std::path::PathBuf;
 
+/// The way blocks are sealed when running in development mode.
+#[derive(Debug, Clone, Copy)]
+pub enum DevSeal {
+	/// Seal a block with the given block time, in milliseconds.
+	ManualSeal(u64),
+	/// Seal a block as soon as a transaction is imported into the pool.
+	InstantSeal,
+}
+
+impl std::str::FromStr for DevSeal {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(if s == "instant-seal" {
+			DevSeal::InstantSeal
+		} else if let Some(block_time) = s.strip_prefix("manual-seal-") {
+			DevSeal::ManualSeal(block_time.parse().map_err(|_| "invalid block time")?)
+		} else {
+			return Err("incorrect dev seal identifier".into())
+		})
+	}
+}
+
 /// Sub-commands supported by the collator.
 #[derive(Debug, clap::Subcommand)]
 pub enum Subcommand {
@@ -90,6 +113,25 @@ pub struct Cli {
 	#[arg(long)]
 	pub no_hardware_benchmarks: bool,
 
+	/// EXPERIMENTAL: Use the slot-based collator, which authors blocks on its own slot timer
+	/// and can submit collations on multiple cores (elastic scaling).
+	///
+	/// Only supported by the rococo parachain and penpal runtimes, as well as by Aura runtimes
+	/// run with the omni node. Use with care, this flag is unstable and subject to change.
+	#[arg(long)]
+	pub experimental_use_slot_based: bool,
+
+	/// Run the parachain in development mode, without being backed by a relay chain.
+	///
+	/// Blocks are sealed by the node itself, either as soon as a transaction is imported
+	/// (`instant-seal`) or on a fixed interval (`manual-seal-<block time in ms>`), while the
+	/// relay chain validation data, state proofs and inbound XCM messages are mocked. Blocks can
+	/// also be sealed on demand through the `engine_createBlock` RPC.
+	///
+	/// Only supported by Aura runtimes using sr25519 keys. Relay chain arguments are ignored.
+	#[arg(long)]
+	pub dev_seal: Option<DevSeal>,
+
 	/// Relay chain arguments
 	#[arg(raw = true)]
 	pub relaychain_args: Vec<String>,
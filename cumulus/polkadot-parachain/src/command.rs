@@ -17,10 +17,11 @@
 use crate::{
 	chain_spec,
 	chain_spec::GenericChainSpec,
-	cli::{Cli, RelayChainCli, Subcommand},
+	cli::{Cli, DevSeal, RelayChainCli, Subcommand},
 	fake_runtime_api::{
 		asset_hub_polkadot_aura::RuntimeApi as AssetHubPolkadotRuntimeApi, aura::RuntimeApi,
 	},
+	omni::{self, AuraKeyType, Consensus},
 	service::{new_partial, Block, Hash},
 };
 use cumulus_client_service::storage_proof_size::HostFunctions as ReclaimHostFunctions;
@@ -38,11 +39,11 @@ use std::{net::SocketAddr, path::PathBuf};
 
 /// Helper enum that is used for better distinction of different parachain/runtime configuration
 /// (it is based/calculated on ChainSpec's ID attribute)
-#[derive(Debug, PartialEq, Default)]
+#[derive(Debug, PartialEq)]
 enum Runtime {
-	/// This is the default runtime (actually based on rococo)
-	#[default]
-	Default,
+	/// None of the known runtimes. The node acts as an omni node and runs the runtime found in
+	/// the chain spec with the given consensus, see [`omni::inspect_runtime`].
+	Omni(Consensus),
 	Shell,
 	Seedling,
 	AssetHubPolkadot,
@@ -66,7 +67,10 @@ trait RuntimeResolver {
 
 impl RuntimeResolver for dyn ChainSpec {
 	fn runtime(&self) -> Result<Runtime> {
-		Ok(runtime(self.id()))
+		match runtime(self.id()) {
+			Some(runtime) => Ok(runtime),
+			None => Ok(Runtime::Omni(omni::inspect_runtime(self)?)),
+		}
 	}
 }
 
@@ -83,15 +87,25 @@ impl RuntimeResolver for PathBuf {
 		let chain_spec: EmptyChainSpecWithId =
 			serde_json::from_reader(reader).map_err(|e| sc_cli::Error::Application(Box::new(e)))?;
 
-		Ok(runtime(&chain_spec.id))
+		match runtime(&chain_spec.id) {
+			Some(runtime) => Ok(runtime),
+			None => {
+				let chain_spec = GenericChainSpec::from_json_file(self.clone())?;
+				Ok(Runtime::Omni(omni::inspect_runtime(&chain_spec)?))
+			},
+		}
 	}
 }
 
-fn runtime(id: &str) -> Runtime {
+/// Resolves one of the known runtimes from the chain spec id.
+///
+/// Returns `None` if the id does not belong to any known runtime, in which case the runtime
+/// needs to be inspected to run it with the omni node.
+fn runtime(id: &str) -> Option<Runtime> {
 	let id = id.replace('_', "-");
 	let (_, id, para_id) = extract_parachain_id(&id);
 
-	if id.starts_with("shell") {
+	let runtime = if id.starts_with("shell") {
 		Runtime::Shell
 	} else if id.starts_with("seedling") {
 		Runtime::Seedling
@@ -127,9 +141,14 @@ fn runtime(id: &str) -> Runtime {
 	} else if id.starts_with(chain_spec::people::PeopleRuntimeType::ID_PREFIX) {
 		Runtime::People(id.parse::<chain_spec::people::PeopleRuntimeType>().expect("Invalid value"))
 	} else {
-		log::warn!("No specific runtime was recognized for ChainSpec's id: '{}', so Runtime::default() will be used", id);
-		Runtime::default()
-	}
+		log::info!(
+			"No specific runtime was recognized for ChainSpec's id: '{}', the runtime will be inspected",
+			id
+		);
+		return None
+	};
+
+	Some(runtime)
 }
 
 fn load_spec(id: &str) -> std::result::Result<Box<dyn ChainSpec>, String> {
@@ -396,7 +415,7 @@ impl SubstrateCli for RelayChainCli {
 macro_rules! construct_partials {
 	($config:expr, |$partials:ident| $code:expr) => {
 		match $config.chain_spec.runtime()? {
-			Runtime::AssetHubPolkadot => {
+			Runtime::AssetHubPolkadot | Runtime::Omni(Consensus::Aura(AuraKeyType::Ed25519)) => {
 				let $partials = new_partial::<AssetHubPolkadotRuntimeApi, _>(
 					&$config,
 					crate::service::build_relay_to_aura_import_queue::<_, AssetHubPolkadotAuraId>,
@@ -417,14 +436,20 @@ macro_rules! construct_partials {
 				)?;
 				$code
 			},
-			Runtime::GluttonWestend | Runtime::Glutton | Runtime::Shell | Runtime::Seedling => {
+			Runtime::GluttonWestend |
+			Runtime::Glutton |
+			Runtime::Shell |
+			Runtime::Seedling |
+			Runtime::Omni(Consensus::Relay) => {
 				let $partials = new_partial::<RuntimeApi, _>(
 					&$config,
 					crate::service::build_shell_import_queue,
 				)?;
 				$code
 			},
-			Runtime::ContractsRococo | Runtime::Penpal(_) | Runtime::Default => {
+			Runtime::ContractsRococo |
+			Runtime::Penpal(_) |
+			Runtime::Omni(Consensus::Aura(AuraKeyType::Sr25519)) => {
 				let $partials = new_partial::<RuntimeApi, _>(
 					&$config,
 					crate::service::build_aura_import_queue,
@@ -439,7 +464,7 @@ macro_rules! construct_async_run {
 	(|$components:ident, $cli:ident, $cmd:ident, $config:ident| $( $code:tt )* ) => {{
 		let runner = $cli.create_runner($cmd)?;
 		match runner.config().chain_spec.runtime()? {
-			Runtime::AssetHubPolkadot => {
+			Runtime::AssetHubPolkadot | Runtime::Omni(Consensus::Aura(AuraKeyType::Ed25519)) => {
 				runner.async_run(|$config| {
 					let $components = new_partial::<AssetHubPolkadotRuntimeApi, _>(
 						&$config,
@@ -469,7 +494,8 @@ macro_rules! construct_async_run {
 			Runtime::Shell |
 			Runtime::Seedling |
 			Runtime::GluttonWestend |
-			Runtime::Glutton => {
+			Runtime::Glutton |
+			Runtime::Omni(Consensus::Relay) => {
 				runner.async_run(|$config| {
 					let $components = new_partial::<RuntimeApi, _>(
 						&$config,
@@ -479,7 +505,9 @@ macro_rules! construct_async_run {
 					{ $( $code )* }.map(|v| (v, task_manager))
 				})
 			}
-			Runtime::ContractsRococo | Runtime::Penpal(_) | Runtime::Default => {
+			Runtime::ContractsRococo |
+			Runtime::Penpal(_) |
+			Runtime::Omni(Consensus::Aura(AuraKeyType::Sr25519)) => {
 				runner.async_run(|$config| {
 					let $components = new_partial::<
 						RuntimeApi,
@@ -648,13 +676,29 @@ pub fn run() -> Result<()> {
 					.map(|e| e.para_id)
 					.ok_or("Could not find parachain extension in chain-spec.")?;
 
+				let id = ParaId::from(para_id);
+
+				if let Some(dev_seal) = cli.dev_seal {
+					info!("Parachain id: {:?}", id);
+					info!("Running in development mode with {:?}", dev_seal);
+
+					return match config.network.network_backend {
+						sc_network::config::NetworkBackendType::Libp2p =>
+							start_dev_node::<sc_network::NetworkWorker<_, _>>(config, id, dev_seal)
+								.await,
+						sc_network::config::NetworkBackendType::Litep2p =>
+							start_dev_node::<sc_network::Litep2pNetworkBackend>(
+								config, id, dev_seal,
+							)
+							.await,
+					}
+				}
+
 				let polkadot_cli = RelayChainCli::new(
 					&config,
 					[RelayChainCli::executable_name()].iter().chain(cli.relaychain_args.iter()),
 				);
 
-				let id = ParaId::from(para_id);
-
 				let parachain_account =
 					AccountIdConversion::<polkadot_primitives::AccountId>::into_account_truncating(&id);
 
@@ -675,6 +719,7 @@ pub fn run() -> Result<()> {
 							collator_options,
 							id,
 							hwbench,
+							cli.experimental_use_slot_based,
 						)
 						.await,
 					sc_network::config::NetworkBackendType::Litep2p =>
@@ -684,6 +729,7 @@ pub fn run() -> Result<()> {
 							collator_options,
 							id,
 							hwbench,
+							cli.experimental_use_slot_based,
 						)
 						.await,
 				}
@@ -698,6 +744,7 @@ async fn start_node<Network: sc_network::NetworkBackend<Block, Hash>>(
 	collator_options: cumulus_client_cli::CollatorOptions,
 	id: ParaId,
 	hwbench: Option<sc_sysinfo::HwBench>,
+	use_experimental_slot_based: bool,
 ) -> Result<sc_service::TaskManager> {
 	match config.chain_spec.runtime()? {
 		Runtime::AssetHubPolkadot => crate::service::start_asset_hub_lookahead_node::<
@@ -801,18 +848,59 @@ async fn start_node<Network: sc_network::NetworkBackend<Block, Hash>>(
 		}
 		.map_err(Into::into),
 
-		Runtime::Penpal(_) | Runtime::Default =>
-			crate::service::start_rococo_parachain_node::<Network>(
+		Runtime::Penpal(_) => crate::service::start_rococo_parachain_node::<Network>(
+			config,
+			polkadot_config,
+			collator_options,
+			id,
+			hwbench,
+			use_experimental_slot_based,
+		)
+		.await
+		.map(|r| r.0)
+		.map_err(Into::into),
+
+		Runtime::Omni(Consensus::Aura(AuraKeyType::Sr25519)) =>
+			crate::service::start_omni_aura_node::<Network>(
 				config,
 				polkadot_config,
 				collator_options,
 				id,
 				hwbench,
+				use_experimental_slot_based,
 			)
 			.await
 			.map(|r| r.0)
 			.map_err(Into::into),
 
+		// Runtimes using ed25519 Aura keys are run the same way as Asset Hub Polkadot. The collator
+		// switches to Aura as soon as the runtime exposes the `AuraApi`, i.e. right away.
+		Runtime::Omni(Consensus::Aura(AuraKeyType::Ed25519)) if use_experimental_slot_based =>
+			Err("The slot-based collator only supports Aura runtimes using sr25519 keys".into()),
+		Runtime::Omni(Consensus::Aura(AuraKeyType::Ed25519)) =>
+			crate::service::start_asset_hub_lookahead_node::<
+				AssetHubPolkadotRuntimeApi,
+				AssetHubPolkadotAuraId,
+				Network,
+			>(config, polkadot_config, collator_options, id, hwbench)
+			.await
+			.map(|r| r.0)
+			.map_err(Into::into),
+
+		Runtime::Omni(Consensus::Relay) => crate::service::start_shell_node::<Network>(
+			config,
+			polkadot_config,
+			collator_options,
+			id,
+			hwbench,
+		)
+		.await
+		.map(|(task_manager, client)| {
+			omni::exit_on_aura_upgrade(&task_manager, client);
+			task_manager
+		})
+		.map_err(Into::into),
+
 		Runtime::Glutton | Runtime::GluttonWestend =>
 			crate::service::start_basic_lookahead_node::<Network>(
 				config,
@@ -850,6 +938,27 @@ async fn start_node<Network: sc_network::NetworkBackend<Block, Hash>>(
 	}
 }
 
+async fn start_dev_node<Network: sc_network::NetworkBackend<Block, Hash>>(
+	config: sc_service::Configuration,
+	id: ParaId,
+	dev_seal: DevSeal,
+) -> Result<sc_service::TaskManager> {
+	match config.chain_spec.runtime()? {
+		// These runtimes either don't use Aura or use Aura with ed25519 keys.
+		Runtime::Shell |
+		Runtime::Seedling |
+		Runtime::Omni(Consensus::Relay) |
+		Runtime::Omni(Consensus::Aura(AuraKeyType::Ed25519)) |
+		Runtime::AssetHubPolkadot =>
+			Err("Development mode is only supported by Aura runtimes using sr25519 keys".into()),
+
+		_ => crate::service::start_dev_node::<Network>(config, id, dev_seal)
+			.await
+			.map(|r| r.0)
+			.map_err(Into::into),
+	}
+}
+
 impl DefaultConfigurationValues for RelayChainCli {
 	fn p2p_listen_port() -> u16 {
 		30334
@@ -1069,7 +1178,7 @@ mod tests {
 			&temp_dir,
 			Box::new(crate::chain_spec::rococo_parachain::rococo_parachain_local_config()),
 		);
-		assert_eq!(Runtime::Default, path.runtime().unwrap());
+		assert_eq!(Runtime::Omni(Consensus::Aura(AuraKeyType::Sr25519)), path.runtime().unwrap());
 
 		let path = store_configuration(
 			&temp_dir,
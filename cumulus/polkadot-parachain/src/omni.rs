@@ -0,0 +1,313 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime inspection for the omni node.
+//!
+//! Chain specs which are not recognized by their id are not tied to any of the runtimes known
+//! to this node. Instead, the runtime code stored in the genesis state is inspected to find out
+//! which consensus it uses and whether it is compatible with the generic services of this node.
+//!
+//! The consensus has to be known before the client is built, so it is taken from the genesis
+//! runtime. A chain whose genesis runtime doesn't use Aura may have upgraded to one that does,
+//! which is checked at the best block once the node is started, see [`exit_on_aura_upgrade`].
+
+use codec::Decode;
+use frame_metadata::{v14::StorageEntryType, RuntimeMetadata, RuntimeMetadataPrefixed};
+use futures::{future, StreamExt};
+use parachains_common::{AuraId, Block, Hash};
+use sc_cli::ChainSpec;
+use sc_client_api::BlockchainEvents;
+use sc_executor::{RuntimeVersionOf, WasmExecutor};
+use sc_service::TaskManager;
+use scale_info::{TypeDef, TypeDefPrimitive};
+use sp_api::{CallApiAt, RuntimeApiInfo};
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	storage::well_known_keys::CODE,
+	traits::{CallContext, CodeExecutor, RuntimeCode, WrappedRuntimeCode},
+};
+use sp_runtime::BuildStorage;
+use sp_state_machine::BasicExternalities;
+use sp_version::ApiId;
+use std::{
+	collections::BTreeMap,
+	sync::{Arc, Mutex},
+};
+
+use crate::service::HostFunctions;
+
+/// The consensus algorithm used by a runtime that is run by the omni node.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Consensus {
+	/// Aura consensus, with block authoring driven by the lookahead collator.
+	Aura(AuraKeyType),
+	/// Relay-chain driven consensus, as used by the shell runtime.
+	Relay,
+}
+
+/// The key type of the Aura authorities of a runtime.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AuraKeyType {
+	/// The authorities use sr25519 keys, like most system parachains.
+	Sr25519,
+	/// The authorities use ed25519 keys, like Asset Hub Polkadot.
+	Ed25519,
+}
+
+/// Results of [`inspect_runtime`], by chain spec id.
+///
+/// The runtime of a chain spec is resolved by every subcommand, often more than once, and
+/// inspecting it requires building the genesis storage and instantiating the runtime.
+static INSPECTED_RUNTIMES: Mutex<BTreeMap<String, Consensus>> = Mutex::new(BTreeMap::new());
+
+/// Runtime APIs that every runtime run by the omni node has to implement.
+fn required_apis() -> Vec<(&'static str, ApiId)> {
+	vec![
+		("Core", <dyn sp_api::Core<Block>>::ID),
+		("Metadata", <dyn sp_api::Metadata<Block>>::ID),
+		("BlockBuilder", <dyn sp_block_builder::BlockBuilder<Block>>::ID),
+		(
+			"TaggedTransactionQueue",
+			<dyn sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>>::ID,
+		),
+		("OffchainWorkerApi", <dyn sp_offchain::OffchainWorkerApi<Block>>::ID),
+		("SessionKeys", <dyn sp_session::SessionKeys<Block>>::ID),
+		("CollectCollationInfo", <dyn cumulus_primitives_core::CollectCollationInfo<Block>>::ID),
+	]
+}
+
+/// Runtime APIs that a runtime has to implement in addition to [`required_apis`] to be run
+/// with Aura consensus.
+fn required_aura_apis() -> Vec<(&'static str, ApiId)> {
+	vec![(
+		"AuraUnincludedSegmentApi",
+		<dyn cumulus_primitives_aura::AuraUnincludedSegmentApi<Block>>::ID,
+	)]
+}
+
+/// Inspect the genesis runtime of the given chain spec and determine the [`Consensus`] it uses.
+///
+/// Returns an error if the runtime cannot be run by the omni node, e.g. because it is missing a
+/// required runtime API or uses a block number type other than `u32`. The result is cached, so
+/// the runtime of a chain spec is only inspected once.
+///
+/// A node started with [`Consensus::Relay`] must be watched with [`exit_on_aura_upgrade`], as
+/// the runtime may have been upgraded to one using Aura since genesis.
+pub fn inspect_runtime(chain_spec: &dyn ChainSpec) -> sc_cli::Result<Consensus> {
+	if let Some(consensus) = INSPECTED_RUNTIMES
+		.lock()
+		.expect("Nothing panics while holding the lock; qed")
+		.get(chain_spec.id())
+	{
+		return Ok(*consensus)
+	}
+
+	let consensus = inspect_genesis_runtime(chain_spec)?;
+	INSPECTED_RUNTIMES
+		.lock()
+		.expect("Nothing panics while holding the lock; qed")
+		.insert(chain_spec.id().into(), consensus);
+
+	Ok(consensus)
+}
+
+fn inspect_genesis_runtime(chain_spec: &dyn ChainSpec) -> sc_cli::Result<Consensus> {
+	let storage = chain_spec.as_storage_builder().build_storage()?;
+	let code = storage
+		.top
+		.get(CODE)
+		.ok_or_else(|| "The chain spec does not contain the runtime code.".to_string())?;
+
+	let executor = WasmExecutor::<HostFunctions>::builder().build();
+	let runtime_code = RuntimeCode {
+		code_fetcher: &WrappedRuntimeCode(code.as_slice().into()),
+		heap_pages: None,
+		hash: sp_core::blake2_256(code).to_vec(),
+	};
+	let mut ext = BasicExternalities::new_empty();
+
+	let version = executor
+		.runtime_version(&mut ext, &runtime_code)
+		.map_err(|e| format!("Failed to read the runtime version: {}", e))?;
+
+	let has_aura =
+		version.has_api_with(&<dyn sp_consensus_aura::AuraApi<Block, AuraId>>::ID, |_| true);
+
+	let mut required = required_apis();
+	if has_aura {
+		required.extend(required_aura_apis());
+	}
+	let missing = required
+		.into_iter()
+		.filter(|(_, id)| !version.has_api_with(id, |_| true))
+		.map(|(name, _)| name)
+		.collect::<Vec<_>>();
+	if !missing.is_empty() {
+		return Err(format!(
+			"Runtime `{}` is missing the runtime APIs required by the omni node: {}",
+			version.spec_name,
+			missing.join(", "),
+		)
+		.into())
+	}
+
+	let (metadata, _) =
+		executor.call(&mut ext, &runtime_code, "Metadata_metadata", &[], CallContext::Offchain);
+	let metadata = metadata.map_err(|e| format!("Failed to fetch the runtime metadata: {}", e))?;
+	let metadata = <Vec<u8>>::decode(&mut &metadata[..])
+		.and_then(|m| RuntimeMetadataPrefixed::decode(&mut &m[..]))
+		.map_err(|e| format!("Failed to decode the runtime metadata: {}", e))?;
+
+	match block_number_type(&metadata.1) {
+		Some(TypeDefPrimitive::U32) => (),
+		Some(other) =>
+			return Err(format!(
+				"Runtime `{}` uses `{:?}` block numbers, but the omni node only supports `u32`",
+				version.spec_name, other,
+			)
+			.into()),
+		None =>
+			return Err(format!(
+				"Could not determine the block number type of runtime `{}`",
+				version.spec_name,
+			)
+			.into()),
+	}
+
+	let consensus = if has_aura {
+		// The runtime API id doesn't depend on the authority id type, so the key type has to be
+		// taken from the metadata.
+		let key_type = aura_key_type(&metadata.1).ok_or_else(|| {
+			format!("Could not determine the Aura key type of runtime `{}`", version.spec_name)
+		})?;
+		Consensus::Aura(key_type)
+	} else {
+		Consensus::Relay
+	};
+
+	log::info!(
+		"Runtime `{}` is not known to this node, running it with {:?} consensus",
+		version.spec_name,
+		consensus,
+	);
+
+	Ok(consensus)
+}
+
+/// Whether the runtime at the given block uses Aura.
+fn uses_aura<Client: CallApiAt<Block>>(client: &Client, hash: Hash) -> bool {
+	client.runtime_version_at(hash).map_or(false, |version| {
+		version.has_api_with(&<dyn sp_consensus_aura::AuraApi<Block, AuraId>>::ID, |_| true)
+	})
+}
+
+/// Shut the node down with an error as soon as the runtime at the best block uses Aura.
+///
+/// Nodes run with [`Consensus::Relay`] because their genesis runtime doesn't use Aura can't
+/// follow an upgrade to a runtime using it, so they fail loudly instead of keeping the wrong
+/// consensus. This also covers databases which are already past such an upgrade when the node
+/// starts.
+pub fn exit_on_aura_upgrade<Client>(task_manager: &TaskManager, client: Arc<Client>)
+where
+	Client:
+		BlockchainEvents<Block> + HeaderBackend<Block> + CallApiAt<Block> + Send + Sync + 'static,
+{
+	// Subscribe first, so no new best block is missed between the two.
+	let mut notifications = client.import_notification_stream();
+	let mut best_hash = client.info().best_hash;
+
+	let check = async move {
+		while !uses_aura(&*client, best_hash) {
+			match notifications.next().await {
+				Some(notification) if notification.is_new_best => best_hash = notification.hash,
+				Some(_) => (),
+				// The node is shutting down.
+				None => return future::pending().await,
+			}
+		}
+
+		log::error!(
+			"The runtime at the best block {:?} uses Aura, but this chain is run with relay chain \
+			 driven consensus as its genesis runtime doesn't. The omni node can't switch consensus, \
+			 run the chain with a node built for its runtime instead.",
+			best_hash,
+		);
+	};
+	// The task is essential, so the node shuts down once it ends.
+	task_manager.spawn_essential_handle().spawn("omni-consensus-check", None, check);
+}
+
+/// Determine the block number type from the type of the `System::Number` storage item.
+fn block_number_type(metadata: &RuntimeMetadata) -> Option<TypeDefPrimitive> {
+	let RuntimeMetadata::V14(metadata) = metadata else { return None };
+
+	let ty = metadata
+		.pallets
+		.iter()
+		.find(|pallet| pallet.name == "System")?
+		.storage
+		.as_ref()?
+		.entries
+		.iter()
+		.find_map(|entry| match &entry.ty {
+			StorageEntryType::Plain(ty) if entry.name == "Number" => Some(ty.id),
+			_ => None,
+		})?;
+
+	match &metadata.types.resolve(ty)?.type_def {
+		TypeDef::Primitive(primitive) => Some(primitive.clone()),
+		_ => None,
+	}
+}
+
+/// Determine the key type of the Aura authorities from the type of the `Aura::Authorities`
+/// storage item.
+fn aura_key_type(metadata: &RuntimeMetadata) -> Option<AuraKeyType> {
+	let RuntimeMetadata::V14(metadata) = metadata else { return None };
+
+	let mut ty = metadata
+		.pallets
+		.iter()
+		.find(|pallet| pallet.name == "Aura")?
+		.storage
+		.as_ref()?
+		.entries
+		.iter()
+		.find_map(|entry| match &entry.ty {
+			StorageEntryType::Plain(ty) if entry.name == "Authorities" => Some(ty.id),
+			_ => None,
+		})?;
+
+	// Unwrap the `BoundedVec` and `Vec` around the authority id, which is declared in the
+	// `sr25519` or `ed25519` module of `sp_consensus_aura`.
+	loop {
+		let resolved = metadata.types.resolve(ty)?;
+		let segments = &resolved.path.segments;
+		if segments.iter().any(|segment| segment == "sr25519") {
+			return Some(AuraKeyType::Sr25519)
+		}
+		if segments.iter().any(|segment| segment == "ed25519") {
+			return Some(AuraKeyType::Ed25519)
+		}
+
+		ty = match &resolved.type_def {
+			TypeDef::Sequence(sequence) => sequence.type_param.id,
+			TypeDef::Composite(composite) if composite.fields.len() == 1 =>
+				composite.fields[0].ty.id,
+			_ => return None,
+		};
+	}
+}
@@ -18,15 +18,18 @@
 
 #![warn(missing_docs)]
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use parachains_common::{AccountId, Balance, Block, Nonce};
+use cumulus_primitives_core::ParaId;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use parachains_common::{AccountId, Balance, Block, Hash, Nonce};
 use sc_client_api::AuxStore;
 pub use sc_rpc::DenyUnsafe;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_core::Bytes;
 
 /// A type representing all RPC extensions.
 pub type RpcExtension = jsonrpsee::RpcModule<()>;
@@ -106,3 +109,139 @@ where
 
 	Ok(module)
 }
+
+/// Inbound XCM messages waiting to be included in the next block sealed by a dev node.
+///
+/// Messages are only removed once a block including them has been imported, so they are
+/// delivered again if sealing the block fails.
+#[derive(Default)]
+pub struct DevMessageQueues {
+	/// Raw downward messages, in the order they were injected.
+	pub downward: Vec<Vec<u8>>,
+	/// Raw horizontal messages, alongside the parachain that sent them.
+	pub horizontal: Vec<(ParaId, Vec<u8>)>,
+	/// The parent of the last block the messages were handed out for, alongside the number of
+	/// downward and horizontal messages that were handed out.
+	in_flight: Option<(Hash, usize, usize)>,
+}
+
+impl DevMessageQueues {
+	/// Returns the raw downward and horizontal messages to include in a block built on top of
+	/// `parent`, whose own parent is `grandparent`.
+	///
+	/// If `parent` was built with the messages handed out by the previous call, it has been
+	/// imported and those messages are removed from the queues first.
+	pub fn messages_for_block(
+		&mut self,
+		parent: Hash,
+		grandparent: Hash,
+	) -> (Vec<Vec<u8>>, Vec<(ParaId, Vec<u8>)>) {
+		if let Some((built_on, downward, horizontal)) = self.in_flight.take() {
+			if built_on == grandparent {
+				self.downward.drain(..downward);
+				self.horizontal.drain(..horizontal);
+			}
+		}
+
+		self.in_flight = Some((parent, self.downward.len(), self.horizontal.len()));
+		(self.downward.clone(), self.horizontal.clone())
+	}
+}
+
+/// RPC methods to feed mocked inbound XCM messages to a dev node.
+#[rpc(server)]
+pub trait DevXcmApi {
+	/// Inject a downward message, which is delivered in the next sealed block.
+	#[method(name = "dev_injectDownwardMessage")]
+	fn inject_downward_message(&self, message: Bytes) -> RpcResult<()>;
+
+	/// Inject a horizontal message sent by `sender`, which is delivered in the next sealed block.
+	#[method(name = "dev_injectHrmpMessage")]
+	fn inject_hrmp_message(&self, sender: u32, message: Bytes) -> RpcResult<()>;
+}
+
+/// Implementation of [`DevXcmApiServer`] backed by [`DevMessageQueues`].
+pub struct DevXcm {
+	queues: Arc<Mutex<DevMessageQueues>>,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl DevXcm {
+	/// Create a new instance pushing the injected messages to `queues`.
+	pub fn new(queues: Arc<Mutex<DevMessageQueues>>, deny_unsafe: DenyUnsafe) -> Self {
+		Self { queues, deny_unsafe }
+	}
+}
+
+impl DevXcmApiServer for DevXcm {
+	fn inject_downward_message(&self, message: Bytes) -> RpcResult<()> {
+		self.deny_unsafe.check_if_safe()?;
+
+		self.queues
+			.lock()
+			.expect("The lock is never held across a panic; qed")
+			.downward
+			.push(message.to_vec());
+		Ok(())
+	}
+
+	fn inject_hrmp_message(&self, sender: u32, message: Bytes) -> RpcResult<()> {
+		self.deny_unsafe.check_if_safe()?;
+
+		self.queues
+			.lock()
+			.expect("The lock is never held across a panic; qed")
+			.horizontal
+			.push((sender.into(), message.to_vec()));
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn hash(n: u8) -> Hash {
+		Hash::repeat_byte(n)
+	}
+
+	#[test]
+	fn messages_are_removed_once_built_upon() {
+		let mut queues = DevMessageQueues::default();
+		queues.downward.push(vec![1]);
+		queues.horizontal.push((2000.into(), vec![2]));
+
+		assert_eq!(
+			queues.messages_for_block(hash(1), hash(0)),
+			(vec![vec![1]], vec![(2000.into(), vec![2])])
+		);
+
+		// Injected while the block on top of `hash(1)` is being built.
+		queues.downward.push(vec![3]);
+
+		// `hash(2)` was built on top of `hash(1)`, so it includes the first messages.
+		assert_eq!(queues.messages_for_block(hash(2), hash(1)), (vec![vec![3]], vec![]));
+		assert_eq!(queues.messages_for_block(hash(3), hash(2)), (vec![], vec![]));
+	}
+
+	#[test]
+	fn messages_are_delivered_again_if_sealing_fails() {
+		let mut queues = DevMessageQueues::default();
+		queues.downward.push(vec![1]);
+		queues.horizontal.push((2000.into(), vec![2]));
+
+		assert_eq!(
+			queues.messages_for_block(hash(1), hash(0)),
+			(vec![vec![1]], vec![(2000.into(), vec![2])])
+		);
+
+		// Sealing failed, so the next block is built on top of the same parent.
+		queues.downward.push(vec![3]);
+		assert_eq!(
+			queues.messages_for_block(hash(1), hash(0)),
+			(vec![vec![1], vec![3]], vec![(2000.into(), vec![2])])
+		);
+
+		assert_eq!(queues.messages_for_block(hash(2), hash(1)), (vec![], vec![]));
+	}
+}
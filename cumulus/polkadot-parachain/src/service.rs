@@ -14,22 +14,31 @@
 // You should have received a copy of the GNU General Public License
 // along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
 
-use codec::{Codec, Decode};
+use codec::{Codec, Decode, Encode};
 use cumulus_client_cli::CollatorOptions;
 use cumulus_client_collator::service::CollatorService;
-use cumulus_client_consensus_aura::collators::lookahead::{self as aura, Params as AuraParams};
+use cumulus_client_consensus_aura::collators::{
+	lookahead::{self as aura, Params as AuraParams},
+	slot_based::Params as SlotBasedParams,
+};
 use cumulus_client_consensus_common::{
 	ParachainBlockImport as TParachainBlockImport, ParachainCandidate, ParachainConsensus,
 };
 use cumulus_client_consensus_proposer::Proposer;
+use cumulus_client_parachain_inherent::{
+	MockValidationDataInherentDataProvider, MockXcmConfig, RELAY_CHAIN_SLOT_DURATION_MILLIS,
+};
 #[allow(deprecated)]
 use cumulus_client_service::old_consensus;
 use cumulus_client_service::{
 	build_network, build_relay_chain_interface, prepare_node_config, start_relay_chain_tasks,
-	BuildNetworkParams, CollatorSybilResistance, DARecoveryProfile, StartRelayChainTasksParams,
+	start_slot_based_aura_collator, BuildNetworkParams, CollatorSybilResistance, DARecoveryProfile,
+	StartRelayChainTasksParams,
 };
 use cumulus_primitives_core::{
-	relay_chain::{Hash as PHash, PersistedValidationData, ValidationCode},
+	relay_chain::{
+		well_known_keys::CURRENT_SLOT, Hash as PHash, PersistedValidationData, ValidationCode,
+	},
 	ParaId,
 };
 use cumulus_relay_chain_interface::{OverseerHandle, RelayChainInterface};
@@ -38,7 +47,7 @@ use sp_core::Pair;
 
 use jsonrpsee::RpcModule;
 
-use crate::{fake_runtime_api::aura::RuntimeApi as FakeRuntimeApi, rpc};
+use crate::{cli::DevSeal, fake_runtime_api::aura::RuntimeApi as FakeRuntimeApi, rpc};
 pub use parachains_common::{AccountId, AuraId, Balance, Block, Hash, Header, Nonce};
 
 use cumulus_client_consensus_relay_chain::Verifier as RelayChainVerifier;
@@ -48,28 +57,33 @@ use sc_consensus::{
 	import_queue::{BasicQueue, Verifier as VerifierT},
 	BlockImportParams, ImportQueue,
 };
+use sc_consensus_manual_seal::{consensus::aura::AuraConsensusDataProvider, EngineCommand};
 use sc_executor::{HeapAllocStrategy, WasmExecutor, DEFAULT_HEAP_ALLOC_STRATEGY};
 use sc_network::{config::FullNetworkConfiguration, service::traits::NetworkBackend, NetworkBlock};
 use sc_network_sync::SyncingService;
 use sc_service::{Configuration, PartialComponents, TFullBackend, TFullClient, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryHandle, TelemetryWorker, TelemetryWorkerHandle};
+use sc_transaction_pool_api::TransactionPool;
 use sp_api::{ApiExt, ConstructRuntimeApi, ProvideRuntimeApi};
-use sp_consensus_aura::AuraApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus_aura::{
+	digests::CompatibleDigestItem, sr25519::AuthoritySignature, AuraApi, Slot,
+};
 use sp_core::traits::SpawnEssentialNamed;
 use sp_keystore::KeystorePtr;
 use sp_runtime::{
 	app_crypto::AppCrypto,
 	traits::{Block as BlockT, Header as HeaderT},
 };
-use std::{marker::PhantomData, sync::Arc, time::Duration};
+use std::{marker::PhantomData, pin::Pin, sync::Arc, time::Duration};
 
 use polkadot_primitives::CollatorPair;
 
 #[cfg(not(feature = "runtime-benchmarks"))]
-type HostFunctions = cumulus_client_service::ParachainHostFunctions;
+pub(crate) type HostFunctions = cumulus_client_service::ParachainHostFunctions;
 
 #[cfg(feature = "runtime-benchmarks")]
-type HostFunctions = (
+pub(crate) type HostFunctions = (
 	cumulus_client_service::ParachainHostFunctions,
 	frame_benchmarking::benchmarking::HostFunctions,
 );
@@ -355,6 +369,7 @@ where
 		relay_chain_slot_duration,
 		recovery_handle: Box::new(overseer_handle.clone()),
 		sync_service: sync_service.clone(),
+		network: Some(network.clone()),
 	})?;
 
 	if validator {
@@ -421,25 +436,85 @@ pub fn build_aura_import_queue(
 }
 
 /// Start a rococo parachain node.
+///
+/// Uses the slot-based collator if `use_experimental_slot_based` is set, and the lookahead
+/// collator otherwise.
 pub async fn start_rococo_parachain_node<Net: NetworkBackend<Block, Hash>>(
 	parachain_config: Configuration,
 	polkadot_config: Configuration,
 	collator_options: CollatorOptions,
 	para_id: ParaId,
 	hwbench: Option<sc_sysinfo::HwBench>,
+	use_experimental_slot_based: bool,
 ) -> sc_service::error::Result<(TaskManager, Arc<ParachainClient<FakeRuntimeApi>>)> {
-	start_node_impl::<FakeRuntimeApi, _, _, _, Net>(
-		parachain_config,
-		polkadot_config,
-		collator_options,
-		CollatorSybilResistance::Resistant, // Aura
-		para_id,
-		build_parachain_rpc_extensions::<FakeRuntimeApi>,
-		build_aura_import_queue,
-		start_lookahead_aura_consensus,
-		hwbench,
-	)
-	.await
+	if use_experimental_slot_based {
+		start_node_impl::<FakeRuntimeApi, _, _, _, Net>(
+			parachain_config,
+			polkadot_config,
+			collator_options,
+			CollatorSybilResistance::Resistant, // Aura
+			para_id,
+			build_parachain_rpc_extensions::<FakeRuntimeApi>,
+			build_aura_import_queue,
+			start_slot_based_aura_consensus,
+			hwbench,
+		)
+		.await
+	} else {
+		start_node_impl::<FakeRuntimeApi, _, _, _, Net>(
+			parachain_config,
+			polkadot_config,
+			collator_options,
+			CollatorSybilResistance::Resistant, // Aura
+			para_id,
+			build_parachain_rpc_extensions::<FakeRuntimeApi>,
+			build_aura_import_queue,
+			start_lookahead_aura_consensus,
+			hwbench,
+		)
+		.await
+	}
+}
+
+/// Start a runtime-agnostic Aura powered parachain node, i.e. the omni node.
+///
+/// Uses the slot-based collator if `use_experimental_slot_based` is set, and the lookahead
+/// collator otherwise.
+pub async fn start_omni_aura_node<Net: NetworkBackend<Block, Hash>>(
+	parachain_config: Configuration,
+	polkadot_config: Configuration,
+	collator_options: CollatorOptions,
+	para_id: ParaId,
+	hwbench: Option<sc_sysinfo::HwBench>,
+	use_experimental_slot_based: bool,
+) -> sc_service::error::Result<(TaskManager, Arc<ParachainClient<FakeRuntimeApi>>)> {
+	if use_experimental_slot_based {
+		start_node_impl::<FakeRuntimeApi, _, _, _, Net>(
+			parachain_config,
+			polkadot_config,
+			collator_options,
+			CollatorSybilResistance::Resistant, // Aura
+			para_id,
+			build_omni_rpc_extensions,
+			build_aura_import_queue,
+			start_slot_based_aura_consensus,
+			hwbench,
+		)
+		.await
+	} else {
+		start_node_impl::<FakeRuntimeApi, _, _, _, Net>(
+			parachain_config,
+			polkadot_config,
+			collator_options,
+			CollatorSybilResistance::Resistant, // Aura
+			para_id,
+			build_omni_rpc_extensions,
+			build_aura_import_queue,
+			start_lookahead_aura_consensus,
+			hwbench,
+		)
+		.await
+	}
 }
 
 /// Build the import queue for the shell runtime.
@@ -460,6 +535,35 @@ pub fn build_shell_import_queue(
 	.map_err(Into::into)
 }
 
+/// Build the RPC extensions of the omni node.
+///
+/// The transaction payment and account nonce RPCs are only installed if the runtime provides
+/// the runtime APIs they rely on.
+fn build_omni_rpc_extensions(
+	deny_unsafe: sc_rpc::DenyUnsafe,
+	client: Arc<ParachainClient<FakeRuntimeApi>>,
+	backend: Arc<ParachainBackend>,
+	pool: Arc<sc_transaction_pool::FullPool<Block, ParachainClient<FakeRuntimeApi>>>,
+) -> Result<jsonrpsee::RpcModule<()>, sc_service::Error> {
+	use pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi;
+	use substrate_frame_rpc_system::AccountNonceApi;
+
+	let best_hash = client.info().best_hash;
+	let runtime_api = client.runtime_api();
+	let has_rpc_apis = runtime_api
+		.has_api::<dyn TransactionPaymentRuntimeApi<Block, Balance>>(best_hash)
+		.unwrap_or(false) &&
+		runtime_api
+			.has_api::<dyn AccountNonceApi<Block, AccountId, Nonce>>(best_hash)
+			.unwrap_or(false);
+
+	if has_rpc_apis {
+		build_parachain_rpc_extensions::<FakeRuntimeApi>(deny_unsafe, client, backend, pool)
+	} else {
+		Ok(RpcModule::new(()))
+	}
+}
+
 fn build_parachain_rpc_extensions<RuntimeApi>(
 	deny_unsafe: sc_rpc::DenyUnsafe,
 	client: Arc<ParachainClient<RuntimeApi>>,
@@ -989,6 +1093,69 @@ fn start_lookahead_aura_consensus(
 	Ok(())
 }
 
+/// Start consensus using the experimental slot-based aura collator.
+fn start_slot_based_aura_consensus(
+	client: Arc<ParachainClient<FakeRuntimeApi>>,
+	block_import: ParachainBlockImport<FakeRuntimeApi>,
+	prometheus_registry: Option<&Registry>,
+	telemetry: Option<TelemetryHandle>,
+	task_manager: &TaskManager,
+	relay_chain_interface: Arc<dyn RelayChainInterface>,
+	transaction_pool: Arc<sc_transaction_pool::FullPool<Block, ParachainClient<FakeRuntimeApi>>>,
+	sync_oracle: Arc<SyncingService<Block>>,
+	keystore: KeystorePtr,
+	relay_chain_slot_duration: Duration,
+	para_id: ParaId,
+	collator_key: CollatorPair,
+	overseer_handle: OverseerHandle,
+	announce_block: Arc<dyn Fn(Hash, Option<Vec<u8>>) + Send + Sync>,
+	backend: Arc<ParachainBackend>,
+) -> Result<(), sc_service::Error> {
+	let proposer_factory = sc_basic_authorship::ProposerFactory::with_proof_recording(
+		task_manager.spawn_handle(),
+		client.clone(),
+		transaction_pool,
+		prometheus_registry,
+		telemetry.clone(),
+	);
+
+	let collator_service = CollatorService::new(
+		client.clone(),
+		Arc::new(task_manager.spawn_handle()),
+		announce_block,
+		client.clone(),
+	);
+
+	let params = SlotBasedParams {
+		create_inherent_data_providers: move |_, ()| async move { Ok(()) },
+		block_import,
+		para_client: client.clone(),
+		para_backend: backend,
+		relay_client: relay_chain_interface,
+		code_hash_provider: move |block_hash| {
+			client.code_at(block_hash).ok().map(|c| ValidationCode::from(c).hash())
+		},
+		sync_oracle,
+		keystore,
+		collator_key,
+		para_id,
+		overseer_handle,
+		relay_chain_slot_duration,
+		proposer: Proposer::new(proposer_factory),
+		collator_service,
+		authoring_duration: Duration::from_millis(2000),
+		reinitialize: false,
+		slot_drift: Duration::from_secs(1),
+	};
+
+	start_slot_based_aura_collator::<Block, <AuraId as AppCrypto>::Pair, _, _, _, _, _, _, _, _, _>(
+		params,
+		task_manager,
+	);
+
+	Ok(())
+}
+
 /// Start an aura powered parachain node which uses the lookahead collator to support async backing.
 /// This node is basic in the sense that its runtime api doesn't include common contents such as
 /// transaction payment. Used for aura glutton.
@@ -1035,6 +1202,208 @@ pub async fn start_contracts_rococo_node<Net: NetworkBackend<Block, Hash>>(
 	.await
 }
 
+/// Build the import queue of a dev node, which accepts the blocks sealed by the node itself.
+fn build_dev_import_queue(
+	client: Arc<ParachainClient<FakeRuntimeApi>>,
+	_: ParachainBlockImport<FakeRuntimeApi>,
+	config: &Configuration,
+	_: Option<TelemetryHandle>,
+	task_manager: &TaskManager,
+) -> Result<sc_consensus::DefaultImportQueue<Block>, sc_service::Error> {
+	Ok(sc_consensus_manual_seal::import_queue(
+		Box::new(client),
+		&task_manager.spawn_essential_handle(),
+		config.prometheus_registry(),
+	))
+}
+
+/// Start an Aura powered parachain node in development mode.
+///
+/// The node is not backed by any relay chain: it seals blocks on its own, as configured by
+/// `dev_seal`, and mocks the relay chain validation data and state proof. Downward and horizontal
+/// messages injected through the `dev_*` RPCs are delivered in the next sealed block.
+pub async fn start_dev_node<Net: NetworkBackend<Block, Hash>>(
+	parachain_config: Configuration,
+	para_id: ParaId,
+	dev_seal: DevSeal,
+) -> sc_service::error::Result<(TaskManager, Arc<ParachainClient<FakeRuntimeApi>>)> {
+	let params = new_partial::<FakeRuntimeApi, _>(&parachain_config, build_dev_import_queue)?;
+	let (_, mut telemetry, _) = params.other;
+
+	let client = params.client.clone();
+	let backend = params.backend.clone();
+	let mut task_manager = params.task_manager;
+	let transaction_pool = params.transaction_pool.clone();
+	let prometheus_registry = parachain_config.prometheus_registry().cloned();
+	let net_config = FullNetworkConfiguration::<_, _, Net>::new(&parachain_config.network);
+	let metrics = Net::register_notification_metrics(prometheus_registry.as_ref());
+
+	let (network, system_rpc_tx, tx_handler_controller, start_network, sync_service) =
+		sc_service::build_network(sc_service::BuildNetworkParams {
+			config: &parachain_config,
+			net_config,
+			client: client.clone(),
+			transaction_pool: transaction_pool.clone(),
+			spawn_handle: task_manager.spawn_handle(),
+			import_queue: params.import_queue,
+			block_announce_validator_builder: None,
+			warp_sync_params: None,
+			block_relay: None,
+			metrics,
+		})?;
+
+	let (manual_seal_sink, manual_seal_stream) = futures::channel::mpsc::channel(1024);
+	let message_queues = Arc::new(std::sync::Mutex::new(rpc::DevMessageQueues::default()));
+
+	let rpc_builder = {
+		let client = client.clone();
+		let transaction_pool = transaction_pool.clone();
+		let backend_for_rpc = backend.clone();
+		let message_queues = message_queues.clone();
+
+		Box::new(move |deny_unsafe, _| {
+			use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApiServer};
+
+			let mut module = build_omni_rpc_extensions(
+				deny_unsafe,
+				client.clone(),
+				backend_for_rpc.clone(),
+				transaction_pool.clone(),
+			)?;
+			module
+				.merge(ManualSeal::new(manual_seal_sink.clone()).into_rpc())
+				.map_err(|e| sc_service::Error::Application(Box::new(e)))?;
+			module
+				.merge(rpc::DevXcm::new(message_queues.clone(), deny_unsafe).into_rpc())
+				.map_err(|e| sc_service::Error::Application(Box::new(e)))?;
+
+			Ok(module)
+		})
+	};
+
+	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+		rpc_builder,
+		client: client.clone(),
+		transaction_pool: transaction_pool.clone(),
+		task_manager: &mut task_manager,
+		config: parachain_config,
+		keystore: params.keystore_container.keystore(),
+		backend: backend.clone(),
+		network,
+		sync_service,
+		system_rpc_tx,
+		tx_handler_controller,
+		telemetry: telemetry.as_mut(),
+	})?;
+
+	let seal_commands: Pin<Box<dyn Stream<Item = EngineCommand<Hash>> + Send>> = match dev_seal {
+		DevSeal::InstantSeal => Box::pin(transaction_pool.import_notification_stream().map(|_| {
+			EngineCommand::SealNewBlock {
+				create_empty: false,
+				finalize: true,
+				parent_hash: None,
+				sender: None,
+			}
+		})),
+		DevSeal::ManualSeal(block_time) => Box::pin(stream::unfold((), move |_| async move {
+			futures_timer::Delay::new(Duration::from_millis(block_time)).await;
+			let command = EngineCommand::SealNewBlock {
+				create_empty: true,
+				finalize: true,
+				parent_hash: None,
+				sender: None,
+			};
+			Some((command, ()))
+		})),
+	};
+
+	let slot_duration = cumulus_client_consensus_aura::slot_duration(&*client)?;
+	let create_inherent_data_providers = {
+		let client = client.clone();
+
+		move |parent: Hash, ()| {
+			let client = client.clone();
+			let message_queues = message_queues.clone();
+
+			async move {
+				let parent_header = client
+					.header(parent)?
+					.ok_or_else(|| format!("Could not find header of parent block {parent:?}"))?;
+
+				// The para slot has to increase with every block and has to match the mocked relay
+				// chain slot, so we derive the timestamp from the relay chain slot.
+				let previous_slot = parent_header
+					.digest()
+					.logs()
+					.iter()
+					.find_map(CompatibleDigestItem::<AuthoritySignature>::as_aura_pre_digest)
+					.unwrap_or_default();
+				let para_slot_duration = slot_duration.as_millis();
+				let para_slot = (*previous_slot + 1)
+					.max(sp_timestamp::Timestamp::current().as_millis() / para_slot_duration);
+				let relay_slot_duration = RELAY_CHAIN_SLOT_DURATION_MILLIS as u64;
+				let relay_slot = (para_slot * para_slot_duration).div_ceil(relay_slot_duration);
+				let timestamp = sp_timestamp::InherentDataProvider::new(
+					(relay_slot * relay_slot_duration).into(),
+				);
+
+				let (raw_downward_messages, raw_horizontal_messages) = message_queues
+					.lock()
+					.expect("The lock is never held across a panic; qed")
+					.messages_for_block(parent, *parent_header.parent_hash());
+
+				let mocked_parachain = MockValidationDataInherentDataProvider {
+					current_para_block: *parent_header.number(),
+					para_id,
+					current_para_block_head: Some(parent_header.encode().into()),
+					relay_offset: 1000,
+					relay_blocks_per_para_block: 1,
+					para_blocks_per_relay_epoch: 10,
+					relay_randomness_config: (),
+					xcm_config: MockXcmConfig::new(&*client, parent, Default::default()),
+					raw_downward_messages,
+					raw_horizontal_messages,
+					additional_key_values: Some(vec![(
+						CURRENT_SLOT.to_vec(),
+						Slot::from(relay_slot).encode(),
+					)]),
+				};
+
+				Ok::<_, Box<dyn std::error::Error + Send + Sync>>((timestamp, mocked_parachain))
+			}
+		}
+	};
+
+	let proposer = sc_basic_authorship::ProposerFactory::new(
+		task_manager.spawn_handle(),
+		client.clone(),
+		transaction_pool.clone(),
+		prometheus_registry.as_ref(),
+		telemetry.as_ref().map(|t| t.handle()),
+	);
+
+	let params = sc_consensus_manual_seal::ManualSealParams {
+		block_import: client.clone(),
+		env: proposer,
+		client: client.clone(),
+		pool: transaction_pool,
+		select_chain: sc_consensus::LongestChain::new(backend),
+		commands_stream: Box::pin(stream::select(seal_commands, manual_seal_stream)),
+		consensus_data_provider: Some(Box::new(AuraConsensusDataProvider::new(client.clone()))),
+		create_inherent_data_providers,
+	};
+
+	task_manager.spawn_essential_handle().spawn_blocking(
+		"manual-seal",
+		None,
+		sc_consensus_manual_seal::run_manual_seal(params),
+	);
+
+	start_network.start_network();
+
+	Ok((task_manager, client))
+}
+
 /// Checks that the hardware meets the requirements and print a warning otherwise.
 fn warn_if_slow_hardware(hwbench: &sc_sysinfo::HwBench) {
 	// Polkadot para-chains should generally use these requirements to ensure that the relay-chain
@@ -425,4 +425,10 @@ sp_api::impl_runtime_apis! {
 			unimplemented!()
 		}
 	}
+
+	impl xcm_fee_payment_runtime_api::conversions::LocationToAccountApi<Block, AccountId> for Runtime {
+		fn convert_location(_: VersionedLocation) -> Result<AccountId, xcm_fee_payment_runtime_api::conversions::Error> {
+			unimplemented!()
+		}
+	}
 }
@@ -193,6 +193,7 @@ pub fn node_config(
 		role,
 		tokio_handle,
 		transaction_pool: Default::default(),
+		transaction_pool_type: Default::default(),
 		network: network_config,
 		keystore: KeystoreConfig::InMemory,
 		database: DatabaseSource::RocksDb { path: root.join("db"), cache_size: 128 },
@@ -218,6 +219,7 @@ pub fn node_config(
 		rpc_rate_limit: None,
 		rpc_rate_limit_whitelisted_ips: Default::default(),
 		rpc_rate_limit_trust_proxy_headers: Default::default(),
+		rpc_remote_state: false,
 		prometheus_config: None,
 		telemetry_endpoints: None,
 		default_heap_pages: None,
@@ -171,7 +171,7 @@ where
 		let _ = <pallet_xcm::Pallet<Runtime>>::send(
 			RawOrigin::Root.into(),
 			Box::new(VersionedLocation::V4(destination)),
-			Box::new(VersionedXcm::V4(program)),
+			Box::new(VersionedXcm::V5(program)),
 		)?;
 		Ok(())
 	}
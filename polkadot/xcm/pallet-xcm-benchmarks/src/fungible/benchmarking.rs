@@ -299,6 +299,60 @@ benchmarks_instance_pallet! {
 		}
 	}
 
+	initiate_transfer {
+		let x in 1 .. 1000;
+		// The `remote_xcm` influences `InitiateTransfer` total weight based on its encoded size,
+		// since it is sent along with the transferred assets, so use `ClearOrigin` as the
+		// smallest encoded instruction, like `export_message` does.
+		let remote_xcm = Xcm(vec![ClearOrigin; x as usize]);
+		let (sender_account, sender_location) = account_and_location::<T>(1);
+		let dest_location = T::valid_destination()?;
+		let dest_account = T::AccountIdConverter::convert_location(&dest_location).unwrap();
+
+		let (expected_fees_mode, expected_assets_in_holding) = T::DeliveryHelper::ensure_successful_delivery(
+			&sender_location,
+			&dest_location,
+			FeeReason::InitiateTransfer,
+		);
+		let sender_account_balance_before = T::TransactAsset::balance(&sender_account);
+
+		// Only the remote fees are transferred, the transfer of every other asset is weighed
+		// by the instruction transferring it the same way.
+		let asset = T::get_asset();
+		let mut holding = T::worst_case_holding(1);
+		holding.push(asset.clone());
+		if let Some(expected_assets_in_holding) = expected_assets_in_holding {
+			for a in expected_assets_in_holding.into_inner() {
+				holding.push(a);
+			}
+		}
+
+		// our dest must have no balance initially.
+		assert!(T::TransactAsset::balance(&dest_account).is_zero());
+
+		let mut executor = new_executor::<T>(sender_location);
+		executor.set_holding(holding.into());
+		if let Some(expected_fees_mode) = expected_fees_mode {
+			executor.set_fees_mode(expected_fees_mode);
+		}
+		let instruction = Instruction::<XcmCallOf<T>>::InitiateTransfer {
+			destination: dest_location,
+			remote_fees: Some(AssetTransferFilter::ReserveDeposit(asset.into())),
+			// Re-anchoring the origin is more expensive than clearing it.
+			preserve_origin: true,
+			assets: Default::default(),
+			remote_xcm,
+		};
+		let xcm = Xcm(vec![instruction]);
+	}: {
+		executor.bench_process(xcm)?;
+	} verify {
+		// Check we charged the delivery fees
+		assert!(T::TransactAsset::balance(&sender_account) <= sender_account_balance_before);
+		// dest should have received the remote fees.
+		assert!(!T::TransactAsset::balance(&dest_account).is_zero());
+	}
+
 	impl_benchmark_test_suite!(
 		Pallet,
 		crate::fungible::mock::new_test_ext(),
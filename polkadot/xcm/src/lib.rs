@@ -36,13 +36,14 @@ use scale_info::TypeInfo;
 pub mod v2;
 pub mod v3;
 pub mod v4;
+pub mod v5;
 
 pub mod lts {
 	pub use super::v4::*;
 }
 
 pub mod latest {
-	pub use super::v4::*;
+	pub use super::v5::*;
 }
 
 mod double_encoded;
@@ -130,7 +131,8 @@ macro_rules! versioned_type {
 			fn into_version(self, n: Version) -> Result<Self, ()> {
 				Ok(match n {
 					3 => Self::V3(self.try_into()?),
-					4 => Self::V4(self.try_into()?),
+					// These types are unchanged in XCM v5.
+					4 | 5 => Self::V4(self.try_into()?),
 					_ => return Err(()),
 				})
 			}
@@ -242,7 +244,8 @@ macro_rules! versioned_type {
 				Ok(match n {
 					1 | 2 => Self::V2(self.try_into()?),
 					3 => Self::V3(self.try_into()?),
-					4 => Self::V4(self.try_into()?),
+					// These types are unchanged in XCM v5.
+					4 | 5 => Self::V4(self.try_into()?),
 					_ => return Err(()),
 				})
 			}
@@ -436,6 +439,8 @@ pub enum VersionedXcm<RuntimeCall> {
 	V3(v3::Xcm<RuntimeCall>),
 	#[codec(index = 4)]
 	V4(v4::Xcm<RuntimeCall>),
+	#[codec(index = 5)]
+	V5(v5::Xcm<RuntimeCall>),
 }
 
 impl<C> IntoVersion for VersionedXcm<C> {
@@ -444,6 +449,7 @@ impl<C> IntoVersion for VersionedXcm<C> {
 			2 => Self::V2(self.try_into()?),
 			3 => Self::V3(self.try_into()?),
 			4 => Self::V4(self.try_into()?),
+			5 => Self::V5(self.try_into()?),
 			_ => return Err(()),
 		})
 	}
@@ -455,6 +461,7 @@ impl<C> IdentifyVersion for VersionedXcm<C> {
 			Self::V2(_) => v2::VERSION,
 			Self::V3(_) => v3::VERSION,
 			Self::V4(_) => v4::VERSION,
+			Self::V5(_) => v5::VERSION,
 		}
 	}
 }
@@ -494,6 +501,12 @@ impl<RuntimeCall> From<v4::Xcm<RuntimeCall>> for VersionedXcm<RuntimeCall> {
 	}
 }
 
+impl<RuntimeCall> From<v5::Xcm<RuntimeCall>> for VersionedXcm<RuntimeCall> {
+	fn from(x: v5::Xcm<RuntimeCall>) -> Self {
+		VersionedXcm::V5(x)
+	}
+}
+
 impl<RuntimeCall> TryFrom<VersionedXcm<RuntimeCall>> for v2::Xcm<RuntimeCall> {
 	type Error = ();
 	fn try_from(x: VersionedXcm<RuntimeCall>) -> Result<Self, ()> {
@@ -505,6 +518,11 @@ impl<RuntimeCall> TryFrom<VersionedXcm<RuntimeCall>> for v2::Xcm<RuntimeCall> {
 				let v3: v3::Xcm<RuntimeCall> = x.try_into()?;
 				v3.try_into()
 			},
+			V5(x) => {
+				let v4: v4::Xcm<RuntimeCall> = x.try_into()?;
+				let v3: v3::Xcm<RuntimeCall> = v4.try_into()?;
+				v3.try_into()
+			},
 		}
 	}
 }
@@ -517,6 +535,10 @@ impl<Call> TryFrom<VersionedXcm<Call>> for v3::Xcm<Call> {
 			V2(x) => x.try_into(),
 			V3(x) => Ok(x),
 			V4(x) => x.try_into(),
+			V5(x) => {
+				let v4: v4::Xcm<Call> = x.try_into()?;
+				v4.try_into()
+			},
 		}
 	}
 }
@@ -532,6 +554,27 @@ impl<Call> TryFrom<VersionedXcm<Call>> for v4::Xcm<Call> {
 			},
 			V3(x) => x.try_into(),
 			V4(x) => Ok(x),
+			V5(x) => x.try_into(),
+		}
+	}
+}
+
+impl<Call> TryFrom<VersionedXcm<Call>> for v5::Xcm<Call> {
+	type Error = ();
+	fn try_from(x: VersionedXcm<Call>) -> Result<Self, ()> {
+		use VersionedXcm::*;
+		match x {
+			V2(x) => {
+				let v3: v3::Xcm<Call> = x.try_into()?;
+				let v4: v4::Xcm<Call> = v3.try_into()?;
+				v4.try_into()
+			},
+			V3(x) => {
+				let v4: v4::Xcm<Call> = x.try_into()?;
+				v4.try_into()
+			},
+			V4(x) => x.try_into(),
+			V5(x) => Ok(x),
 		}
 	}
 }
@@ -619,9 +662,26 @@ impl GetVersion for AlwaysV4 {
 	}
 }
 
+/// `WrapVersion` implementation which attempts to always convert the XCM to version 5 before
+/// wrapping it.
+pub struct AlwaysV5;
+impl WrapVersion for AlwaysV5 {
+	fn wrap_version<Call>(
+		_: &latest::Location,
+		xcm: impl Into<VersionedXcm<Call>>,
+	) -> Result<VersionedXcm<Call>, ()> {
+		Ok(VersionedXcm::<Call>::V5(xcm.into().try_into()?))
+	}
+}
+impl GetVersion for AlwaysV5 {
+	fn get_version_for(_dest: &latest::Location) -> Option<Version> {
+		Some(v5::VERSION)
+	}
+}
+
 /// `WrapVersion` implementation which attempts to always convert the XCM to the latest version
 /// before wrapping it.
-pub type AlwaysLatest = AlwaysV4;
+pub type AlwaysLatest = AlwaysV5;
 
 /// `WrapVersion` implementation which attempts to always convert the XCM to the most recent Long-
 /// Term-Support version before wrapping it.
@@ -629,10 +689,10 @@ pub type AlwaysLts = AlwaysV4;
 
 pub mod prelude {
 	pub use super::{
-		latest::prelude::*, AlwaysLatest, AlwaysLts, AlwaysV2, AlwaysV3, AlwaysV4, GetVersion,
-		IdentifyVersion, IntoVersion, Unsupported, Version as XcmVersion, VersionedAsset,
-		VersionedAssetId, VersionedAssets, VersionedInteriorLocation, VersionedLocation,
-		VersionedResponse, VersionedXcm, WrapVersion,
+		latest::prelude::*, AlwaysLatest, AlwaysLts, AlwaysV2, AlwaysV3, AlwaysV4, AlwaysV5,
+		GetVersion, IdentifyVersion, IntoVersion, Unsupported, Version as XcmVersion,
+		VersionedAsset, VersionedAssetId, VersionedAssets, VersionedInteriorLocation,
+		VersionedLocation, VersionedResponse, VersionedXcm, WrapVersion,
 	};
 }
 
@@ -655,9 +715,15 @@ pub mod opaque {
 		// Then override with the opaque types in v4
 		pub use crate::v4::opaque::{Instruction, Xcm};
 	}
+	pub mod v5 {
+		// Everything from v5
+		pub use crate::v5::*;
+		// Then override with the opaque types in v5
+		pub use crate::v5::opaque::{Instruction, Xcm};
+	}
 
 	pub mod latest {
-		pub use super::v4::*;
+		pub use super::v5::*;
 	}
 
 	pub mod lts {
@@ -709,7 +775,7 @@ fn size_limits() {
     }
 
 	check_sizes! {
-		(crate::latest::Instruction<()>, 112),
+		(crate::latest::Instruction<()>, 128),
 		(crate::latest::Asset, 80),
 		(crate::latest::Location, 24),
 		(crate::latest::AssetId, 40),
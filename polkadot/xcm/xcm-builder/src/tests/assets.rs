@@ -197,6 +197,126 @@ fn reserve_transfer_should_work() {
 	assert_eq!(sent_xcm(), vec![(Parachain(2).into(), expected_msg, expected_hash)]);
 }
 
+#[test]
+fn initiate_transfer_with_remote_fees_should_work() {
+	AllowUnpaidFrom::set(vec![[Parachain(1)].into()]);
+	// Child parachain #1 owns 1000 tokens held by us in reserve.
+	add_asset(Parachain(1), (Here, 1000));
+	// The remote account owned by gav.
+	let three: Location = [AccountIndex64 { index: 3, network: None }].into();
+
+	// They want to move all of them to parachain #2, using 100 of them to pay for execution there.
+	let message = Xcm(vec![
+		WithdrawAsset((Here, 1000u128).into()),
+		InitiateTransfer {
+			destination: Parachain(2).into(),
+			remote_fees: Some(AssetTransferFilter::ReserveDeposit(Definite(
+				(Here, 100u128).into(),
+			))),
+			preserve_origin: false,
+			assets: vec![AssetTransferFilter::ReserveDeposit(Wild(AllCounted(1)))]
+				.try_into()
+				.unwrap(),
+			remote_xcm: Xcm::<()>(vec![DepositAsset {
+				assets: AllCounted(1).into(),
+				beneficiary: three.clone(),
+			}]),
+		},
+	]);
+	let mut hash = fake_message_hash(&message);
+	let r = XcmExecutor::<TestConfig>::prepare_and_execute(
+		Parachain(1),
+		message,
+		&mut hash,
+		Weight::from_parts(50, 50),
+		Weight::zero(),
+	);
+	assert_eq!(r, Outcome::Complete { used: Weight::from_parts(20, 20) });
+
+	let expected_msg = Xcm::<()>(vec![
+		ReserveAssetDeposited((Parent, 100u128).into()),
+		BuyExecution { fees: (Parent, 100u128).into(), weight_limit: Unlimited },
+		ReserveAssetDeposited((Parent, 900u128).into()),
+		ClearOrigin,
+		DepositAsset { assets: AllCounted(1).into(), beneficiary: three },
+	]);
+	let expected_hash = fake_message_hash(&expected_msg);
+	assert_eq!(asset_list(Parachain(1)), vec![]);
+	assert_eq!(asset_list(Parachain(2)), vec![(Here, 1000).into()]);
+	assert_eq!(sent_xcm(), vec![(Parachain(2).into(), expected_msg, expected_hash)]);
+}
+
+#[test]
+fn initiate_transfer_without_remote_fees_should_preserve_origin() {
+	AllowUnpaidFrom::set(vec![[Parachain(1)].into()]);
+	// Child parachain #1 owns 1000 tokens whose reserve is parachain #2.
+	add_asset(Parachain(1), (Parachain(2), 1000));
+
+	// They want to withdraw them from the reserve and keep acting as themselves there.
+	let message = Xcm(vec![
+		WithdrawAsset((Parachain(2), 1000u128).into()),
+		InitiateTransfer {
+			destination: Parachain(2).into(),
+			remote_fees: None,
+			preserve_origin: true,
+			assets: vec![AssetTransferFilter::ReserveWithdraw(Wild(AllCounted(1)))]
+				.try_into()
+				.unwrap(),
+			remote_xcm: Xcm::<()>(vec![]),
+		},
+	]);
+	let mut hash = fake_message_hash(&message);
+	let r = XcmExecutor::<TestConfig>::prepare_and_execute(
+		Parachain(1),
+		message,
+		&mut hash,
+		Weight::from_parts(50, 50),
+		Weight::zero(),
+	);
+	assert_eq!(r, Outcome::Complete { used: Weight::from_parts(20, 20) });
+
+	let expected_msg = Xcm::<()>(vec![
+		UnpaidExecution { weight_limit: Unlimited, check_origin: None },
+		WithdrawAsset((Here, 1000u128).into()),
+		AliasOrigin((Parent, Parachain(1)).into()),
+	]);
+	let expected_hash = fake_message_hash(&expected_msg);
+	assert_eq!(asset_list(Parachain(1)), vec![]);
+	assert_eq!(sent_xcm(), vec![(Parachain(2).into(), expected_msg, expected_hash)]);
+}
+
+#[test]
+fn initiate_transfer_should_fail_without_remote_fees_in_holding() {
+	AllowUnpaidFrom::set(vec![[Parachain(1)].into()]);
+	add_asset(Parachain(1), (Here, 1000));
+
+	let message = Xcm(vec![
+		WithdrawAsset((Here, 1000u128).into()),
+		InitiateTransfer {
+			destination: Parachain(2).into(),
+			remote_fees: Some(AssetTransferFilter::ReserveDeposit(Definite(
+				(Parent, 100u128).into(),
+			))),
+			preserve_origin: false,
+			assets: Default::default(),
+			remote_xcm: Xcm::<()>(vec![]),
+		},
+	]);
+	let mut hash = fake_message_hash(&message);
+	let r = XcmExecutor::<TestConfig>::prepare_and_execute(
+		Parachain(1),
+		message,
+		&mut hash,
+		Weight::from_parts(50, 50),
+		Weight::zero(),
+	);
+	assert_eq!(
+		r,
+		Outcome::Incomplete { used: Weight::from_parts(20, 20), error: XcmError::NotHoldingFees }
+	);
+	assert_eq!(sent_xcm(), vec![]);
+}
+
 #[test]
 fn burn_should_work() {
 	// we'll let them have message execution for free.
@@ -513,6 +513,43 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		Ok((reanchored, reanchor_context))
 	}
 
+	/// Move `assets`, which were already taken out of holding, towards `destination` in the way
+	/// given by `filter` and return the instruction which loads them into holding there.
+	fn transfer_from_holding(
+		&self,
+		filter: &AssetTransferFilter,
+		assets: AssetsInHolding,
+		destination: &Location,
+	) -> Result<Instruction<()>, XcmError> {
+		Ok(match filter {
+			AssetTransferFilter::Teleport(_) => {
+				for asset in assets.assets_iter() {
+					Config::AssetTransactor::can_check_out(destination, &asset, &self.context)?;
+				}
+				for asset in assets.assets_iter() {
+					Config::AssetTransactor::check_out(destination, &asset, &self.context);
+				}
+				// Note that we pass `None` as `maybe_failed_bin` and drop any assets which cannot
+				// be reanchored because we have already checked all assets out.
+				ReceiveTeleportedAsset(Self::reanchored(assets, destination, None))
+			},
+			AssetTransferFilter::ReserveDeposit(_) => {
+				for asset in assets.assets_iter() {
+					Config::AssetTransactor::deposit_asset(
+						&asset,
+						destination,
+						Some(&self.context),
+					)?;
+				}
+				ReserveAssetDeposited(Self::reanchored(assets, destination, None))
+			},
+			// The derivatives are burnt by simply dropping them from holding, the reserve then
+			// withdraws the originals from our sovereign account.
+			AssetTransferFilter::ReserveWithdraw(_) =>
+				WithdrawAsset(Self::reanchored(assets, destination, None)),
+		})
+	}
+
 	/// NOTE: Any assets which were unable to be reanchored are introduced into `failed_bin`.
 	fn reanchored(
 		mut assets: AssetsInHolding,
@@ -925,6 +962,58 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				}
 				result
 			},
+			InitiateTransfer { destination, remote_fees, preserve_origin, assets, remote_xcm } => {
+				let old_holding = self.holding.clone();
+				let result = Config::TransactionalProcessor::process(|| {
+					let mut message = Vec::with_capacity(assets.len() + remote_xcm.len() + 3);
+					// The fees are transferred first, so that the destination can buy execution
+					// for the rest of the message with them.
+					if let Some(remote_fees) = remote_fees {
+						let fees = self
+							.holding
+							.try_take(remote_fees.inner().clone())
+							.map_err(|_| XcmError::NotHoldingFees)?;
+						let load_fees =
+							self.transfer_from_holding(&remote_fees, fees, &destination)?;
+						let fees = match &load_fees {
+							ReceiveTeleportedAsset(fees) |
+							ReserveAssetDeposited(fees) |
+							WithdrawAsset(fees) => match fees.inner().as_slice() {
+								[fees] => fees.clone(),
+								_ => return Err(XcmError::NotHoldingFees),
+							},
+							_ => return Err(XcmError::NotHoldingFees),
+						};
+						message.push(load_fees);
+						message.push(BuyExecution { fees, weight_limit: Unlimited });
+					} else {
+						message
+							.push(UnpaidExecution { weight_limit: Unlimited, check_origin: None });
+					}
+					for filter in assets.into_iter() {
+						let transferred = self.holding.saturating_take(filter.inner().clone());
+						message.push(self.transfer_from_holding(
+							&filter,
+							transferred,
+							&destination,
+						)?);
+					}
+					if preserve_origin {
+						let origin = self.origin_ref().cloned().ok_or(XcmError::BadOrigin)?;
+						let (origin, _) = Self::try_reanchor(origin, &destination)?;
+						message.push(AliasOrigin(origin));
+					} else {
+						message.push(ClearOrigin);
+					}
+					message.extend(remote_xcm.0.into_iter());
+					self.send(destination, Xcm(message), FeeReason::InitiateTransfer)?;
+					Ok(())
+				});
+				if Config::TransactionalProcessor::IS_TRANSACTIONAL && result.is_err() {
+					self.holding = old_holding;
+				}
+				result
+			},
 			ReportHolding { response_info, assets } => {
 				// Note that we pass `None` as `maybe_failed_bin` since no assets were ever removed
 				// from Holding.
@@ -0,0 +1,64 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for converting XCM locations into local accounts.
+
+use codec::{Decode, Encode};
+use frame_support::pallet_prelude::TypeInfo;
+use sp_std::marker::PhantomData;
+use xcm::{latest::Location, VersionedLocation};
+use xcm_executor::traits::ConvertLocation;
+
+sp_api::decl_runtime_apis! {
+	/// API for converting a `Location` into the local account it controls.
+	///
+	/// The conversion is done with the converters the runtime uses for its `LocationToAccountId`
+	/// (e.g. `HashedDescription`, `ParentIsPreset` or `SiblingParachainConvertsVia`), so wallets
+	/// and explorers see the same account as the XCM executor.
+	pub trait LocationToAccountApi<AccountId> where AccountId: Decode {
+		/// Converts `location` into the local `AccountId` it maps to.
+		///
+		/// # Arguments
+		///
+		/// * `location`: `VersionedLocation` to convert.
+		fn convert_location(location: VersionedLocation) -> Result<AccountId, Error>;
+	}
+}
+
+/// Errors returned by [`LocationToAccountApi::convert_location`].
+#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub enum Error {
+	/// The location could not be converted by any of the configured converters.
+	#[codec(index = 0)]
+	Unsupported,
+
+	/// Converting a versioned data structure from one version to another failed.
+	#[codec(index = 1)]
+	VersionedConversionFailed,
+}
+
+/// A helper implementing `LocationToAccountApi::convert_location` on top of the `Conversion`
+/// configured by the runtime.
+pub struct LocationToAccountHelper<AccountId, Conversion>(PhantomData<(AccountId, Conversion)>);
+impl<AccountId, Conversion: ConvertLocation<AccountId>>
+	LocationToAccountHelper<AccountId, Conversion>
+{
+	pub fn convert_location(location: VersionedLocation) -> Result<AccountId, Error> {
+		let location =
+			Location::try_from(location).map_err(|_| Error::VersionedConversionFailed)?;
+		Conversion::convert_location(&location).ok_or(Error::Unsupported)
+	}
+}
@@ -21,9 +21,13 @@
 //! feature.
 //! Users of these APIs should call the transfers API and pass the result to the
 //! fees API.
+//! It also offers an API for converting locations into the local accounts they map to.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+/// Location conversion API.
+/// Given a `Location`, it returns the local account it maps to.
+pub mod conversions;
 /// Dry-run API.
 /// Given an extrinsic or an XCM program, it returns the outcome of its execution.
 pub mod dry_run;
@@ -14,13 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Tests for using both the XCM fee payment API and the dry-run API.
+//! Tests for using the XCM fee payment API, the dry-run API and the location conversion API.
 
 use frame_system::RawOrigin;
 use sp_api::ProvideRuntimeApi;
 use sp_runtime::testing::H256;
 use xcm::prelude::*;
-use xcm_fee_payment_runtime_api::{dry_run::DryRunApi, fees::XcmPaymentApi};
+use xcm_fee_payment_runtime_api::{
+	conversions::{Error as LocationToAccountApiError, LocationToAccountApi},
+	dry_run::DryRunApi,
+	fees::XcmPaymentApi,
+};
 
 mod mock;
 use mock::{
@@ -353,3 +357,30 @@ fn dry_run_xcm() {
 		);
 	});
 }
+
+#[test]
+fn convert_location_to_account_works() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let client = TestClient;
+		let runtime_api = client.runtime_api();
+
+		// A local account index.
+		let location =
+			VersionedLocation::from(Location::new(0, [AccountIndex64 { index: 7, network: None }]));
+		let account = runtime_api.convert_location(H256::zero(), location).unwrap();
+		assert_eq!(account, Ok(7));
+
+		// A sibling parachain, given in an older XCM version.
+		let location = VersionedLocation::from(Location::new(1, [Parachain(2000)]))
+			.into_version(3)
+			.unwrap();
+		let account = runtime_api.convert_location(H256::zero(), location).unwrap();
+		assert_eq!(account, Ok(2000));
+
+		// A location which none of the converters handle.
+		let location =
+			VersionedLocation::from(Location::new(1, [Parachain(2000), PalletInstance(50)]));
+		let account = runtime_api.convert_location(H256::zero(), location).unwrap();
+		assert_eq!(account, Err(LocationToAccountApiError::Unsupported));
+	});
+}
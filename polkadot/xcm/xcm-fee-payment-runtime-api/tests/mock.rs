@@ -15,7 +15,8 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Mock runtime for tests.
-//! Implements both runtime APIs for fee estimation and getting the messages for transfers.
+//! Implements both runtime APIs for fee estimation and getting the messages for transfers, as
+//! well as the API for converting locations into accounts.
 
 use codec::Encode;
 use frame_support::{
@@ -45,6 +46,9 @@ use xcm_executor::{
 };
 
 use xcm_fee_payment_runtime_api::{
+	conversions::{
+		Error as LocationToAccountApiError, LocationToAccountApi, LocationToAccountHelper,
+	},
 	dry_run::{CallDryRunEffects, DryRunApi, Error as XcmDryRunApiError, XcmDryRunEffects},
 	fees::{Error as XcmPaymentApiError, XcmPaymentApi},
 };
@@ -450,6 +454,12 @@ sp_api::mock_impl_runtime_apis! {
 		}
 	}
 
+	impl LocationToAccountApi<Block, AccountId> for RuntimeApi {
+		fn convert_location(location: VersionedLocation) -> Result<AccountId, LocationToAccountApiError> {
+			LocationToAccountHelper::<AccountId, LocationToAccountId>::convert_location(location)
+		}
+	}
+
 	impl DryRunApi<Block, RuntimeCall, RuntimeEvent, OriginCaller> for RuntimeApi {
 		fn dry_run_call(origin: OriginCaller, call: RuntimeCall) -> Result<CallDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
 			use xcm_executor::RecordXcm;
@@ -35,7 +35,7 @@ use sc_transaction_pool_api::{
 };
 use sp_consensus::{Environment, Proposer};
 use sp_inherents::InherentDataProvider;
-use sp_runtime::{traits::NumberFor, OpaqueExtrinsic};
+use sp_runtime::OpaqueExtrinsic;
 
 use crate::{
 	common::SizeType,
@@ -261,7 +261,7 @@ impl sc_transaction_pool_api::TransactionPool for Transactions {
 
 	fn ready_at(
 		&self,
-		_at: NumberFor<Self::Block>,
+		_at: Self::Hash,
 	) -> Pin<
 		Box<
 			dyn Future<
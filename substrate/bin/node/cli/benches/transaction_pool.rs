@@ -63,6 +63,7 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 			reject_future_transactions: false,
 			ban_time: Duration::from_secs(30 * 60),
 		},
+		transaction_pool_type: Default::default(),
 		network: network_config,
 		keystore: KeystoreConfig::InMemory,
 		database: DatabaseSource::RocksDb { path: root.join("db"), cache_size: 128 },
@@ -85,6 +86,7 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 		rpc_rate_limit: None,
 		rpc_rate_limit_whitelisted_ips: Default::default(),
 		rpc_rate_limit_trust_proxy_headers: Default::default(),
+		rpc_remote_state: false,
 		prometheus_config: None,
 		telemetry_endpoints: None,
 		default_heap_pages: None,
@@ -107,6 +109,7 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 		node_cli::service::new_full_base::<sc_network::NetworkWorker<_, _>>(
 			config,
 			None,
+			Default::default(),
 			false,
 			|_, _| (),
 		)
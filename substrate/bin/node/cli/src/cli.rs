@@ -33,6 +33,10 @@ pub struct Cli {
 	#[clap(flatten)]
 	pub mixnet_params: sc_cli::MixnetParams,
 
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub statement_store_params: StatementStoreParams,
+
 	/// Disable automatic hardware benchmarks.
 	///
 	/// By default these benchmarks are automatically ran at startup and measure
@@ -48,6 +52,53 @@ pub struct Cli {
 	pub storage_monitor: sc_storage_monitor::StorageMonitorParams,
 }
 
+/// Parameters used to configure the statement store.
+#[derive(Debug, Clone, clap::Args)]
+pub struct StatementStoreParams {
+	/// Strategy used to evict statements once the statement store or a topic is full.
+	#[arg(long, value_enum, value_name = "STRATEGY", default_value_t = EvictionStrategy::Reject)]
+	pub statement_store_eviction: EvictionStrategy,
+
+	/// Maximum number of statements with the same topic in the statement store.
+	///
+	/// No limit by default.
+	#[arg(long, value_name = "COUNT")]
+	pub statement_store_max_per_topic: Option<usize>,
+}
+
+impl StatementStoreParams {
+	/// Returns the statement store options.
+	pub fn options(&self) -> sc_statement_store::Options {
+		sc_statement_store::Options {
+			eviction_strategy: self.statement_store_eviction.into(),
+			max_statements_per_topic: self.statement_store_max_per_topic,
+			..Default::default()
+		}
+	}
+}
+
+/// Strategy used to evict statements from the statement store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum EvictionStrategy {
+	/// Don't evict anything, new statements are ignored until there is room for them.
+	Reject,
+	/// Evict statements with a lower priority than the new statement.
+	LowestPriority,
+	/// Evict the statements that were added to the store first.
+	Oldest,
+}
+
+impl From<EvictionStrategy> for sc_statement_store::EvictionStrategy {
+	fn from(strategy: EvictionStrategy) -> Self {
+		match strategy {
+			EvictionStrategy::Reject => Self::Reject,
+			EvictionStrategy::LowestPriority => Self::LowestPriority,
+			EvictionStrategy::Oldest => Self::Oldest,
+		}
+	}
+}
+
 /// Possible subcommands of the main binary.
 #[derive(Debug, clap::Subcommand)]
 pub enum Subcommand {
@@ -88,9 +139,15 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export a verifiable snapshot of the state of a given block.
+	ExportSnapshot(sc_cli::ExportSnapshotCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
+	/// Import a state snapshot.
+	ImportSnapshot(sc_cli::ImportSnapshotCmd),
+
 	/// Remove the whole chain.
 	PurgeChain(sc_cli::PurgeChainCmd),
 
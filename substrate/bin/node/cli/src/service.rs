@@ -79,7 +79,7 @@ type FullBeefyBlockImport<InnerBlockImport> = beefy::import::BeefyBlockImport<
 >;
 
 /// The transaction pool type definition.
-pub type TransactionPool = sc_transaction_pool::FullPool<Block, FullClient>;
+pub type TransactionPool = sc_transaction_pool::TransactionPoolHandle<Block, FullClient>;
 
 /// The minimum period of blocks on which justifications will be
 /// imported and generated.
@@ -168,13 +168,14 @@ pub fn create_extrinsic(
 pub fn new_partial(
 	config: &Configuration,
 	mixnet_config: Option<&sc_mixnet::Config>,
+	statement_store_options: sc_statement_store::Options,
 ) -> Result<
 	sc_service::PartialComponents<
 		FullClient,
 		FullBackend,
 		FullSelectChain,
 		sc_consensus::DefaultImportQueue<Block>,
-		sc_transaction_pool::FullPool<Block, FullClient>,
+		TransactionPool,
 		(
 			impl Fn(
 				node_rpc::DenyUnsafe,
@@ -226,7 +227,8 @@ pub fn new_partial(
 
 	let select_chain = sc_consensus::LongestChain::new(backend.clone());
 
-	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+	let transaction_pool = TransactionPool::new_full(
+		config.transaction_pool_type,
 		config.transaction_pool.clone(),
 		config.role.is_authority().into(),
 		config.prometheus_registry(),
@@ -286,7 +288,7 @@ pub fn new_partial(
 
 	let statement_store = sc_statement_store::Store::new_shared(
 		&config.data_path,
-		Default::default(),
+		statement_store_options,
 		client.clone(),
 		keystore_container.local_keystore(),
 		config.prometheus_registry(),
@@ -395,6 +397,7 @@ pub struct NewFullBase {
 pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 	config: Configuration,
 	mixnet_config: Option<sc_mixnet::Config>,
+	statement_store_options: sc_statement_store::Options,
 	disable_hardware_benchmarks: bool,
 	with_startup_data: impl FnOnce(
 		&sc_consensus_babe::BabeBlockImport<
@@ -432,7 +435,7 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		transaction_pool,
 		other:
 			(rpc_builder, import_setup, rpc_setup, mut telemetry, statement_store, mixnet_api_backend),
-	} = new_partial(&config, mixnet_config.as_ref())?;
+	} = new_partial(&config, mixnet_config.as_ref(), statement_store_options)?;
 
 	let metrics = N::register_notification_metrics(
 		config.prometheus_config.as_ref().map(|cfg| &cfg.registry),
@@ -532,6 +535,7 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 			Some(keystore_container.keystore()),
 			mixnet_notification_service
 				.expect("`NotificationService` exists since mixnet was enabled; qed"),
+			Box::new(task_manager.spawn_handle()),
 		);
 		task_manager.spawn_handle().spawn("mixnet", None, mixnet);
 	}
@@ -808,6 +812,7 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 /// Builds a new service for a full client.
 pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceError> {
 	let mixnet_config = cli.mixnet_params.config(config.role.is_authority());
+	let statement_store_options = cli.statement_store_params.options();
 	let database_path = config.database.path().map(Path::to_path_buf);
 
 	let task_manager = match config.network.network_backend {
@@ -815,6 +820,7 @@ pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceE
 			let task_manager = new_full_base::<sc_network::NetworkWorker<_, _>>(
 				config,
 				mixnet_config,
+				statement_store_options,
 				cli.no_hardware_benchmarks,
 				|_, _| (),
 			)
@@ -825,6 +831,7 @@ pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceE
 			let task_manager = new_full_base::<sc_network::Litep2pNetworkBackend>(
 				config,
 				mixnet_config,
+				statement_store_options,
 				cli.no_hardware_benchmarks,
 				|_, _| (),
 			)
@@ -912,6 +919,7 @@ mod tests {
 					new_full_base::<sc_network::NetworkWorker<_, _>>(
 						config,
 						None,
+						Default::default(),
 						false,
 						|block_import: &sc_consensus_babe::BabeBlockImport<Block, _, _>,
 						 babe_link: &sc_consensus_babe::BabeLink<Block>| {
@@ -1102,6 +1110,7 @@ mod tests {
 					new_full_base::<sc_network::NetworkWorker<_, _>>(
 						config,
 						None,
+						Default::default(),
 						false,
 						|_, _| (),
 					)?;
@@ -0,0 +1,74 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+#![cfg(unix)]
+
+use assert_cmd::cargo::cargo_bin;
+use std::{fs, process::Command};
+use tempfile::tempdir;
+
+use substrate_cli_test_utils as common;
+
+#[tokio::test]
+async fn export_import_snapshot_works() {
+	let base_path = tempdir().expect("could not create a temp dir");
+	let snapshot_file = base_path.path().join("snapshot");
+
+	common::run_node_for_a_while(base_path.path(), &["--dev", "--no-hardware-benchmarks"]).await;
+
+	let output = Command::new(cargo_bin("substrate-node"))
+		.args(&["export-snapshot", "--dev", "-d"])
+		.arg(base_path.path())
+		.arg(&snapshot_file)
+		.output()
+		.unwrap();
+	assert!(output.status.success());
+	assert!(fs::metadata(&snapshot_file).unwrap().len() > 0, "snapshot should not be empty");
+
+	// The hash of the exported block is logged as `#<number> (<hash>)`.
+	let logged_output = String::from_utf8_lossy(&output.stderr);
+	let block_hash = logged_output
+		.split("Exporting state snapshot of block #")
+		.nth(1)
+		.and_then(|rest| rest.split_once('(')?.1.split_once(')'))
+		.map(|(hash, _)| hash.to_string())
+		.expect("the exported block is logged");
+
+	// A snapshot of another block is rejected.
+	let import_path = tempdir().expect("could not create a temp dir");
+	let output = Command::new(cargo_bin("substrate-node"))
+		.args(&["import-snapshot", "--dev", "-d"])
+		.arg(import_path.path())
+		.args(&["--block-hash", &format!("0x{}", "00".repeat(32))])
+		.arg(&snapshot_file)
+		.output()
+		.unwrap();
+	assert!(!output.status.success());
+
+	// The snapshot is imported into an empty database.
+	let output = Command::new(cargo_bin("substrate-node"))
+		.args(&["import-snapshot", "--dev", "-d"])
+		.arg(import_path.path())
+		.args(&["--block-hash", &block_hash])
+		.arg(&snapshot_file)
+		.output()
+		.unwrap();
+	let logged_output = String::from_utf8_lossy(&output.stderr);
+	assert!(output.status.success());
+	assert!(logged_output.contains("Imported state snapshot"));
+}
@@ -201,7 +201,7 @@ where
 	)?;
 	io.merge(
 		Grandpa::new(
-			subscription_executor,
+			subscription_executor.clone(),
 			shared_authority_set.clone(),
 			shared_voter_state,
 			justification_stream,
@@ -218,7 +218,8 @@ where
 	io.merge(StateMigration::new(client.clone(), backend, deny_unsafe).into_rpc())?;
 	io.merge(Dev::new(client, deny_unsafe).into_rpc())?;
 	let statement_store =
-		sc_rpc::statement::StatementStore::new(statement_store, deny_unsafe).into_rpc();
+		sc_rpc::statement::StatementStore::new(statement_store, deny_unsafe, subscription_executor)
+			.into_rpc();
 	io.merge(statement_store)?;
 
 	if let Some(mixnet_api) = mixnet_api {
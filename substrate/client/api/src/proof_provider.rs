@@ -18,6 +18,7 @@
 
 //! Proof utilities
 use crate::{CompactProof, StorageProof};
+use futures::future::BoxFuture;
 use sp_runtime::traits::Block as BlockT;
 use sp_state_machine::{KeyValueStates, KeyValueStorageLevel};
 use sp_storage::ChildInfo;
@@ -91,3 +92,19 @@ pub trait ProofProvider<Block: BlockT> {
 		start_keys: &[Vec<u8>],
 	) -> sp_blockchain::Result<(KeyValueStates, usize)>;
 }
+
+/// Interface for reading the state of blocks that is not available locally, for example because
+/// it has been pruned, from read proofs provided by remote nodes.
+pub trait RemoteStorageProvider<Block: BlockT>: Send + Sync {
+	/// Reads the values of `keys` in the state of the block with the given `header`, or in the
+	/// child trie `child_info` of that state.
+	///
+	/// The values are returned in the order of `keys`. Implementations must verify the values
+	/// against the state root of the `header`.
+	fn remote_read(
+		&self,
+		header: &Block::Header,
+		child_info: Option<&ChildInfo>,
+		keys: Vec<Vec<u8>>,
+	) -> BoxFuture<'static, sp_blockchain::Result<Vec<Option<Vec<u8>>>>>;
+}
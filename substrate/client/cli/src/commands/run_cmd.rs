@@ -32,6 +32,7 @@ use regex::Regex;
 use sc_service::{
 	config::{
 		BasePath, IpNetwork, PrometheusConfig, RpcBatchRequestConfig, TransactionPoolOptions,
+		TransactionPoolType,
 	},
 	ChainSpec, Role,
 };
@@ -112,6 +113,13 @@ pub struct RunCmd {
 	#[arg(long)]
 	pub rpc_rate_limit_trust_proxy_headers: bool,
 
+	/// Answer storage queries for blocks whose state has been pruned by fetching read proofs from
+	/// remote peers.
+	///
+	/// The proofs are verified against the state root of the locally stored header of the block.
+	#[arg(long)]
+	pub rpc_remote_state: bool,
+
 	/// Set the maximum RPC request payload size for both HTTP and WS in megabytes.
 	#[arg(long, default_value_t = RPC_DEFAULT_MAX_REQUEST_SIZE_MB)]
 	pub rpc_max_request_size: u32,
@@ -465,10 +473,18 @@ impl CliConfiguration for RunCmd {
 		Ok(self.rpc_rate_limit_trust_proxy_headers)
 	}
 
+	fn rpc_remote_state(&self) -> Result<bool> {
+		Ok(self.rpc_remote_state)
+	}
+
 	fn transaction_pool(&self, is_dev: bool) -> Result<TransactionPoolOptions> {
 		Ok(self.pool_config.transaction_pool(is_dev))
 	}
 
+	fn transaction_pool_type(&self) -> Result<TransactionPoolType> {
+		Ok(self.pool_config.pool_type.into())
+	}
+
 	fn max_runtime_instances(&self) -> Result<Option<usize>> {
 		Ok(Some(self.runtime_params.max_runtime_instances))
 	}
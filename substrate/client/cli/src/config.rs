@@ -29,7 +29,7 @@ use sc_service::{
 		BasePath, Configuration, DatabaseSource, IpNetwork, KeystoreConfig, NetworkConfiguration,
 		NodeKeyConfig, OffchainWorkerConfig, OutputFormat, PrometheusConfig, PruningMode, Role,
 		RpcBatchRequestConfig, RpcMethods, TelemetryEndpoints, TransactionPoolOptions,
-		WasmExecutionMethod,
+		TransactionPoolType, WasmExecutionMethod,
 	},
 	BlocksPruning, ChainSpec, TracingReceiver,
 };
@@ -156,6 +156,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(Default::default())
 	}
 
+	/// Get the transaction pool type
+	///
+	/// By default this is `TransactionPoolType::SingleState`.
+	fn transaction_pool_type(&self) -> Result<TransactionPoolType> {
+		Ok(Default::default())
+	}
+
 	/// Get the network configuration
 	///
 	/// By default this is retrieved from `NetworkParams` if it is available otherwise it creates
@@ -359,6 +366,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(false)
 	}
 
+	/// Whether the storage of blocks with pruned state is read from remote peers for RPC queries.
+	///
+	/// By default this is `false`.
+	fn rpc_remote_state(&self) -> Result<bool> {
+		Ok(false)
+	}
+
 	/// Get the prometheus configuration (`None` if disabled)
 	///
 	/// By default this is `None`.
@@ -503,6 +517,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			impl_version: C::impl_version(),
 			tokio_handle,
 			transaction_pool: self.transaction_pool(is_dev)?,
+			transaction_pool_type: self.transaction_pool_type()?,
 			network: self.network_config(
 				&chain_spec,
 				is_dev,
@@ -535,6 +550,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			rpc_rate_limit: self.rpc_rate_limit()?,
 			rpc_rate_limit_whitelisted_ips: self.rpc_rate_limit_whitelisted_ips()?,
 			rpc_rate_limit_trust_proxy_headers: self.rpc_rate_limit_trust_proxy_headers()?,
+			rpc_remote_state: self.rpc_remote_state()?,
 			prometheus_config: self
 				.prometheus_config(DCV::prometheus_listen_port(), &chain_spec)?,
 			telemetry_endpoints,
@@ -26,6 +26,8 @@ use sc_network::{
 		NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, SetConfig, TransportConfig,
 	},
 	multiaddr::Protocol,
+	peer_store::PeerStoreConfig,
+	PeerId,
 };
 use sc_service::{
 	config::{Multiaddr, MultiaddrWithPeerId},
@@ -33,6 +35,9 @@ use sc_service::{
 };
 use std::{borrow::Cow, num::NonZeroUsize, path::PathBuf};
 
+/// Name of the file peer reputations are persisted to in the network configuration directory.
+const PEER_STORE_FILE: &str = "peer_store.json";
+
 /// Parameters used to create the network configuration.
 #[derive(Debug, Clone, Args)]
 pub struct NetworkParams {
@@ -54,6 +59,37 @@ pub struct NetworkParams {
 	#[arg(long)]
 	pub reserved_only: bool,
 
+	/// Specify a list of peers that are never banned or disconnected because of their
+	/// reputation.
+	#[arg(long, value_name = "PEER_ID", num_args = 1..)]
+	pub allowed_peers: Vec<PeerId>,
+
+	/// Specify a list of peers that are always banned.
+	#[arg(long, value_name = "PEER_ID", num_args = 1..)]
+	pub banned_peers: Vec<PeerId>,
+
+	/// Reputation under which peers are banned.
+	///
+	/// Defaults to the threshold of the network backend.
+	#[arg(long, value_name = "REPUTATION", allow_negative_numbers = true)]
+	pub peer_ban_threshold: Option<i32>,
+
+	/// Override the reputation change applied for the given reason.
+	///
+	/// For example `--reputation-override "Same block request multiple times=-1000"`.
+	#[arg(
+		long,
+		value_name = "REASON=VALUE",
+		value_parser = parse_reputation_override,
+		num_args = 1..
+	)]
+	pub reputation_override: Vec<(String, i32)>,
+
+	/// Persist peer reputations to the network configuration directory and restore them on
+	/// startup.
+	#[arg(long)]
+	pub persist_peer_reputations: bool,
+
 	/// Public address that other nodes will use to connect to this node.
 	///
 	/// This can be used if there's a proxy in front of this node.
@@ -246,6 +282,17 @@ impl NetworkParams {
 				is_dev || matches!(chain_type, ChainType::Local | ChainType::Development),
 		};
 
+		let peer_store = PeerStoreConfig {
+			banned_threshold: self.peer_ban_threshold,
+			reputation_overrides: self.reputation_override.iter().cloned().collect(),
+			allowed_peers: self.allowed_peers.iter().cloned().collect(),
+			banned_peers: self.banned_peers.iter().cloned().collect(),
+			persistence_path: net_config_path
+				.as_ref()
+				.filter(|_| self.persist_peer_reputations)
+				.map(|path| path.join(PEER_STORE_FILE)),
+		};
+
 		NetworkConfiguration {
 			boot_nodes,
 			net_config_path,
@@ -279,10 +326,20 @@ impl NetworkParams {
 			ipfs_server: self.ipfs_server,
 			sync_mode: self.sync.into(),
 			network_backend: self.network_backend.into(),
+			peer_store,
 		}
 	}
 }
 
+/// Parse a `REASON=VALUE` reputation override.
+fn parse_reputation_override(s: &str) -> Result<(String, i32), String> {
+	let (reason, value) = s
+		.rsplit_once('=')
+		.ok_or_else(|| format!("Expected `REASON=VALUE`, got `{s}`"))?;
+	let value = value.trim().parse().map_err(|e| format!("Invalid reputation value: {e}"))?;
+	Ok((reason.trim().to_string(), value))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -333,4 +390,20 @@ mod tests {
 
 		assert_eq!(SyncMode::Warp, params.network_params.sync);
 	}
+
+	#[test]
+	fn reputation_override_is_parsed() {
+		let params = Cli::try_parse_from([
+			"",
+			"--reputation-override",
+			"Same block request multiple times=-1000",
+		])
+		.expect("Parses network params");
+
+		assert_eq!(
+			params.network_params.reputation_override,
+			vec![("Same block request multiple times".to_string(), -1000)],
+		);
+		assert!(Cli::try_parse_from(["", "--reputation-override", "no value"]).is_err());
+	}
 }
@@ -34,6 +34,8 @@ pub struct PruningParams {
 	///  - archive: Keep the state of all blocks.
 	///  - 'archive-canonical' Keep only the state of finalized blocks.
 	///  - number Keep the state of the last number of finalized blocks.
+	///  - 'number:interval' Keep the state of the last number of finalized blocks and of every
+	///  block with a number that is a multiple of interval. Requires paritydb.
 	/// [default: 256]
 	#[arg(alias = "pruning", long, value_name = "PRUNING_MODE")]
 	pub state_pruning: Option<DatabasePruningMode>,
@@ -64,7 +66,7 @@ impl PruningParams {
 
 	/// Get the block pruning value from the parameters
 	pub fn blocks_pruning(&self) -> error::Result<BlocksPruning> {
-		Ok(self.blocks_pruning.into())
+		self.blocks_pruning.try_into().map_err(error::Error::Input)
 	}
 }
 
@@ -81,6 +83,14 @@ pub enum DatabasePruningMode {
 	ArchiveCanonical,
 	/// Keep the data of the last number of finalized blocks.
 	Custom(u32),
+	/// Keep the data of the last number of finalized blocks and of every block with a number
+	/// that is a multiple of `interval`.
+	Checkpoints {
+		/// Number of finalized blocks to keep.
+		blocks: u32,
+		/// Interval of the checkpoint blocks.
+		interval: u32,
+	},
 }
 
 impl std::str::FromStr for DatabasePruningMode {
@@ -90,6 +100,14 @@ impl std::str::FromStr for DatabasePruningMode {
 		match input {
 			"archive" => Ok(Self::Archive),
 			"archive-canonical" => Ok(Self::ArchiveCanonical),
+			bc if bc.contains(':') => {
+				let (blocks, interval) = bc.split_once(':').expect("contains ':'; qed");
+				match (blocks.parse(), interval.parse()) {
+					(Ok(blocks), Ok(interval)) if interval > 0 =>
+						Ok(Self::Checkpoints { blocks, interval }),
+					_ => Err("Invalid pruning mode specified".to_string()),
+				}
+			},
 			bc => bc
 				.parse()
 				.map_err(|_| "Invalid pruning mode specified".to_string())
@@ -104,16 +122,22 @@ impl Into<PruningMode> for DatabasePruningMode {
 			DatabasePruningMode::Archive => PruningMode::ArchiveAll,
 			DatabasePruningMode::ArchiveCanonical => PruningMode::ArchiveCanonical,
 			DatabasePruningMode::Custom(n) => PruningMode::blocks_pruning(n),
+			DatabasePruningMode::Checkpoints { blocks, interval } =>
+				PruningMode::checkpoints_pruning(blocks, interval),
 		}
 	}
 }
 
-impl Into<BlocksPruning> for DatabasePruningMode {
-	fn into(self) -> BlocksPruning {
-		match self {
-			DatabasePruningMode::Archive => BlocksPruning::KeepAll,
-			DatabasePruningMode::ArchiveCanonical => BlocksPruning::KeepFinalized,
-			DatabasePruningMode::Custom(n) => BlocksPruning::Some(n),
+impl TryFrom<DatabasePruningMode> for BlocksPruning {
+	type Error = String;
+
+	fn try_from(mode: DatabasePruningMode) -> Result<Self, Self::Error> {
+		match mode {
+			DatabasePruningMode::Archive => Ok(BlocksPruning::KeepAll),
+			DatabasePruningMode::ArchiveCanonical => Ok(BlocksPruning::KeepFinalized),
+			DatabasePruningMode::Custom(n) => Ok(BlocksPruning::Some(n)),
+			DatabasePruningMode::Checkpoints { .. } =>
+				Err("Checkpoints are only supported for state pruning".to_string()),
 		}
 	}
 }
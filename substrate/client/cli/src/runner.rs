@@ -245,6 +245,7 @@ mod tests {
 				role: sc_service::Role::Authority,
 				tokio_handle: runtime.handle().clone(),
 				transaction_pool: Default::default(),
+				transaction_pool_type: Default::default(),
 				network: NetworkConfiguration::new_memory(),
 				keystore: sc_service::config::KeystoreConfig::InMemory,
 				database: sc_client_db::DatabaseSource::ParityDb { path: root.clone() },
@@ -278,6 +279,7 @@ mod tests {
 				rpc_rate_limit: None,
 				rpc_rate_limit_whitelisted_ips: Default::default(),
 				rpc_rate_limit_trust_proxy_headers: Default::default(),
+				rpc_remote_state: false,
 				prometheus_config: None,
 				telemetry_endpoints: None,
 				default_heap_pages: None,
@@ -16,7 +16,11 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use super::{config::Config, error::Error, request::Request};
+use super::{
+	config::Config,
+	error::Error,
+	request::{Request, StorageReadReply},
+};
 use futures::{
 	channel::{mpsc, oneshot},
 	SinkExt,
@@ -66,4 +70,28 @@ impl Api {
 			reply_receiver.await.map_err(|_| Error::ServiceUnavailable)?
 		}
 	}
+
+	/// Read storage via the mixnet, so that the node serving the request doesn't learn who is
+	/// reading the keys.
+	///
+	/// `at` is the SCALE-encoded hash of the block to read the storage at; the serving mixnode's
+	/// best block is used if `None`. The proof in the reply is checked against the state root of
+	/// the block it was read at before the reply is returned; replies for blocks which are not
+	/// known locally are rejected with [`Error::UnknownBlock`]. If `at` is `None`, this means the
+	/// local node must already have imported the serving mixnode's best block.
+	///
+	/// Returns a [`Future`] which returns another `Future`, see
+	/// [`submit_extrinsic`](Self::submit_extrinsic).
+	pub async fn read_storage(
+		&mut self,
+		at: Option<Bytes>,
+		keys: Vec<Bytes>,
+	) -> impl Future<Output = Result<StorageReadReply, Error>> {
+		let (reply_sender, reply_receiver) = oneshot::channel();
+		let res = self.request_sender.feed(Request::read_storage(at, keys, reply_sender)).await;
+		async move {
+			res.map_err(|_| Error::ServiceUnavailable)?;
+			reply_receiver.await.map_err(|_| Error::ServiceUnavailable)?
+		}
+	}
 }
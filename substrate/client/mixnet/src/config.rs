@@ -45,6 +45,16 @@ pub struct SubstrateConfig {
 	/// Maximum number of extrinsics being actively submitted. If a submit extrinsic request's
 	/// delay elapses and we are already at this limit, the request will simply be dropped.
 	pub max_pending_extrinsics: usize,
+
+	/// Maximum number of fragments a read storage reply may span. Replies are sent using SURBs
+	/// included in the request, so this determines how many SURBs are included in read storage
+	/// requests. Replies that don't fit are not delivered.
+	pub max_storage_read_reply_fragments: usize,
+	/// Maximum number of keys in a read storage request. Requests for more keys are rejected.
+	pub max_storage_read_keys: usize,
+	/// Maximum number of storage reads being actively served. When at the limit, any read storage
+	/// requests that arrive will simply be dropped.
+	pub max_pending_storage_reads: usize,
 }
 
 impl Default for SubstrateConfig {
@@ -59,6 +69,10 @@ impl Default for SubstrateConfig {
 			extrinsic_queue_capacity: 50,
 			mean_extrinsic_delay: Duration::from_secs(1),
 			max_pending_extrinsics: 20,
+
+			max_storage_read_reply_fragments: 8,
+			max_storage_read_keys: 64,
+			max_pending_storage_reads: 8,
 		}
 	}
 }
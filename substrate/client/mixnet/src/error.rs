@@ -45,6 +45,13 @@ pub enum Error {
 	/// Received a malformed reply.
 	#[error("Received a malformed reply from the mixnet")]
 	BadReply,
+	/// Received a storage read reply for a block which isn't known locally, so the reply couldn't
+	/// be checked against its state root.
+	#[error("Received a storage read reply for a block which is not known locally")]
+	UnknownBlock,
+	/// Received a storage read reply whose proof doesn't match the state root of its block.
+	#[error("Received a storage read reply with an invalid proof")]
+	BadProof,
 	/// Failed to post the request to the mixnet. Note that some [`PostErr`] variants, eg
 	/// [`PostErr::NotEnoughSpaceInQueue`], are handled internally and will never be returned from
 	/// the top-level API.
@@ -39,6 +39,7 @@ pub use self::{
 	config::{Config, CoreConfig, SubstrateConfig},
 	error::{Error, RemoteErr},
 	protocol::{peers_set_config, protocol_name},
+	request::StorageReadReply,
 	run::run,
 };
 pub use mixnet::core::{KxSecret, PostErr, TopologyErr};
@@ -19,16 +19,21 @@
 //! Sender-side request logic. Some things from this module are also used on the receiver side, eg
 //! [`extrinsic_delay`], but most of the receiver-side request logic lives elsewhere.
 
-use super::{config::SubstrateConfig, error::Error};
+use super::{
+	config::SubstrateConfig,
+	error::{Error, RemoteErr},
+};
 use blake2::{
 	digest::{consts::U16, Mac},
 	Blake2bMac,
 };
-use codec::{Decode, DecodeAll};
+use codec::{Codec, Decode, DecodeAll, Encode};
 use futures::channel::oneshot;
 use log::debug;
 use mixnet::core::{Delay, MessageId, PostErr, Scattered};
-use sp_core::Bytes;
+use sc_client_api::StorageProof;
+use sp_core::{Bytes, Hasher};
+use sp_state_machine::read_proof_check;
 use std::time::Duration;
 
 const LOG_TARGET: &str = "mixnet";
@@ -39,11 +44,14 @@ fn send_err<T>(reply_sender: oneshot::Sender<Result<T, Error>>, err: Error) {
 	}
 }
 
-fn send_reply<T: Decode>(reply_sender: oneshot::Sender<Result<T, Error>>, mut data: &[u8]) {
-	let res = match Result::decode_all(&mut data) {
+fn decode_reply<T: Decode>(mut data: &[u8]) -> Result<T, Error> {
+	match Result::decode_all(&mut data) {
 		Ok(res) => res.map_err(Error::Remote),
 		Err(_) => Err(Error::BadReply),
-	};
+	}
+}
+
+fn send_reply<T>(reply_sender: oneshot::Sender<Result<T, Error>>, res: Result<T, Error>) {
 	match reply_sender.send(res) {
 		Ok(_) => (),
 		Err(Ok(_)) => debug!(target: LOG_TARGET, "Failed to send reply to requester"),
@@ -65,24 +73,126 @@ pub fn extrinsic_delay(message_id: &MessageId, config: &SubstrateConfig) -> Dura
 	delay.to_duration(config.mean_extrinsic_delay)
 }
 
+/// First byte of a read storage request, identifying it as such.
+pub const READ_STORAGE: u8 = 2;
+
+/// Body of a read storage request.
+#[derive(Decode, Encode)]
+pub struct StorageReadRequest {
+	/// SCALE-encoded hash of the block to read the storage at. If `None`, the mixnode's best block
+	/// is used. Either way the reply is only accepted if the block is known locally.
+	pub at: Option<Vec<u8>>,
+	/// Keys to read.
+	pub keys: Vec<Vec<u8>>,
+}
+
+/// Decode the body of a read storage request received by a mixnode.
+///
+/// Requests for more than [`SubstrateConfig::max_storage_read_keys`] keys are rejected.
+pub fn decode_storage_read_request(
+	mut data: &[u8],
+	config: &SubstrateConfig,
+) -> Result<StorageReadRequest, RemoteErr> {
+	let request = StorageReadRequest::decode_all(&mut data)
+		.map_err(|err| RemoteErr::Decode(format!("Bad storage request: {}", err)))?;
+	if request.keys.len() > config.max_storage_read_keys {
+		return Err(RemoteErr::Other(format!(
+			"Too many keys in storage request ({}, maximum is {})",
+			request.keys.len(),
+			config.max_storage_read_keys,
+		)))
+	}
+	Ok(request)
+}
+
+/// Reply to a read storage request.
+#[derive(Clone, Debug, PartialEq, Eq, Decode, Encode)]
+pub struct StorageReadReply {
+	/// SCALE-encoded hash of the block the storage was read at.
+	pub at: Vec<u8>,
+	/// Values of the requested keys, in request order. `None` if there is no value for a key.
+	pub values: Vec<Option<Vec<u8>>>,
+	/// Proof of the values against the state root of the block, as a list of trie nodes.
+	pub proof: Vec<Vec<u8>>,
+}
+
+/// Check that the values of a read storage reply are those of the requested keys, as proven by
+/// the proof of the reply against `state_root`, the state root of the block read at.
+pub fn check_storage_read_reply<H>(
+	request: &StorageReadRequest,
+	reply: &StorageReadReply,
+	state_root: H::Out,
+) -> Result<(), Error>
+where
+	H: Hasher + 'static,
+	H::Out: Ord + Codec,
+{
+	if reply.values.len() != request.keys.len() {
+		return Err(Error::BadReply)
+	}
+	let proof = StorageProof::new(reply.proof.iter().cloned());
+	let proven =
+		read_proof_check::<H, _>(state_root, proof, &request.keys).map_err(|_| Error::BadProof)?;
+	for (key, value) in request.keys.iter().zip(&reply.values) {
+		if proven.get(key) != Some(value) {
+			return Err(Error::BadProof)
+		}
+	}
+	Ok(())
+}
+
 /// Request parameters and local reply channel. Stored by the
 /// [`RequestManager`](mixnet::request_manager::RequestManager).
 pub enum Request {
-	SubmitExtrinsic { extrinsic: Bytes, reply_sender: oneshot::Sender<Result<(), Error>> },
+	SubmitExtrinsic {
+		extrinsic: Bytes,
+		reply_sender: oneshot::Sender<Result<(), Error>>,
+	},
+	ReadStorage {
+		request: StorageReadRequest,
+		reply_sender: oneshot::Sender<Result<StorageReadReply, Error>>,
+	},
 }
 
 impl Request {
+	/// Create a read storage request.
+	pub fn read_storage(
+		at: Option<Bytes>,
+		keys: Vec<Bytes>,
+		reply_sender: oneshot::Sender<Result<StorageReadReply, Error>>,
+	) -> Self {
+		let request = StorageReadRequest {
+			at: at.map(|at| at.0),
+			keys: keys.into_iter().map(|key| key.0).collect(),
+		};
+		Request::ReadStorage { request, reply_sender }
+	}
+
 	/// Forward an error to the user of the mixnet service.
 	fn send_err(self, err: Error) {
 		match self {
 			Request::SubmitExtrinsic { reply_sender, .. } => send_err(reply_sender, err),
+			Request::ReadStorage { reply_sender, .. } => send_err(reply_sender, err),
 		}
 	}
 
-	/// Forward a reply to the user of the mixnet service.
-	pub fn send_reply(self, data: &[u8]) {
+	/// Forward a reply to the user of the mixnet service. Read storage replies are forwarded only
+	/// if `verify_storage_read` accepts them.
+	pub fn send_reply(
+		self,
+		data: &[u8],
+		verify_storage_read: impl FnOnce(&StorageReadRequest, &StorageReadReply) -> Result<(), Error>,
+	) {
 		match self {
-			Request::SubmitExtrinsic { reply_sender, .. } => send_reply(reply_sender, data),
+			Request::SubmitExtrinsic { reply_sender, .. } =>
+				send_reply(reply_sender, decode_reply(data)),
+			Request::ReadStorage { request, reply_sender } => {
+				let res = decode_reply(data).and_then(|reply| {
+					verify_storage_read(&request, &reply)?;
+					Ok(reply)
+				});
+				send_reply(reply_sender, res)
+			},
 		}
 	}
 }
@@ -94,18 +204,24 @@ impl mixnet::request_manager::Request for Request {
 		match self {
 			Request::SubmitExtrinsic { extrinsic, .. } =>
 				f([&[SUBMIT_EXTRINSIC], extrinsic.as_ref()].as_slice().into()),
+			Request::ReadStorage { request, .. } =>
+				f([&[READ_STORAGE], request.encode().as_slice()].as_slice().into()),
 		}
 	}
 
 	fn num_surbs(&self, context: &Self::Context) -> usize {
 		match self {
 			Request::SubmitExtrinsic { .. } => context.surb_factor,
+			Request::ReadStorage { .. } =>
+				context.max_storage_read_reply_fragments * context.surb_factor,
 		}
 	}
 
 	fn handling_delay(&self, message_id: &MessageId, context: &Self::Context) -> Duration {
 		match self {
 			Request::SubmitExtrinsic { .. } => extrinsic_delay(message_id, context),
+			// Storage reads are handled as soon as they are received
+			Request::ReadStorage { .. } => Duration::ZERO,
 		}
 	}
 
@@ -117,3 +233,133 @@ impl mixnet::request_manager::Request for Request {
 		self.send_err(Error::NoReply);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::storage::StateVersion;
+	use sp_runtime::traits::BlakeTwo256;
+	use sp_state_machine::{prove_read, InMemoryBackend};
+	use std::collections::BTreeMap;
+
+	fn read_storage_request(
+		keys: usize,
+	) -> (Request, oneshot::Receiver<Result<StorageReadReply, Error>>) {
+		let (reply_sender, reply_receiver) = oneshot::channel();
+		let keys = (0..keys).map(|key| vec![key as u8].into()).collect();
+		(Request::read_storage(Some(vec![1; 32].into()), keys, reply_sender), reply_receiver)
+	}
+
+	fn encoded_request(request: &Request) -> Vec<u8> {
+		match request {
+			Request::ReadStorage { request, .. } => request.encode(),
+			Request::SubmitExtrinsic { .. } => panic!("Not a read storage request"),
+		}
+	}
+
+	#[test]
+	fn read_storage_request_round_trip() {
+		let config = SubstrateConfig::default();
+		let (request, _) = read_storage_request(2);
+
+		let decoded = decode_storage_read_request(&encoded_request(&request), &config).unwrap();
+		assert_eq!(decoded.at, Some(vec![1; 32]));
+		assert_eq!(decoded.keys, vec![vec![0], vec![1]]);
+	}
+
+	#[test]
+	fn read_storage_request_with_too_many_keys_is_rejected() {
+		let config = SubstrateConfig { max_storage_read_keys: 2, ..Default::default() };
+		let (request, _) = read_storage_request(3);
+
+		assert!(matches!(
+			decode_storage_read_request(&encoded_request(&request), &config),
+			Err(RemoteErr::Other(_))
+		));
+	}
+
+	#[test]
+	fn bad_read_storage_request_is_rejected() {
+		let config = SubstrateConfig::default();
+
+		assert!(matches!(
+			decode_storage_read_request(&[1, 2, 3], &config),
+			Err(RemoteErr::Decode(_))
+		));
+	}
+
+	#[test]
+	fn read_storage_reply_is_forwarded() {
+		let reply = StorageReadReply {
+			at: vec![1; 32],
+			values: vec![Some(vec![2]), None],
+			proof: vec![vec![3]],
+		};
+		let (request, mut reply_receiver) = read_storage_request(2);
+		request.send_reply(&Ok::<_, RemoteErr>(reply.clone()).encode(), |_, _| Ok(()));
+		assert_eq!(reply_receiver.try_recv().unwrap().unwrap().unwrap(), reply);
+
+		let (request, mut reply_receiver) = read_storage_request(2);
+		request
+			.send_reply(&Ok::<_, RemoteErr>(reply.clone()).encode(), |_, _| Err(Error::BadProof));
+		assert!(matches!(reply_receiver.try_recv().unwrap().unwrap(), Err(Error::BadProof)));
+
+		let (request, mut reply_receiver) = read_storage_request(2);
+		request.send_reply(
+			&Err::<StorageReadReply, _>(RemoteErr::Other("Failed".into())).encode(),
+			|_, _| Ok(()),
+		);
+		assert!(matches!(
+			reply_receiver.try_recv().unwrap().unwrap(),
+			Err(Error::Remote(RemoteErr::Other(_)))
+		));
+
+		let (request, mut reply_receiver) = read_storage_request(2);
+		request.send_reply(&[0xff], |_, _| Ok(()));
+		assert!(matches!(reply_receiver.try_recv().unwrap().unwrap(), Err(Error::BadReply)));
+	}
+
+	#[test]
+	fn read_storage_reply_is_checked_against_state_root() {
+		let backend = InMemoryBackend::<BlakeTwo256>::from((
+			BTreeMap::from([(vec![0], vec![2])]),
+			StateVersion::V1,
+		));
+		let state_root = *backend.root();
+		let keys = vec![vec![0], vec![1]];
+		let proof = prove_read(backend, &keys).unwrap();
+		let request = StorageReadRequest { at: None, keys };
+		let reply = StorageReadReply {
+			at: vec![1; 32],
+			values: vec![Some(vec![2]), None],
+			proof: proof.into_iter_nodes().collect(),
+		};
+		assert!(check_storage_read_reply::<BlakeTwo256>(&request, &reply, state_root).is_ok());
+
+		// Values which don't match the proof are rejected.
+		let forged = StorageReadReply { values: vec![Some(vec![3]), None], ..reply.clone() };
+		assert!(matches!(
+			check_storage_read_reply::<BlakeTwo256>(&request, &forged, state_root),
+			Err(Error::BadProof)
+		));
+		let forged =
+			StorageReadReply { values: vec![Some(vec![2]), Some(vec![4])], ..reply.clone() };
+		assert!(matches!(
+			check_storage_read_reply::<BlakeTwo256>(&request, &forged, state_root),
+			Err(Error::BadProof)
+		));
+
+		// A proof of another state is rejected.
+		assert!(matches!(
+			check_storage_read_reply::<BlakeTwo256>(&request, &reply, Default::default()),
+			Err(Error::BadProof)
+		));
+
+		// Replies missing values are rejected.
+		let truncated = StorageReadReply { values: vec![Some(vec![2])], ..reply };
+		assert!(matches!(
+			check_storage_read_reply::<BlakeTwo256>(&request, &truncated, state_root),
+			Err(Error::BadReply)
+		));
+	}
+}
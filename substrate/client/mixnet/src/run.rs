@@ -21,20 +21,24 @@
 use super::{
 	api::ApiBackend,
 	config::{Config, SubstrateConfig},
-	error::RemoteErr,
+	error::{Error, RemoteErr},
 	extrinsic_queue::ExtrinsicQueue,
 	maybe_inf_delay::MaybeInfDelay,
 	packet_dispatcher::PacketDispatcher,
 	peer_id::to_core_peer_id,
-	request::{extrinsic_delay, Request, SUBMIT_EXTRINSIC},
+	request::{
+		check_storage_read_reply, decode_storage_read_request, extrinsic_delay, Request,
+		StorageReadReply, StorageReadRequest, READ_STORAGE, SUBMIT_EXTRINSIC,
+	},
 	sync_with_runtime::sync_with_runtime,
 };
 use bytes::Bytes;
 use codec::{Decode, DecodeAll, Encode};
 use futures::{
+	channel::oneshot,
 	future::{pending, Either},
 	stream::FuturesUnordered,
-	FutureExt, StreamExt,
+	Future, FutureExt, StreamExt,
 };
 use log::{debug, error, trace, warn};
 use mixnet::{
@@ -42,7 +46,7 @@ use mixnet::{
 	reply_manager::{ReplyContext, ReplyManager},
 	request_manager::RequestManager,
 };
-use sc_client_api::{BlockchainEvents, HeaderBackend};
+use sc_client_api::{Backend, BlockchainEvents, HeaderBackend, ProofProvider, StorageProvider};
 use sc_network::{
 	service::traits::{NetworkService, NotificationEvent, ValidationResult},
 	NetworkPeers, NetworkStateInfo, NotificationService, ProtocolName,
@@ -52,10 +56,11 @@ use sc_transaction_pool_api::{
 };
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_consensus::SyncOracle;
+use sp_core::{storage::StorageKey, traits::SpawnNamed};
 use sp_keystore::{KeystoreExt, KeystorePtr};
 use sp_mixnet::{runtime_api::MixnetApi, types::Mixnode};
 use sp_runtime::{
-	traits::{Block, Header},
+	traits::{Block, HashingFor, Header},
 	transaction_validity::TransactionSource,
 	Saturating,
 };
@@ -77,6 +82,84 @@ fn complete_submit_extrinsic<X>(
 	reply_manager.complete(reply_context, data.encode(), mixnet);
 }
 
+fn read_storage<B, BE, C>(
+	client: &C,
+	request: StorageReadRequest,
+) -> Result<StorageReadReply, RemoteErr>
+where
+	B: Block,
+	BE: Backend<B>,
+	C: HeaderBackend<B> + ProofProvider<B> + StorageProvider<B, BE>,
+{
+	let hash = match request.at {
+		Some(at) => B::Hash::decode_all(&mut at.as_slice())
+			.map_err(|err| RemoteErr::Decode(format!("Bad block hash: {}", err)))?,
+		None => client.info().best_hash,
+	};
+
+	let values = request
+		.keys
+		.iter()
+		.map(|key| {
+			let value = client.storage(hash, &StorageKey(key.clone()))?;
+			Ok(value.map(|value| value.0))
+		})
+		.collect::<sc_client_api::blockchain::Result<Vec<_>>>()
+		.map_err(|err| RemoteErr::Other(err.to_string()))?;
+	let proof = client
+		.read_proof(hash, &mut request.keys.iter().map(Vec::as_slice))
+		.map_err(|err| RemoteErr::Other(err.to_string()))?;
+
+	Ok(StorageReadReply { at: hash.encode(), values, proof: proof.into_iter_nodes().collect() })
+}
+
+/// Serve a read storage request on a blocking task, so that reading the state doesn't stall the
+/// mixnet service. The returned future completes with the reply once the read is done.
+fn spawn_read_storage<B, BE, C>(
+	spawner: &dyn SpawnNamed,
+	client: Arc<C>,
+	request: StorageReadRequest,
+	reply_context: ReplyContext,
+) -> impl Future<Output = (Result<StorageReadReply, RemoteErr>, ReplyContext)>
+where
+	B: Block,
+	BE: Backend<B>,
+	C: HeaderBackend<B> + ProofProvider<B> + StorageProvider<B, BE> + Send + Sync + 'static,
+{
+	let (res_sender, res_receiver) = oneshot::channel();
+	spawner.spawn_blocking(
+		"mixnet-read-storage",
+		Some("mixnet"),
+		Box::pin(async move {
+			let _ = res_sender.send(read_storage(&*client, request));
+		}),
+	);
+	async move {
+		let res = res_receiver
+			.await
+			.unwrap_or_else(|_| Err(RemoteErr::Other("Storage read was cancelled".into())));
+		(res, reply_context)
+	}
+}
+
+/// Check a read storage reply received from a remote node. The reply is only accepted if the block
+/// it was served at is known locally and the proof matches the state root of that block.
+fn verify_storage_read<B: Block, C: HeaderBackend<B>>(
+	client: &C,
+	request: &StorageReadRequest,
+	reply: &StorageReadReply,
+) -> Result<(), Error> {
+	if request.at.as_ref().is_some_and(|at| *at != reply.at) {
+		return Err(Error::BadReply)
+	}
+	let hash = B::Hash::decode_all(&mut reply.at.as_slice()).map_err(|_| Error::BadReply)?;
+	let header = client.header(hash).ok().flatten().ok_or(Error::UnknownBlock)?;
+	check_storage_read_reply::<HashingFor<B>>(request, reply, *header.state_root())
+}
+
+/// Handle a packet received from the network. Returns a read storage request which should be
+/// served by the caller, if the packet completed one. Read storage replies are checked with
+/// `verify_storage_read` before being forwarded to the requester.
 fn handle_packet<X, E: Decode>(
 	packet: &Packet,
 	mixnet: &mut Mixnet<X>,
@@ -84,10 +167,11 @@ fn handle_packet<X, E: Decode>(
 	reply_manager: &mut ReplyManager,
 	extrinsic_queue: &mut ExtrinsicQueue<E>,
 	config: &SubstrateConfig,
-) {
+	verify_storage_read: impl FnOnce(&StorageReadRequest, &StorageReadReply) -> Result<(), Error>,
+) -> Option<(StorageReadRequest, ReplyContext)> {
 	match mixnet.handle_packet(packet) {
 		Some(Message::Request(message)) => {
-			let Some((reply_context, data)) = reply_manager.insert(message, mixnet) else { return };
+			let (reply_context, data) = reply_manager.insert(message, mixnet)?;
 
 			match data.as_slice() {
 				[SUBMIT_EXTRINSIC, encoded_extrinsic @ ..] => {
@@ -95,7 +179,7 @@ fn handle_packet<X, E: Decode>(
 						debug!(target: LOG_TARGET, "No space in extrinsic queue; dropping request");
 						// We don't send a reply in this case; we want the requester to retry
 						reply_manager.abandon(reply_context);
-						return
+						return None
 					}
 
 					// Decode the extrinsic
@@ -109,7 +193,7 @@ fn handle_packet<X, E: Decode>(
 								Err(RemoteErr::Decode(format!("Bad extrinsic: {}", err))),
 								mixnet,
 							);
-							return
+							return None
 						},
 					};
 
@@ -117,6 +201,15 @@ fn handle_packet<X, E: Decode>(
 						Instant::now() + extrinsic_delay(reply_context.message_id(), config);
 					extrinsic_queue.insert(deadline, extrinsic, reply_context);
 				},
+				[READ_STORAGE, encoded_request @ ..] =>
+					match decode_storage_read_request(encoded_request, config) {
+						Ok(request) => return Some((request, reply_context)),
+						Err(err) => reply_manager.complete(
+							reply_context,
+							Err::<StorageReadReply, _>(err).encode(),
+							mixnet,
+						),
+					},
 				_ => {
 					debug!(target: LOG_TARGET, "Unrecognised request; discarding");
 					// To keep things simple we don't bother sending a reply in this case. The
@@ -132,12 +225,13 @@ fn handle_packet<X, E: Decode>(
 					"Received reply to already-completed request with message ID {:x?}",
 					message.request_id
 				);
-				return
+				return None
 			};
-			request.send_reply(&message.data);
+			request.send_reply(&message.data, verify_storage_read);
 		},
 		None => (),
 	}
+	None
 }
 
 fn time_until(instant: Instant) -> Duration {
@@ -145,8 +239,9 @@ fn time_until(instant: Instant) -> Duration {
 }
 
 /// Run the mixnet service. If `keystore` is `None`, the service will not attempt to register the
-/// local node as a mixnode, even if `config.register` is `true`.
-pub async fn run<B, C, S, P>(
+/// local node as a mixnode, even if `config.register` is `true`. Read storage requests are served
+/// on blocking tasks spawned with `spawner`.
+pub async fn run<B, BE, C, S, P>(
 	config: Config,
 	mut api_backend: ApiBackend,
 	client: Arc<C>,
@@ -156,9 +251,18 @@ pub async fn run<B, C, S, P>(
 	transaction_pool: Arc<P>,
 	keystore: Option<KeystorePtr>,
 	mut notification_service: Box<dyn NotificationService>,
+	spawner: Box<dyn SpawnNamed>,
 ) where
 	B: Block,
-	C: BlockchainEvents<B> + ProvideRuntimeApi<B> + HeaderBackend<B>,
+	BE: Backend<B>,
+	C: BlockchainEvents<B>
+		+ ProvideRuntimeApi<B>
+		+ HeaderBackend<B>
+		+ ProofProvider<B>
+		+ StorageProvider<B, BE>
+		+ Send
+		+ Sync
+		+ 'static,
 	C::Api: MixnetApi<B>,
 	S: SyncOracle,
 	P: TransactionPool<Block = B> + LocalTransactionPool<Block = B> + 'static,
@@ -196,6 +300,7 @@ pub async fn run<B, C, S, P>(
 	let mut next_retry_delay = MaybeInfDelay::new(None);
 	let mut next_extrinsic_delay = MaybeInfDelay::new(None);
 	let mut submit_extrinsic_results = FuturesUnordered::new();
+	let mut read_storage_results = FuturesUnordered::new();
 
 	loop {
 		let mut next_request = if request_manager.has_space() {
@@ -263,9 +368,22 @@ pub async fn run<B, C, S, P>(
 					let notification: Bytes = notification.into();
 
 					match notification.as_ref().try_into() {
-						Ok(packet) => handle_packet(packet,
+						Ok(packet) => if let Some((request, reply_context)) = handle_packet(packet,
 							&mut mixnet, &mut request_manager, &mut reply_manager,
-							&mut extrinsic_queue, &config.substrate),
+							&mut extrinsic_queue, &config.substrate,
+							|request, reply| verify_storage_read::<B, _>(&*client, request, reply))
+						{
+							if read_storage_results.len() < config.substrate.max_pending_storage_reads {
+								read_storage_results.push(spawn_read_storage(
+									&*spawner, client.clone(), request, reply_context));
+							} else {
+								// There are already too many pending storage reads, just drop
+								// this one. We don't send a reply; we want the requester to retry.
+								debug!(target: LOG_TARGET,
+									"Too many pending storage reads; dropped read storage request");
+								reply_manager.abandon(reply_context);
+							}
+						},
 						Err(_) => debug!(target: LOG_TARGET,
 							"Dropped incorrectly sized packet ({} bytes) from {peer}",
 							notification.len(),
@@ -346,6 +464,11 @@ pub async fn run<B, C, S, P>(
 				};
 				complete_submit_extrinsic(&mut reply_manager, reply_context, res, &mut mixnet);
 			}
+
+			res_reply_context = read_storage_results.select_next_some() => {
+				let (res, reply_context) = res_reply_context;
+				reply_manager.complete(reply_context, res.encode(), &mut mixnet);
+			}
 		}
 
 		let events = mixnet.take_events();
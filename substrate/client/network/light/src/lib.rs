@@ -19,4 +19,5 @@
 //! Light client data structures of the networking layer.
 
 pub mod light_client_requests;
+pub mod remote_storage;
 mod schema;
@@ -0,0 +1,94 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Remote storage request protocol.
+//!
+//! Nodes read the state of blocks which is not available locally, for example because it has
+//! been pruned, from read proofs provided by their peers. The proofs are verified against the
+//! state root of the locally stored header.
+
+use codec::{Decode, Encode};
+use sc_client_api::StorageProof;
+use sc_network::{config::ProtocolId, request_responses::IncomingRequest, NetworkBackend};
+use sp_runtime::traits::Block;
+
+use std::time::Duration;
+
+/// For incoming remote storage requests.
+pub mod handler;
+/// For outgoing remote storage requests.
+pub mod requester;
+
+/// Maximum number of keys read with a single request.
+pub const MAX_KEYS_PER_REQUEST: usize = 64;
+
+/// Request for the values of storage keys in the state of a block.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub(crate) struct RemoteStorageRequest<Hash> {
+	/// Block whose state is read.
+	pub block: Hash,
+	/// Prefixed storage key of the child trie the keys are read from, `None` for the main trie.
+	pub child_storage_key: Option<Vec<u8>>,
+	/// Keys to read, at most [`MAX_KEYS_PER_REQUEST`].
+	pub keys: Vec<Vec<u8>>,
+}
+
+/// Response to a [`RemoteStorageRequest`].
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub(crate) enum RemoteStorageResponse {
+	/// Read proof of the requested keys.
+	Proof(StorageProof),
+	/// The state of the block is not available to the responder.
+	Unavailable,
+}
+
+/// Generate the remote storage protocol name from the genesis hash and fork id.
+fn generate_protocol_name<Hash: AsRef<[u8]>>(genesis_hash: Hash, fork_id: Option<&str>) -> String {
+	let genesis_hash = genesis_hash.as_ref();
+	if let Some(fork_id) = fork_id {
+		format!("/{}/{}/remote-storage/1", array_bytes::bytes2hex("", genesis_hash), fork_id)
+	} else {
+		format!("/{}/remote-storage/1", array_bytes::bytes2hex("", genesis_hash))
+	}
+}
+
+/// Generate the legacy remote storage protocol name from chain specific protocol identifier.
+fn generate_legacy_protocol_name(protocol_id: &ProtocolId) -> String {
+	format!("/{}/remote-storage/1", protocol_id.as_ref())
+}
+
+/// Generates a `RequestResponseProtocolConfig` for the remote storage request protocol.
+pub fn generate_protocol_config<
+	Hash: AsRef<[u8]>,
+	B: Block,
+	N: NetworkBackend<B, <B as Block>::Hash>,
+>(
+	protocol_id: &ProtocolId,
+	genesis_hash: Hash,
+	fork_id: Option<&str>,
+	inbound_queue: async_channel::Sender<IncomingRequest>,
+) -> N::RequestResponseProtocolConfig {
+	N::request_response_config(
+		generate_protocol_name(genesis_hash, fork_id).into(),
+		std::iter::once(generate_legacy_protocol_name(protocol_id).into()).collect(),
+		1 * 1024 * 1024,
+		16 * 1024 * 1024,
+		Duration::from_secs(15),
+		Some(inbound_queue),
+	)
+}
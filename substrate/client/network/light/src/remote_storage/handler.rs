@@ -0,0 +1,181 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Helper for incoming remote storage requests.
+//!
+//! Handle (i.e. answer) incoming remote storage requests from a remote peer received via
+//! `crate::request_responses::RequestResponsesBehaviour` with [`RemoteStorageRequestHandler`].
+
+use super::{RemoteStorageRequest, RemoteStorageResponse, MAX_KEYS_PER_REQUEST};
+use codec::{Decode, Encode};
+use futures::prelude::*;
+use log::{debug, trace};
+use sc_client_api::{BlockBackend, ProofProvider};
+use sc_network::{
+	config::ProtocolId,
+	request_responses::{IncomingRequest, OutgoingResponse},
+	NetworkBackend, ReputationChange,
+};
+use sc_network_types::PeerId;
+use sp_core::storage::{ChildInfo, ChildType, PrefixedStorageKey};
+use sp_runtime::traits::Block;
+use std::{marker::PhantomData, sync::Arc};
+
+const LOG_TARGET: &str = "remote-storage-request-handler";
+
+/// Incoming requests bounded queue size, matching the light client request limit.
+const MAX_REMOTE_STORAGE_REQUEST_QUEUE: usize = 20;
+
+/// Handler for incoming remote storage requests from a remote peer.
+pub struct RemoteStorageRequestHandler<B, Client> {
+	request_receiver: async_channel::Receiver<IncomingRequest>,
+	/// Blockchain client.
+	client: Arc<Client>,
+	_block: PhantomData<B>,
+}
+
+impl<B, Client> RemoteStorageRequestHandler<B, Client>
+where
+	B: Block,
+	Client: BlockBackend<B> + ProofProvider<B> + Send + Sync + 'static,
+{
+	/// Create a new [`RemoteStorageRequestHandler`].
+	pub fn new<N: NetworkBackend<B, <B as Block>::Hash>>(
+		protocol_id: &ProtocolId,
+		fork_id: Option<&str>,
+		client: Arc<Client>,
+	) -> (Self, N::RequestResponseProtocolConfig) {
+		let (tx, request_receiver) = async_channel::bounded(MAX_REMOTE_STORAGE_REQUEST_QUEUE);
+
+		let protocol_config = super::generate_protocol_config::<_, B, N>(
+			protocol_id,
+			client
+				.block_hash(0u32.into())
+				.ok()
+				.flatten()
+				.expect("Genesis block exists; qed"),
+			fork_id,
+			tx,
+		);
+
+		(Self { client, request_receiver, _block: PhantomData::default() }, protocol_config)
+	}
+
+	/// Run [`RemoteStorageRequestHandler`].
+	pub async fn run(mut self) {
+		while let Some(request) = self.request_receiver.next().await {
+			let IncomingRequest { peer, payload, pending_response } = request;
+
+			let response = match self.handle_request(peer, payload) {
+				Ok(response_data) => {
+					trace!(target: LOG_TARGET, "Handled remote storage request from {}.", peer);
+					OutgoingResponse {
+						result: Ok(response_data),
+						reputation_changes: Vec::new(),
+						sent_feedback: None,
+					}
+				},
+				Err(e) => {
+					debug!(
+						target: LOG_TARGET,
+						"Failed to handle remote storage request from {}: {}", peer, e,
+					);
+
+					let reputation_changes = match e {
+						HandleRequestError::BadRequest(_) | HandleRequestError::Codec(_) => {
+							vec![ReputationChange::new(-(1 << 12), "bad request")]
+						},
+					};
+
+					OutgoingResponse { result: Err(()), reputation_changes, sent_feedback: None }
+				},
+			};
+
+			if pending_response.send(response).is_err() {
+				debug!(
+					target: LOG_TARGET,
+					"Failed to send remote storage response to {}.", peer,
+				);
+			}
+		}
+	}
+
+	fn handle_request(
+		&mut self,
+		peer: PeerId,
+		payload: Vec<u8>,
+	) -> Result<Vec<u8>, HandleRequestError> {
+		let request = RemoteStorageRequest::<B::Hash>::decode(&mut &payload[..])?;
+
+		if request.keys.is_empty() {
+			return Err(HandleRequestError::BadRequest("Remote storage request without keys."))
+		}
+		if request.keys.len() > MAX_KEYS_PER_REQUEST {
+			return Err(HandleRequestError::BadRequest("Too many keys in remote storage request."))
+		}
+
+		trace!(
+			target: LOG_TARGET,
+			"Remote storage request from {} ({} keys at {:?}).",
+			peer,
+			request.keys.len(),
+			request.block,
+		);
+
+		let mut keys = request.keys.iter().map(AsRef::as_ref);
+		let proof = match &request.child_storage_key {
+			None => self.client.read_proof(request.block, &mut keys),
+			Some(storage_key) => {
+				let child_info =
+					match ChildType::from_prefixed_key(PrefixedStorageKey::new_ref(storage_key)) {
+						Some((ChildType::ParentKeyId, storage_key)) =>
+							ChildInfo::new_default(storage_key),
+						None =>
+							return Err(HandleRequestError::BadRequest("Invalid child storage key.")),
+					};
+				self.client.read_child_proof(request.block, &child_info, &mut keys)
+			},
+		};
+
+		let response = match proof {
+			Ok(proof) => RemoteStorageResponse::Proof(proof),
+			Err(error) => {
+				trace!(
+					target: LOG_TARGET,
+					"Remote storage request from {} at {:?} failed with: {}",
+					peer,
+					request.block,
+					error,
+				);
+				RemoteStorageResponse::Unavailable
+			},
+		};
+
+		Ok(response.encode())
+	}
+}
+
+#[derive(Debug, thiserror::Error)]
+enum HandleRequestError {
+	/// A bad request has been received.
+	#[error("bad request: {0}")]
+	BadRequest(&'static str),
+	/// Decoding of the request failed.
+	#[error("codec error: {0}")]
+	Codec(#[from] codec::Error),
+}
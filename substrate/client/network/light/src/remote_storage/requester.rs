@@ -0,0 +1,287 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Helper for outgoing remote storage requests.
+//!
+//! [`RemoteStorageRequester`] reads the state of blocks which is not available locally, for
+//! example because it has been pruned, by sending remote storage requests to the peers. The read
+//! proofs of the responses are verified against the state root of the locally stored header.
+
+use super::{RemoteStorageRequest, RemoteStorageResponse, MAX_KEYS_PER_REQUEST};
+use codec::{Decode, Encode};
+use futures::{future::BoxFuture, FutureExt};
+use log::{debug, trace};
+use sc_client_api::{ChildInfo, RemoteStorageProvider};
+use sc_network::{IfDisconnected, NetworkRequest, ProtocolName, RequestFailure};
+use sc_network_sync::SyncingService;
+use sc_network_types::PeerId;
+use sp_core::Hasher;
+use sp_runtime::traits::{Block, HashingFor, Header, NumberFor};
+use std::sync::Arc;
+
+const LOG_TARGET: &str = "remote-storage-request-sender";
+
+/// Maximum number of peers a single remote read is attempted with.
+const MAX_PEERS_PER_READ: usize = 5;
+
+/// Reads the state of blocks from read proofs provided by remote peers.
+///
+/// Peers which don't have the state of the block answer without a proof, in which case the next
+/// peer is tried.
+pub struct RemoteStorageRequester<B: Block> {
+	network: Arc<dyn NetworkRequest + Send + Sync>,
+	sync_service: Arc<SyncingService<B>>,
+	protocol_name: ProtocolName,
+}
+
+impl<B: Block> RemoteStorageRequester<B> {
+	/// Create a new [`RemoteStorageRequester`].
+	pub fn new(
+		network: Arc<dyn NetworkRequest + Send + Sync>,
+		sync_service: Arc<SyncingService<B>>,
+		genesis_hash: B::Hash,
+		fork_id: Option<&str>,
+	) -> Self {
+		let protocol_name = super::generate_protocol_name(genesis_hash, fork_id).into();
+		Self { network, sync_service, protocol_name }
+	}
+}
+
+impl<B: Block> RemoteStorageProvider<B> for RemoteStorageRequester<B> {
+	fn remote_read(
+		&self,
+		header: &B::Header,
+		child_info: Option<&ChildInfo>,
+		keys: Vec<Vec<u8>>,
+	) -> BoxFuture<'static, sp_blockchain::Result<Vec<Option<Vec<u8>>>>> {
+		if keys.len() > MAX_KEYS_PER_REQUEST {
+			return futures::future::ready(Err(sp_blockchain::Error::Msg(format!(
+				"At most {MAX_KEYS_PER_REQUEST} keys can be read remotely at once"
+			))))
+			.boxed()
+		}
+
+		let network = self.network.clone();
+		let sync_service = self.sync_service.clone();
+		let protocol_name = self.protocol_name.clone();
+		let number = *header.number();
+		let state_root = *header.state_root();
+		let child_info = child_info.cloned();
+		let payload = RemoteStorageRequest {
+			block: header.hash(),
+			child_storage_key: child_info
+				.as_ref()
+				.map(|child_info| child_info.prefixed_storage_key().into_inner()),
+			keys: keys.clone(),
+		}
+		.encode();
+
+		async move {
+			for peer in candidate_peers(&sync_service, number).await? {
+				let response = network
+					.request(
+						peer,
+						protocol_name.clone(),
+						payload.clone(),
+						None,
+						IfDisconnected::ImmediateError,
+					)
+					.await
+					.map_err(RequestError::Network)
+					.and_then(|(response, _)| {
+						check_response::<HashingFor<B>>(
+							&response,
+							state_root,
+							child_info.as_ref(),
+							&keys,
+						)
+					});
+
+				match response {
+					Ok(values) => {
+						trace!(
+							target: LOG_TARGET,
+							"Remote read of block #{} from {}.", number, peer,
+						);
+						return Ok(values)
+					},
+					Err(e) => debug!(
+						target: LOG_TARGET,
+						"Remote read of block #{} from {} failed: {}", number, peer, e,
+					),
+				}
+			}
+
+			Err(sp_blockchain::Error::RemoteFetchFailed)
+		}
+		.boxed()
+	}
+}
+
+/// Returns the full node peers whose best block is at least the block with the given `number`.
+async fn candidate_peers<B: Block>(
+	sync_service: &SyncingService<B>,
+	number: NumberFor<B>,
+) -> sp_blockchain::Result<Vec<PeerId>> {
+	let peers = sync_service
+		.peers_info()
+		.await
+		.map_err(|_| sp_blockchain::Error::RemoteFetchCancelled)?;
+
+	Ok(peers
+		.into_iter()
+		.filter(|(_, info)| info.roles.is_full() && info.best_number >= number)
+		.map(|(peer, _)| peer)
+		.take(MAX_PEERS_PER_READ)
+		.collect())
+}
+
+/// Decodes the response and verifies its read proof against `state_root`.
+///
+/// Returns the values of `keys`, in the same order.
+fn check_response<H>(
+	response: &[u8],
+	state_root: H::Out,
+	child_info: Option<&ChildInfo>,
+	keys: &[Vec<u8>],
+) -> Result<Vec<Option<Vec<u8>>>, RequestError>
+where
+	H: Hasher + 'static,
+	H::Out: Ord + Decode + Encode,
+{
+	let proof = match RemoteStorageResponse::decode(&mut &response[..])? {
+		RemoteStorageResponse::Proof(proof) => proof,
+		RemoteStorageResponse::Unavailable => return Err(RequestError::MissingProof),
+	};
+
+	let mut values = match child_info {
+		None => sp_state_machine::read_proof_check::<H, _>(state_root, proof, keys),
+		Some(child_info) =>
+			sp_state_machine::read_child_proof_check::<H, _>(state_root, proof, child_info, keys),
+	}
+	.map_err(|e| RequestError::InvalidProof(e.to_string()))?;
+
+	Ok(keys.iter().map(|key| values.remove(key).flatten()).collect())
+}
+
+#[derive(Debug, thiserror::Error)]
+enum RequestError {
+	#[error("Request failed: {0}.")]
+	Network(#[from] RequestFailure),
+	#[error("The remote has no proof, the state of the block is probably pruned.")]
+	MissingProof,
+	#[error("codec error: {0}")]
+	Codec(#[from] codec::Error),
+	#[error("invalid read proof: {0}")]
+	InvalidProof(String),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::storage::StateVersion;
+	use sp_runtime::traits::BlakeTwo256;
+	use sp_state_machine::{prove_child_read, prove_read, InMemoryBackend, StorageProof};
+
+	const KEY: &[u8] = b"key";
+	const VALUE: &[u8] = b"value";
+	const MISSING_KEY: &[u8] = b"missing";
+
+	fn backend() -> InMemoryBackend<BlakeTwo256> {
+		let child_info = ChildInfo::new_default(b"child");
+		let storage = vec![
+			(None, vec![(KEY.to_vec(), Some(VALUE.to_vec()))]),
+			(Some(child_info), vec![(KEY.to_vec(), Some(VALUE.to_vec()))]),
+		];
+		InMemoryBackend::<BlakeTwo256>::from((storage, StateVersion::V1))
+	}
+
+	fn proof_response(proof: StorageProof) -> Vec<u8> {
+		RemoteStorageResponse::Proof(proof).encode()
+	}
+
+	#[test]
+	fn check_response_returns_values_in_key_order() {
+		let backend = backend();
+		let root = *backend.root();
+		let keys = vec![MISSING_KEY.to_vec(), KEY.to_vec()];
+		let response = proof_response(prove_read(backend, &keys).unwrap());
+
+		assert_eq!(
+			check_response::<BlakeTwo256>(&response, root, None, &keys).unwrap(),
+			vec![None, Some(VALUE.to_vec())],
+		);
+	}
+
+	#[test]
+	fn check_response_reads_child_trie() {
+		let child_info = ChildInfo::new_default(b"child");
+		let backend = backend();
+		let root = *backend.root();
+		let keys = vec![KEY.to_vec()];
+		let response = proof_response(prove_child_read(backend, &child_info, &keys).unwrap());
+
+		assert_eq!(
+			check_response::<BlakeTwo256>(&response, root, Some(&child_info), &keys).unwrap(),
+			vec![Some(VALUE.to_vec())],
+		);
+	}
+
+	#[test]
+	fn check_response_rejects_proof_for_other_root() {
+		let backend = backend();
+		let keys = vec![KEY.to_vec()];
+		let response = proof_response(prove_read(backend, &keys).unwrap());
+
+		assert!(matches!(
+			check_response::<BlakeTwo256>(&response, Default::default(), None, &keys),
+			Err(RequestError::InvalidProof(_)),
+		));
+	}
+
+	#[test]
+	fn check_response_rejects_unavailable_and_garbage() {
+		let root = *backend().root();
+		let keys = vec![KEY.to_vec()];
+
+		assert!(matches!(
+			check_response::<BlakeTwo256>(
+				&RemoteStorageResponse::Unavailable.encode(),
+				root,
+				None,
+				&keys,
+			),
+			Err(RequestError::MissingProof),
+		));
+		assert!(matches!(
+			check_response::<BlakeTwo256>(&[0xff, 0xff], root, None, &keys),
+			Err(RequestError::Codec(_)),
+		));
+	}
+
+	#[test]
+	fn request_roundtrips() {
+		let child_info = ChildInfo::new_default(b"child");
+		let request = RemoteStorageRequest {
+			block: sp_core::H256::repeat_byte(1),
+			child_storage_key: Some(child_info.prefixed_storage_key().into_inner()),
+			keys: vec![KEY.to_vec()],
+		};
+		assert_eq!(RemoteStorageRequest::decode(&mut &request.encode()[..]).unwrap(), request);
+	}
+}
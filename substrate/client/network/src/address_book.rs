@@ -0,0 +1,366 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Persistent book of known peer addresses.
+//!
+//! Addresses of identified peers supporting the Kademlia protocol of the chain are recorded
+//! together with the time they were last seen and the protocols they support, and written to the
+//! network configuration directory on a background thread. On startup the book is used to seed the
+//! DHT and the peer store, so a restarted node doesn't have to go through the bootnodes again.
+
+use sc_network_types::{multiaddr::Multiaddr, PeerId};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashMap,
+	fs,
+	path::{Path, PathBuf},
+	str::FromStr,
+	sync::mpsc::{self, SyncSender, TrySendError},
+	thread::{self, JoinHandle},
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Log target for this file.
+const LOG_TARGET: &str = "sub-libp2p::address-book";
+
+/// Name of the address book file in the network configuration directory.
+const ADDRESS_BOOK_FILE: &str = "known_peers.json";
+
+/// Maximum number of peers kept in the address book.
+const MAX_PEERS: usize = 1000;
+
+/// Maximum number of addresses kept per peer.
+const MAX_ADDRESSES_PER_PEER: usize = 10;
+
+/// Peers not seen for this long are dropped from the address book.
+const FORGET_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Minimum interval between two writes of the address book to disk.
+const PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Known addresses of a peer as persisted on disk.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Entry {
+	/// Listen addresses of the peer.
+	addresses: Vec<String>,
+	/// Seconds since the Unix epoch the peer was last seen at.
+	last_seen: u64,
+	/// Protocols supported by the peer.
+	protocols: Vec<String>,
+}
+
+/// Address book as written to disk, keyed by base58 peer ID.
+type Persisted = HashMap<String, Entry>;
+
+/// Book of known peer addresses, optionally persisted to disk.
+#[derive(Debug)]
+pub struct AddressBook {
+	/// Thread writing the address book to disk, `None` keeps it in memory only.
+	writer: Option<(SyncSender<Persisted>, JoinHandle<()>)>,
+	/// Kademlia protocols of the chain. Only peers supporting one of them are recorded.
+	kademlia_protocols: Vec<String>,
+	/// Known peers.
+	peers: HashMap<PeerId, Entry>,
+	/// Whether there are changes not yet written to disk.
+	dirty: bool,
+	/// Instant the address book was last written to disk.
+	last_persist: Instant,
+}
+
+impl AddressBook {
+	/// Create an address book persisted to `ADDRESS_BOOK_FILE` in `net_config_path`, loading
+	/// the entries persisted by a previous run. Only peers supporting one of
+	/// `kademlia_protocols` are recorded and returned by [`Self::known_addresses`].
+	///
+	/// Missing or malformed files are not fatal, the address book just starts empty.
+	pub fn load(net_config_path: Option<&Path>, kademlia_protocols: Vec<String>) -> Self {
+		let path = net_config_path.map(|path| path.join(ADDRESS_BOOK_FILE));
+		let persisted: Persisted = match path.as_ref().map(fs::read) {
+			Some(Ok(data)) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+				log::warn!(target: LOG_TARGET, "Failed to decode the address book: {e}");
+				Default::default()
+			}),
+			Some(Err(e)) if e.kind() != std::io::ErrorKind::NotFound => {
+				log::warn!(target: LOG_TARGET, "Failed to read the address book: {e}");
+				Default::default()
+			},
+			_ => Default::default(),
+		};
+
+		let now = unix_time();
+		let peers = persisted
+			.into_iter()
+			.filter(|(_, entry)| entry.last_seen + FORGET_AFTER.as_secs() > now)
+			.filter(|(_, entry)| supports_any(&entry.protocols, &kademlia_protocols))
+			.filter_map(|(peer_id, entry)| Some((PeerId::from_str(&peer_id).ok()?, entry)))
+			.collect::<HashMap<_, _>>();
+
+		log::debug!(target: LOG_TARGET, "Loaded {} known peers", peers.len());
+
+		let writer = path.and_then(|path| {
+			spawn_writer(path)
+				.map_err(
+					|e| log::warn!(target: LOG_TARGET, "Failed to spawn the address book writer: {e}"),
+				)
+				.ok()
+		});
+
+		Self { writer, kademlia_protocols, peers, dirty: false, last_persist: Instant::now() }
+	}
+
+	/// Known peers and their addresses, most recently seen first.
+	pub fn known_addresses(&self) -> Vec<(PeerId, Vec<Multiaddr>)> {
+		let mut peers = self.peers.iter().collect::<Vec<_>>();
+		peers.sort_by(|(_, a), (_, b)| b.last_seen.cmp(&a.last_seen));
+
+		peers
+			.into_iter()
+			.map(|(peer_id, entry)| {
+				let addresses = entry
+					.addresses
+					.iter()
+					.filter_map(|address| Multiaddr::from_str(address).ok())
+					.collect();
+				(*peer_id, addresses)
+			})
+			.collect()
+	}
+
+	/// Record the listen addresses and supported protocols of an identified peer. Peers not
+	/// supporting the Kademlia protocol of the chain are forgotten.
+	pub fn on_peer_identified(
+		&mut self,
+		peer_id: PeerId,
+		addresses: impl IntoIterator<Item = Multiaddr>,
+		protocols: impl IntoIterator<Item = String>,
+	) {
+		if self.writer.is_none() {
+			return
+		}
+
+		let protocols = protocols.into_iter().collect::<Vec<_>>();
+		if !supports_any(&protocols, &self.kademlia_protocols) {
+			if self.peers.remove(&peer_id).is_some() {
+				self.dirty = true;
+			}
+			return
+		}
+
+		let mut addresses =
+			addresses.into_iter().map(|address| address.to_string()).collect::<Vec<_>>();
+		if addresses.is_empty() {
+			return
+		}
+		addresses.truncate(MAX_ADDRESSES_PER_PEER);
+
+		let entry = self.peers.entry(peer_id).or_default();
+		entry.addresses = addresses;
+		entry.protocols = protocols;
+		entry.last_seen = unix_time();
+		self.dirty = true;
+
+		if self.peers.len() > MAX_PEERS {
+			// Forget the peer that wasn't seen for the longest time.
+			if let Some(oldest) = self
+				.peers
+				.iter()
+				.min_by_key(|(_, entry)| entry.last_seen)
+				.map(|(peer, _)| *peer)
+			{
+				self.peers.remove(&oldest);
+			}
+		}
+	}
+
+	/// Write the address book to disk if it changed and it wasn't written recently.
+	pub fn maybe_persist(&mut self) {
+		if self.dirty && self.last_persist.elapsed() >= PERSIST_INTERVAL {
+			self.persist();
+		}
+	}
+
+	/// Hand the address book to the writer thread if it changed. If the previous write is still
+	/// in progress, the address book is kept dirty and written on a later call.
+	pub fn persist(&mut self) {
+		let Some((sender, _)) = &self.writer else { return };
+		if !self.dirty {
+			return
+		}
+
+		match sender.try_send(self.snapshot()) {
+			Ok(()) => {
+				self.dirty = false;
+				self.last_persist = Instant::now();
+			},
+			Err(TrySendError::Full(_)) =>
+				log::trace!(target: LOG_TARGET, "Address book write in progress, retrying later"),
+			Err(TrySendError::Disconnected(_)) => {
+				log::warn!(target: LOG_TARGET, "Address book writer terminated");
+				self.writer = None;
+			},
+		}
+	}
+
+	/// Entries of the address book as written to disk.
+	fn snapshot(&self) -> Persisted {
+		self.peers
+			.iter()
+			.map(|(peer_id, entry)| (peer_id.to_base58(), entry.clone()))
+			.collect()
+	}
+}
+
+impl Drop for AddressBook {
+	fn drop(&mut self) {
+		let Some((sender, handle)) = self.writer.take() else { return };
+		// Flush the pending changes and wait for the writer to finish, so nothing is lost on
+		// shutdown.
+		if self.dirty {
+			let _ = sender.send(self.snapshot());
+		}
+		drop(sender);
+		if handle.join().is_err() {
+			log::warn!(target: LOG_TARGET, "Address book writer panicked");
+		}
+	}
+}
+
+/// Spawn the thread writing the snapshots it receives to `path`.
+fn spawn_writer(path: PathBuf) -> std::io::Result<(SyncSender<Persisted>, JoinHandle<()>)> {
+	let (sender, receiver) = mpsc::sync_channel::<Persisted>(1);
+	let handle = thread::Builder::new().name("address-book".into()).spawn(move || {
+		for persisted in receiver {
+			write(&path, &persisted);
+		}
+	})?;
+	Ok((sender, handle))
+}
+
+/// Write the address book to `path`.
+fn write(path: &Path, persisted: &Persisted) {
+	// Write next to the target first and then rename, so a crash never leaves a truncated
+	// file behind.
+	let result = serde_json::to_vec(persisted).map_err(std::io::Error::from).and_then(|data| {
+		let tmp_path = path.with_extension("tmp");
+		fs::write(&tmp_path, data)?;
+		fs::rename(&tmp_path, path)
+	});
+
+	match result {
+		Ok(()) => log::trace!(target: LOG_TARGET, "Persisted {} known peers", persisted.len()),
+		Err(e) => log::warn!(target: LOG_TARGET, "Failed to persist the address book: {e}"),
+	}
+}
+
+/// Whether `protocols` contains any of `wanted`.
+fn supports_any(protocols: &[String], wanted: &[String]) -> bool {
+	protocols.iter().any(|protocol| wanted.contains(protocol))
+}
+
+/// Current time in seconds since the Unix epoch.
+fn unix_time() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const KADEMLIA: &str = "/dot/kad";
+
+	fn load(path: Option<&Path>) -> AddressBook {
+		AddressBook::load(path, vec![KADEMLIA.into()])
+	}
+
+	#[test]
+	fn address_book_survives_restart() {
+		let dir = tempfile::tempdir().unwrap();
+		let peer = PeerId::random();
+		let address: Multiaddr = "/ip4/127.0.0.1/tcp/30333".parse().unwrap();
+		let protocols = vec!["/ipfs/ping/1.0.0".to_string(), KADEMLIA.to_string()];
+
+		let mut book = load(Some(dir.path()));
+		book.on_peer_identified(peer, vec![address.clone()], protocols.clone());
+		drop(book);
+
+		let book = load(Some(dir.path()));
+		assert_eq!(book.known_addresses(), vec![(peer, vec![address])]);
+		assert_eq!(book.peers[&peer].protocols, protocols);
+	}
+
+	#[test]
+	fn in_memory_address_book_records_nothing() {
+		let mut book = load(None);
+		book.on_peer_identified(
+			PeerId::random(),
+			vec!["/ip4/127.0.0.1/tcp/30333".parse().unwrap()],
+			vec![KADEMLIA.into()],
+		);
+
+		assert!(book.known_addresses().is_empty());
+	}
+
+	#[test]
+	fn peers_of_other_chains_are_not_recorded() {
+		let dir = tempfile::tempdir().unwrap();
+		let peer = PeerId::random();
+		let address: Multiaddr = "/ip4/127.0.0.1/tcp/30333".parse().unwrap();
+
+		let mut book = load(Some(dir.path()));
+		book.on_peer_identified(peer, vec![address.clone()], vec!["/ksmcc3/kad".into()]);
+		assert!(book.known_addresses().is_empty());
+
+		// A recorded peer which stops supporting the protocol is forgotten.
+		book.on_peer_identified(peer, vec![address.clone()], vec![KADEMLIA.into()]);
+		assert_eq!(book.known_addresses(), vec![(peer, vec![address.clone()])]);
+		book.on_peer_identified(peer, vec![address], vec!["/ksmcc3/kad".into()]);
+		assert!(book.known_addresses().is_empty());
+	}
+
+	#[test]
+	fn persisted_peers_of_other_chains_are_ignored() {
+		let dir = tempfile::tempdir().unwrap();
+		let mut book = load(Some(dir.path()));
+		book.on_peer_identified(
+			PeerId::random(),
+			vec!["/ip4/127.0.0.1/tcp/30333".parse().unwrap()],
+			vec![KADEMLIA.into()],
+		);
+		drop(book);
+
+		let book = AddressBook::load(Some(dir.path()), vec!["/ksmcc3/kad".into()]);
+		assert!(book.known_addresses().is_empty());
+	}
+
+	#[test]
+	fn stale_peers_are_forgotten() {
+		let dir = tempfile::tempdir().unwrap();
+		let mut book = load(Some(dir.path()));
+		book.on_peer_identified(
+			PeerId::random(),
+			vec!["/ip4/127.0.0.1/tcp/30333".parse().unwrap()],
+			vec![KADEMLIA.into()],
+		);
+		book.peers.values_mut().for_each(|entry| entry.last_seen = 0);
+		drop(book);
+
+		assert!(load(Some(dir.path())).known_addresses().is_empty());
+	}
+}
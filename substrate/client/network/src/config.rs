@@ -23,7 +23,7 @@
 
 pub use crate::{
 	discovery::DEFAULT_KADEMLIA_REPLICATION_FACTOR,
-	peer_store::PeerStoreProvider,
+	peer_store::{PeerStoreConfig, PeerStoreProvider},
 	protocol::{notification_service, NotificationsSink, ProtocolHandlePair},
 	request_responses::{
 		IncomingRequest, OutgoingResponse, ProtocolConfig as RequestResponseConfig,
@@ -673,6 +673,9 @@ pub struct NetworkConfiguration {
 
 	/// Networking backend used for P2P communication.
 	pub network_backend: NetworkBackendType,
+
+	/// Peer reputation and banning policy.
+	pub peer_store: PeerStoreConfig,
 }
 
 impl NetworkConfiguration {
@@ -706,6 +709,7 @@ impl NetworkConfiguration {
 			yamux_window_size: None,
 			ipfs_server: false,
 			network_backend: NetworkBackendType::Libp2p,
+			peer_store: Default::default(),
 		}
 	}
 
@@ -796,7 +800,7 @@ impl<B: BlockT + 'static, H: ExHashT, N: NetworkBackend<B, H>> FullNetworkConfig
 	/// Create new [`FullNetworkConfiguration`].
 	pub fn new(network_config: &NetworkConfiguration) -> Self {
 		let bootnodes = network_config.boot_nodes.iter().map(|bootnode| bootnode.peer_id).collect();
-		let peer_store = N::peer_store(bootnodes);
+		let peer_store = N::peer_store(bootnodes, network_config.peer_store.clone());
 		let peer_store_handle = peer_store.handle();
 
 		Self {
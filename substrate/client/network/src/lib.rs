@@ -242,6 +242,7 @@
 //!
 //! More precise usage details are still being worked on and will likely change in the future.
 
+mod address_book;
 mod behaviour;
 mod bitswap;
 mod litep2p;
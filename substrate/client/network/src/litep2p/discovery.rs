@@ -188,12 +188,12 @@ pub struct Discovery {
 }
 
 /// Legacy (fallback) Kademlia protocol name based on `protocol_id`.
-fn legacy_kademlia_protocol_name(id: &ProtocolId) -> ProtocolName {
+pub(crate) fn legacy_kademlia_protocol_name(id: &ProtocolId) -> ProtocolName {
 	ProtocolName::from(format!("/{}/kad", id.as_ref()))
 }
 
 /// Kademlia protocol name based on `genesis_hash` and `fork_id`.
-fn kademlia_protocol_name<Hash: AsRef<[u8]>>(
+pub(crate) fn kademlia_protocol_name<Hash: AsRef<[u8]>>(
 	genesis_hash: Hash,
 	fork_id: Option<&str>,
 ) -> ProtocolName {
@@ -19,14 +19,17 @@
 //! `NetworkBackend` implementation for `litep2p`.
 
 use crate::{
+	address_book::AddressBook,
 	config::{
 		FullNetworkConfiguration, IncomingRequest, NodeKeyConfig, NotificationHandshake, Params,
-		SetConfig, TransportConfig,
+		PeerStoreConfig, SetConfig, TransportConfig,
 	},
 	error::Error,
 	event::{DhtEvent, Event},
 	litep2p::{
-		discovery::{Discovery, DiscoveryEvent},
+		discovery::{
+			kademlia_protocol_name, legacy_kademlia_protocol_name, Discovery, DiscoveryEvent,
+		},
 		peerstore::Peerstore,
 		service::{Litep2pNetworkService, NetworkServiceCommand},
 		shim::{
@@ -186,6 +189,9 @@ pub struct Litep2pNetworkBackend {
 
 	/// External addresses.
 	external_addresses: Arc<RwLock<HashSet<Multiaddr>>>,
+
+	/// Addresses of identified peers, persisted across restarts.
+	address_book: AddressBook,
 }
 
 impl Litep2pNetworkBackend {
@@ -433,7 +439,26 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkBackend<B, H> for Litep2pNetworkBac
 
 		let mut config_builder =
 			Self::configure_transport(&params.network_config).with_keypair(keypair.clone());
-		let known_addresses = params.network_config.known_addresses();
+		let kademlia_protocols = [
+			kademlia_protocol_name(params.genesis_hash, params.fork_id.as_deref()),
+			legacy_kademlia_protocol_name(&params.protocol_id),
+		]
+		.into_iter()
+		.map(|protocol| protocol.to_string())
+		.collect();
+		let address_book = AddressBook::load(
+			params.network_config.network_config.net_config_path.as_deref(),
+			kademlia_protocols,
+		);
+		// seed the DHT and the peer store with the addresses persisted by a previous run
+		let known_addresses = params
+			.network_config
+			.known_addresses()
+			.into_iter()
+			.chain(address_book.known_addresses().into_iter().flat_map(|(peer, addresses)| {
+				addresses.into_iter().map(move |address| (peer, address))
+			}))
+			.collect::<Vec<_>>();
 		let peer_store_handle = params.network_config.peer_store_handle();
 		let executor = Arc::new(Litep2pExecutor { executor: params.executor });
 
@@ -613,6 +638,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkBackend<B, H> for Litep2pNetworkBac
 			peers: HashMap::new(),
 			litep2p,
 			external_addresses,
+			address_book,
 		})
 	}
 
@@ -620,8 +646,11 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkBackend<B, H> for Litep2pNetworkBac
 		Arc::clone(&self.network_service)
 	}
 
-	fn peer_store(bootnodes: Vec<sc_network_types::PeerId>) -> Self::PeerStore {
-		Peerstore::new(bootnodes)
+	fn peer_store(
+		bootnodes: Vec<sc_network_types::PeerId>,
+		config: PeerStoreConfig,
+	) -> Self::PeerStore {
+		Peerstore::with_config(bootnodes, config)
 	}
 
 	fn register_notification_metrics(registry: Option<&Registry>) -> NotificationMetrics {
@@ -685,6 +714,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkBackend<B, H> for Litep2pNetworkBac
 				.get(&self.block_announce_protocol)
 				.map_or(0usize, |handle| handle.connected_peers.load(Ordering::Relaxed));
 			self.num_connected.store(num_connected_peers, Ordering::Relaxed);
+			self.address_book.maybe_persist();
 
 			tokio::select! {
 				command = self.cmd_rx.next() => match command {
@@ -900,6 +930,11 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkBackend<B, H> for Litep2pNetworkBac
 						}
 					}
 					Some(DiscoveryEvent::Identified { peer, listen_addresses, supported_protocols, .. }) => {
+						self.address_book.on_peer_identified(
+							peer.into(),
+							listen_addresses.iter().cloned().map(Into::into),
+							supported_protocols.iter().map(|protocol| protocol.to_string()),
+						);
 						self.discovery.add_self_reported_address(peer, supported_protocols, listen_addresses).await;
 					}
 					Some(DiscoveryEvent::ExternalAddressDiscovered { address }) => {
@@ -22,7 +22,10 @@
 //! such as their addresses, reputations, supported protocols etc.
 
 use crate::{
-	peer_store::{PeerStoreProvider, ProtocolHandle},
+	peer_store::{
+		load_reputations, PeerStoreConfig, PeerStoreProvider, ProtocolHandle, ReputationsWriter,
+		PERSIST_INTERVAL,
+	},
 	service::traits::PeerStore,
 	ObservedRole, ReputationChange,
 };
@@ -75,10 +78,6 @@ impl Default for PeerInfo {
 }
 
 impl PeerInfo {
-	fn is_banned(&self) -> bool {
-		self.reputation < BANNED_THRESHOLD
-	}
-
 	fn decay_reputation(&mut self, seconds_passed: u64) {
 		// Note that decaying the reputation value happens "on its own",
 		// so we don't do `bump_last_updated()`.
@@ -103,6 +102,14 @@ impl PeerInfo {
 pub struct PeerstoreHandleInner {
 	peers: HashMap<PeerId, PeerInfo>,
 	protocols: Vec<Arc<dyn ProtocolHandle>>,
+	config: PeerStoreConfig,
+}
+
+impl PeerstoreHandleInner {
+	fn is_banned(&self, peer: &PeerId) -> bool {
+		let reputation = self.peers.get(peer).map_or(0i32, |info| info.reputation);
+		self.config.is_banned(peer, reputation, BANNED_THRESHOLD)
+	}
 }
 
 #[derive(Debug, Clone, Default)]
@@ -138,11 +145,26 @@ impl PeerstoreHandle {
 		lock.peers
 			.retain(|_, info| info.reputation != 0 || info.last_updated + FORGET_AFTER > now);
 	}
+
+	/// Returns the path the peer reputations are persisted to, if enabled.
+	fn persistence_path(&self) -> Option<std::path::PathBuf> {
+		self.0.lock().config.persistence_path.clone()
+	}
+
+	/// Returns the reputations of the known peers.
+	fn reputations(&self) -> Vec<(PeerId, i32)> {
+		self.0
+			.lock()
+			.peers
+			.iter()
+			.map(|(peer, info)| (*peer, info.reputation))
+			.collect()
+	}
 }
 
 impl PeerStoreProvider for PeerstoreHandle {
 	fn is_banned(&self, peer: &PeerId) -> bool {
-		self.0.lock().peers.get(peer).map_or(false, |info| info.is_banned())
+		self.0.lock().is_banned(peer)
 	}
 
 	/// Register a protocol handle to disconnect peers whose reputation drops below the threshold.
@@ -161,28 +183,20 @@ impl PeerStoreProvider for PeerstoreHandle {
 
 		log::trace!(target: LOG_TARGET, "report peer {reputation_change:?}");
 
+		let value = lock.config.reputation_change(&reputation_change);
 		match lock.peers.get_mut(&peer) {
 			Some(info) => {
-				info.reputation = info.reputation.saturating_add(reputation_change.value);
+				info.reputation = info.reputation.saturating_add(value);
 			},
 			None => {
 				lock.peers.insert(
 					peer,
-					PeerInfo {
-						reputation: reputation_change.value,
-						last_updated: Instant::now(),
-						role: None,
-					},
+					PeerInfo { reputation: value, last_updated: Instant::now(), role: None },
 				);
 			},
 		}
 
-		if lock
-			.peers
-			.get(&peer)
-			.expect("peer exist since it was just modified; qed")
-			.is_banned()
-		{
+		if lock.is_banned(&peer) {
 			log::warn!(target: LOG_TARGET, "{peer:?} banned, disconnecting, reason: {}", reputation_change.reason);
 
 			for sender in &lock.protocols {
@@ -214,7 +228,9 @@ impl PeerStoreProvider for PeerstoreHandle {
 			.peers
 			.iter()
 			.filter_map(|(peer, info)| {
-				(!ignored.contains(&peer) && !info.is_banned()).then_some((*peer, info.reputation))
+				(!ignored.contains(&peer) &&
+					!handle.config.is_banned(peer, info.reputation, BANNED_THRESHOLD))
+				.then_some((*peer, info.reputation))
 			})
 			.collect::<Vec<(PeerId, _)>>();
 		candidates.sort_by(|(_, a), (_, b)| b.cmp(a));
@@ -256,11 +272,26 @@ pub struct Peerstore {
 impl Peerstore {
 	/// Create new [`Peerstore`].
 	pub fn new(bootnodes: Vec<PeerId>) -> Self {
-		let peerstore_handle = PeerstoreHandle(Arc::new(Mutex::new(Default::default())));
+		Self::with_config(bootnodes, Default::default())
+	}
+
+	/// Create new [`Peerstore`] with the given reputation policy.
+	///
+	/// Reputations persisted by a previous run are restored if persistence is enabled.
+	pub fn with_config(bootnodes: Vec<PeerId>, config: PeerStoreConfig) -> Self {
+		let persisted =
+			config.persistence_path.as_deref().map(load_reputations).unwrap_or_default();
+		let peerstore_handle = PeerstoreHandle(Arc::new(Mutex::new(PeerstoreHandleInner {
+			config,
+			..Default::default()
+		})));
 
 		for bootnode in bootnodes {
 			peerstore_handle.add_known_peer(bootnode);
 		}
+		for (peer, reputation) in persisted {
+			peerstore_handle.0.lock().peers.entry(peer).or_default().reputation = reputation;
+		}
 
 		Self { peerstore_handle }
 	}
@@ -288,6 +319,8 @@ impl Peerstore {
 	async fn run(self) {
 		let started = Instant::now();
 		let mut latest_time_update = started;
+		let mut latest_persist = started;
+		let mut writer = ReputationsWriter::new(self.peerstore_handle.persistence_path());
 
 		loop {
 			let now = Instant::now();
@@ -301,6 +334,12 @@ impl Peerstore {
 			};
 
 			self.peerstore_handle.progress_time(seconds_passed);
+
+			if writer.is_enabled() && now - latest_persist >= PERSIST_INTERVAL {
+				latest_persist = now;
+				writer.persist(self.peerstore_handle.reputations());
+			}
+
 			let _ = Delay::new(Duration::from_secs(1)).await;
 		}
 	}
@@ -26,11 +26,19 @@ use log::trace;
 use parking_lot::Mutex;
 use partial_sort::PartialSort;
 use sc_network_common::{role::ObservedRole, types::ReputationChange};
+use serde::{Deserialize, Serialize};
 use std::{
 	cmp::{Ord, Ordering, PartialOrd},
 	collections::{hash_map::Entry, HashMap, HashSet},
 	fmt::Debug,
-	sync::Arc,
+	fs,
+	path::{Path, PathBuf},
+	str::FromStr,
+	sync::{
+		mpsc::{self, SyncSender, TrySendError},
+		Arc,
+	},
+	thread::{self, JoinHandle},
 	time::{Duration, Instant},
 };
 use wasm_timer::Delay;
@@ -58,6 +66,186 @@ const INVERSE_DECREMENT: i32 = 200;
 /// Amount of time between the moment we last updated the [`PeerStore`] entry and the moment we
 /// remove it, once the reputation value reaches 0.
 const FORGET_AFTER: Duration = Duration::from_secs(3600);
+/// Interval of persisting peer reputations to disk, if enabled.
+pub(crate) const PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Reputation and banning policy of the peer store.
+///
+/// Shared by the libp2p and litep2p peer stores. The default value keeps the built-in
+/// reputation rules of the backend and doesn't persist anything.
+#[derive(Debug, Clone, Default)]
+pub struct PeerStoreConfig {
+	/// Reputation under which peers are banned. `None` uses the default of the backend.
+	pub banned_threshold: Option<i32>,
+	/// Reputation change values replacing the ones reported by protocols, keyed by the reason
+	/// of the change.
+	///
+	/// Reasons are specific to the protocol reporting the change, so this allows adjusting
+	/// penalties per protocol.
+	pub reputation_overrides: HashMap<String, i32>,
+	/// Peers that are never banned or disconnected because of their reputation.
+	pub allowed_peers: HashSet<sc_network_types::PeerId>,
+	/// Peers that are always banned.
+	pub banned_peers: HashSet<sc_network_types::PeerId>,
+	/// File the peer reputations are persisted to and restored from on startup. `None` keeps
+	/// them in memory only.
+	pub persistence_path: Option<PathBuf>,
+}
+
+impl PeerStoreConfig {
+	/// Reputation change value to apply for `change`, taking overrides into account.
+	pub(crate) fn reputation_change(&self, change: &ReputationChange) -> i32 {
+		self.reputation_overrides.get(change.reason).copied().unwrap_or(change.value)
+	}
+
+	/// Check whether a peer with the given `reputation` is banned.
+	pub(crate) fn is_banned(
+		&self,
+		peer_id: &sc_network_types::PeerId,
+		reputation: i32,
+		default_threshold: i32,
+	) -> bool {
+		if self.banned_peers.contains(peer_id) {
+			return true
+		}
+
+		!self.allowed_peers.contains(peer_id) &&
+			reputation < self.banned_threshold.unwrap_or(default_threshold)
+	}
+}
+
+/// Peer reputation as persisted on disk.
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedPeer {
+	peer_id: String,
+	reputation: i32,
+}
+
+/// Load persisted peer reputations from `path`.
+///
+/// Missing or malformed files are not fatal, the peer store just starts from scratch.
+pub(crate) fn load_reputations(path: &Path) -> Vec<(sc_network_types::PeerId, i32)> {
+	let persisted: Vec<PersistedPeer> = match fs::read(path) {
+		Ok(data) => match serde_json::from_slice(&data) {
+			Ok(persisted) => persisted,
+			Err(e) => {
+				log::warn!(
+					target: LOG_TARGET,
+					"Failed to decode peer reputations from {}: {e}",
+					path.display(),
+				);
+				return Vec::new()
+			},
+		},
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+		Err(e) => {
+			log::warn!(
+				target: LOG_TARGET,
+				"Failed to read peer reputations from {}: {e}",
+				path.display(),
+			);
+			return Vec::new()
+		},
+	};
+
+	persisted
+		.into_iter()
+		.filter_map(|peer| {
+			sc_network_types::PeerId::from_str(&peer.peer_id)
+				.ok()
+				.map(|peer_id| (peer_id, peer.reputation))
+		})
+		.collect()
+}
+
+/// Persist peer reputations to `path`.
+///
+/// The file is written next to the target first and then renamed, so a crash never leaves a
+/// truncated file behind.
+fn persist_reputations(
+	path: &Path,
+	reputations: impl Iterator<Item = (sc_network_types::PeerId, i32)>,
+) {
+	let persisted = reputations
+		.filter(|(_, reputation)| *reputation != 0)
+		.map(|(peer_id, reputation)| PersistedPeer { peer_id: peer_id.to_base58(), reputation })
+		.collect::<Vec<_>>();
+
+	let result = serde_json::to_vec(&persisted).map_err(std::io::Error::from).and_then(|data| {
+		let tmp_path = path.with_extension("tmp");
+		fs::write(&tmp_path, data)?;
+		fs::rename(&tmp_path, path)
+	});
+
+	if let Err(e) = result {
+		log::warn!(
+			target: LOG_TARGET,
+			"Failed to persist peer reputations to {}: {e}",
+			path.display(),
+		);
+	}
+}
+
+/// Writes the peer reputations it is handed to disk on a background thread, so the peer store
+/// event loop never blocks on the file system.
+pub(crate) struct ReputationsWriter {
+	writer: Option<(SyncSender<Vec<(sc_network_types::PeerId, i32)>>, JoinHandle<()>)>,
+}
+
+impl ReputationsWriter {
+	/// Spawn the thread writing the peer reputations to `path`, if persistence is enabled.
+	pub(crate) fn new(path: Option<PathBuf>) -> Self {
+		let writer = path.and_then(|path| {
+			let (sender, receiver) = mpsc::sync_channel::<Vec<_>>(1);
+			thread::Builder::new()
+				.name("peer-store".into())
+				.spawn(move || {
+					for reputations in receiver {
+						persist_reputations(&path, reputations.into_iter());
+					}
+				})
+				.map_err(
+					|e| log::warn!(target: LOG_TARGET, "Failed to spawn the peer store writer: {e}"),
+				)
+				.ok()
+				.map(|handle| (sender, handle))
+		});
+
+		Self { writer }
+	}
+
+	/// Whether the peer reputations are persisted.
+	pub(crate) fn is_enabled(&self) -> bool {
+		self.writer.is_some()
+	}
+
+	/// Hand the peer reputations to the writer thread. If the previous write is still in
+	/// progress, they are dropped and a later snapshot is written instead.
+	pub(crate) fn persist(&mut self, reputations: Vec<(sc_network_types::PeerId, i32)>) {
+		let Some((sender, _)) = &self.writer else { return };
+
+		match sender.try_send(reputations) {
+			Ok(()) => {},
+			Err(TrySendError::Full(_)) =>
+				log::trace!(target: LOG_TARGET, "Peer reputations write in progress, skipping"),
+			Err(TrySendError::Disconnected(_)) => {
+				log::warn!(target: LOG_TARGET, "Peer store writer terminated");
+				self.writer = None;
+			},
+		}
+	}
+}
+
+impl Drop for ReputationsWriter {
+	fn drop(&mut self) {
+		let Some((sender, handle)) = self.writer.take() else { return };
+		// Wait for the write in progress to finish, so the file is complete on shutdown.
+		drop(sender);
+		if handle.join().is_err() {
+			log::warn!(target: LOG_TARGET, "Peer store writer panicked");
+		}
+	}
+}
 
 /// Trait describing the required functionality from a `Peerset` handle.
 pub trait ProtocolHandle: Debug + Send + Sync {
@@ -205,10 +393,6 @@ impl PartialOrd for PeerInfo {
 }
 
 impl PeerInfo {
-	fn is_banned(&self) -> bool {
-		self.reputation < BANNED_THRESHOLD
-	}
-
 	fn add_reputation(&mut self, increment: i32) {
 		self.reputation = self.reputation.saturating_add(increment);
 		self.bump_last_updated();
@@ -242,11 +426,17 @@ impl PeerInfo {
 struct PeerStoreInner {
 	peers: HashMap<PeerId, PeerInfo>,
 	protocols: Vec<Arc<dyn ProtocolHandle>>,
+	config: PeerStoreConfig,
 }
 
 impl PeerStoreInner {
 	fn is_banned(&self, peer_id: &PeerId) -> bool {
-		self.peers.get(peer_id).map_or(false, |info| info.is_banned())
+		let reputation = self.peers.get(peer_id).map_or(0, |info| info.reputation);
+		self.is_banned_with(peer_id, reputation)
+	}
+
+	fn is_banned_with(&self, peer_id: &PeerId, reputation: i32) -> bool {
+		self.config.is_banned(&(*peer_id).into(), reputation, BANNED_THRESHOLD)
 	}
 
 	fn register_protocol(&mut self, protocol_handle: Arc<dyn ProtocolHandle>) {
@@ -267,18 +457,20 @@ impl PeerStoreInner {
 	}
 
 	fn report_peer(&mut self, peer_id: PeerId, change: ReputationChange) {
+		let value = self.config.reputation_change(&change);
 		let peer_info = self.peers.entry(peer_id).or_default();
-		peer_info.add_reputation(change.value);
+		peer_info.add_reputation(value);
+		let reputation = peer_info.reputation;
 
-		if peer_info.reputation < BANNED_THRESHOLD {
+		if self.is_banned_with(&peer_id, reputation) {
 			self.protocols.iter().for_each(|handle| handle.disconnect_peer(peer_id.into()));
 
 			log::warn!(
 				target: LOG_TARGET,
 				"Report {}: {:+} to {}. Reason: {}. Banned, disconnecting.",
 				peer_id,
-				change.value,
-				peer_info.reputation,
+				value,
+				reputation,
 				change.reason,
 			);
 		} else {
@@ -286,8 +478,8 @@ impl PeerStoreInner {
 				target: LOG_TARGET,
 				"Report {}: {:+} to {}. Reason: {}.",
 				peer_id,
-				change.value,
-				peer_info.reputation,
+				value,
+				reputation,
 				change.reason,
 			);
 		}
@@ -319,7 +511,8 @@ impl PeerStoreInner {
 			.peers
 			.iter()
 			.filter_map(|(peer_id, info)| {
-				(!info.is_banned() && !ignored.contains(peer_id)).then_some((*peer_id, *info))
+				(!self.is_banned_with(peer_id, info.reputation) && !ignored.contains(peer_id))
+					.then_some((*peer_id, *info))
 			})
 			.collect::<Vec<_>>();
 		let count = std::cmp::min(count, candidates.len());
@@ -371,14 +564,24 @@ pub struct PeerStore {
 impl PeerStore {
 	/// Create a new peer store from the list of bootnodes.
 	pub fn new(bootnodes: Vec<PeerId>) -> Self {
+		Self::with_config(bootnodes, Default::default())
+	}
+
+	/// Create a new peer store from the list of bootnodes and the reputation policy.
+	///
+	/// Reputations persisted by a previous run are restored if persistence is enabled.
+	pub fn with_config(bootnodes: Vec<PeerId>, config: PeerStoreConfig) -> Self {
+		let mut peers: HashMap<PeerId, PeerInfo> =
+			bootnodes.into_iter().map(|peer_id| (peer_id, PeerInfo::default())).collect();
+
+		if let Some(path) = &config.persistence_path {
+			for (peer_id, reputation) in load_reputations(path) {
+				peers.entry(peer_id.into()).or_default().reputation = reputation;
+			}
+		}
+
 		PeerStore {
-			inner: Arc::new(Mutex::new(PeerStoreInner {
-				peers: bootnodes
-					.into_iter()
-					.map(|peer_id| (peer_id, PeerInfo::default()))
-					.collect(),
-				protocols: Vec::new(),
-			})),
+			inner: Arc::new(Mutex::new(PeerStoreInner { peers, protocols: Vec::new(), config })),
 		}
 	}
 
@@ -391,6 +594,8 @@ impl PeerStore {
 	pub async fn run(self) {
 		let started = Instant::now();
 		let mut latest_time_update = started;
+		let mut latest_persist = started;
+		let mut writer = ReputationsWriter::new(self.inner.lock().config.persistence_path.clone());
 
 		loop {
 			let now = Instant::now();
@@ -403,7 +608,28 @@ impl PeerStore {
 				elapsed_now.as_secs() - elapsed_latest.as_secs()
 			};
 
-			self.inner.lock().progress_time(seconds_passed);
+			let to_persist = {
+				let mut inner = self.inner.lock();
+				inner.progress_time(seconds_passed);
+
+				if writer.is_enabled() && now - latest_persist >= PERSIST_INTERVAL {
+					latest_persist = now;
+					let reputations = inner
+						.peers
+						.iter()
+						.map(|(peer_id, info)| ((*peer_id).into(), info.reputation))
+						.collect::<Vec<_>>();
+					Some(reputations)
+				} else {
+					None
+				}
+			};
+
+			// Hand the reputations to the writer without holding the lock.
+			if let Some(reputations) = to_persist {
+				writer.persist(reputations);
+			}
+
 			let _ = Delay::new(Duration::from_secs(1)).await;
 		}
 	}
@@ -422,7 +648,9 @@ impl PeerStoreT for PeerStore {
 
 #[cfg(test)]
 mod tests {
-	use super::PeerInfo;
+	use super::{PeerInfo, PeerStore, PeerStoreConfig, PeerStoreProvider, BANNED_THRESHOLD};
+	use sc_network_common::types::ReputationChange;
+	use std::collections::HashSet;
 
 	#[test]
 	fn decaying_zero_reputation_yields_zero() {
@@ -489,4 +717,65 @@ mod tests {
 		peer_info.decay_reputation(SECONDS / 2);
 		assert_eq!(peer_info.reputation, 0);
 	}
+
+	#[test]
+	fn allowed_peers_are_never_banned() {
+		let allowed = sc_network_types::PeerId::random();
+		let other = sc_network_types::PeerId::random();
+		let config = PeerStoreConfig {
+			allowed_peers: [allowed].into_iter().collect(),
+			..Default::default()
+		};
+		let handle = PeerStore::with_config(Vec::new(), config).handle();
+
+		handle.report_peer(allowed, ReputationChange::new_fatal("test"));
+		handle.report_peer(other, ReputationChange::new_fatal("test"));
+
+		assert!(!handle.is_banned(&allowed));
+		assert!(handle.is_banned(&other));
+		assert_eq!(handle.outgoing_candidates(2, HashSet::new()), vec![allowed]);
+	}
+
+	#[test]
+	fn banned_peers_are_always_banned() {
+		let banned = sc_network_types::PeerId::random();
+		let config =
+			PeerStoreConfig { banned_peers: [banned].into_iter().collect(), ..Default::default() };
+		let handle = PeerStore::with_config(vec![banned.into()], config).handle();
+
+		assert!(handle.is_banned(&banned));
+		assert!(handle.outgoing_candidates(1, HashSet::new()).is_empty());
+	}
+
+	#[test]
+	fn reputation_overrides_and_threshold_are_applied() {
+		let peer = sc_network_types::PeerId::random();
+		let config = PeerStoreConfig {
+			banned_threshold: Some(-100),
+			reputation_overrides: [("Minor misbehavior".to_string(), -101)].into_iter().collect(),
+			..Default::default()
+		};
+		let handle = PeerStore::with_config(Vec::new(), config).handle();
+
+		handle.report_peer(peer, ReputationChange::new(-1, "Minor misbehavior"));
+
+		assert_eq!(handle.peer_reputation(&peer), -101);
+		assert!(-101 > BANNED_THRESHOLD);
+		assert!(handle.is_banned(&peer));
+	}
+
+	#[test]
+	fn reputations_are_restored_from_disk() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("peer_store.json");
+		let peer = sc_network_types::PeerId::random();
+
+		let mut writer = super::ReputationsWriter::new(Some(path.clone()));
+		writer.persist(vec![(peer, -1_000)]);
+		drop(writer);
+
+		let config = PeerStoreConfig { persistence_path: Some(path), ..Default::default() };
+		let handle = PeerStore::with_config(Vec::new(), config).handle();
+		assert_eq!(handle.peer_reputation(&peer), -1_000);
+	}
 }
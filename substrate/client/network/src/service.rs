@@ -28,19 +28,20 @@
 //! which is then processed by [`NetworkWorker::next_action`].
 
 use crate::{
+	address_book::AddressBook,
 	behaviour::{self, Behaviour, BehaviourOut},
 	bitswap::BitswapRequestHandler,
 	config::{
 		parse_addr, FullNetworkConfiguration, IncomingRequest, MultiaddrWithPeerId,
 		NonDefaultSetConfig, NotificationHandshake, Params, SetConfig, TransportConfig,
 	},
-	discovery::DiscoveryConfig,
+	discovery::{kademlia_protocol_name, legacy_kademlia_protocol_name, DiscoveryConfig},
 	error::Error,
 	event::{DhtEvent, Event},
 	network_state::{
 		NetworkState, NotConnectedPeer as NetworkStateNotConnectedPeer, Peer as NetworkStatePeer,
 	},
-	peer_store::{PeerStore, PeerStoreProvider},
+	peer_store::{PeerStore, PeerStoreConfig, PeerStoreProvider},
 	protocol::{self, NotifsHandlerError, Protocol, Ready},
 	protocol_controller::{self, ProtoSetConfig, ProtocolController, SetId},
 	request_responses::{IfDisconnected, ProtocolConfig as RequestResponseConfig, RequestFailure},
@@ -186,8 +187,11 @@ where
 	}
 
 	/// Create `PeerStore`.
-	fn peer_store(bootnodes: Vec<sc_network_types::PeerId>) -> Self::PeerStore {
-		PeerStore::new(bootnodes.into_iter().map(From::from).collect())
+	fn peer_store(
+		bootnodes: Vec<sc_network_types::PeerId>,
+		config: PeerStoreConfig,
+	) -> Self::PeerStore {
+		PeerStore::with_config(bootnodes.into_iter().map(From::from).collect(), config)
 	}
 
 	fn register_notification_metrics(registry: Option<&Registry>) -> NotificationMetrics {
@@ -629,6 +633,24 @@ where
 			);
 		}
 
+		// Seed the DHT and the peer store with the addresses persisted by a previous run. Only
+		// peers recorded as supporting the Kademlia protocol of this chain are returned, as
+		// `add_known_address` doesn't check the supported protocols.
+		let kademlia_protocols = [
+			kademlia_protocol_name(params.genesis_hash, params.fork_id.as_deref()),
+			legacy_kademlia_protocol_name(&params.protocol_id),
+		]
+		.into_iter()
+		.map(|protocol| String::from_utf8_lossy(&protocol).into_owned())
+		.collect();
+		let address_book =
+			AddressBook::load(network_config.net_config_path.as_deref(), kademlia_protocols);
+		for (peer_id, addresses) in address_book.known_addresses() {
+			for address in addresses {
+				swarm.behaviour_mut().add_known_address(peer_id.into(), address.into());
+			}
+		}
+
 		let listen_addresses_set = Arc::new(Mutex::new(HashSet::new()));
 
 		let service = Arc::new(NetworkService {
@@ -658,6 +680,7 @@ where
 			boot_node_ids,
 			reported_invalid_boot_nodes: Default::default(),
 			peer_store_handle: Arc::clone(&peer_store_handle),
+			address_book,
 			notif_protocol_handles,
 			_marker: Default::default(),
 			_block: Default::default(),
@@ -1374,6 +1397,8 @@ where
 	reported_invalid_boot_nodes: HashSet<PeerId>,
 	/// Peer reputation store handle.
 	peer_store_handle: Arc<dyn PeerStoreProvider>,
+	/// Addresses of identified peers, persisted across restarts.
+	address_book: AddressBook,
 	/// Notification protocol handles.
 	notif_protocol_handles: Vec<protocol::ProtocolHandle>,
 	/// Marker to pin the `H` generic. Serves no purpose except to not break backwards
@@ -1413,6 +1438,8 @@ where
 			},
 		};
 
+		self.address_book.maybe_persist();
+
 		// Update the `num_connected` count shared with the `NetworkService`.
 		let num_connected_peers = self.network_service.behaviour().user_protocol().num_sync_peers();
 		self.num_connected.store(num_connected_peers, Ordering::Relaxed);
@@ -1585,6 +1612,11 @@ where
 					);
 					listen_addrs.truncate(30);
 				}
+				self.address_book.on_peer_identified(
+					peer_id.into(),
+					listen_addrs.iter().cloned().map(Into::into),
+					protocols.iter().map(|protocol| protocol.to_string()),
+				);
 				for addr in listen_addrs {
 					self.network_service
 						.behaviour_mut()
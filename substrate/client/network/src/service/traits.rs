@@ -21,7 +21,10 @@
 //! Traits defined by `sc-network`.
 
 use crate::{
-	config::{IncomingRequest, MultiaddrWithPeerId, NotificationHandshake, Params, SetConfig},
+	config::{
+		IncomingRequest, MultiaddrWithPeerId, NotificationHandshake, Params, PeerStoreConfig,
+		SetConfig,
+	},
 	error::{self, Error},
 	event::Event,
 	network_state::NetworkState,
@@ -133,8 +136,8 @@ pub trait NetworkBackend<B: BlockT + 'static, H: ExHashT>: Send + 'static {
 	/// Get handle to `NetworkService` of the `NetworkBackend`.
 	fn network_service(&self) -> Arc<dyn NetworkService>;
 
-	/// Create [`PeerStore`].
-	fn peer_store(bootnodes: Vec<PeerId>) -> Self::PeerStore;
+	/// Create [`PeerStore`] applying the reputation policy in `config`.
+	fn peer_store(bootnodes: Vec<PeerId>, config: PeerStoreConfig) -> Self::PeerStore;
 
 	/// Register metrics that are used by the notification protocols.
 	fn register_notification_metrics(registry: Option<&Registry>) -> NotificationMetrics;
@@ -51,8 +51,8 @@ pub trait ChildStateApi<Hash> {
 	) -> Result<Vec<StorageKey>, Error>;
 
 	/// Returns a child storage entry at a specific block's state.
-	#[method(name = "childstate_getStorage", blocking)]
-	fn storage(
+	#[method(name = "childstate_getStorage")]
+	async fn storage(
 		&self,
 		child_storage_key: PrefixedStorageKey,
 		key: StorageKey,
@@ -69,8 +69,8 @@ pub trait ChildStateApi<Hash> {
 	) -> Result<Vec<Option<StorageData>>, Error>;
 
 	/// Returns the hash of a child storage entry at a block's state.
-	#[method(name = "childstate_getStorageHash", blocking)]
-	fn storage_hash(
+	#[method(name = "childstate_getStorageHash")]
+	async fn storage_hash(
 		&self,
 		child_storage_key: PrefixedStorageKey,
 		key: StorageKey,
@@ -78,8 +78,8 @@ pub trait ChildStateApi<Hash> {
 	) -> Result<Option<Hash>, Error>;
 
 	/// Returns the size of a child storage entry at a block's state.
-	#[method(name = "childstate_getStorageSize", blocking)]
-	fn storage_size(
+	#[method(name = "childstate_getStorageSize")]
+	async fn storage_size(
 		&self,
 		child_storage_key: PrefixedStorageKey,
 		key: StorageKey,
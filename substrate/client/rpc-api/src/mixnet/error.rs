@@ -33,6 +33,8 @@ impl From<Error> for ErrorObjectOwned {
 			sc_mixnet::Error::ServiceUnavailable => BASE_ERROR + 1,
 			sc_mixnet::Error::NoReply => BASE_ERROR + 2,
 			sc_mixnet::Error::BadReply => BASE_ERROR + 3,
+			sc_mixnet::Error::UnknownBlock => BASE_ERROR + 4,
+			sc_mixnet::Error::BadProof => BASE_ERROR + 5,
 			sc_mixnet::Error::Post(PostErr::TooManyFragments) => BASE_ERROR + 101,
 			sc_mixnet::Error::Post(PostErr::SessionMixnodesNotKnown(_)) => BASE_ERROR + 102,
 			sc_mixnet::Error::Post(PostErr::SessionDisabled(_)) => BASE_ERROR + 103,
@@ -22,11 +22,34 @@ pub mod error;
 
 use error::Error;
 use jsonrpsee::proc_macros::rpc;
+use serde::{Deserialize, Serialize};
 use sp_core::Bytes;
 
+/// Storage read over the mixnet, returned by `mixnet_readStorage`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRead {
+	/// SCALE-encoded hash of the block the storage was read at.
+	pub at: Bytes,
+	/// Values of the requested keys, in request order.
+	pub values: Vec<Option<Bytes>>,
+	/// A proof that the values are included in the storage trie of the block.
+	pub proof: Vec<Bytes>,
+}
+
 #[rpc(client, server)]
 pub trait MixnetApi {
 	/// Submit encoded extrinsic over the mixnet for inclusion in block.
 	#[method(name = "mixnet_submitExtrinsic")]
 	async fn submit_extrinsic(&self, extrinsic: Bytes) -> Result<(), Error>;
+
+	/// Read storage over the mixnet, hiding from the serving node who is reading the keys.
+	///
+	/// `at` is the SCALE-encoded hash of the block to read the storage at. If `None`, the best
+	/// block of the serving node is used. The returned values are checked against the state root
+	/// of the block, which must be known to the local node, so reads at the best block of the
+	/// serving node fail if the local node hasn't imported it yet.
+	#[method(name = "mixnet_readStorage")]
+	async fn read_storage(&self, keys: Vec<Bytes>, at: Option<Bytes>)
+		-> Result<StorageRead, Error>;
 }
@@ -29,6 +29,14 @@ pub enum Error {
 	/// Statement store internal error.
 	#[error("Statement store error")]
 	StatementStore(String),
+	/// Provided count exceeds maximum value.
+	#[error("count exceeds maximum value. value: {value}, max: {max}")]
+	InvalidCount {
+		/// Provided value
+		value: u32,
+		/// Maximum allowed value
+		max: u32,
+	},
 	/// Call to an unsafe RPC was denied.
 	#[error(transparent)]
 	UnsafeRpcCalled(#[from] crate::policy::UnsafeRpcError),
@@ -45,6 +53,8 @@ impl From<Error> for ErrorObjectOwned {
 				format!("Statement store error: {message}"),
 				None::<()>,
 			),
+			Error::InvalidCount { .. } =>
+				ErrorObject::owned(BASE_ERROR + 2, e.to_string(), None::<()>),
 			Error::UnsafeRpcCalled(e) => e.into(),
 		}
 	}
@@ -19,10 +19,47 @@
 //! Substrate Statement Store RPC API.
 
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
 use sp_core::Bytes;
 
 pub mod error;
 
+/// Filter selecting statements by topics and decryption key.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementFilter {
+	/// Only match statements which include all of these topics.
+	#[serde(default)]
+	pub match_all_topics: Vec<[u8; 32]>,
+	/// Only match statements whose decryption key is identified as `dest`. Statements with no
+	/// `DecryptionKey` field are matched if this is `None`.
+	#[serde(default)]
+	pub dest: Option<[u8; 32]>,
+}
+
+/// A page of statements returned by `statement_query`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementPage {
+	/// SCALE-encoded statements, ordered by hash.
+	pub statements: Vec<Bytes>,
+	/// Hash of the last returned statement, to be passed as `start_after` to get the next page.
+	/// `None` if there are no more statements.
+	pub next_start_after: Option<[u8; 32]>,
+}
+
+/// Statements stored on behalf of an account, returned by `statement_accountStats`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStats {
+	/// Account the statements were submitted by.
+	pub account: [u8; 32],
+	/// Number of statements.
+	pub statements: u32,
+	/// Total data size of the statements.
+	pub data_size: u64,
+}
+
 /// Substrate statement RPC API
 #[rpc(client, server)]
 pub trait StatementApi {
@@ -50,6 +87,24 @@ pub trait StatementApi {
 		dest: [u8; 32],
 	) -> RpcResult<Vec<Bytes>>;
 
+	/// Return up to `count` SCALE-encoded statements matching `filter`, ordered by hash and
+	/// starting after the statement with hash `start_after`.
+	#[method(name = "statement_query")]
+	fn query(
+		&self,
+		filter: StatementFilter,
+		start_after: Option<[u8; 32]>,
+		count: u32,
+	) -> RpcResult<StatementPage>;
+
+	/// Subscribe to new SCALE-encoded statements matching `filter`.
+	#[subscription(
+		name = "statement_subscribe" => "statement_statement",
+		unsubscribe = "statement_unsubscribe",
+		item = Bytes,
+	)]
+	fn subscribe(&self, filter: StatementFilter);
+
 	/// Submit a pre-encoded statement.
 	#[method(name = "statement_submit")]
 	fn submit(&self, encoded: Bytes) -> RpcResult<()>;
@@ -57,4 +112,13 @@ pub trait StatementApi {
 	/// Remove a statement from the store.
 	#[method(name = "statement_remove")]
 	fn remove(&self, statement_hash: [u8; 32]) -> RpcResult<()>;
+
+	/// Return the number and total data size of statements stored for each account.
+	#[method(name = "statement_accountStats")]
+	fn account_stats(&self) -> RpcResult<Vec<AccountStats>>;
+
+	/// Remove all statements submitted by `account` and matching `filter` from the store. Either
+	/// may be omitted to match any statement. Returns the number of removed statements.
+	#[method(name = "statement_purge")]
+	fn purge(&self, account: Option<[u8; 32]>, filter: Option<StatementFilter>) -> RpcResult<u32>;
 }
@@ -20,16 +20,24 @@
 
 use crate::{
 	archive::{error::Error as ArchiveError, ArchiveApiServer},
-	common::events::{ArchiveStorageResult, PaginatedStorageQuery},
-	hex_string, MethodResult,
+	common::events::{
+		ArchiveStorageDiffEvent, ArchiveStorageDiffItem, ArchiveStorageResult,
+		PaginatedStorageQuery,
+	},
+	hex_string, MethodResult, SubscriptionTaskExecutor,
 };
 
 use codec::Encode;
-use jsonrpsee::core::{async_trait, RpcResult};
+use futures::FutureExt;
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	PendingSubscriptionSink, SubscriptionSink,
+};
 use sc_client_api::{
 	Backend, BlockBackend, BlockchainEvents, CallExecutor, ChildInfo, ExecutorProvider, StorageKey,
 	StorageProvider,
 };
+use sc_rpc::utils::to_sub_message;
 use sp_api::{CallApiAt, CallContext};
 use sp_blockchain::{
 	Backend as BlockChainBackend, Error as BlockChainError, HeaderBackend, HeaderMetadata,
@@ -41,7 +49,7 @@ use sp_runtime::{
 };
 use std::{collections::HashSet, marker::PhantomData, sync::Arc};
 
-use super::archive_storage::ArchiveStorage;
+use super::archive_storage::{ArchiveStorage, ArchiveStorageDiff, DiffDetails};
 
 /// The configuration of [`Archive`].
 pub struct ArchiveConfig {
@@ -64,6 +72,10 @@ const MAX_DESCENDANT_RESPONSES: usize = 5;
 /// `MAX_DESCENDANT_RESPONSES`.
 const MAX_QUERIED_ITEMS: usize = 8;
 
+/// The number of `archive_storageDiff` events buffered before the iteration
+/// over the storage waits for the subscriber to catch up.
+const STORAGE_DIFF_BUFFER_SIZE: usize = 16;
+
 impl Default for ArchiveConfig {
 	fn default() -> Self {
 		Self {
@@ -79,6 +91,8 @@ pub struct Archive<BE: Backend<Block>, Block: BlockT, Client> {
 	client: Arc<Client>,
 	/// Backend of the chain.
 	backend: Arc<BE>,
+	/// Executor to spawn subscriptions.
+	executor: SubscriptionTaskExecutor,
 	/// The hexadecimal encoded hash of the genesis block.
 	genesis_hash: String,
 	/// The maximum number of items the `archive_storage` can return for a descendant query before
@@ -96,12 +110,14 @@ impl<BE: Backend<Block>, Block: BlockT, Client> Archive<BE, Block, Client> {
 		client: Arc<Client>,
 		backend: Arc<BE>,
 		genesis_hash: GenesisHash,
+		executor: SubscriptionTaskExecutor,
 		config: ArchiveConfig,
 	) -> Self {
 		let genesis_hash = hex_string(&genesis_hash.as_ref());
 		Self {
 			client,
 			backend,
+			executor,
 			genesis_hash,
 			storage_max_descendant_responses: config.max_descendant_responses,
 			storage_max_queried_items: config.max_queried_items,
@@ -122,6 +138,31 @@ fn parse_hex_param(param: String) -> Result<Vec<u8>, ArchiveError> {
 	array_bytes::hex2bytes(&param).map_err(|_| ArchiveError::InvalidParam(param))
 }
 
+/// Parse the hex-encoded items of the `archive_storageDiff` method.
+fn parse_diff_items(
+	items: Vec<ArchiveStorageDiffItem<String>>,
+) -> Result<Vec<DiffDetails>, ArchiveError> {
+	items
+		.into_iter()
+		.map(|item| {
+			let key = StorageKey(parse_hex_param(item.key)?);
+			let child_trie_key = item
+				.child_trie_key
+				.map(parse_hex_param)
+				.transpose()?
+				.map(ChildInfo::new_default_from_vec);
+
+			Ok(DiffDetails { key, return_type: item.return_type, child_trie_key })
+		})
+		.collect()
+}
+
+/// Report an error to the subscriber of the `archive_storageDiff` method.
+async fn send_diff_error(sink: &SubscriptionSink, error: String) {
+	let msg = to_sub_message(sink, &ArchiveStorageDiffEvent::err(error));
+	let _ = sink.send(msg).await;
+}
+
 #[async_trait]
 impl<BE, Block, Client> ArchiveApiServer<Block::Hash> for Archive<BE, Block, Client>
 where
@@ -277,4 +318,56 @@ where
 		);
 		Ok(storage_client.handle_query(hash, items, child_trie))
 	}
+
+	fn archive_unstable_storage_diff(
+		&self,
+		pending: PendingSubscriptionSink,
+		hash: Block::Hash,
+		items: Vec<ArchiveStorageDiffItem<String>>,
+		previous_hash: Option<Block::Hash>,
+	) {
+		let storage_client = ArchiveStorageDiff::new(self.client.clone());
+		let client = self.client.clone();
+		let executor = self.executor.clone();
+
+		let fut = async move {
+			let Ok(sink) = pending.accept().await else { return };
+
+			let items = match parse_diff_items(items) {
+				Ok(items) => items,
+				Err(error) => return send_diff_error(&sink, error.to_string()).await,
+			};
+
+			let previous_hash = match previous_hash {
+				Some(previous_hash) => previous_hash,
+				None => match client.header(hash) {
+					Ok(Some(header)) => *header.parent_hash(),
+					Ok(None) =>
+						return send_diff_error(
+							&sink,
+							format!("Block header is not present: {:?}", hash),
+						)
+						.await,
+					Err(error) => return send_diff_error(&sink, error.to_string()).await,
+				},
+			};
+
+			// The storage is iterated on a blocking task, while the events are forwarded
+			// to the subscriber as they are consumed.
+			let (tx, mut rx) = tokio::sync::mpsc::channel(STORAGE_DIFF_BUFFER_SIZE);
+			let diff_fut = async move {
+				storage_client.handle_trie_queries(hash, previous_hash, items, tx).await
+			};
+			executor.spawn_blocking("substrate-rpc-subscription", Some("rpc"), diff_fut.boxed());
+
+			while let Some(event) = rx.recv().await {
+				let msg = to_sub_message(&sink, &event);
+				if sink.send(msg).await.is_err() {
+					return
+				}
+			}
+		};
+
+		sc_rpc::utils::spawn_subscription_task(&self.executor, fut);
+	}
 }
@@ -16,16 +16,24 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-//! Implementation of the `archive_storage` method.
+//! Implementation of the `archive_storage` and `archive_storageDiff` methods.
 
-use std::sync::Arc;
+use std::{marker::PhantomData, sync::Arc};
 
-use sc_client_api::{Backend, ChildInfo, StorageKey, StorageProvider};
+use sc_client_api::{Backend, ChildInfo, MerkleValue, StorageKey, StorageProvider};
 use sp_runtime::traits::Block as BlockT;
+use tokio::sync::mpsc;
 
-use crate::common::{
-	events::{ArchiveStorageResult, PaginatedStorageQuery, StorageQueryType},
-	storage::{IterQueryType, QueryIter, Storage},
+use crate::{
+	common::{
+		events::{
+			ArchiveStorageDiffEvent, ArchiveStorageDiffOperationType, ArchiveStorageDiffResult,
+			ArchiveStorageDiffType, ArchiveStorageResult, PaginatedStorageQuery, StorageQueryType,
+			StorageResultType,
+		},
+		storage::{IterQueryType, QueryIter, Storage},
+	},
+	hex_string,
 };
 
 /// Generates the events of the `archive_storage` method.
@@ -127,3 +135,358 @@ where
 		ArchiveStorageResult::ok(storage_results, discarded_items)
 	}
 }
+
+/// A parsed item of the `archive_storageDiff` method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffDetails {
+	/// The key prefix under which differences are reported.
+	pub key: StorageKey,
+	/// The type of the reported result.
+	pub return_type: ArchiveStorageDiffType,
+	/// The child trie of the key, if any.
+	pub child_trie_key: Option<ChildInfo>,
+}
+
+/// The key prefixes and the requested results of a single trie.
+struct TrieQueries {
+	/// The child trie, or `None` for the main trie.
+	child_trie_key: Option<ChildInfo>,
+	/// The items that target this trie.
+	items: Vec<DiffDetails>,
+}
+
+impl TrieQueries {
+	/// Returns the prefixes that must be iterated to cover all items.
+	///
+	/// Prefixes that are covered by a shorter prefix are not iterated twice.
+	fn iteration_prefixes(&self) -> Vec<StorageKey> {
+		let mut prefixes: Vec<_> = self.items.iter().map(|item| item.key.clone()).collect();
+		prefixes.sort();
+		prefixes.dedup();
+
+		let mut result: Vec<StorageKey> = Vec::with_capacity(prefixes.len());
+		for prefix in prefixes {
+			match result.last() {
+				Some(last) if prefix.0.starts_with(&last.0) => continue,
+				_ => result.push(prefix),
+			}
+		}
+		result
+	}
+
+	/// Returns the result types requested for the given key.
+	fn return_types(&self, key: &StorageKey) -> (bool, bool) {
+		let mut value = false;
+		let mut hash = false;
+		for item in self.items.iter().filter(|item| key.0.starts_with(&item.key.0)) {
+			match item.return_type {
+				ArchiveStorageDiffType::Value => value = true,
+				ArchiveStorageDiffType::Hash => hash = true,
+			}
+		}
+		(value, hash)
+	}
+}
+
+/// Generates the events of the `archive_storageDiff` method.
+pub struct ArchiveStorageDiff<Client, Block, BE> {
+	/// Substrate client.
+	client: Arc<Client>,
+	_phandom: PhantomData<(BE, Block)>,
+}
+
+impl<Client, Block, BE> ArchiveStorageDiff<Client, Block, BE> {
+	/// Constructs a new [`ArchiveStorageDiff`].
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { client, _phandom: PhantomData }
+	}
+}
+
+impl<Client, Block, BE> ArchiveStorageDiff<Client, Block, BE>
+where
+	Block: BlockT + 'static,
+	BE: Backend<Block> + 'static,
+	Client: StorageProvider<Block, BE> + 'static,
+{
+	/// Group the provided items by the trie they target.
+	///
+	/// If no items are provided, all the differences of the main trie are reported.
+	fn group_by_trie(items: Vec<DiffDetails>) -> Vec<TrieQueries> {
+		if items.is_empty() {
+			return vec![TrieQueries {
+				child_trie_key: None,
+				items: vec![DiffDetails {
+					key: StorageKey(Vec::new()),
+					return_type: ArchiveStorageDiffType::Value,
+					child_trie_key: None,
+				}],
+			}]
+		}
+
+		let mut tries: Vec<TrieQueries> = Vec::new();
+		for item in items {
+			match tries.iter_mut().find(|trie| trie.child_trie_key == item.child_trie_key) {
+				Some(trie) => trie.items.push(item),
+				None => tries.push(TrieQueries {
+					child_trie_key: item.child_trie_key.clone(),
+					items: vec![item],
+				}),
+			}
+		}
+		tries
+	}
+
+	/// Returns the first key under `prefix` that is equal to or greater than `from` at the given
+	/// block.
+	fn next_key(
+		&self,
+		at: Block::Hash,
+		prefix: &StorageKey,
+		from: &StorageKey,
+		child_key: Option<&ChildInfo>,
+	) -> Result<Option<StorageKey>, String> {
+		// The iteration starts after `from`.
+		if self.storage_hash(at, from, child_key)?.is_some() {
+			return Ok(Some(from.clone()))
+		}
+
+		let mut keys_iter = if let Some(child_key) = child_key {
+			self.client
+				.child_storage_keys(at, child_key.to_owned(), Some(prefix), Some(from))
+		} else {
+			self.client.storage_keys(at, Some(prefix), Some(from))
+		}
+		.map_err(|error| error.to_string())?;
+
+		Ok(keys_iter.next())
+	}
+
+	/// Returns the bytes following `prefix` in the keys of either block, in order.
+	fn child_bytes(
+		&self,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+		prefix: &StorageKey,
+		child_key: Option<&ChildInfo>,
+	) -> Result<Vec<u8>, String> {
+		let next_byte = |at, byte| -> Result<Option<u8>, String> {
+			let mut from = prefix.0.clone();
+			from.push(byte);
+			let key = self.next_key(at, prefix, &StorageKey(from), child_key)?;
+			Ok(key.map(|key| key.0[prefix.0.len()]))
+		};
+
+		let mut bytes = Vec::new();
+		let mut from = Some(0);
+		while let Some(byte) = from {
+			let next = match (next_byte(hash, byte)?, next_byte(previous_hash, byte)?) {
+				(Some(current), Some(previous)) => current.min(previous),
+				(Some(next), None) | (None, Some(next)) => next,
+				(None, None) => break,
+			};
+			bytes.push(next);
+			from = next.checked_add(1);
+		}
+
+		Ok(bytes)
+	}
+
+	/// Returns `true` if the keys and the values under the prefix are the same in both blocks.
+	///
+	/// Identical subtrees have the same closest descendant merkle value. The first keys of the
+	/// subtrees are compared as well, since the merkle value doesn't cover the key of the subtree
+	/// root above the prefix.
+	fn is_subtree_unchanged(
+		&self,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+		prefix: &StorageKey,
+		child_key: Option<&ChildInfo>,
+	) -> Result<bool, String> {
+		let merkle_value = |at| {
+			if let Some(child_key) = child_key {
+				self.client.child_closest_merkle_value(at, child_key, prefix)
+			} else {
+				self.client.closest_merkle_value(at, prefix)
+			}
+			.map(|maybe_value| {
+				maybe_value.map(|value| match value {
+					MerkleValue::Node(data) => data,
+					MerkleValue::Hash(hash) => hash.as_ref().to_vec(),
+				})
+			})
+			.map_err(|error| error.to_string())
+		};
+
+		if merkle_value(hash)? != merkle_value(previous_hash)? {
+			return Ok(false)
+		}
+
+		Ok(self.next_key(hash, prefix, prefix, child_key)? ==
+			self.next_key(previous_hash, prefix, prefix, child_key)?)
+	}
+
+	/// Fetch the value or the hash of the key at the given block.
+	fn fetch_result(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+		child_key: Option<&ChildInfo>,
+		return_type: ArchiveStorageDiffType,
+	) -> Result<Option<StorageResultType>, String> {
+		let result = match return_type {
+			ArchiveStorageDiffType::Value => {
+				let value = if let Some(child_key) = child_key {
+					self.client.child_storage(hash, child_key, key)
+				} else {
+					self.client.storage(hash, key)
+				};
+				value.map(|maybe_value| {
+					maybe_value.map(|value| StorageResultType::Value(hex_string(&value.0)))
+				})
+			},
+			ArchiveStorageDiffType::Hash => {
+				let value_hash = if let Some(child_key) = child_key {
+					self.client.child_storage_hash(hash, child_key, key)
+				} else {
+					self.client.storage_hash(hash, key)
+				};
+				value_hash.map(|maybe_hash| {
+					maybe_hash
+						.map(|value_hash| StorageResultType::Hash(hex_string(&value_hash.as_ref())))
+				})
+			},
+		};
+
+		result.map_err(|error| error.to_string())
+	}
+
+	/// Returns the hash of the value of the key at the given block.
+	fn storage_hash(
+		&self,
+		at: Block::Hash,
+		key: &StorageKey,
+		child_key: Option<&ChildInfo>,
+	) -> Result<Option<Block::Hash>, String> {
+		if let Some(child_key) = child_key {
+			self.client.child_storage_hash(at, child_key, key)
+		} else {
+			self.client.storage_hash(at, key)
+		}
+		.map_err(|error| error.to_string())
+	}
+
+	/// Report the differences of a single key.
+	///
+	/// Returns `false` if the subscriber is no longer interested in the events.
+	async fn send_key_diff(
+		&self,
+		trie: &TrieQueries,
+		key: StorageKey,
+		operation_type: ArchiveStorageDiffOperationType,
+		at: Block::Hash,
+		sender: &mpsc::Sender<ArchiveStorageDiffEvent>,
+	) -> Result<bool, String> {
+		let child_key = trie.child_trie_key.as_ref();
+		let (value, hash) = trie.return_types(&key);
+
+		let return_types = [
+			value.then_some(ArchiveStorageDiffType::Value),
+			hash.then_some(ArchiveStorageDiffType::Hash),
+		];
+		for return_type in return_types.into_iter().flatten() {
+			let Some(result) = self.fetch_result(at, &key, child_key, return_type)? else {
+				continue
+			};
+
+			let event = ArchiveStorageDiffEvent::StorageDiff(ArchiveStorageDiffResult {
+				key: hex_string(&key.0),
+				result,
+				operation_type,
+				child_trie_key: child_key.map(|child_key| hex_string(&child_key.storage_key())),
+			});
+			if sender.send(event).await.is_err() {
+				return Ok(false)
+			}
+		}
+
+		Ok(true)
+	}
+
+	/// Walk the tries of both blocks under the given prefix and report the differences.
+	///
+	/// Subtrees that are the same in both blocks are skipped, so only the keys along the
+	/// modified paths are visited.
+	///
+	/// Returns `false` if the subscriber is no longer interested in the events.
+	async fn handle_prefix(
+		&self,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+		trie: &TrieQueries,
+		prefix: StorageKey,
+		sender: &mpsc::Sender<ArchiveStorageDiffEvent>,
+	) -> Result<bool, String> {
+		let child_key = trie.child_trie_key.as_ref();
+		// The prefixes left to compare, with the smallest one last such that the keys are
+		// reported in lexicographic order.
+		let mut pending = vec![prefix];
+
+		while let Some(prefix) = pending.pop() {
+			if self.is_subtree_unchanged(hash, previous_hash, &prefix, child_key)? {
+				continue
+			}
+
+			let children = self.child_bytes(hash, previous_hash, &prefix, child_key)?;
+			pending.extend(children.into_iter().rev().map(|byte| {
+				let mut key = prefix.0.clone();
+				key.push(byte);
+				StorageKey(key)
+			}));
+
+			let current = self.storage_hash(hash, &prefix, child_key)?;
+			let previous = self.storage_hash(previous_hash, &prefix, child_key)?;
+			let (operation_type, at) = match (current, previous) {
+				(Some(current), Some(previous)) if current != previous =>
+					(ArchiveStorageDiffOperationType::Modified, hash),
+				(Some(_), None) => (ArchiveStorageDiffOperationType::Added, hash),
+				(None, Some(_)) => (ArchiveStorageDiffOperationType::Deleted, previous_hash),
+				_ => continue,
+			};
+
+			if !self.send_key_diff(trie, prefix, operation_type, at, sender).await? {
+				return Ok(false)
+			}
+		}
+
+		Ok(true)
+	}
+
+	/// Generate the events of the `archive_storageDiff` method.
+	///
+	/// The differences are reported in lexicographic order of the keys for each trie.
+	/// The events are sent through the provided bounded channel, such that a slow
+	/// subscriber applies back-pressure on the iteration.
+	pub async fn handle_trie_queries(
+		&self,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+		items: Vec<DiffDetails>,
+		sender: mpsc::Sender<ArchiveStorageDiffEvent>,
+	) {
+		for trie in Self::group_by_trie(items) {
+			for prefix in trie.iteration_prefixes() {
+				match self.handle_prefix(hash, previous_hash, &trie, prefix, &sender).await {
+					Ok(true) => {},
+					Ok(false) => return,
+					Err(error) => {
+						let _ = sender.send(ArchiveStorageDiffEvent::err(error)).await;
+						return
+					},
+				}
+			}
+		}
+
+		let _ = sender.send(ArchiveStorageDiffEvent::StorageDiffDone).await;
+	}
+}
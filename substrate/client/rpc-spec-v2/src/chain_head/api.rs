@@ -94,6 +94,30 @@ pub trait ChainHeadApi<Hash> {
 		child_trie: Option<String>,
 	) -> ResponsePayload<'static, MethodResponse>;
 
+	/// Reports the changes of the given storage keys for every new block of the subscription.
+	///
+	/// Once started, an `operationStorageDiff` event is generated after the `newBlock` event of
+	/// every block imported from now on. The changes are computed from the storage changes of the
+	/// block import, compared to the parent block. Keys are matched exactly for the `value` and
+	/// `hash` query types, and by prefix for the `descendantsValues` and `descendantsHashes`
+	/// query types. Like `chainHead_storage`, large diffs are split over multiple events and the
+	/// `waitingForContinue` event is generated in between.
+	///
+	/// Every item reserves one operation of the subscription until the operation is stopped with
+	/// `chainHead_stopOperation` or the subscription ends. Calling the method again does not stop
+	/// the previous operation.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "chainHead_v1_storageDiff", raw_method)]
+	async fn chain_head_unstable_storage_diff(
+		&self,
+		follow_subscription: String,
+		items: Vec<StorageQuery<String>>,
+		child_trie: Option<String>,
+	) -> ResponsePayload<'static, MethodResponse>;
+
 	/// Call into the Runtime API at a specified block's state.
 	///
 	/// # Unstable
@@ -138,9 +162,9 @@ pub trait ChainHeadApi<Hash> {
 		operation_id: String,
 	) -> Result<(), Error>;
 
-	/// Stops an operation started with chainHead_v1_body, chainHead_v1_call, or
-	/// chainHead_v1_storage. If the operation was still in progress, this interrupts it. If
-	/// the operation was already finished, this call has no effect.
+	/// Stops an operation started with chainHead_v1_body, chainHead_v1_call,
+	/// chainHead_v1_storage or chainHead_v1_storageDiff. If the operation was still in progress,
+	/// this interrupts it. If the operation was already finished, this call has no effect.
 	///
 	/// # Unstable
 	///
@@ -20,6 +20,7 @@
 
 use super::{
 	chain_head_storage::ChainHeadStorage,
+	chain_head_storage_diff::ChainHeadStorageDiff,
 	event::{MethodResponseStarted, OperationBodyDone, OperationCallDone},
 };
 use crate::{
@@ -30,7 +31,7 @@ use crate::{
 		event::{FollowEvent, MethodResponse, OperationError},
 		subscription::{SubscriptionManagement, SubscriptionManagementError},
 	},
-	common::events::StorageQuery,
+	common::events::{StorageQuery, StorageQueryType},
 	hex_string, SubscriptionTaskExecutor,
 };
 use codec::Encode;
@@ -450,6 +451,99 @@ where
 		rp
 	}
 
+	async fn chain_head_unstable_storage_diff(
+		&self,
+		connection_details: ConnectionDetails,
+		follow_subscription: String,
+		items: Vec<StorageQuery<String>>,
+		child_trie: Option<String>,
+	) -> ResponsePayload<'static, MethodResponse> {
+		if !self
+			.subscriptions
+			.contains_subscription(connection_details.id(), &follow_subscription)
+		{
+			// The spec says to return `LimitReached` if the follow subscription is invalid or
+			// stale.
+			return ResponsePayload::success(MethodResponse::LimitReached);
+		}
+
+		// Gain control over parameter parsing and returned error.
+		let items = match items
+			.into_iter()
+			.map(|query| {
+				// Merkle values cannot be derived from the storage changes of a block.
+				if query.query_type == StorageQueryType::ClosestDescendantMerkleValue {
+					return Err(ChainHeadRpcError::InvalidParam(
+						"closestDescendantMerkleValue is not supported".into(),
+					))
+				}
+				let key = StorageKey(parse_hex_param(query.key)?);
+				Ok(StorageQuery { key, query_type: query.query_type })
+			})
+			.collect::<Result<Vec<_>, ChainHeadRpcError>>()
+		{
+			Ok(items) => items,
+			Err(err) => {
+				return ResponsePayload::error(err);
+			},
+		};
+
+		let child_trie = match child_trie.map(|child_trie| parse_hex_param(child_trie)).transpose()
+		{
+			Ok(c) => c.map(ChildInfo::new_default_from_vec),
+			Err(e) => return ResponsePayload::error(e),
+		};
+
+		let mut storage_diff_guard =
+			match self.subscriptions.register_storage_diff(&follow_subscription, items.len()) {
+				Ok(guard) => guard,
+				Err(_) => return ResponsePayload::success(MethodResponse::LimitReached),
+			};
+
+		// Subscribe before responding, such that the changes of the following blocks are not
+		// missed.
+		let child_filter = child_trie
+			.as_ref()
+			.map(|child_trie| vec![(StorageKey(child_trie.storage_key().to_vec()), None)]);
+		let changes =
+			match self.client.storage_changes_notification_stream(None, child_filter.as_deref()) {
+				Ok(changes) => changes,
+				Err(err) =>
+					return ResponsePayload::error(ChainHeadRpcError::InternalError(err.to_string())),
+			};
+
+		let operation = storage_diff_guard.operation();
+		let operation_id = operation.operation_id();
+
+		// The number of operations we are allowed to execute.
+		let num_operations = operation.num_reserved();
+		let discarded = items.len().saturating_sub(num_operations);
+		let mut items = items;
+		items.truncate(num_operations);
+
+		let storage_diff = ChainHeadStorageDiff::<Client, Block, BE>::new(
+			self.client.clone(),
+			items,
+			child_trie,
+			changes,
+			self.operation_max_storage_items,
+		);
+		let (rp, rp_fut) = method_started_response(operation_id, Some(discarded));
+		let fut = async move {
+			// Wait for the server to send out the response and if it produces an error no event
+			// should be generated.
+			if rp_fut.await.is_err() {
+				return;
+			}
+
+			storage_diff.generate_events(storage_diff_guard).await;
+		};
+		self.executor
+			.spawn_blocking("substrate-rpc-subscription", Some("rpc"), fut.boxed());
+
+		rp
+	}
+
 	async fn chain_head_unstable_call(
 		&self,
 		connection_details: ConnectionDetails,
@@ -20,6 +20,7 @@
 
 use crate::chain_head::{
 	chain_head::{LOG_TARGET, MAX_PINNED_BLOCKS},
+	chain_head_storage_diff::ReportedBlocksSender,
 	event::{
 		BestBlockChanged, Finalized, FollowEvent, Initialized, NewBlock, RuntimeEvent,
 		RuntimeVersionEvent,
@@ -29,6 +30,7 @@ use crate::chain_head::{
 use futures::{
 	channel::oneshot,
 	stream::{self, Stream, StreamExt},
+	FutureExt,
 };
 use futures_util::future::Either;
 use jsonrpsee::SubscriptionSink;
@@ -37,6 +39,7 @@ use sc_client_api::{
 	Backend, BlockBackend, BlockImportNotification, BlockchainEvents, FinalityNotification,
 };
 use sc_rpc::utils::to_sub_message;
+use sc_utils::mpsc::TracingUnboundedReceiver;
 use schnellru::{ByLength, LruMap};
 use sp_api::CallApiAt;
 use sp_blockchain::{
@@ -75,6 +78,10 @@ pub struct ChainHeadFollower<BE: Backend<Block>, Block: BlockT, Client> {
 	/// Stop all subscriptions if the distance between the leaves and the current finalized
 	/// block is larger than this value.
 	max_lagging_distance: usize,
+	/// Notify the ongoing `chainHead_storageDiff` operations of the reported blocks.
+	storage_diffs: Vec<ReportedBlocksSender<Block::Hash>>,
+	/// Receive the operations started by the `chainHead_storageDiff` method.
+	storage_diff_receiver: Option<TracingUnboundedReceiver<ReportedBlocksSender<Block::Hash>>>,
 }
 
 impl<BE: Backend<Block>, Block: BlockT, Client> ChainHeadFollower<BE, Block, Client> {
@@ -98,6 +105,8 @@ impl<BE: Backend<Block>, Block: BlockT, Client> ChainHeadFollower<BE, Block, Cli
 				MAX_PINNED_BLOCKS.try_into().unwrap_or(u32::MAX),
 			)),
 			max_lagging_distance,
+			storage_diffs: Vec::new(),
+			storage_diff_receiver: None,
 		}
 	}
 }
@@ -377,6 +386,9 @@ where
 			with_runtime: self.with_runtime,
 		});
 
+		// The storage changes of the block are reported after the block.
+		self.notify_storage_diffs(block_hash, parent_block_hash);
+
 		if !is_best_block {
 			return vec![new_block]
 		}
@@ -403,6 +415,21 @@ where
 		}
 	}
 
+	/// Notify the ongoing `chainHead_storageDiff` operations of the given block.
+	fn notify_storage_diffs(&mut self, block_hash: Block::Hash, parent_block_hash: Block::Hash) {
+		// Operations are started by `chainHead_storageDiff` before the blocks they report.
+		if let Some(receiver) = self.storage_diff_receiver.as_mut() {
+			while let Some(Some(storage_diff)) = receiver.next().now_or_never() {
+				self.storage_diffs.push(storage_diff);
+			}
+		}
+
+		// Operations which ended are no longer notified.
+		self.storage_diffs.retain(|storage_diff| {
+			storage_diff.unbounded_send((block_hash, parent_block_hash)).is_ok()
+		});
+	}
+
 	/// Handle the import of new blocks by generating the appropriate events.
 	fn handle_import_blocks(
 		&mut self,
@@ -688,6 +715,8 @@ where
 			.response_receiver
 			.map(|response| NotificationType::MethodResponse(response));
 
+		self.storage_diff_receiver = Some(sub_data.storage_diff_receiver);
+
 		let startup_point = StartupPoint::from(self.client.info());
 		let initial_events = match self.generate_init_events(&startup_point) {
 			Ok(blocks) => blocks,
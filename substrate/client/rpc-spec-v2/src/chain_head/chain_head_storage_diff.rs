@@ -0,0 +1,396 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the `chainHead_storageDiff` method.
+
+use std::{cmp::Ordering, collections::VecDeque, iter::Peekable, marker::PhantomData, sync::Arc};
+
+use futures::{FutureExt, StreamExt};
+use sc_client_api::{
+	Backend, ChildInfo, KeysIter, StorageEventStream, StorageKey, StorageNotification,
+	StorageProvider,
+};
+use sc_utils::mpsc::{TracingUnboundedReceiver, TracingUnboundedSender};
+use sp_runtime::traits::{Block as BlockT, Hash as HashT, HashingFor};
+
+use crate::{
+	chain_head::{
+		event::{FollowEvent, OperationError, OperationId, OperationStorageDiff},
+		subscription::{RegisteredOperation, StorageDiffGuard},
+	},
+	common::events::{
+		ArchiveStorageDiffOperationType, ArchiveStorageDiffResult, StorageQuery, StorageQueryType,
+		StorageResultType,
+	},
+	hex_string,
+};
+
+/// The maximum number of storage change notifications kept for blocks
+/// that were not yet announced by the `chainHead_follow` subscription.
+const MAX_PENDING_CHANGES: usize = 16;
+
+/// Sends the hash and the parent hash of the blocks reported by the `chainHead_follow`
+/// subscription to a `chainHead_storageDiff` operation.
+pub type ReportedBlocksSender<Hash> = TracingUnboundedSender<(Hash, Hash)>;
+
+/// Receives the hash and the parent hash of the blocks reported by the `chainHead_follow`
+/// subscription.
+pub type ReportedBlocksReceiver<Hash> = TracingUnboundedReceiver<(Hash, Hash)>;
+
+/// A key changed by a block, with its value at the block and at the parent of the block.
+type Change = (StorageKey, Option<Vec<u8>>, Option<Vec<u8>>);
+
+/// The position the storage diff of a block continues from: the index of the item and the last
+/// key reported for it.
+type DiffPosition = (usize, Option<StorageKey>);
+
+/// Generates the events of the `chainHead_storageDiff` method.
+///
+/// The operation is started by the `chainHead_storageDiff` method and is notified by the
+/// `chainHead_follow` subscription of every reported block, after its `NewBlock` event.
+pub struct ChainHeadStorageDiff<Client, Block: BlockT, BE> {
+	/// Storage client.
+	client: Arc<Client>,
+	/// The storage items to report.
+	items: Vec<StorageQuery<StorageKey>>,
+	/// The child trie of the storage items.
+	child_trie: Option<ChildInfo>,
+	/// The storage changes of the imported blocks.
+	changes: StorageEventStream<Block::Hash>,
+	/// Storage changes received ahead of the import notification of their block.
+	pending: VecDeque<StorageNotification<Block::Hash>>,
+	/// The maximum number of items reported by the `chainHead_storageDiff` before
+	/// pagination is required.
+	operation_max_storage_items: usize,
+	_phantom: PhantomData<BE>,
+}
+
+impl<Client, Block, BE> ChainHeadStorageDiff<Client, Block, BE>
+where
+	Block: BlockT + 'static,
+	BE: Backend<Block> + 'static,
+	Client: StorageProvider<Block, BE> + 'static,
+{
+	/// Constructs a new [`ChainHeadStorageDiff`].
+	pub fn new(
+		client: Arc<Client>,
+		items: Vec<StorageQuery<StorageKey>>,
+		child_trie: Option<ChildInfo>,
+		changes: StorageEventStream<Block::Hash>,
+		operation_max_storage_items: usize,
+	) -> Self {
+		Self {
+			client,
+			items,
+			child_trie,
+			changes,
+			pending: VecDeque::new(),
+			operation_max_storage_items: operation_max_storage_items.max(1),
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Extracts the storage changes of the given block from the notifications received so far.
+	fn take_changes(&mut self, hash: Block::Hash) -> Option<StorageNotification<Block::Hash>> {
+		// The storage changes of a block are notified before the import notification
+		// of the block, therefore they are already available.
+		while let Some(Some(notification)) = self.changes.next().now_or_never() {
+			if self.pending.len() == MAX_PENDING_CHANGES {
+				self.pending.pop_front();
+			}
+			self.pending.push_back(notification);
+		}
+
+		let index = self.pending.iter().position(|notification| notification.block == hash)?;
+		self.pending.remove(index)
+	}
+
+	/// Returns the value of the key at the given block, in the child trie of the operation.
+	fn storage(&self, at: Block::Hash, key: &StorageKey) -> Result<Option<Vec<u8>>, String> {
+		match &self.child_trie {
+			Some(child_trie) => self.client.child_storage(at, child_trie, key),
+			None => self.client.storage(at, key),
+		}
+		.map(|value| value.map(|value| value.0))
+		.map_err(|error| error.to_string())
+	}
+
+	/// Returns the keys starting with `prefix` after `start_key` at the given block, in the child
+	/// trie of the operation.
+	fn storage_keys(
+		&self,
+		at: Block::Hash,
+		prefix: &StorageKey,
+		start_key: Option<&StorageKey>,
+	) -> Result<KeysIter<BE::State, Block>, String> {
+		match &self.child_trie {
+			Some(child_trie) =>
+				self.client.child_storage_keys(at, child_trie.clone(), Some(prefix), start_key),
+			None => self.client.storage_keys(at, Some(prefix), start_key),
+		}
+		.map_err(|error| error.to_string())
+	}
+
+	/// Returns the keys of the item after `start_key` changed by the block, with their values at
+	/// the block and at its parent, from the storage change notification of the block.
+	fn notified_changes<'a>(
+		&'a self,
+		item: &StorageQuery<StorageKey>,
+		notification: &'a StorageNotification<Block::Hash>,
+		parent_hash: Block::Hash,
+		start_key: Option<&StorageKey>,
+	) -> impl Iterator<Item = Result<Change, String>> + 'a {
+		let child_key = self.child_trie.as_ref().map(|child_trie| child_trie.storage_key());
+
+		let mut changes = notification
+			.changes
+			.iter()
+			.filter(|(change_child_key, key, _)| {
+				change_child_key.map(|change_child_key| &change_child_key.0[..]) == child_key &&
+					is_match(item, key) &&
+					start_key.map_or(true, |start_key| *key > start_key)
+			})
+			.map(|(_, key, value)| (key, value))
+			.collect::<Vec<_>>();
+		// The diff continues from the last reported key.
+		changes.sort_by_key(|(key, _)| *key);
+
+		changes.into_iter().map(move |(key, value)| {
+			let previous = self.storage(parent_hash, key)?;
+			Ok((key.clone(), value.map(|value| value.0.clone()), previous))
+		})
+	}
+
+	/// Returns the keys of the item after `start_key` changed by the block, with their values at
+	/// the block and at its parent, by walking the keys of both blocks in order.
+	///
+	/// This is used when the storage change notification of the block is not available.
+	fn trie_changes<'a>(
+		&'a self,
+		item: &StorageQuery<StorageKey>,
+		hash: Block::Hash,
+		parent_hash: Block::Hash,
+		start_key: Option<&StorageKey>,
+	) -> Result<impl Iterator<Item = Result<Change, String>> + 'a, String> {
+		let keys: Box<dyn Iterator<Item = StorageKey>> = if item.query_type.is_descendant_query() {
+			Box::new(MergedKeys {
+				left: self.storage_keys(hash, &item.key, start_key)?.peekable(),
+				right: self.storage_keys(parent_hash, &item.key, start_key)?.peekable(),
+			})
+		} else if start_key.map_or(true, |start_key| &item.key > start_key) {
+			Box::new(std::iter::once(item.key.clone()))
+		} else {
+			Box::new(std::iter::empty())
+		};
+
+		Ok(keys.map(move |key| {
+			let value = self.storage(hash, &key)?;
+			let previous = self.storage(parent_hash, &key)?;
+			Ok((key, value, previous))
+		}))
+	}
+
+	/// Returns up to `operation_max_storage_items` items of the diff of the given block starting
+	/// from `position`, and the position to continue from if there are more.
+	///
+	/// The changes are taken from the storage change notification of the block. If it is not
+	/// available, e.g. for blocks imported during a major sync or with their state, the keys of
+	/// the block and of its parent are compared instead.
+	fn diff_page(
+		&self,
+		notification: Option<&StorageNotification<Block::Hash>>,
+		hash: Block::Hash,
+		parent_hash: Block::Hash,
+		position: DiffPosition,
+	) -> Result<(Vec<ArchiveStorageDiffResult>, Option<DiffPosition>), String> {
+		let child_trie_key =
+			self.child_trie.as_ref().map(|child_trie| hex_string(&child_trie.storage_key()));
+		let (start_index, start_key) = position;
+
+		let mut items = Vec::new();
+		let mut last_position = (start_index, None);
+		for (index, item) in self.items.iter().enumerate().skip(start_index) {
+			let start_key = if index == start_index { start_key.as_ref() } else { None };
+			let changes: Box<dyn Iterator<Item = Result<Change, String>> + '_> = match notification {
+				Some(notification) =>
+					Box::new(self.notified_changes(item, notification, parent_hash, start_key)),
+				None => Box::new(self.trie_changes(item, hash, parent_hash, start_key)?),
+			};
+
+			for change in changes {
+				let (key, value, previous) = change?;
+				let Some((operation_type, result)) = diff_result::<Block>(item, value, previous)
+				else {
+					continue
+				};
+
+				// Large diffs are split over multiple events, similar to `chainHead_storage`.
+				if items.len() == self.operation_max_storage_items {
+					return Ok((items, Some(last_position)))
+				}
+
+				items.push(ArchiveStorageDiffResult {
+					key: hex_string(&key.0),
+					result,
+					operation_type,
+					child_trie_key: child_trie_key.clone(),
+				});
+				last_position = (index, Some(key));
+			}
+		}
+
+		Ok((items, None))
+	}
+
+	/// Generate the storage diff events of the given block, generating the `WaitingForContinue`
+	/// event if necessary.
+	///
+	/// Returns false if the operation was stopped.
+	async fn generate_block_events(
+		&mut self,
+		operation: &mut RegisteredOperation,
+		sender: &TracingUnboundedSender<FollowEvent<Block::Hash>>,
+		hash: Block::Hash,
+		parent_hash: Block::Hash,
+	) -> bool {
+		let notification = self.take_changes(hash);
+
+		let mut position = (0, None);
+		loop {
+			let result = self.diff_page(notification.as_ref(), hash, parent_hash, position);
+			let (items, maybe_next_position) = match result {
+				Ok(result) => result,
+				Err(error) => {
+					let _ = sender.unbounded_send(FollowEvent::OperationError(OperationError {
+						operation_id: operation.operation_id(),
+						error,
+					}));
+					return true
+				},
+			};
+
+			if !items.is_empty() {
+				let _ = sender.unbounded_send(FollowEvent::OperationStorageDiff(
+					OperationStorageDiff {
+						operation_id: operation.operation_id(),
+						block_hash: hash,
+						items,
+					},
+				));
+			}
+
+			let Some(next_position) = maybe_next_position else { return true };
+			let _ = sender.unbounded_send(FollowEvent::OperationWaitingForContinue(OperationId {
+				operation_id: operation.operation_id(),
+			}));
+
+			// The operation might be continued or cancelled only after the
+			// `OperationWaitingForContinue` is generated above.
+			operation.wait_for_continue().await;
+			if operation.was_stopped() {
+				return false
+			}
+
+			position = next_position;
+		}
+	}
+
+	/// Generate the storage diff events of the blocks reported by the `chainHead_follow`
+	/// subscription, until the operation is stopped or the subscription ends.
+	pub async fn generate_events(mut self, mut storage_diff_guard: StorageDiffGuard<Block>) {
+		let sender = storage_diff_guard.response_sender();
+		let mut blocks = storage_diff_guard.start();
+		let operation = storage_diff_guard.operation();
+
+		// The operation can also be stopped while it waits for the next block.
+		operation.allow_stop();
+
+		while let Some((hash, parent_hash)) = blocks.next().await {
+			if operation.was_stopped() {
+				return
+			}
+
+			if !self.generate_block_events(operation, &sender, hash, parent_hash).await {
+				return
+			}
+		}
+	}
+}
+
+/// Returns the operation type and the result reported by the item for a key with the given
+/// values at the block and at its parent, or `None` if the value did not change.
+fn diff_result<Block: BlockT>(
+	item: &StorageQuery<StorageKey>,
+	value: Option<Vec<u8>>,
+	previous: Option<Vec<u8>>,
+) -> Option<(ArchiveStorageDiffOperationType, StorageResultType)> {
+	let (operation_type, value) = match (value, previous) {
+		(None, None) => return None,
+		(Some(value), Some(previous)) if value == previous => return None,
+		(None, Some(previous)) => (ArchiveStorageDiffOperationType::Deleted, previous),
+		(Some(value), None) => (ArchiveStorageDiffOperationType::Added, value),
+		(Some(value), Some(_)) => (ArchiveStorageDiffOperationType::Modified, value),
+	};
+
+	let result = match item.query_type {
+		StorageQueryType::Value | StorageQueryType::DescendantsValues =>
+			StorageResultType::Value(hex_string(&value)),
+		StorageQueryType::Hash | StorageQueryType::DescendantsHashes =>
+			StorageResultType::Hash(hex_string(&HashingFor::<Block>::hash(&value).as_ref())),
+		// Rejected by the `chainHead_storageDiff` method.
+		StorageQueryType::ClosestDescendantMerkleValue => return None,
+	};
+	Some((operation_type, result))
+}
+
+/// Returns true if the key is reported by the given item.
+fn is_match(item: &StorageQuery<StorageKey>, key: &StorageKey) -> bool {
+	if item.query_type.is_descendant_query() {
+		key.0.starts_with(&item.key.0)
+	} else {
+		key == &item.key
+	}
+}
+
+/// Iterates over the keys of two sorted iterators in order, yielding the keys present in both
+/// only once.
+struct MergedKeys<I: Iterator<Item = StorageKey>> {
+	left: Peekable<I>,
+	right: Peekable<I>,
+}
+
+impl<I: Iterator<Item = StorageKey>> Iterator for MergedKeys<I> {
+	type Item = StorageKey;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let ordering = match (self.left.peek(), self.right.peek()) {
+			(Some(left), Some(right)) => left.cmp(right),
+			(Some(_), None) => Ordering::Less,
+			(None, _) => Ordering::Greater,
+		};
+
+		match ordering {
+			Ordering::Less => self.left.next(),
+			Ordering::Greater => self.right.next(),
+			Ordering::Equal => {
+				self.right.next();
+				self.left.next()
+			},
+		}
+	}
+}
@@ -23,7 +23,7 @@ use sp_api::ApiError;
 use sp_version::RuntimeVersion;
 use std::collections::BTreeMap;
 
-use crate::common::events::StorageResult;
+use crate::common::events::{ArchiveStorageDiffResult, StorageResult};
 
 /// The operation could not be processed due to an error.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -245,6 +245,18 @@ pub struct OperationStorageItems {
 	pub items: Vec<StorageResult>,
 }
 
+/// The storage changes of a block produced by the `chainHead_storageDiff` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationStorageDiff<Hash> {
+	/// The operation id of the event.
+	pub operation_id: String,
+	/// The block that introduced the changes.
+	pub block_hash: Hash,
+	/// The changed keys, compared to the parent of the block.
+	pub items: Vec<ArchiveStorageDiffResult>,
+}
+
 /// Indicate a problem during the operation.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -271,6 +283,8 @@ pub struct OperationError {
 /// - OperationWaitingForContinue: Generated after OperationStorageItems and requires the user to
 ///   call `chainHead_continue`
 /// - OperationStorageDone: The `chianHead_storage` method has produced all the results
+/// - OperationStorageDiff: The storage changes of a block reported after its `NewBlock` event, for
+///   the keys registered by `chainHead_storageDiff`
 /// - OperationInaccessible: The server was unable to provide the result, retries might succeed in
 ///   the future
 /// - OperationError: The server encountered an error, retries will not succeed
@@ -302,6 +316,9 @@ pub enum FollowEvent<Hash> {
 	OperationWaitingForContinue(OperationId),
 	/// The responses of the `chainHead_storage` method have been produced.
 	OperationStorageDone(OperationId),
+	/// The storage changes of a newly imported block, for the keys registered by the
+	/// `chainHead_storageDiff` method.
+	OperationStorageDiff(OperationStorageDiff<Hash>),
 	/// The RPC server was unable to provide the response of the following operation id.
 	///
 	/// Repeating the same operation in the future might succeed.
@@ -340,7 +357,7 @@ pub struct MethodResponseStarted {
 
 #[cfg(test)]
 mod tests {
-	use crate::common::events::StorageResultType;
+	use crate::common::events::{ArchiveStorageDiffOperationType, StorageResultType};
 
 	use super::*;
 
@@ -547,6 +564,27 @@ mod tests {
 		assert_eq!(event_dec, event);
 	}
 
+	#[test]
+	fn follow_op_storage_diff_event() {
+		let event: FollowEvent<String> = FollowEvent::OperationStorageDiff(OperationStorageDiff {
+			operation_id: "123".into(),
+			block_hash: "0x1".into(),
+			items: vec![ArchiveStorageDiffResult {
+				key: "0x2".into(),
+				result: StorageResultType::Value("0x123".to_string()),
+				operation_type: ArchiveStorageDiffOperationType::Modified,
+				child_trie_key: None,
+			}],
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"operationStorageDiff","operationId":"123","blockHash":"0x1","items":[{"key":"0x2","value":"0x123","type":"modified"}]}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: FollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
 	#[test]
 	fn follow_op_wait_event() {
 		let event: FollowEvent<String> =
@@ -34,6 +34,7 @@ pub mod event;
 
 mod chain_head_follow;
 mod chain_head_storage;
+mod chain_head_storage_diff;
 mod subscription;
 
 pub use api::ChainHeadApiServer;
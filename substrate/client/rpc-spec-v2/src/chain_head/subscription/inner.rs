@@ -27,7 +27,11 @@ use std::{
 	time::{Duration, Instant},
 };
 
-use crate::chain_head::{subscription::SubscriptionManagementError, FollowEvent};
+use crate::chain_head::{
+	chain_head_storage_diff::{ReportedBlocksReceiver, ReportedBlocksSender},
+	subscription::SubscriptionManagementError,
+	FollowEvent,
+};
 
 /// The queue size after which the `sc_utils::mpsc::tracing_unbounded` would produce warnings.
 const QUEUE_SIZE_WARNING: usize = 512;
@@ -189,7 +193,9 @@ impl OperationState {
 	/// Returns nothing in accordance with `chainHead_v1_stopOperation`.
 	pub fn stop_operation(&self) {
 		// `waitingForContinue` not generated.
-		if !self.shared_state.requested_continue.load(std::sync::atomic::Ordering::Acquire) {
+		if !self.shared_state.requested_continue.load(std::sync::atomic::Ordering::Acquire) &&
+			!self.shared_state.stop_allowed.load(std::sync::atomic::Ordering::Acquire)
+		{
 			return
 		}
 
@@ -210,6 +216,8 @@ struct SharedOperationState {
 	requested_continue: AtomicBool,
 	/// True if the operation was cancelled by the user.
 	operation_stopped: AtomicBool,
+	/// True if the operation can be stopped without generating `waitingForContinue`.
+	stop_allowed: AtomicBool,
 }
 
 impl SharedOperationState {
@@ -220,6 +228,7 @@ impl SharedOperationState {
 		Arc::new(SharedOperationState {
 			requested_continue: AtomicBool::new(false),
 			operation_stopped: AtomicBool::new(false),
+			stop_allowed: AtomicBool::new(false),
 		})
 	}
 }
@@ -259,6 +268,13 @@ impl RegisteredOperation {
 			.store(false, std::sync::atomic::Ordering::Release);
 	}
 
+	/// Allow the operation to be stopped via `chainHead_stopOperation` at any time.
+	///
+	/// This is used by operations that never generate the `waitingForContinue` event.
+	pub fn allow_stop(&self) {
+		self.shared_state.stop_allowed.store(true, std::sync::atomic::Ordering::Release);
+	}
+
 	/// Returns true if the current operation was stopped.
 	pub fn was_stopped(&self) -> bool {
 		self.shared_state.operation_stopped.load(std::sync::atomic::Ordering::Acquire)
@@ -353,6 +369,9 @@ struct SubscriptionState<Block: BlockT> {
 	///
 	/// This object is cloned between methods.
 	response_sender: TracingUnboundedSender<FollowEvent<Block::Hash>>,
+	/// Registers the `chainHead_storageDiff` operations with the `chainHead_follow`
+	/// subscription, which notifies them of the reported blocks.
+	storage_diff_sender: TracingUnboundedSender<ReportedBlocksSender<Block::Hash>>,
 	/// The ongoing operations of a subscription.
 	operations: Operations,
 	/// Track the block hashes available for this subscription.
@@ -537,6 +556,37 @@ impl<Block: BlockT, BE: Backend<Block>> Drop for BlockGuard<Block, BE> {
 	}
 }
 
+/// Reserves capacity for a `chainHead_storageDiff` operation.
+///
+/// The operation is notified of the blocks reported by the `chainHead_follow` subscription
+/// after [`StorageDiffGuard::start`] and lasts until it is stopped or the subscription ends.
+pub struct StorageDiffGuard<Block: BlockT> {
+	storage_diff_sender: TracingUnboundedSender<ReportedBlocksSender<Block::Hash>>,
+	response_sender: TracingUnboundedSender<FollowEvent<Block::Hash>>,
+	operation: RegisteredOperation,
+}
+
+impl<Block: BlockT> StorageDiffGuard<Block> {
+	/// Send message responses from the `chainHead_storageDiff` method to `chainHead_follow`.
+	pub fn response_sender(&self) -> TracingUnboundedSender<FollowEvent<Block::Hash>> {
+		self.response_sender.clone()
+	}
+
+	/// Get the details of the registered operation.
+	pub fn operation(&mut self) -> &mut RegisteredOperation {
+		&mut self.operation
+	}
+
+	/// Start receiving the hash and the parent hash of the blocks reported by the
+	/// `chainHead_follow` subscription from now on.
+	pub fn start(&self) -> ReportedBlocksReceiver<Block::Hash> {
+		let (sender, receiver) =
+			tracing_unbounded("chain-head-storage-diff-blocks", QUEUE_SIZE_WARNING);
+		let _ = self.storage_diff_sender.unbounded_send(sender);
+		receiver
+	}
+}
+
 /// The data propagated back to the `chainHead_follow` method after
 /// the subscription is successfully inserted.
 pub struct InsertedSubscriptionData<Block: BlockT> {
@@ -544,6 +594,8 @@ pub struct InsertedSubscriptionData<Block: BlockT> {
 	pub rx_stop: oneshot::Receiver<()>,
 	/// Receive message responses from the `chainHead` methods.
 	pub response_receiver: TracingUnboundedReceiver<FollowEvent<Block::Hash>>,
+	/// Receive the operations started by the `chainHead_storageDiff` method.
+	pub storage_diff_receiver: TracingUnboundedReceiver<ReportedBlocksSender<Block::Hash>>,
 }
 
 pub struct SubscriptionsInner<Block: BlockT, BE: Backend<Block>> {
@@ -595,16 +647,19 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 			let (tx_stop, rx_stop) = oneshot::channel();
 			let (response_sender, response_receiver) =
 				tracing_unbounded("chain-head-method-responses", QUEUE_SIZE_WARNING);
+			let (storage_diff_sender, storage_diff_receiver) =
+				tracing_unbounded("chain-head-storage-diff", QUEUE_SIZE_WARNING);
 			let state = SubscriptionState::<Block> {
 				with_runtime,
 				tx_stop: Some(tx_stop),
 				response_sender,
+				storage_diff_sender,
 				blocks: Default::default(),
 				operations: Operations::new(self.max_ongoing_operations),
 			};
 			entry.insert(state);
 
-			Some(InsertedSubscriptionData { rx_stop, response_receiver })
+			Some(InsertedSubscriptionData { rx_stop, response_receiver, storage_diff_receiver })
 		} else {
 			None
 		}
@@ -837,6 +892,27 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionsInner<Block, BE> {
 		)
 	}
 
+	pub fn register_storage_diff(
+		&mut self,
+		sub_id: &str,
+		to_reserve: usize,
+	) -> Result<StorageDiffGuard<Block>, SubscriptionManagementError> {
+		let Some(sub) = self.subs.get_mut(sub_id) else {
+			return Err(SubscriptionManagementError::SubscriptionAbsent)
+		};
+
+		let Some(operation) = sub.register_operation(to_reserve) else {
+			// Error when the server cannot execute at least one operation.
+			return Err(SubscriptionManagementError::ExceededLimits)
+		};
+
+		Ok(StorageDiffGuard {
+			storage_diff_sender: sub.storage_diff_sender.clone(),
+			response_sender: sub.response_sender.clone(),
+			operation,
+		})
+	}
+
 	pub fn get_operation(&mut self, sub_id: &str, id: &str) -> Option<OperationState> {
 		let state = self.subs.get(sub_id)?;
 		state.get_operation(id)
@@ -973,10 +1049,13 @@ mod tests {
 	fn sub_state_register_twice() {
 		let (response_sender, _response_receiver) =
 			tracing_unbounded("test-chain-head-method-responses", QUEUE_SIZE_WARNING);
+		let (storage_diff_sender, _storage_diff_receiver) =
+			tracing_unbounded("test-chain-head-storage-diff", QUEUE_SIZE_WARNING);
 		let mut sub_state = SubscriptionState::<Block> {
 			with_runtime: false,
 			tx_stop: None,
 			response_sender,
+			storage_diff_sender,
 			operations: Operations::new(MAX_OPERATIONS_PER_SUB),
 			blocks: Default::default(),
 		};
@@ -1002,10 +1081,13 @@ mod tests {
 	fn sub_state_register_unregister() {
 		let (response_sender, _response_receiver) =
 			tracing_unbounded("test-chain-head-method-responses", QUEUE_SIZE_WARNING);
+		let (storage_diff_sender, _storage_diff_receiver) =
+			tracing_unbounded("test-chain-head-storage-diff", QUEUE_SIZE_WARNING);
 		let mut sub_state = SubscriptionState::<Block> {
 			with_runtime: false,
 			tx_stop: None,
 			response_sender,
+			storage_diff_sender,
 			blocks: Default::default(),
 			operations: Operations::new(MAX_OPERATIONS_PER_SUB),
 		};
@@ -34,7 +34,7 @@ use self::inner::SubscriptionsInner;
 
 pub use self::inner::OperationState;
 pub use error::SubscriptionManagementError;
-pub use inner::{BlockGuard, InsertedSubscriptionData};
+pub use inner::{BlockGuard, InsertedSubscriptionData, RegisteredOperation, StorageDiffGuard};
 
 /// Manage block pinning / unpinning for subscription IDs.
 pub struct SubscriptionManagement<Block: BlockT, BE: Backend<Block>> {
@@ -176,6 +176,19 @@ impl<Block: BlockT, BE: Backend<Block>> SubscriptionManagement<Block, BE> {
 		inner.lock_block(sub_id, hash, to_reserve)
 	}
 
+	/// Reserve capacity for a `chainHead_storageDiff` operation.
+	///
+	/// Returns an error if the subscription ID is invalid or the limit of ongoing operations
+	/// was exceeded.
+	pub fn register_storage_diff(
+		&self,
+		sub_id: &str,
+		to_reserve: usize,
+	) -> Result<StorageDiffGuard<Block>, SubscriptionManagementError> {
+		let mut inner = self.inner.write();
+		inner.register_storage_diff(sub_id, to_reserve)
+	}
+
 	/// Get the operation state.
 	pub fn get_operation(&self, sub_id: &str, operation_id: &str) -> Option<OperationState> {
 		let mut inner = self.inner.write();
@@ -18,8 +18,15 @@
 
 use super::*;
 use crate::{
-	chain_head::{api::ChainHeadApiClient, event::MethodResponse, test_utils::ChainHeadMockClient},
-	common::events::{StorageQuery, StorageQueryType, StorageResultType},
+	chain_head::{
+		api::ChainHeadApiClient,
+		event::{MethodResponse, OperationStorageDiff},
+		test_utils::ChainHeadMockClient,
+	},
+	common::events::{
+		ArchiveStorageDiffOperationType, ArchiveStorageDiffResult, StorageQuery, StorageQueryType,
+		StorageResultType,
+	},
 	hex_string,
 };
 use assert_matches::assert_matches;
@@ -3219,6 +3226,298 @@ async fn stop_storage_operation() {
 	.await;
 }
 
+#[tokio::test]
+async fn follow_storage_diff() {
+	let (mut client, api, mut sub, sub_id, block) = setup_api().await;
+	let block_hash = block.header.hash();
+
+	// Merkle values cannot be reported.
+	let err = api
+		.call::<_, serde_json::Value>(
+			"chainHead_v1_storageDiff",
+			rpc_params![
+				&sub_id,
+				vec![StorageQuery {
+					key: hex_string(b":m"),
+					query_type: StorageQueryType::ClosestDescendantMerkleValue
+				}]
+			],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::JsonRpc(ref err) if err.code() == super::error::json_rpc_spec::INVALID_PARAM_ERROR
+	);
+
+	let response: MethodResponse = api
+		.call(
+			"chainHead_v1_storageDiff",
+			rpc_params![
+				&sub_id,
+				vec![
+					StorageQuery {
+						key: hex_string(b":m"),
+						query_type: StorageQueryType::DescendantsValues
+					},
+					StorageQuery { key: hex_string(b":mock"), query_type: StorageQueryType::Hash }
+				]
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => {
+			assert_eq!(started.discarded_items, Some(0));
+			started.operation_id
+		},
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+
+	// Import a block that adds keys.
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(block_hash)
+		.with_parent_block_number(1)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":mo".to_vec(), Some(b"ab".to_vec())).unwrap();
+	builder.push_storage_change(b":mock".to_vec(), Some(b"abcd".to_vec())).unwrap();
+	builder.push_storage_change(b":other".to_vec(), Some(b"abc".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+	let event: FollowEvent<String> = get_next_event(&mut sub).await;
+	let expected = FollowEvent::OperationStorageDiff(OperationStorageDiff {
+		operation_id: operation_id.clone(),
+		block_hash: format!("{:?}", block_hash),
+		items: vec![
+			ArchiveStorageDiffResult {
+				key: hex_string(b":mo"),
+				result: StorageResultType::Value(hex_string(b"ab")),
+				operation_type: ArchiveStorageDiffOperationType::Added,
+				child_trie_key: None,
+			},
+			ArchiveStorageDiffResult {
+				key: hex_string(b":mock"),
+				result: StorageResultType::Value(hex_string(b"abcd")),
+				operation_type: ArchiveStorageDiffOperationType::Added,
+				child_trie_key: None,
+			},
+			ArchiveStorageDiffResult {
+				key: hex_string(b":mock"),
+				result: StorageResultType::Hash(format!("{:?}", Blake2Hasher::hash(b"abcd"))),
+				operation_type: ArchiveStorageDiffOperationType::Added,
+				child_trie_key: None,
+			},
+		],
+	});
+	assert_eq!(event, expected);
+
+	// Import a block that modifies and deletes keys.
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(block_hash)
+		.with_parent_block_number(2)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":mo".to_vec(), Some(b"abc".to_vec())).unwrap();
+	builder.push_storage_change(b":mock".to_vec(), None).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageDiff(diff) if diff.operation_id == operation_id &&
+			diff.items.len() == 3 &&
+			diff.items[0].key == hex_string(b":mo") &&
+			diff.items[0].operation_type == ArchiveStorageDiffOperationType::Modified &&
+			diff.items[0].result == StorageResultType::Value(hex_string(b"abc")) &&
+			diff.items[1].key == hex_string(b":mock") &&
+			diff.items[1].operation_type == ArchiveStorageDiffOperationType::Deleted &&
+			diff.items[1].result == StorageResultType::Value(hex_string(b"abcd"))
+	);
+
+	// Stop the operation.
+	let _res: () = api.call("chainHead_v1_stopOperation", [&sub_id, &operation_id]).await.unwrap();
+
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(block_hash)
+		.with_parent_block_number(3)
+		.build()
+		.unwrap();
+	builder.push_storage_change(b":mo".to_vec(), Some(b"abcd".to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// No diff events are generated after the operation was stopped.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+	does_not_produce_event::<FollowEvent<String>>(
+		&mut sub,
+		std::time::Duration::from_secs(DOES_NOT_PRODUCE_EVENTS_SECONDS),
+	)
+	.await;
+}
+
+#[tokio::test]
+async fn follow_storage_diff_without_storage_notification() {
+	let (mut client, api, mut sub, sub_id, block) = setup_api().await;
+	let mut block_hash = block.header.hash();
+
+	let response: MethodResponse = api
+		.call(
+			"chainHead_v1_storageDiff",
+			rpc_params![
+				&sub_id,
+				vec![StorageQuery { key: hex_string(b":mo"), query_type: StorageQueryType::Value }]
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+
+	// Import more blocks than the 16 storage change notifications kept by the operation, such
+	// that the notification of the first block is dropped before its diff is generated.
+	let mut hashes = Vec::new();
+	for number in 1..=17u8 {
+		let mut builder = BlockBuilderBuilder::new(&*client)
+			.on_parent_block(block_hash)
+			.with_parent_block_number(number as u64)
+			.build()
+			.unwrap();
+		builder.push_storage_change(b":mo".to_vec(), Some(vec![number])).unwrap();
+		let block = builder.build().unwrap().block;
+		block_hash = block.header.hash();
+		client.import(BlockOrigin::Own, block).await.unwrap();
+		hashes.push(block_hash);
+	}
+
+	// The diff of every block is reported, from the state of the blocks if need be. The diffs
+	// are reported in order, but interleaved with the events of the following blocks.
+	for (number, hash) in (1..=17u8).zip(hashes) {
+		let event = loop {
+			match get_next_event::<FollowEvent<String>>(&mut sub).await {
+				FollowEvent::NewBlock(_) | FollowEvent::BestBlockChanged(_) => continue,
+				event => break event,
+			}
+		};
+		let expected = FollowEvent::OperationStorageDiff(OperationStorageDiff {
+			operation_id: operation_id.clone(),
+			block_hash: format!("{:?}", hash),
+			items: vec![ArchiveStorageDiffResult {
+				key: hex_string(b":mo"),
+				result: StorageResultType::Value(hex_string(&[number])),
+				operation_type: if number == 1 {
+					ArchiveStorageDiffOperationType::Added
+				} else {
+					ArchiveStorageDiffOperationType::Modified
+				},
+				child_trie_key: None,
+			}],
+		});
+		assert_eq!(event, expected);
+	}
+}
+
+#[tokio::test]
+async fn follow_storage_diff_pagination() {
+	let (mut client, api, mut sub, sub_id, block) = setup_api().await;
+	let block_hash = block.header.hash();
+
+	let response: MethodResponse = api
+		.call(
+			"chainHead_v1_storageDiff",
+			rpc_params![
+				&sub_id,
+				vec![StorageQuery {
+					key: hex_string(b":m"),
+					query_type: StorageQueryType::DescendantsValues
+				}]
+			],
+		)
+		.await
+		.unwrap();
+	let operation_id = match response {
+		MethodResponse::Started(started) => started.operation_id,
+		MethodResponse::LimitReached => panic!("Expected started response"),
+	};
+
+	// Import a block that changes more keys than reported by a single event.
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(block_hash)
+		.with_parent_block_number(1)
+		.build()
+		.unwrap();
+	for index in 0..MAX_PAGINATION_LIMIT as u8 + 2 {
+		builder
+			.push_storage_change(vec![b':', b'm', b'0' + index], Some(vec![index]))
+			.unwrap();
+	}
+	let block = builder.build().unwrap().block;
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::NewBlock(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::BestBlockChanged(_)
+	);
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageDiff(diff) if diff.operation_id == operation_id &&
+			diff.items.len() == MAX_PAGINATION_LIMIT &&
+			diff.items[0].key == hex_string(b":m0")
+	);
+
+	// Pagination event.
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationWaitingForContinue(res) if res.operation_id == operation_id
+	);
+	does_not_produce_event::<FollowEvent<String>>(
+		&mut sub,
+		std::time::Duration::from_secs(DOES_NOT_PRODUCE_EVENTS_SECONDS),
+	)
+	.await;
+
+	// The diff continues from the last reported key.
+	let _res: () = api.call("chainHead_v1_continue", [&sub_id, &operation_id]).await.unwrap();
+	assert_matches!(
+		get_next_event::<FollowEvent<String>>(&mut sub).await,
+		FollowEvent::OperationStorageDiff(diff) if diff.operation_id == operation_id &&
+			diff.items.len() == 2 &&
+			diff.items[0].key == hex_string(b":m5") &&
+			diff.items[1].key == hex_string(b":m6")
+	);
+}
+
 #[tokio::test]
 async fn storage_closest_merkle_value() {
 	let (mut client, api, mut sub, sub_id, block) = setup_api().await;
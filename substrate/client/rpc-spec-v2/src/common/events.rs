@@ -143,6 +143,83 @@ pub struct ArchiveStorageMethodErr {
 	pub error: String,
 }
 
+/// The type of the result reported by the `archive_storageDiff` method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveStorageDiffType {
+	/// Report the value of the key.
+	Value,
+	/// Report the hash of the value of the key.
+	Hash,
+}
+
+/// The storage item of the `archive_storageDiff` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStorageDiffItem<Key> {
+	/// The provided key prefix.
+	pub key: Key,
+	/// The type of the reported result.
+	pub return_type: ArchiveStorageDiffType,
+	/// The child trie key, if the item targets a child trie.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub child_trie_key: Option<Key>,
+}
+
+/// The operation that happened to a key between two blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveStorageDiffOperationType {
+	/// The key was added.
+	Added,
+	/// The value of the key was modified.
+	Modified,
+	/// The key was deleted.
+	Deleted,
+}
+
+/// The difference reported for a single key by the `archive_storageDiff` and
+/// `chainHead_storageDiff` methods.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStorageDiffResult {
+	/// The hex-encoded key of the result.
+	pub key: String,
+	/// The value or hash of the key.
+	///
+	/// For deleted keys this is the value or hash at the previous block.
+	#[serde(flatten)]
+	pub result: StorageResultType,
+	/// The operation that happened to the key.
+	#[serde(rename = "type")]
+	pub operation_type: ArchiveStorageDiffOperationType,
+	/// The hex-encoded child trie key, if the key belongs to a child trie.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub child_trie_key: Option<String>,
+}
+
+/// The event generated by the `archive_storageDiff` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "event")]
+pub enum ArchiveStorageDiffEvent {
+	/// The difference of a key between the two blocks.
+	StorageDiff(ArchiveStorageDiffResult),
+	/// The query encountered an error and no further events are produced.
+	StorageDiffError(ArchiveStorageMethodErr),
+	/// All the differences have been reported.
+	StorageDiffDone,
+}
+
+impl ArchiveStorageDiffEvent {
+	/// Create a new `ArchiveStorageDiffEvent::StorageDiffError` event.
+	pub fn err(error: String) -> Self {
+		Self::StorageDiffError(ArchiveStorageMethodErr { error })
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -239,6 +316,84 @@ mod tests {
 		assert_eq!(dec, item);
 	}
 
+	#[test]
+	fn archive_storage_diff_item() {
+		let item = ArchiveStorageDiffItem {
+			key: "0x1",
+			return_type: ArchiveStorageDiffType::Value,
+			child_trie_key: None,
+		};
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","returnType":"value"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageDiffItem<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		let item = ArchiveStorageDiffItem {
+			key: "0x1",
+			return_type: ArchiveStorageDiffType::Hash,
+			child_trie_key: Some("0x2"),
+		};
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","returnType":"hash","childTrieKey":"0x2"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageDiffItem<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+	}
+
+	#[test]
+	fn archive_storage_diff_event() {
+		let event = ArchiveStorageDiffEvent::StorageDiff(ArchiveStorageDiffResult {
+			key: "0x1".into(),
+			result: StorageResultType::Value("0x2".into()),
+			operation_type: ArchiveStorageDiffOperationType::Added,
+			child_trie_key: None,
+		});
+		// Encode
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"storageDiff","key":"0x1","value":"0x2","type":"added"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageDiffEvent = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, event);
+
+		let event = ArchiveStorageDiffEvent::StorageDiff(ArchiveStorageDiffResult {
+			key: "0x1".into(),
+			result: StorageResultType::Hash("0x2".into()),
+			operation_type: ArchiveStorageDiffOperationType::Deleted,
+			child_trie_key: Some("0x3".into()),
+		});
+		// Encode
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"storageDiff","key":"0x1","hash":"0x2","type":"deleted","childTrieKey":"0x3"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageDiffEvent = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, event);
+
+		let event = ArchiveStorageDiffEvent::err("error".into());
+		// Encode
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"storageDiffError","error":"error"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageDiffEvent = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, event);
+
+		let event = ArchiveStorageDiffEvent::StorageDiffDone;
+		// Encode
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"storageDiffDone"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageDiffEvent = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, event);
+	}
+
 	#[test]
 	fn storage_query_paginated() {
 		let item = PaginatedStorageQuery {
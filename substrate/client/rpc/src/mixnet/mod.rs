@@ -21,7 +21,7 @@
 use jsonrpsee::core::async_trait;
 use sc_mixnet::Api;
 use sc_rpc_api::mixnet::error::Error;
-pub use sc_rpc_api::mixnet::MixnetApiServer;
+pub use sc_rpc_api::mixnet::{MixnetApiServer, StorageRead};
 use sp_core::Bytes;
 
 /// Mixnet API.
@@ -44,4 +44,22 @@ impl MixnetApiServer for Mixnet {
 		};
 		Ok(fut.await.map_err(Error)?)
 	}
+
+	async fn read_storage(
+		&self,
+		keys: Vec<Bytes>,
+		at: Option<Bytes>,
+	) -> Result<StorageRead, Error> {
+		// We only hold the lock while pushing the request into the requests channel
+		let fut = {
+			let mut api = self.0.lock().await;
+			api.read_storage(at, keys).await
+		};
+		let reply = fut.await.map_err(Error)?;
+		Ok(StorageRead {
+			at: reply.at.into(),
+			values: reply.values.into_iter().map(|value| value.map(Into::into)).collect(),
+			proof: reply.proof.into_iter().map(Into::into).collect(),
+		})
+	}
 }
@@ -27,7 +27,8 @@ mod tests;
 use crate::SubscriptionTaskExecutor;
 use jsonrpsee::{core::async_trait, PendingSubscriptionSink};
 use sc_client_api::{
-	Backend, BlockBackend, BlockchainEvents, ExecutorProvider, ProofProvider, StorageProvider,
+	Backend, BlockBackend, BlockchainEvents, ExecutorProvider, ProofProvider,
+	RemoteStorageProvider, StorageProvider,
 };
 use sc_rpc_api::DenyUnsafe;
 use sp_api::{CallApiAt, Metadata, ProvideRuntimeApi};
@@ -83,14 +84,14 @@ where
 	) -> Result<Vec<StorageKey>, Error>;
 
 	/// Returns a storage entry at a specific block's state.
-	fn storage(
+	async fn storage(
 		&self,
 		block: Option<Block::Hash>,
 		key: StorageKey,
 	) -> Result<Option<StorageData>, Error>;
 
 	/// Returns the hash of a storage entry at a block's state.
-	fn storage_hash(
+	async fn storage_hash(
 		&self,
 		block: Option<Block::Hash>,
 		key: StorageKey,
@@ -190,6 +191,44 @@ where
 	(State { backend, deny_unsafe }, ChildState { backend: child_backend })
 }
 
+/// Create new state API that works on full node, reading the storage of blocks whose state is not
+/// available in `backend` from `remote_storage`.
+pub fn new_full_with_remote_storage<BE, Block: BlockT, Client>(
+	client: Arc<Client>,
+	backend: Arc<BE>,
+	executor: SubscriptionTaskExecutor,
+	deny_unsafe: DenyUnsafe,
+	remote_storage: Arc<dyn RemoteStorageProvider<Block>>,
+) -> (State<Block, Client>, ChildState<Block, Client>)
+where
+	Block: BlockT + 'static,
+	Block::Hash: Unpin,
+	BE: Backend<Block> + 'static,
+	Client: ExecutorProvider<Block>
+		+ StorageProvider<Block, BE>
+		+ ProofProvider<Block>
+		+ HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ BlockchainEvents<Block>
+		+ CallApiAt<Block>
+		+ HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ ProvideRuntimeApi<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: Metadata<Block>,
+{
+	let child_backend = Box::new(
+		self::state_full::FullState::new(client.clone(), executor.clone())
+			.with_remote_storage(backend.clone(), remote_storage.clone()),
+	);
+	let backend = Box::new(
+		self::state_full::FullState::new(client, executor)
+			.with_remote_storage(backend, remote_storage),
+	);
+	(State { backend, deny_unsafe }, ChildState { backend: child_backend })
+}
+
 /// State API with subscriptions support.
 pub struct State<Block, Client> {
 	backend: Box<dyn StateBackend<Block, Client>>,
@@ -244,20 +283,20 @@ where
 			.map_err(Into::into)
 	}
 
-	fn storage(
+	async fn storage(
 		&self,
 		key: StorageKey,
 		block: Option<Block::Hash>,
 	) -> Result<Option<StorageData>, Error> {
-		self.backend.storage(block, key).map_err(Into::into)
+		self.backend.storage(block, key).await.map_err(Into::into)
 	}
 
-	fn storage_hash(
+	async fn storage_hash(
 		&self,
 		key: StorageKey,
 		block: Option<Block::Hash>,
 	) -> Result<Option<Block::Hash>, Error> {
-		self.backend.storage_hash(block, key).map_err(Into::into)
+		self.backend.storage_hash(block, key).await.map_err(Into::into)
 	}
 
 	async fn storage_size(
@@ -333,6 +372,7 @@ where
 }
 
 /// Child state backend API.
+#[async_trait]
 pub trait ChildStateBackend<Block: BlockT, Client>: Send + Sync + 'static
 where
 	Block: BlockT + 'static,
@@ -366,7 +406,7 @@ where
 	) -> Result<Vec<StorageKey>, Error>;
 
 	/// Returns a child storage entry at a specific block's state.
-	fn storage(
+	async fn storage(
 		&self,
 		block: Option<Block::Hash>,
 		storage_key: PrefixedStorageKey,
@@ -382,7 +422,7 @@ where
 	) -> Result<Vec<Option<StorageData>>, Error>;
 
 	/// Returns the hash of a child storage entry at a block's state.
-	fn storage_hash(
+	async fn storage_hash(
 		&self,
 		block: Option<Block::Hash>,
 		storage_key: PrefixedStorageKey,
@@ -390,13 +430,13 @@ where
 	) -> Result<Option<Block::Hash>, Error>;
 
 	/// Returns the size of a child storage entry at a block's state.
-	fn storage_size(
+	async fn storage_size(
 		&self,
 		block: Option<Block::Hash>,
 		storage_key: PrefixedStorageKey,
 		key: StorageKey,
 	) -> Result<Option<u64>, Error> {
-		self.storage(block, storage_key, key).map(|x| x.map(|x| x.0.len() as u64))
+		self.storage(block, storage_key, key).await.map(|x| x.map(|x| x.0.len() as u64))
 	}
 }
 
@@ -405,6 +445,7 @@ pub struct ChildState<Block, Client> {
 	backend: Box<dyn ChildStateBackend<Block, Client>>,
 }
 
+#[async_trait]
 impl<Block, Client> ChildStateApiServer<Block::Hash> for ChildState<Block, Client>
 where
 	Block: BlockT + 'static,
@@ -432,13 +473,13 @@ where
 			.map_err(Into::into)
 	}
 
-	fn storage(
+	async fn storage(
 		&self,
 		storage_key: PrefixedStorageKey,
 		key: StorageKey,
 		block: Option<Block::Hash>,
 	) -> Result<Option<StorageData>, Error> {
-		self.backend.storage(block, storage_key, key).map_err(Into::into)
+		self.backend.storage(block, storage_key, key).await.map_err(Into::into)
 	}
 
 	fn storage_entries(
@@ -450,22 +491,22 @@ where
 		self.backend.storage_entries(block, storage_key, keys).map_err(Into::into)
 	}
 
-	fn storage_hash(
+	async fn storage_hash(
 		&self,
 		storage_key: PrefixedStorageKey,
 		key: StorageKey,
 		block: Option<Block::Hash>,
 	) -> Result<Option<Block::Hash>, Error> {
-		self.backend.storage_hash(block, storage_key, key).map_err(Into::into)
+		self.backend.storage_hash(block, storage_key, key).await.map_err(Into::into)
 	}
 
-	fn storage_size(
+	async fn storage_size(
 		&self,
 		storage_key: PrefixedStorageKey,
 		key: StorageKey,
 		block: Option<Block::Hash>,
 	) -> Result<Option<u64>, Error> {
-		self.backend.storage_size(block, storage_key, key).map_err(Into::into)
+		self.backend.storage_size(block, storage_key, key).await.map_err(Into::into)
 	}
 
 	fn read_child_proof(
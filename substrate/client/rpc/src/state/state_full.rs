@@ -34,7 +34,7 @@ use futures::{future, stream, StreamExt};
 use jsonrpsee::{core::async_trait, types::ErrorObject, PendingSubscriptionSink};
 use sc_client_api::{
 	Backend, BlockBackend, BlockchainEvents, CallExecutor, ExecutorProvider, ProofProvider,
-	StorageProvider,
+	RemoteStorageProvider, StorageProvider,
 };
 use sc_rpc_api::state::ReadProof;
 use sp_api::{CallApiAt, Metadata, ProvideRuntimeApi};
@@ -49,7 +49,7 @@ use sp_core::{
 	traits::CallContext,
 	Bytes,
 };
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, Hash as HashT, HashingFor};
 use sp_version::RuntimeVersion;
 
 /// The maximum time allowed for an RPC call when running without unsafe RPC enabled.
@@ -61,10 +61,19 @@ struct QueryStorageRange<Block: BlockT> {
 	pub hashes: Vec<Block::Hash>,
 }
 
+/// Source of the state of blocks which is not available locally.
+struct RemoteStorage<BE, Block: BlockT> {
+	/// Backend telling whether the state of a block is available locally.
+	backend: Arc<BE>,
+	/// Provider the state which is not available locally is read from.
+	provider: Arc<dyn RemoteStorageProvider<Block>>,
+}
+
 /// State API backend for full nodes.
 pub struct FullState<BE, Block: BlockT, Client> {
 	client: Arc<Client>,
 	executor: SubscriptionTaskExecutor,
+	remote_storage: Option<RemoteStorage<BE, Block>>,
 	_phantom: PhantomData<(BE, Block)>,
 }
 
@@ -79,7 +88,17 @@ where
 {
 	/// Create new state API backend for full nodes.
 	pub fn new(client: Arc<Client>, executor: SubscriptionTaskExecutor) -> Self {
-		Self { client, executor, _phantom: PhantomData }
+		Self { client, executor, remote_storage: None, _phantom: PhantomData }
+	}
+
+	/// Read the storage of blocks whose state is not available in `backend` from `provider`.
+	pub fn with_remote_storage(
+		mut self,
+		backend: Arc<BE>,
+		provider: Arc<dyn RemoteStorageProvider<Block>>,
+	) -> Self {
+		self.remote_storage = Some(RemoteStorage { backend, provider });
+		self
 	}
 
 	/// Returns given block hash or best block hash if None is passed.
@@ -87,6 +106,50 @@ where
 		Ok(hash.unwrap_or_else(|| self.client.info().best_hash))
 	}
 
+	/// Runs `read` on the local state in a blocking task, so the database isn't read on the async
+	/// executor.
+	async fn local_storage<R: Send + 'static>(
+		&self,
+		read: impl FnOnce(&Client) -> ClientResult<R> + Send + 'static,
+	) -> ClientResult<R>
+	where
+		Client: Send + Sync + 'static,
+	{
+		let client = self.client.clone();
+		super::utils::spawn_blocking_with_timeout(None, move |_| Ok(read(&client)))
+			.await
+			.map_err(|error| ClientError::Application(Box::new(error)))?
+	}
+
+	/// Reads `key` from the remote storage provider, after reading it from the local state of
+	/// `block` failed with `error`.
+	///
+	/// Only blocks whose header is known locally, but whose state is missing or has been pruned,
+	/// are read remotely. Otherwise, or if there is no remote storage provider, `error` is
+	/// returned.
+	async fn remote_storage(
+		&self,
+		block: Block::Hash,
+		child_info: Option<&ChildInfo>,
+		key: StorageKey,
+		error: ClientError,
+	) -> ClientResult<Option<StorageData>> {
+		let Some(remote_storage) = &self.remote_storage else { return Err(error) };
+		let Some(header) = self.client.header(block)? else { return Err(error) };
+		if remote_storage.backend.have_state_at(block, *header.number()) {
+			return Err(error)
+		}
+
+		log::debug!(
+			target: "rpc",
+			"State of block {:?} is not available locally ({}), reading it from remote peers",
+			block,
+			error,
+		);
+		let values = remote_storage.provider.remote_read(&header, child_info, vec![key.0]).await?;
+		Ok(values.into_iter().next().flatten().map(StorageData))
+	}
+
 	/// Validates block range.
 	fn query_storage_range(
 		&self,
@@ -243,14 +306,18 @@ where
 			.map_err(client_err)
 	}
 
-	fn storage(
+	async fn storage(
 		&self,
 		block: Option<Block::Hash>,
 		key: StorageKey,
 	) -> std::result::Result<Option<StorageData>, Error> {
-		self.block_or_best(block)
-			.and_then(|block| self.client.storage(block, &key))
-			.map_err(client_err)
+		let block = self.block_or_best(block).map_err(client_err)?;
+		let local_key = key.clone();
+		match self.local_storage(move |client| client.storage(block, &local_key)).await {
+			Err(e) => self.remote_storage(block, None, key, e).await,
+			local => local,
+		}
+		.map_err(client_err)
 	}
 
 	async fn storage_size(
@@ -302,14 +369,21 @@ where
 		.map_err(|error| Error::Client(Box::new(error)))?
 	}
 
-	fn storage_hash(
+	async fn storage_hash(
 		&self,
 		block: Option<Block::Hash>,
 		key: StorageKey,
 	) -> std::result::Result<Option<Block::Hash>, Error> {
-		self.block_or_best(block)
-			.and_then(|block| self.client.storage_hash(block, &key))
-			.map_err(client_err)
+		let block = self.block_or_best(block).map_err(client_err)?;
+		let local_key = key.clone();
+		match self.local_storage(move |client| client.storage_hash(block, &local_key)).await {
+			Err(e) => self
+				.remote_storage(block, None, key, e)
+				.await
+				.map(|data| data.map(|data| HashingFor::<Block>::hash(&data.0))),
+			local => local,
+		}
+		.map_err(client_err)
 	}
 
 	fn metadata(&self, block: Option<Block::Hash>) -> std::result::Result<Bytes, Error> {
@@ -479,6 +553,7 @@ where
 	}
 }
 
+#[async_trait]
 impl<BE, Block, Client> ChildStateBackend<Block, Client> for FullState<BE, Block, Client>
 where
 	Block: BlockT + 'static,
@@ -568,22 +643,26 @@ where
 			.map_err(client_err)
 	}
 
-	fn storage(
+	async fn storage(
 		&self,
 		block: Option<Block::Hash>,
 		storage_key: PrefixedStorageKey,
 		key: StorageKey,
 	) -> std::result::Result<Option<StorageData>, Error> {
-		self.block_or_best(block)
-			.and_then(|block| {
-				let child_info = match ChildType::from_prefixed_key(&storage_key) {
-					Some((ChildType::ParentKeyId, storage_key)) =>
-						ChildInfo::new_default(storage_key),
-					None => return Err(sp_blockchain::Error::InvalidChildStorageKey),
-				};
-				self.client.child_storage(block, &child_info, &key)
-			})
-			.map_err(client_err)
+		let block = self.block_or_best(block).map_err(client_err)?;
+		let child_info = match ChildType::from_prefixed_key(&storage_key) {
+			Some((ChildType::ParentKeyId, storage_key)) => ChildInfo::new_default(storage_key),
+			None => return Err(client_err(sp_blockchain::Error::InvalidChildStorageKey)),
+		};
+		let (local_child_info, local_key) = (child_info.clone(), key.clone());
+		let local = self
+			.local_storage(move |client| client.child_storage(block, &local_child_info, &local_key))
+			.await;
+		match local {
+			Err(e) => self.remote_storage(block, Some(&child_info), key, e).await,
+			local => local,
+		}
+		.map_err(client_err)
 	}
 
 	fn storage_entries(
@@ -609,22 +688,31 @@ where
 			.collect()
 	}
 
-	fn storage_hash(
+	async fn storage_hash(
 		&self,
 		block: Option<Block::Hash>,
 		storage_key: PrefixedStorageKey,
 		key: StorageKey,
 	) -> std::result::Result<Option<Block::Hash>, Error> {
-		self.block_or_best(block)
-			.and_then(|block| {
-				let child_info = match ChildType::from_prefixed_key(&storage_key) {
-					Some((ChildType::ParentKeyId, storage_key)) =>
-						ChildInfo::new_default(storage_key),
-					None => return Err(sp_blockchain::Error::InvalidChildStorageKey),
-				};
-				self.client.child_storage_hash(block, &child_info, &key)
+		let block = self.block_or_best(block).map_err(client_err)?;
+		let child_info = match ChildType::from_prefixed_key(&storage_key) {
+			Some((ChildType::ParentKeyId, storage_key)) => ChildInfo::new_default(storage_key),
+			None => return Err(client_err(sp_blockchain::Error::InvalidChildStorageKey)),
+		};
+		let (local_child_info, local_key) = (child_info.clone(), key.clone());
+		let local = self
+			.local_storage(move |client| {
+				client.child_storage_hash(block, &local_child_info, &local_key)
 			})
-			.map_err(client_err)
+			.await;
+		match local {
+			Err(e) => self
+				.remote_storage(block, Some(&child_info), key, e)
+				.await
+				.map(|data| data.map(|data| HashingFor::<Block>::hash(&data.0))),
+			local => local,
+		}
+		.map_err(client_err)
 	}
 }
 
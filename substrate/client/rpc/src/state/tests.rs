@@ -29,7 +29,7 @@ use sp_core::{hash::H256, storage::ChildInfo};
 use std::sync::Arc;
 use substrate_test_runtime_client::{
 	prelude::*,
-	runtime::{ExtrinsicBuilder, Transfer},
+	runtime::{Block, ExtrinsicBuilder, Transfer},
 };
 
 const STORAGE_KEY: &[u8] = b"child";
@@ -68,13 +68,14 @@ async fn should_return_storage() {
 	assert_eq!(
 		client
 			.storage(key.clone(), Some(genesis_hash).into())
+			.await
 			.map(|x| x.map(|x| x.0.len()))
 			.unwrap()
 			.unwrap() as usize,
 		VALUE.len(),
 	);
 	assert_matches!(
-		client.storage_hash(key.clone(), Some(genesis_hash).into()).map(|x| x.is_some()),
+		client.storage_hash(key.clone(), Some(genesis_hash).into()).await.map(|x| x.is_some()),
 		Ok(true)
 	);
 	assert_eq!(
@@ -88,6 +89,7 @@ async fn should_return_storage() {
 	assert_eq!(
 		child
 			.storage(prefixed_storage_key(), key, Some(genesis_hash).into())
+			.await
 			.map(|x| x.map(|x| x.0.len()))
 			.unwrap()
 			.unwrap() as usize,
@@ -146,20 +148,17 @@ async fn should_return_child_storage() {
 	let key = StorageKey(b"key".to_vec());
 
 	assert_matches!(
-		child.storage(
-			child_key.clone(),
-			key.clone(),
-			Some(genesis_hash).into(),
-		),
+		child.storage(child_key.clone(), key.clone(), Some(genesis_hash).into()).await,
 		Ok(Some(StorageData(ref d))) if d[0] == 42 && d.len() == 1
 	);
 	assert_matches!(
 		child
-			.storage_hash(child_key.clone(), key.clone(), Some(genesis_hash).into(),)
+			.storage_hash(child_key.clone(), key.clone(), Some(genesis_hash).into())
+			.await
 			.map(|x| x.is_some()),
 		Ok(true)
 	);
-	assert_matches!(child.storage_size(child_key.clone(), key.clone(), None), Ok(Some(1)));
+	assert_matches!(child.storage_size(child_key.clone(), key.clone(), None).await, Ok(Some(1)));
 }
 
 #[tokio::test]
@@ -193,10 +192,14 @@ async fn should_return_child_storage_entries() {
 	assert_matches!(
 		child
 			.storage_hash(child_key.clone(), keys[0].clone(), Some(genesis_hash).into())
+			.await
 			.map(|x| x.is_some()),
 		Ok(true)
 	);
-	assert_matches!(child.storage_size(child_key.clone(), keys[0].clone(), None), Ok(Some(1)));
+	assert_matches!(
+		child.storage_size(child_key.clone(), keys[0].clone(), None).await,
+		Ok(Some(1))
+	);
 }
 
 #[tokio::test]
@@ -539,3 +542,106 @@ async fn concrete_storage_subscriptions_are_rpc_safe() {
 
 	assert!(sub.is_ok());
 }
+
+/// Answers every remote read with the same value, counting the reads.
+#[derive(Default)]
+struct MockRemoteStorage {
+	reads: std::sync::atomic::AtomicUsize,
+}
+
+impl MockRemoteStorage {
+	const VALUE: &'static [u8] = b"remote value";
+
+	fn reads(&self) -> usize {
+		self.reads.load(std::sync::atomic::Ordering::SeqCst)
+	}
+}
+
+impl RemoteStorageProvider<Block> for MockRemoteStorage {
+	fn remote_read(
+		&self,
+		_header: &<Block as BlockT>::Header,
+		_child_info: Option<&ChildInfo>,
+		keys: Vec<Vec<u8>>,
+	) -> futures::future::BoxFuture<'static, sp_blockchain::Result<Vec<Option<Vec<u8>>>>> {
+		use futures::FutureExt;
+
+		self.reads.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+		futures::future::ready(Ok(keys.iter().map(|_| Some(Self::VALUE.to_vec())).collect()))
+			.boxed()
+	}
+}
+
+#[tokio::test]
+async fn should_read_pruned_state_from_remote_storage() {
+	let builder = TestClientBuilder::with_pruning_window(1);
+	let backend = builder.backend();
+	let client = Arc::new(builder.build());
+	let genesis_hash = client.genesis_hash();
+
+	for _ in 0..3 {
+		let block = BlockBuilderBuilder::new(&*client)
+			.on_parent_block(client.chain_info().best_hash)
+			.with_parent_block_number(client.chain_info().best_number)
+			.build()
+			.unwrap()
+			.build()
+			.unwrap()
+			.block;
+		client.import_as_final(BlockOrigin::Own, block).await.unwrap();
+	}
+	assert!(!backend.have_state_at(genesis_hash, 0));
+
+	let remote_storage = Arc::new(MockRemoteStorage::default());
+	let (api, child) = new_full_with_remote_storage(
+		client,
+		backend,
+		test_executor(),
+		DenyUnsafe::No,
+		remote_storage.clone(),
+	);
+	let key = StorageKey(b":mock".to_vec());
+
+	assert_eq!(
+		api.storage(key.clone(), Some(genesis_hash)).await.unwrap(),
+		Some(StorageData(MockRemoteStorage::VALUE.to_vec())),
+	);
+	assert_eq!(
+		api.storage_hash(key.clone(), Some(genesis_hash)).await.unwrap(),
+		Some(sp_crypto_hashing::blake2_256(MockRemoteStorage::VALUE).into()),
+	);
+	assert_eq!(
+		child.storage(prefixed_storage_key(), key, Some(genesis_hash)).await.unwrap(),
+		Some(StorageData(MockRemoteStorage::VALUE.to_vec())),
+	);
+	assert_eq!(remote_storage.reads(), 3);
+}
+
+#[tokio::test]
+async fn should_not_read_available_state_from_remote_storage() {
+	const KEY: &[u8] = b":mock";
+	const VALUE: &[u8] = b"hello world";
+
+	let builder = TestClientBuilder::new().add_extra_storage(KEY.to_vec(), VALUE.to_vec());
+	let backend = builder.backend();
+	let client = Arc::new(builder.build());
+	let genesis_hash = client.genesis_hash();
+
+	let remote_storage = Arc::new(MockRemoteStorage::default());
+	let (api, _child) = new_full_with_remote_storage(
+		client,
+		backend,
+		test_executor(),
+		DenyUnsafe::No,
+		remote_storage.clone(),
+	);
+	let key = StorageKey(KEY.to_vec());
+
+	assert_eq!(
+		api.storage(key.clone(), Some(genesis_hash)).await.unwrap(),
+		Some(StorageData(VALUE.to_vec())),
+	);
+	// The header of the block isn't known, so its state root can't be checked either.
+	assert!(api.storage(key, Some(H256::repeat_byte(1))).await.is_err());
+	assert_eq!(remote_storage.reads(), 0);
+}
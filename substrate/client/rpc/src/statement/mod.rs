@@ -18,19 +18,35 @@
 
 //! Substrate statement store API.
 
+use crate::{
+	utils::{pipe_from_stream, spawn_subscription_task},
+	SubscriptionTaskExecutor,
+};
 use codec::{Decode, Encode};
-use jsonrpsee::core::{async_trait, RpcResult};
+use futures::{future, StreamExt};
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	types::ErrorObject,
+	PendingSubscriptionSink,
+};
 /// Re-export the API for backward compatibility.
-pub use sc_rpc_api::statement::{error::Error, StatementApiServer};
+pub use sc_rpc_api::statement::{
+	error::Error, AccountStats, StatementApiServer, StatementFilter, StatementPage,
+};
 use sc_rpc_api::DenyUnsafe;
 use sp_core::Bytes;
 use sp_statement_store::{StatementSource, SubmitResult};
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Maximum number of statements returned by a single `statement_query` call.
+const STATEMENT_QUERY_MAX_COUNT: u32 = 1000;
 
 /// Statement store API
 pub struct StatementStore {
 	store: Arc<dyn sp_statement_store::StatementStore>,
 	deny_unsafe: DenyUnsafe,
+	/// Executor to spawn subscriptions.
+	executor: SubscriptionTaskExecutor,
 }
 
 impl StatementStore {
@@ -38,8 +54,9 @@ impl StatementStore {
 	pub fn new(
 		store: Arc<dyn sp_statement_store::StatementStore>,
 		deny_unsafe: DenyUnsafe,
+		executor: SubscriptionTaskExecutor,
 	) -> Self {
-		StatementStore { store, deny_unsafe }
+		StatementStore { store, deny_unsafe, executor }
 	}
 }
 
@@ -87,6 +104,48 @@ impl StatementApiServer for StatementStore {
 			.collect())
 	}
 
+	fn query(
+		&self,
+		filter: StatementFilter,
+		start_after: Option<[u8; 32]>,
+		count: u32,
+	) -> RpcResult<StatementPage> {
+		self.deny_unsafe.check_if_safe()?;
+
+		if count > STATEMENT_QUERY_MAX_COUNT {
+			return Err(Error::InvalidCount { value: count, max: STATEMENT_QUERY_MAX_COUNT }.into())
+		}
+
+		let (statements, has_more) = self
+			.store
+			.matching_statements(&filter.match_all_topics, filter.dest, start_after, count as usize)
+			.map_err(|e| Error::StatementStore(e.to_string()))?;
+		let next_start_after =
+			if has_more { statements.last().map(|(hash, _)| *hash) } else { None };
+
+		Ok(StatementPage {
+			statements: statements.into_iter().map(|(_, s)| s.encode().into()).collect(),
+			next_start_after,
+		})
+	}
+
+	fn subscribe(&self, pending: PendingSubscriptionSink, filter: StatementFilter) {
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			spawn_subscription_task(&self.executor, pending.reject(ErrorObject::from(err)));
+			return
+		}
+
+		let stream = self.store.subscribe_statements().filter_map(move |(_, statement)| {
+			future::ready(
+				statement
+					.matches(&filter.match_all_topics, filter.dest)
+					.then(|| Bytes::from(statement.encode())),
+			)
+		});
+
+		spawn_subscription_task(&self.executor, pipe_from_stream(pending, stream));
+	}
+
 	fn submit(&self, encoded: Bytes) -> RpcResult<()> {
 		let statement = Decode::decode(&mut &*encoded)
 			.map_err(|e| Error::StatementStore(format!("Error decoding statement: {:?}", e)))?;
@@ -105,4 +164,51 @@ impl StatementApiServer for StatementStore {
 	fn remove(&self, hash: [u8; 32]) -> RpcResult<()> {
 		Ok(self.store.remove(&hash).map_err(|e| Error::StatementStore(e.to_string()))?)
 	}
+
+	fn account_stats(&self) -> RpcResult<Vec<AccountStats>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let statements =
+			self.store.statements().map_err(|e| Error::StatementStore(e.to_string()))?;
+		let mut stats = BTreeMap::<[u8; 32], (u32, u64)>::new();
+		for (_, statement) in statements {
+			if let Some(account) = statement.account_id() {
+				let (count, size) = stats.entry(account).or_default();
+				*count += 1;
+				*size += statement.data_len() as u64;
+			}
+		}
+
+		Ok(stats
+			.into_iter()
+			.map(|(account, (statements, data_size))| AccountStats {
+				account,
+				statements,
+				data_size,
+			})
+			.collect())
+	}
+
+	fn purge(&self, account: Option<[u8; 32]>, filter: Option<StatementFilter>) -> RpcResult<u32> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let statements =
+			self.store.statements().map_err(|e| Error::StatementStore(e.to_string()))?;
+		let mut purged = 0;
+		for (hash, statement) in statements {
+			if account.map_or(false, |account| statement.account_id() != Some(account)) {
+				continue
+			}
+			if filter
+				.as_ref()
+				.map_or(false, |filter| !statement.matches(&filter.match_all_topics, filter.dest))
+			{
+				continue
+			}
+			self.store.remove(&hash).map_err(|e| Error::StatementStore(e.to_string()))?;
+			purged += 1;
+		}
+
+		Ok(purged)
+	}
 }
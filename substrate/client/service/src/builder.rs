@@ -32,7 +32,8 @@ use prometheus_endpoint::Registry;
 use sc_chain_spec::get_extension;
 use sc_client_api::{
 	execution_extensions::ExecutionExtensions, proof_provider::ProofProvider, BadBlocks,
-	BlockBackend, BlockchainEvents, ExecutorProvider, ForkBlocks, StorageProvider, UsageProvider,
+	BlockBackend, BlockchainEvents, ExecutorProvider, ForkBlocks, RemoteStorageProvider,
+	StorageProvider, UsageProvider,
 };
 use sc_client_db::{Backend, DatabaseSettings};
 use sc_consensus::import_queue::ImportQueue;
@@ -50,7 +51,10 @@ use sc_network::{
 	NetworkBackend, NetworkStateInfo,
 };
 use sc_network_common::role::Roles;
-use sc_network_light::light_client_requests::handler::LightClientRequestHandler;
+use sc_network_light::{
+	light_client_requests::handler::LightClientRequestHandler,
+	remote_storage::{handler::RemoteStorageRequestHandler, requester::RemoteStorageRequester},
+};
 use sc_network_sync::{
 	block_relay_protocol::BlockRelayParams, block_request_handler::BlockRequestHandler,
 	engine::SyncingEngine, service::network::NetworkServiceProvider,
@@ -492,6 +496,17 @@ where
 
 	let rpc_id_provider = config.rpc_id_provider.take();
 
+	let remote_storage = config.rpc_remote_state.then(|| {
+		let genesis_hash =
+			client.hash(Zero::zero()).ok().flatten().expect("Genesis block exists; qed");
+		Arc::new(RemoteStorageRequester::new(
+			Arc::new(network.clone()),
+			sync_service.clone(),
+			genesis_hash,
+			config.chain_spec.fork_id(),
+		)) as Arc<dyn RemoteStorageProvider<TBl>>
+	});
+
 	// jsonrpsee RPC
 	let gen_rpc_module = |deny_unsafe: DenyUnsafe| {
 		gen_rpc_module(
@@ -503,6 +518,7 @@ where
 			system_rpc_tx.clone(),
 			&config,
 			backend.clone(),
+			remote_storage.clone(),
 			&*rpc_builder,
 		)
 	};
@@ -605,6 +621,7 @@ pub fn gen_rpc_module<TBl, TBackend, TCl, TRpc, TExPool>(
 	system_rpc_tx: TracingUnboundedSender<sc_rpc::system::Request<TBl>>,
 	config: &Configuration,
 	backend: Arc<TBackend>,
+	remote_storage: Option<Arc<dyn RemoteStorageProvider<TBl>>>,
 	rpc_builder: &(dyn Fn(DenyUnsafe, SubscriptionTaskExecutor) -> Result<RpcModule<TRpc>, Error>),
 ) -> Result<RpcModule<()>, Error>
 where
@@ -640,8 +657,16 @@ where
 
 	let (chain, state, child_state) = {
 		let chain = sc_rpc::chain::new_full(client.clone(), task_executor.clone()).into_rpc();
-		let (state, child_state) =
-			sc_rpc::state::new_full(client.clone(), task_executor.clone(), deny_unsafe);
+		let (state, child_state) = match remote_storage {
+			Some(remote_storage) => sc_rpc::state::new_full_with_remote_storage(
+				client.clone(),
+				backend.clone(),
+				task_executor.clone(),
+				deny_unsafe,
+				remote_storage,
+			),
+			None => sc_rpc::state::new_full(client.clone(), task_executor.clone(), deny_unsafe),
+		};
 		let state = state.into_rpc();
 		let child_state = child_state.into_rpc();
 
@@ -687,6 +712,7 @@ where
 			client.clone(),
 			backend.clone(),
 			genesis_hash,
+			task_executor.clone(),
 			// Defaults to sensible limits for the `Archive`.
 			sc_rpc_spec_v2::archive::ArchiveConfig::default(),
 		)
@@ -896,10 +922,22 @@ where
 		protocol_config
 	};
 
+	let remote_storage_request_protocol_config = {
+		// Allow both outgoing and incoming requests.
+		let (handler, protocol_config) = RemoteStorageRequestHandler::new::<TNet>(
+			&protocol_id,
+			config.chain_spec.fork_id(),
+			client.clone(),
+		);
+		spawn_handle.spawn("remote-storage-request-handler", Some("networking"), handler.run());
+		protocol_config
+	};
+
 	// install request handlers to `FullNetworkConfiguration`
 	net_config.add_request_response_protocol(block_request_protocol_config);
 	net_config.add_request_response_protocol(state_request_protocol_config);
 	net_config.add_request_response_protocol(light_client_request_protocol_config);
+	net_config.add_request_response_protocol(remote_storage_request_protocol_config);
 
 	if let Some(config) = warp_sync_protocol_config {
 		net_config.add_request_response_protocol(config);
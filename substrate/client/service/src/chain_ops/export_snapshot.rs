@@ -0,0 +1,115 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error::Error;
+use codec::Encode;
+use futures::{future, prelude::*};
+use log::info;
+use sc_client_api::{BlockBackend, CompactProof, HeaderBackend, ProofProvider};
+use smallvec::SmallVec;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{io::Write, pin::Pin, sync::Arc, task::Poll};
+
+/// Magic bytes at the start of every state snapshot.
+pub const SNAPSHOT_MAGIC: [u8; 4] = *b"snap";
+
+/// Version of the state snapshot format.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Maximum size in bytes of a single snapshot chunk, the same as a state sync response.
+const MAX_CHUNK_SIZE: usize = 2 * 1024 * 1024;
+
+/// Performs the state snapshot export of the block with the given `hash`.
+///
+/// The snapshot is a stream of SCALE encoded items:
+/// - [`SNAPSHOT_MAGIC`] and [`SNAPSHOT_VERSION`],
+/// - the header of the block and its justifications,
+/// - `Some(CompactProof)` chunks of the state, terminated by `None`.
+///
+/// Every chunk is a range proof of the state, exactly as served by state sync, so the snapshot can
+/// be verified against the state root of the header when imported.
+pub fn export_snapshot<B, C>(
+	client: Arc<C>,
+	hash: B::Hash,
+	mut output: impl Write + 'static,
+) -> Pin<Box<dyn Future<Output = Result<(), Error>>>>
+where
+	C: HeaderBackend<B> + BlockBackend<B> + ProofProvider<B> + 'static,
+	B: BlockT,
+{
+	let mut state_root = None;
+	let mut start_key = SmallVec::<[Vec<u8>; 2]>::new();
+	let mut chunks = 0u64;
+	let mut keys = 0u64;
+
+	// Exporting the snapshot is implemented as a future, because we want the operation to be
+	// interruptible.
+	//
+	// Every time we write a chunk to the output, the `Future` re-schedules itself and returns
+	// `Poll::Pending`.
+	let export = future::poll_fn(move |cx| {
+		let client = &client;
+
+		let root = match state_root {
+			Some(root) => root,
+			None => {
+				let header = client
+					.header(hash)?
+					.ok_or_else(|| Error::Other(format!("Header of block {:?} not found", hash)))?;
+				let justifications = client.justifications(hash)?;
+				info!("Exporting state snapshot of block #{} ({:?})", header.number(), hash);
+
+				output.write_all(&SNAPSHOT_MAGIC)?;
+				output.write_all(&SNAPSHOT_VERSION.encode())?;
+				output.write_all(&header.encode())?;
+				output.write_all(&justifications.encode())?;
+				*state_root.insert(*header.state_root())
+			},
+		};
+
+		let (proof, count) =
+			client.read_proof_collection(hash, start_key.as_slice(), MAX_CHUNK_SIZE)?;
+		output.write_all(&Some(&proof).encode())?;
+		chunks += 1;
+		keys += count as u64;
+
+		// The proof is verified to find out where the next chunk starts, as state sync does.
+		let (values, completed) = client.verify_range_proof(root, proof, start_key.as_slice())?;
+		if completed == 0 {
+			output.write_all(&None::<CompactProof>.encode())?;
+			output.flush()?;
+			info!("🎉 Exported {} keys in {} chunks", keys, chunks);
+			return Poll::Ready(Ok(()))
+		}
+		if !values.update_last_key(completed, &mut start_key) {
+			return Poll::Ready(Err(Error::Other(format!(
+				"Error updating key cursor of chunk #{}, depth: {}",
+				chunks, completed
+			))))
+		}
+		if chunks % 100 == 0 {
+			info!("Exported {} keys in {} chunks", keys, chunks);
+		}
+
+		// Re-schedule the task in order to continue the operation.
+		cx.waker().wake_by_ref();
+		Poll::Pending
+	});
+
+	Box::pin(export)
+}
@@ -0,0 +1,302 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::export_snapshot::{SNAPSHOT_MAGIC, SNAPSHOT_VERSION};
+use crate::error::Error;
+use codec::{Decode, IoReader as CodecIoReader};
+use futures::{future, prelude::*};
+use futures_timer::Delay;
+use log::{info, warn};
+use sc_client_api::{CompactProof, HeaderBackend, ProofProvider};
+use sc_consensus::{
+	import_queue::{BlockImportError, BlockImportStatus, ImportQueue, IncomingBlock, Link},
+	ImportedState,
+};
+use smallvec::SmallVec;
+use sp_consensus::BlockOrigin;
+use sp_core::storage::well_known_keys;
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT, NumberFor},
+	Justifications,
+};
+use sp_state_machine::{KeyValueStates, KeyValueStorageLevel};
+use std::{collections::HashMap, io::Read, pin::Pin, sync::Arc, task::Poll, time::Duration};
+
+/// Number of milliseconds to wait until next poll.
+const DELAY_TIME: u64 = 200;
+
+/// The state read from the snapshot so far, in the form it is handed to the import queue.
+///
+/// The verified key values of every chunk are moved into their storage level as the chunk is
+/// read, and the chunk proof is dropped, so the state is only held once. The import queue takes
+/// the state of a block as a whole, so it can't be handed over in parts.
+struct State {
+	/// The top trie, followed by the child tries in the order they were first read.
+	levels: KeyValueStates,
+	/// Position of the level of every child trie in `levels`, by root.
+	child_levels: HashMap<Vec<u8>, usize>,
+}
+
+impl Default for State {
+	fn default() -> Self {
+		let top = KeyValueStorageLevel {
+			state_root: Vec::new(),
+			parent_storage_keys: Vec::new(),
+			key_values: Vec::new(),
+		};
+		Self { levels: KeyValueStates(vec![top]), child_levels: HashMap::new() }
+	}
+}
+
+impl State {
+	/// The level of the child trie with the given root.
+	fn child_level(&mut self, root: Vec<u8>) -> &mut KeyValueStorageLevel {
+		let levels = &mut self.levels.0;
+		let index = *self.child_levels.entry(root.clone()).or_insert_with(|| {
+			levels.push(KeyValueStorageLevel {
+				state_root: root,
+				parent_storage_keys: Vec::new(),
+				key_values: Vec::new(),
+			});
+			levels.len() - 1
+		});
+		&mut levels[index]
+	}
+
+	/// Moves the verified key values of a snapshot chunk into the state.
+	fn import_values(&mut self, values: KeyValueStates) {
+		for values in values.0 {
+			if values.state_root.is_empty() {
+				for (key, value) in values.key_values {
+					// Read child trie roots.
+					if well_known_keys::is_child_storage_key(&key) {
+						self.child_level(value).parent_storage_keys.push(key);
+					} else {
+						self.levels.0[0].key_values.push((key, value));
+					}
+				}
+			} else {
+				let level = self.child_level(values.state_root);
+				if !level.key_values.is_empty() && level.parent_storage_keys.len() > 1 {
+					// Already imported child trie with same root.
+				} else {
+					level.key_values.extend(values.key_values);
+				}
+			}
+		}
+	}
+}
+
+/// Reads the snapshot magic and version, followed by the header and justifications of the block.
+fn read_preamble<B: BlockT, R: Read>(
+	reader: &mut CodecIoReader<R>,
+) -> Result<(B::Header, Option<Justifications>), String> {
+	let error = |e: codec::Error| format!("Error reading snapshot header: {}", e);
+
+	let magic = <[u8; 4]>::decode(reader).map_err(error)?;
+	let version = u32::decode(reader).map_err(error)?;
+	if magic != SNAPSHOT_MAGIC {
+		return Err("Input is not a state snapshot".into())
+	}
+	if version != SNAPSHOT_VERSION {
+		return Err(format!("Unsupported state snapshot version {}", version))
+	}
+
+	let header = B::Header::decode(reader).map_err(error)?;
+	let justifications = Option::<Justifications>::decode(reader).map_err(error)?;
+	Ok((header, justifications))
+}
+
+enum ImportState<R: Read> {
+	/// We are reading the state chunks from the input.
+	Reading { reader: CodecIoReader<R>, state: State, last_key: SmallVec<[Vec<u8>; 2]> },
+	/// The state was handed over to the import queue, we are waiting for it to be imported.
+	WaitingForImportQueue { delay: Delay },
+}
+
+/// Imports the state snapshot written by [`export_snapshot`](super::export_snapshot).
+///
+/// The snapshot must be of the block with hash `expected_hash`, which has to come from a trusted
+/// source: every chunk of the snapshot is verified against the state root of the snapshot
+/// header, which is only as trustworthy as the header itself. The block is then imported
+/// together with its state, as if state sync had completed.
+pub fn import_snapshot<B, IQ, C>(
+	client: Arc<C>,
+	mut import_queue: IQ,
+	input: impl Read + Send + 'static,
+	expected_hash: B::Hash,
+) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send>>
+where
+	C: HeaderBackend<B> + ProofProvider<B> + Send + Sync + 'static,
+	B: BlockT,
+	IQ: ImportQueue<B> + 'static,
+{
+	struct WaitLink {
+		imported: bool,
+		has_error: bool,
+	}
+
+	impl<B: BlockT> Link<B> for WaitLink {
+		fn blocks_processed(
+			&mut self,
+			imported: usize,
+			_num_expected_blocks: usize,
+			results: Vec<(Result<BlockImportStatus<NumberFor<B>>, BlockImportError>, B::Hash)>,
+		) {
+			self.imported |= imported > 0;
+
+			for result in results {
+				if let (Err(err), hash) = result {
+					warn!("There was an error importing block with hash {:?}: {}", hash, err);
+					self.has_error = true;
+					break
+				}
+			}
+		}
+	}
+
+	let mut reader = CodecIoReader(input);
+	let (header, justifications) = match read_preamble::<B, _>(&mut reader) {
+		Ok(preamble) => preamble,
+		// We've encountered an error while reading the snapshot header
+		// so we can just return a future that returns an error.
+		Err(e) => return future::ready(Err(Error::Other(e))).boxed(),
+	};
+	let hash = header.hash();
+	if hash != expected_hash {
+		return future::ready(Err(Error::Other(format!(
+			"State snapshot is of block {:?}, expected {:?}",
+			hash, expected_hash
+		))))
+		.boxed()
+	}
+	let state_root = *header.state_root();
+	info!("Importing state snapshot of block #{} ({:?})", header.number(), hash);
+
+	let mut link = WaitLink { imported: false, has_error: false };
+	let mut block = Some((header, justifications));
+	let mut chunks = 0u64;
+	let mut state = Some(ImportState::Reading {
+		reader,
+		state: State::default(),
+		last_key: SmallVec::default(),
+	});
+
+	// Importing the snapshot is implemented as a future, because we want the operation to be
+	// interruptible.
+	//
+	// Every time we read a chunk from the input, the `Future` re-schedules itself and returns
+	// `Poll::Pending`.
+	let import = future::poll_fn(move |cx| {
+		let client = &client;
+		let queue = &mut import_queue;
+		match state.take().expect("state should never be None; qed") {
+			ImportState::Reading { mut reader, state: mut values, mut last_key } => {
+				let proof = match Option::<CompactProof>::decode(&mut reader) {
+					Ok(proof) => proof,
+					Err(e) =>
+						return Poll::Ready(Err(Error::Other(format!(
+							"Error reading snapshot chunk #{}: {}",
+							chunks, e
+						)))),
+				};
+
+				let Some(proof) = proof else {
+					return Poll::Ready(Err(Error::Other(format!(
+						"Snapshot ended after {} chunks before the state was complete",
+						chunks
+					))))
+				};
+				chunks += 1;
+
+				let (chunk_values, completed) =
+					match client.verify_range_proof(state_root, proof, last_key.as_slice()) {
+						Ok(verified) => verified,
+						Err(e) =>
+							return Poll::Ready(Err(Error::Other(format!(
+								"Snapshot chunk #{} failed proof verification: {}",
+								chunks, e
+							)))),
+					};
+				if completed != 0 && !chunk_values.update_last_key(completed, &mut last_key) {
+					return Poll::Ready(Err(Error::Other(format!(
+						"Error updating key cursor of chunk #{}, depth: {}",
+						chunks, completed
+					))))
+				}
+				values.import_values(chunk_values);
+
+				if completed != 0 {
+					if chunks % 100 == 0 {
+						info!("Read {} snapshot chunks", chunks);
+					}
+					state = Some(ImportState::Reading { reader, state: values, last_key });
+				} else {
+					info!("Read all {} snapshot chunks, importing the state", chunks);
+					let (header, justifications) =
+						block.take().expect("the state is completed only once; qed");
+					queue.service_ref().import_blocks(
+						BlockOrigin::File,
+						vec![IncomingBlock::<B> {
+							hash,
+							header: Some(header),
+							body: None,
+							indexed_body: None,
+							justifications,
+							origin: None,
+							allow_missing_state: true,
+							import_existing: true,
+							state: Some(ImportedState { block: hash, state: values.levels }),
+							skip_execution: false,
+						}],
+					);
+					let delay = Delay::new(Duration::from_millis(DELAY_TIME));
+					state = Some(ImportState::WaitingForImportQueue { delay });
+				}
+			},
+			ImportState::WaitingForImportQueue { mut delay } => {
+				if link.imported {
+					info!("🎉 Imported state snapshot. Best: #{}", client.info().best_number);
+					return Poll::Ready(Ok(()))
+				}
+
+				// Wait for the delay, because we know the queue is lagging behind.
+				match Pin::new(&mut delay).poll(cx) {
+					Poll::Pending => {
+						state = Some(ImportState::WaitingForImportQueue { delay });
+						return Poll::Pending
+					},
+					Poll::Ready(_) => {
+						delay.reset(Duration::from_millis(DELAY_TIME));
+					},
+				}
+				state = Some(ImportState::WaitingForImportQueue { delay });
+			},
+		}
+
+		queue.poll_actions(cx, &mut link);
+
+		if link.has_error {
+			return Poll::Ready(Err(Error::Other("Error importing the state snapshot".into())))
+		}
+
+		cx.waker().wake_by_ref();
+		Poll::Pending
+	});
+	Box::pin(import)
+}
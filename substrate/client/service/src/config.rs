@@ -36,7 +36,7 @@ pub use sc_network::{
 };
 pub use sc_rpc_server::IpNetwork;
 pub use sc_telemetry::TelemetryEndpoints;
-pub use sc_transaction_pool::Options as TransactionPoolOptions;
+pub use sc_transaction_pool::{Options as TransactionPoolOptions, TransactionPoolType};
 use sp_core::crypto::SecretString;
 use std::{
 	io, iter,
@@ -59,6 +59,8 @@ pub struct Configuration {
 	pub tokio_handle: tokio::runtime::Handle,
 	/// Extrinsic pool configuration.
 	pub transaction_pool: TransactionPoolOptions,
+	/// Type of the extrinsic pool.
+	pub transaction_pool_type: TransactionPoolType,
 	/// Network configuration.
 	pub network: NetworkConfiguration,
 	/// Configuration for the keystore.
@@ -113,6 +115,9 @@ pub struct Configuration {
 	pub rpc_rate_limit_whitelisted_ips: Vec<IpNetwork>,
 	/// RPC rate limit trust proxy headers.
 	pub rpc_rate_limit_trust_proxy_headers: bool,
+	/// Read the storage of blocks whose state is not available locally from remote peers when
+	/// answering RPC queries.
+	pub rpc_remote_state: bool,
 	/// Prometheus endpoint configuration. `None` if disabled.
 	pub prometheus_config: Option<PrometheusConfig>,
 	/// Telemetry service URL. `None` if disabled.
@@ -228,6 +228,7 @@ fn node_config<E: ChainSpecExtension + Clone + 'static + Send + Sync>(
 		role,
 		tokio_handle,
 		transaction_pool: Default::default(),
+		transaction_pool_type: Default::default(),
 		network: network_config,
 		keystore: KeystoreConfig::Path { path: root.join("key"), password: None },
 		database: DatabaseSource::RocksDb { path: root.join("db"), cache_size: 128 },
@@ -251,6 +252,7 @@ fn node_config<E: ChainSpecExtension + Clone + 'static + Send + Sync>(
 		rpc_rate_limit: None,
 		rpc_rate_limit_whitelisted_ips: Default::default(),
 		rpc_rate_limit_trust_proxy_headers: Default::default(),
+		rpc_remote_state: false,
 		prometheus_config: None,
 		telemetry_endpoints: None,
 		default_heap_pages: None,
@@ -62,6 +62,8 @@ const PRUNING_MODE: &[u8] = b"mode";
 const PRUNING_MODE_ARCHIVE: &[u8] = b"archive";
 const PRUNING_MODE_ARCHIVE_CANON: &[u8] = b"archive_canonical";
 const PRUNING_MODE_CONSTRAINED: &[u8] = b"constrained";
+/// Followed by the little-endian checkpoint interval.
+const PRUNING_MODE_CONSTRAINED_CHECKPOINTS: &[u8] = b"constrained_checkpoints";
 pub(crate) const DEFAULT_MAX_BLOCK_CONSTRAINT: u32 = 256;
 
 /// Database value type.
@@ -145,6 +147,8 @@ pub enum StateDbError {
 	BlockUnavailable,
 	/// Block record is missing from the pruning window
 	BlockMissing,
+	/// Checkpoints require a database with reference counting support
+	CheckpointsUnsupported,
 }
 
 impl<E> From<StateDbError> for Error<E> {
@@ -196,6 +200,10 @@ impl fmt::Debug for StateDbError {
 				write!(f, "Trying to get a block record from db while it is not commit to db yet")
 			},
 			Self::BlockMissing => write!(f, "Block record is missing from the pruning window"),
+			Self::CheckpointsUnsupported => write!(
+				f,
+				"State checkpoints require a database with reference counting support, such as paritydb"
+			),
 		}
 	}
 }
@@ -224,6 +232,9 @@ pub struct Constraints {
 	/// Maximum blocks. Defaults to 0 when unspecified, effectively keeping only non-canonical
 	/// states.
 	pub max_blocks: Option<u32>,
+	/// Keep the state of every block with a number that is a multiple of the interval, in
+	/// addition to the pruning window. Fixed when the database is created.
+	pub checkpoint_interval: Option<u32>,
 }
 
 /// Pruning mode.
@@ -240,7 +251,16 @@ pub enum PruningMode {
 impl PruningMode {
 	/// Create a mode that keeps given number of blocks.
 	pub fn blocks_pruning(n: u32) -> PruningMode {
-		PruningMode::Constrained(Constraints { max_blocks: Some(n) })
+		PruningMode::Constrained(Constraints { max_blocks: Some(n), checkpoint_interval: None })
+	}
+
+	/// Create a mode that keeps given number of blocks and the state of every block with a number
+	/// that is a multiple of `interval`.
+	pub fn checkpoints_pruning(n: u32, interval: u32) -> PruningMode {
+		PruningMode::Constrained(Constraints {
+			max_blocks: Some(n),
+			checkpoint_interval: Some(interval),
+		})
 	}
 
 	/// Is this an archive (either ArchiveAll or ArchiveCanonical) pruning mode?
@@ -251,12 +271,17 @@ impl PruningMode {
 		}
 	}
 
-	/// Returns the pruning mode
-	pub fn id(&self) -> &[u8] {
+	/// Returns the pruning mode id. The checkpoint interval is part of the id, as the states kept
+	/// in the database depend on it.
+	pub fn id(&self) -> Vec<u8> {
 		match self {
-			PruningMode::ArchiveAll => PRUNING_MODE_ARCHIVE,
-			PruningMode::ArchiveCanonical => PRUNING_MODE_ARCHIVE_CANON,
-			PruningMode::Constrained(_) => PRUNING_MODE_CONSTRAINED,
+			PruningMode::ArchiveAll => PRUNING_MODE_ARCHIVE.to_vec(),
+			PruningMode::ArchiveCanonical => PRUNING_MODE_ARCHIVE_CANON.to_vec(),
+			PruningMode::Constrained(Constraints { checkpoint_interval: None, .. }) =>
+				PRUNING_MODE_CONSTRAINED.to_vec(),
+			PruningMode::Constrained(Constraints {
+				checkpoint_interval: Some(interval), ..
+			}) => [PRUNING_MODE_CONSTRAINED_CHECKPOINTS, &interval.to_le_bytes()[..]].concat(),
 		}
 	}
 
@@ -265,7 +290,14 @@ impl PruningMode {
 			PRUNING_MODE_ARCHIVE => Some(Self::ArchiveAll),
 			PRUNING_MODE_ARCHIVE_CANON => Some(Self::ArchiveCanonical),
 			PRUNING_MODE_CONSTRAINED => Some(Self::Constrained(Default::default())),
-			_ => None,
+			_ => {
+				let interval = id.strip_prefix(PRUNING_MODE_CONSTRAINED_CHECKPOINTS)?;
+				let interval = u32::from_le_bytes(interval.try_into().ok()?);
+				Some(Self::Constrained(Constraints {
+					checkpoint_interval: Some(interval),
+					..Default::default()
+				}))
+			},
 		}
 	}
 }
@@ -278,7 +310,7 @@ impl Default for PruningMode {
 
 impl Default for Constraints {
 	fn default() -> Self {
-		Self { max_blocks: Some(DEFAULT_MAX_BLOCK_CONSTRAINT) }
+		Self { max_blocks: Some(DEFAULT_MAX_BLOCK_CONSTRAINT), checkpoint_interval: None }
 	}
 }
 
@@ -317,8 +349,9 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 
 		let non_canonical: NonCanonicalOverlay<BlockHash, Key> = NonCanonicalOverlay::new(&db)?;
 		let pruning: Option<RefWindow<BlockHash, Key, D>> = match mode {
-			PruningMode::Constrained(Constraints { max_blocks }) =>
-				Some(RefWindow::new(db, max_blocks.unwrap_or(0), ref_counting)?),
+			PruningMode::Constrained(Constraints { max_blocks, checkpoint_interval }) => Some(
+				RefWindow::new(db, max_blocks.unwrap_or(0), ref_counting, checkpoint_interval)?,
+			),
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
 		};
 
@@ -560,7 +593,7 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDb<BlockHash, Key, D> {
 			let mut cs: CommitSet<Key> = Default::default();
 
 			let key = to_meta_key(PRUNING_MODE, &());
-			let value = selected_mode.id().to_owned();
+			let value = selected_mode.id();
 
 			cs.meta.inserted.push((key, value));
 
@@ -696,7 +729,11 @@ fn choose_pruning_mode(
 		(PruningMode::ArchiveAll, PruningMode::ArchiveAll) => Ok(PruningMode::ArchiveAll),
 		(PruningMode::ArchiveCanonical, PruningMode::ArchiveCanonical) =>
 			Ok(PruningMode::ArchiveCanonical),
-		(PruningMode::Constrained(_), PruningMode::Constrained(requested)) =>
+		// Checkpoints can't be enabled on an existing database, as the states of the past
+		// checkpoints are already pruned, nor changed or disabled, as the kept checkpoints would
+		// never be pruned.
+		(PruningMode::Constrained(stored), PruningMode::Constrained(requested))
+			if stored.checkpoint_interval == requested.checkpoint_interval =>
 			Ok(PruningMode::Constrained(requested)),
 		(stored, requested) => Err(StateDbError::IncompatiblePruningModes { requested, stored }),
 	}
@@ -707,6 +744,7 @@ mod tests {
 	use crate::{
 		test::{make_changeset, make_db, TestDb},
 		Constraints, Error, IsPruned, PruningMode, StateDb, StateDbError,
+		DEFAULT_MAX_BLOCK_CONSTRAINT,
 	};
 	use sp_core::H256;
 
@@ -788,8 +826,10 @@ mod tests {
 
 	#[test]
 	fn block_record_unavailable() {
-		let (mut db, state_db) =
-			make_test_db(PruningMode::Constrained(Constraints { max_blocks: Some(1) }));
+		let (mut db, state_db) = make_test_db(PruningMode::Constrained(Constraints {
+			max_blocks: Some(1),
+			checkpoint_interval: None,
+		}));
 		// import 2 blocks
 		for i in &[5, 6] {
 			db.commit(
@@ -823,13 +863,19 @@ mod tests {
 
 	#[test]
 	fn prune_window_0() {
-		let (db, _) = make_test_db(PruningMode::Constrained(Constraints { max_blocks: Some(0) }));
+		let (db, _) = make_test_db(PruningMode::Constrained(Constraints {
+			max_blocks: Some(0),
+			checkpoint_interval: None,
+		}));
 		assert!(db.data_eq(&make_db(&[21, 3, 922, 94])));
 	}
 
 	#[test]
 	fn prune_window_1() {
-		let (db, sdb) = make_test_db(PruningMode::Constrained(Constraints { max_blocks: Some(1) }));
+		let (db, sdb) = make_test_db(PruningMode::Constrained(Constraints {
+			max_blocks: Some(1),
+			checkpoint_interval: None,
+		}));
 		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(0), 0), IsPruned::Pruned);
 		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(1), 1), IsPruned::Pruned);
 		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(21), 2), IsPruned::Pruned);
@@ -839,7 +885,10 @@ mod tests {
 
 	#[test]
 	fn prune_window_2() {
-		let (db, sdb) = make_test_db(PruningMode::Constrained(Constraints { max_blocks: Some(2) }));
+		let (db, sdb) = make_test_db(PruningMode::Constrained(Constraints {
+			max_blocks: Some(2),
+			checkpoint_interval: None,
+		}));
 		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(0), 0), IsPruned::Pruned);
 		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(1), 1), IsPruned::Pruned);
 		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(21), 2), IsPruned::NotPruned);
@@ -863,7 +912,10 @@ mod tests {
 				)
 				.unwrap(),
 		);
-		let new_mode = PruningMode::Constrained(Constraints { max_blocks: Some(2) });
+		let new_mode = PruningMode::Constrained(Constraints {
+			max_blocks: Some(2),
+			checkpoint_interval: None,
+		});
 		let state_db_open_result: Result<(_, StateDb<H256, H256, TestDb>), _> =
 			StateDb::open(db.clone(), Some(new_mode), false, false);
 		assert!(state_db_open_result.is_err());
@@ -895,6 +947,54 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn checkpoint_interval_is_persisted() {
+		for interval in [None, Some(1), Some(1000)] {
+			let mode = PruningMode::Constrained(Constraints {
+				max_blocks: Some(DEFAULT_MAX_BLOCK_CONSTRAINT),
+				checkpoint_interval: interval,
+			});
+			assert_eq!(PruningMode::from_id(&mode.id()), Some(mode));
+		}
+		assert_eq!(PruningMode::from_id(b"constrained_checkpoints\x01"), None);
+	}
+
+	#[test]
+	fn checkpoint_interval_compatibility() {
+		for (created, reopened, expected) in [
+			(
+				PruningMode::checkpoints_pruning(256, 100),
+				None,
+				Ok(PruningMode::Constrained(Constraints {
+					max_blocks: Some(DEFAULT_MAX_BLOCK_CONSTRAINT),
+					checkpoint_interval: Some(100),
+				})),
+			),
+			(
+				PruningMode::checkpoints_pruning(256, 100),
+				Some(PruningMode::checkpoints_pruning(128, 100)),
+				Ok(PruningMode::checkpoints_pruning(128, 100)),
+			),
+			(
+				PruningMode::checkpoints_pruning(256, 100),
+				Some(PruningMode::checkpoints_pruning(256, 50)),
+				Err(()),
+			),
+			(
+				PruningMode::checkpoints_pruning(256, 100),
+				Some(PruningMode::blocks_pruning(256)),
+				Err(()),
+			),
+			(
+				PruningMode::blocks_pruning(256),
+				Some(PruningMode::checkpoints_pruning(256, 100)),
+				Err(()),
+			),
+		] {
+			check_stored_and_requested_mode_compatibility(Some(created), reopened, expected);
+		}
+	}
+
 	#[test]
 	fn pruning_mode_compatibility() {
 		for (created, reopened, expected) in [
@@ -23,6 +23,11 @@
 //! If a node is re-inserted into the window it gets removed from
 //! the death list.
 //! The changes are journaled in the DB.
+//!
+//! With checkpoints, the state of every block whose number is a multiple of the checkpoint
+//! interval is retained. When pruning the blocks following a checkpoint, only the nodes inserted
+//! after the checkpoint are deleted. The journal records of these blocks are kept until the next
+//! checkpoint is pruned, so the inserted nodes can be restored on startup.
 
 use crate::{
 	noncanonical::LAST_CANONICAL, to_meta_key, CommitSet, Error, Hash, MetaDb, StateDbError,
@@ -30,7 +35,7 @@ use crate::{
 };
 use codec::{Decode, Encode};
 use log::trace;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
 
 pub(crate) const LAST_PRUNED: &[u8] = b"last_pruned";
 const PRUNING_JOURNAL: &[u8] = b"pruning_journal";
@@ -42,6 +47,63 @@ pub struct RefWindow<BlockHash: Hash, Key: Hash, D: MetaDb> {
 	queue: DeathRowQueue<BlockHash, Key, D>,
 	/// Block number that is next to be pruned.
 	base: u64,
+	/// Retained checkpoints, if any.
+	checkpoints: Option<Checkpoints<Key>>,
+}
+
+/// Tracks the nodes inserted since the last pruned checkpoint.
+///
+/// The insertions are counted, which relies on the database counting references as well.
+struct Checkpoints<Key: Hash> {
+	/// Blocks with a number that is a multiple of the interval are checkpoints.
+	interval: u64,
+	/// Number of insertions of every node since the last pruned checkpoint.
+	inserted: HashMap<Key, u32>,
+	/// The first block whose journal record is kept until the next checkpoint is pruned.
+	first_kept: u64,
+}
+
+impl<Key: Hash> Checkpoints<Key> {
+	fn new(interval: u32, base: u64) -> Self {
+		let interval = interval.max(1) as u64;
+		let mut checkpoints = Checkpoints { interval, inserted: HashMap::new(), first_kept: 0 };
+		checkpoints.first_kept = checkpoints.period_start(base);
+		checkpoints
+	}
+
+	/// Returns the first block after the last checkpoint preceding `number`.
+	fn period_start(&self, number: u64) -> u64 {
+		match number {
+			0 => 0,
+			number => (number - 1) / self.interval * self.interval + 1,
+		}
+	}
+
+	fn is_checkpoint(&self, number: u64) -> bool {
+		number % self.interval == 0
+	}
+
+	/// Notes the pruned block and returns the nodes that can be deleted from the database.
+	///
+	/// A node deleted by the block is kept if it is part of the state of the last checkpoint,
+	/// i.e. it was not inserted since then.
+	fn note_pruned<BlockHash: Hash>(&mut self, row: DeathRow<BlockHash, Key>) -> Vec<Key> {
+		let mut deleted = Vec::new();
+		for k in row.deleted {
+			if let Entry::Occupied(mut entry) = self.inserted.entry(k) {
+				*entry.get_mut() -= 1;
+				if *entry.get() == 0 {
+					deleted.push(entry.remove_entry().0);
+				} else {
+					deleted.push(entry.key().clone());
+				}
+			}
+		}
+		for k in row.inserted {
+			*self.inserted.entry(k).or_default() += 1;
+		}
+		deleted
+	}
 }
 
 /// `DeathRowQueue` used to keep track of blocks in the pruning window, there are two flavors:
@@ -136,7 +198,11 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> DeathRowQueue<BlockHash, Key, D> {
 				// cache.
 				if num == base + cache.len() as u64 && cache.len() < *cache_capacity {
 					trace!(target: LOG_TARGET, "Adding to DB backed cache {:?} (#{})", hash, num);
-					cache.push_back(DeathRow { hash, deleted: deleted.into_iter().collect() });
+					cache.push_back(DeathRow {
+						hash,
+						inserted,
+						deleted: deleted.into_iter().collect(),
+					});
 				}
 				*last = Some(num);
 			},
@@ -152,7 +218,11 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> DeathRowQueue<BlockHash, Key, D> {
 				for k in deleted.iter() {
 					death_index.insert(k.clone(), imported_block);
 				}
-				death_rows.push_back(DeathRow { hash, deleted: deleted.into_iter().collect() });
+				death_rows.push_back(DeathRow {
+					hash,
+					inserted: Vec::new(),
+					deleted: deleted.into_iter().collect(),
+				});
 			},
 		}
 	}
@@ -255,8 +325,8 @@ fn load_death_row_from_db<BlockHash: Hash, Key: Hash, D: MetaDb>(
 	let journal_key = to_journal_key(block);
 	match db.get_meta(&journal_key).map_err(Error::Db)? {
 		Some(record) => {
-			let JournalRecord { hash, deleted, .. } = Decode::decode(&mut record.as_slice())?;
-			Ok(Some(DeathRow { hash, deleted: deleted.into_iter().collect() }))
+			let JournalRecord { hash, inserted, deleted } = Decode::decode(&mut record.as_slice())?;
+			Ok(Some(DeathRow { hash, inserted, deleted: deleted.into_iter().collect() }))
 		},
 		None => Ok(None),
 	}
@@ -265,6 +335,8 @@ fn load_death_row_from_db<BlockHash: Hash, Key: Hash, D: MetaDb>(
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct DeathRow<BlockHash: Hash, Key: Hash> {
 	hash: BlockHash,
+	/// Keys inserted by the block, only tracked by the database-backed queue with checkpoints.
+	inserted: Vec<Key>,
 	deleted: HashSet<Key>,
 }
 
@@ -305,7 +377,14 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 		db: D,
 		window_size: u32,
 		count_insertions: bool,
+		checkpoint_interval: Option<u32>,
 	) -> Result<RefWindow<BlockHash, Key, D>, Error<D::Error>> {
+		// Nodes shared by the checkpoint states and later blocks can only be tracked by the
+		// database reference counting.
+		if count_insertions && checkpoint_interval.is_some() {
+			return Err(Error::StateDb(StateDbError::CheckpointsUnsupported))
+		}
+
 		// the block number of the first block in the queue or the next block number if the queue is
 		// empty
 		let base = match db.get_meta(&to_meta_key(LAST_PRUNED, &())).map_err(Error::Db)? {
@@ -319,6 +398,20 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 				None => None,
 			};
 
+		let checkpoints = match checkpoint_interval {
+			Some(interval) => {
+				let mut checkpoints = Checkpoints::new(interval, base);
+				// Restore the nodes inserted by the blocks pruned since the last checkpoint.
+				for block in checkpoints.first_kept..base {
+					if let Some(row) = load_death_row_from_db::<BlockHash, Key, D>(&db, block)? {
+						checkpoints.note_pruned(row);
+					}
+				}
+				Some(checkpoints)
+			},
+			None => None,
+		};
+
 		let queue = if count_insertions {
 			// Highly scientific crafted number for deciding when to print the warning!
 			//
@@ -347,7 +440,7 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 			DeathRowQueue::new_db_backed(db, base, last, window_size)?
 		};
 
-		Ok(RefWindow { queue, base })
+		Ok(RefWindow { queue, base, checkpoints })
 	}
 
 	pub fn window_size(&self) -> u64 {
@@ -377,7 +470,12 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 		// if the queue is empty or the block number exceed the pruning window, we definitely
 		// do not have this block
 		if self.is_empty() || number < self.base || number >= self.base + self.window_size() {
-			return HaveBlock::No
+			// The state of the checkpoints is retained, but their hashes are not known.
+			return match &self.checkpoints {
+				Some(checkpoints) if number < self.base && checkpoints.is_checkpoint(number) =>
+					HaveBlock::Maybe,
+				_ => HaveBlock::No,
+			}
 		}
 		self.queue.have_block(hash, (number - self.base) as usize)
 	}
@@ -387,9 +485,25 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 		if let Some(pruned) = self.queue.pop_front(self.base)? {
 			trace!(target: LOG_TARGET, "Pruning {:?} ({} deleted)", pruned.hash, pruned.deleted.len());
 			let index = self.base;
-			commit.data.deleted.extend(pruned.deleted.into_iter());
+			match &mut self.checkpoints {
+				Some(checkpoints) => {
+					commit.data.deleted.extend(checkpoints.note_pruned(pruned));
+					if checkpoints.is_checkpoint(index) {
+						// The journal records since the previous checkpoint are no longer needed.
+						commit
+							.meta
+							.deleted
+							.extend((checkpoints.first_kept..=index).map(to_journal_key));
+						checkpoints.inserted.clear();
+						checkpoints.first_kept = index + 1;
+					}
+				},
+				None => {
+					commit.data.deleted.extend(pruned.deleted.into_iter());
+					commit.meta.deleted.push(to_journal_key(self.base));
+				},
+			}
 			commit.meta.inserted.push((to_meta_key(LAST_PRUNED, &()), index.encode()));
-			commit.meta.deleted.push(to_journal_key(self.base));
 			self.base += 1;
 			Ok(())
 		} else {
@@ -409,6 +523,9 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 			// This branch is taken if the node imports the target block of a warp sync.
 			// assume that the block was canonicalized
 			self.base = number;
+			if let Some(checkpoints) = &mut self.checkpoints {
+				checkpoints.first_kept = number;
+			}
 			// The parent of the block was the last block that got pruned.
 			commit
 				.meta
@@ -424,11 +541,12 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 			commit.data.inserted.len(),
 			commit.data.deleted.len(),
 		);
-		let inserted = if matches!(self.queue, DeathRowQueue::Mem { .. }) {
-			commit.data.inserted.iter().map(|(k, _)| k.clone()).collect()
-		} else {
-			Default::default()
-		};
+		let inserted =
+			if matches!(self.queue, DeathRowQueue::Mem { .. }) || self.checkpoints.is_some() {
+				commit.data.inserted.iter().map(|(k, _)| k.clone()).collect()
+			} else {
+				Default::default()
+			};
 		let deleted = std::mem::take(&mut commit.data.deleted);
 		let journal_record = JournalRecord { hash: hash.clone(), inserted, deleted };
 		commit.meta.inserted.push((to_journal_key(number), journal_record.encode()));
@@ -443,7 +561,7 @@ mod tests {
 	use crate::{
 		noncanonical::LAST_CANONICAL,
 		test::{make_commit, make_db, TestDb},
-		to_meta_key, CommitSet, Error, Hash, StateDbError, DEFAULT_MAX_BLOCK_CONSTRAINT,
+		to_meta_key, CommitSet, Error, Hash, MetaDb, StateDbError, DEFAULT_MAX_BLOCK_CONSTRAINT,
 	};
 	use codec::Encode;
 	use sp_core::H256;
@@ -451,7 +569,8 @@ mod tests {
 	fn check_journal(pruning: &RefWindow<H256, H256, TestDb>, db: &TestDb) {
 		let count_insertions = matches!(pruning.queue, DeathRowQueue::Mem { .. });
 		let restored: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, count_insertions).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, count_insertions, None)
+				.unwrap();
 		assert_eq!(pruning.base, restored.base);
 		assert_eq!(pruning.queue.get_mem_queue_state(), restored.queue.get_mem_queue_state());
 	}
@@ -460,7 +579,7 @@ mod tests {
 	fn created_from_empty_db() {
 		let db = make_db(&[]);
 		let pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		assert_eq!(pruning.base, 0);
 		let (death_rows, death_index) = pruning.queue.get_mem_queue_state().unwrap();
 		assert!(death_rows.is_empty());
@@ -471,7 +590,7 @@ mod tests {
 	fn prune_empty() {
 		let db = make_db(&[]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		let mut commit = CommitSet::default();
 		assert_eq!(
 			Err(Error::StateDb(StateDbError::BlockUnavailable)),
@@ -487,7 +606,7 @@ mod tests {
 	fn prune_one() {
 		let mut db = make_db(&[1, 2, 3]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		let mut commit = make_commit(&[4, 5], &[1, 3]);
 		let hash = H256::random();
 		pruning.note_canonical(&hash, 0, &mut commit).unwrap();
@@ -517,7 +636,7 @@ mod tests {
 	fn prune_two() {
 		let mut db = make_db(&[1, 2, 3]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		let mut commit = make_commit(&[4], &[1]);
 		pruning.note_canonical(&H256::random(), 0, &mut commit).unwrap();
 		db.commit(&commit);
@@ -543,7 +662,7 @@ mod tests {
 	fn prune_two_pending() {
 		let mut db = make_db(&[1, 2, 3]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		let mut commit = make_commit(&[4], &[1]);
 		pruning.note_canonical(&H256::random(), 0, &mut commit).unwrap();
 		db.commit(&commit);
@@ -566,7 +685,7 @@ mod tests {
 	fn reinserted_survives() {
 		let mut db = make_db(&[1, 2, 3]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		let mut commit = make_commit(&[], &[2]);
 		pruning.note_canonical(&H256::random(), 0, &mut commit).unwrap();
 		db.commit(&commit);
@@ -598,7 +717,7 @@ mod tests {
 	fn reinserted_survive_pending() {
 		let mut db = make_db(&[1, 2, 3]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		let mut commit = make_commit(&[], &[2]);
 		pruning.note_canonical(&H256::random(), 0, &mut commit).unwrap();
 		db.commit(&commit);
@@ -628,7 +747,7 @@ mod tests {
 	fn reinserted_ignores() {
 		let mut db = make_db(&[1, 2, 3]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		let mut commit = make_commit(&[], &[2]);
 		pruning.note_canonical(&H256::random(), 0, &mut commit).unwrap();
 		db.commit(&commit);
@@ -666,7 +785,7 @@ mod tests {
 
 		fn load_pruning_from_db(db: TestDb) -> (usize, u64) {
 			let pruning: RefWindow<u64, H256, TestDb> =
-				RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+				RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 			let (cache, _) = pruning.queue.get_db_backed_queue_state().unwrap();
 			(cache.len(), pruning.base)
 		}
@@ -720,7 +839,7 @@ mod tests {
 	fn db_backed_queue() {
 		let mut db = make_db(&[]);
 		let mut pruning: RefWindow<u64, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		let cache_capacity = DEFAULT_MAX_BLOCK_CONSTRAINT as usize;
 
 		// start as an empty queue
@@ -764,7 +883,7 @@ mod tests {
 
 		// revert the last add that no apply yet
 		// NOTE: do not commit the previous `CommitSet` to db
-		pruning = RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+		pruning = RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		let cache_capacity = DEFAULT_MAX_BLOCK_CONSTRAINT as usize;
 		assert_eq!(pruning.window_size(), cache_capacity as u64 + 10);
 		let (cache, _) = pruning.queue.get_db_backed_queue_state().unwrap();
@@ -785,7 +904,7 @@ mod tests {
 		// load a new queue from db
 		// `cache` is full again but the content of the queue should be the same
 		let pruning: RefWindow<u64, H256, TestDb> =
-			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		assert_eq!(pruning.window_size(), cache_capacity as u64 + 9);
 		let (cache, _) = pruning.queue.get_db_backed_queue_state().unwrap();
 		assert_eq!(cache.len(), cache_capacity);
@@ -798,7 +917,7 @@ mod tests {
 	fn load_block_from_db() {
 		let mut db = make_db(&[]);
 		let mut pruning: RefWindow<u64, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		let cache_capacity = DEFAULT_MAX_BLOCK_CONSTRAINT as usize;
 
 		// import blocks
@@ -835,7 +954,7 @@ mod tests {
 		// load a new queue from db
 		// `cache` should be the same
 		let pruning: RefWindow<u64, H256, TestDb> =
-			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		assert_eq!(pruning.window_size(), 10);
 		let (cache, _) = pruning.queue.get_db_backed_queue_state().unwrap();
 		assert_eq!(cache.len(), 10);
@@ -848,7 +967,7 @@ mod tests {
 	fn get_block_from_queue() {
 		let mut db = make_db(&[]);
 		let mut pruning: RefWindow<u64, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		let cache_capacity = DEFAULT_MAX_BLOCK_CONSTRAINT as u64;
 
 		// import blocks and commit to db
@@ -890,7 +1009,8 @@ mod tests {
 		for count_insertions in [true, false] {
 			let mut db = make_db(&[]);
 			let mut pruning: RefWindow<u64, H256, TestDb> =
-				RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, count_insertions).unwrap();
+				RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, count_insertions, None)
+					.unwrap();
 			let block = 10000;
 
 			// import blocks
@@ -902,9 +1022,62 @@ mod tests {
 			// load a new queue from db
 			// `cache` should be the same
 			let pruning: RefWindow<u64, H256, TestDb> =
-				RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, count_insertions).unwrap();
+				RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, count_insertions, None).unwrap();
 
 			assert_eq!(HaveBlock::Yes, pruning.have_block(&block, block));
 		}
 	}
+
+	#[test]
+	fn checkpoints_require_ref_counting() {
+		let db = make_db(&[]);
+		let pruning: Result<RefWindow<u64, H256, TestDb>, _> =
+			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, true, Some(2));
+		assert_eq!(pruning.err(), Some(Error::StateDb(StateDbError::CheckpointsUnsupported)));
+	}
+
+	#[test]
+	fn checkpoint_state_is_retained() {
+		let mut db = make_db(&[1, 2]);
+		let mut pruning: RefWindow<u64, H256, TestDb> =
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, Some(2)).unwrap();
+
+		// block #2 is a checkpoint, its state is [2, 4]
+		let changes: [(&[u64], &[u64]); 4] =
+			[(&[], &[]), (&[3], &[1]), (&[4], &[3]), (&[5], &[4, 2])];
+		for (number, (inserted, deleted)) in changes.into_iter().enumerate() {
+			let mut commit = make_commit(inserted, deleted);
+			pruning.note_canonical(&(number as u64), number as u64, &mut commit).unwrap();
+			push_last_canonicalized(number as u64, &mut commit);
+			db.commit(&commit);
+		}
+		assert!(db.data_eq(&make_db(&[1, 2, 3, 4, 5])));
+
+		let mut commit = CommitSet::default();
+		for _ in 0..4 {
+			pruning.prune_one(&mut commit).unwrap();
+		}
+		db.commit(&commit);
+
+		// only the node inserted and deleted between the checkpoints is deleted
+		assert!(db.data_eq(&make_db(&[1, 2, 4, 5])));
+		assert_eq!(pruning.have_block(&2, 2), HaveBlock::Maybe);
+		assert_eq!(pruning.have_block(&1, 1), HaveBlock::No);
+		assert_eq!(pruning.have_block(&3, 3), HaveBlock::No);
+
+		// the journal of the blocks since the last pruned checkpoint is kept
+		for block in 0..=2 {
+			assert!(db.get_meta(&to_journal_key(block)).unwrap().is_none());
+		}
+		assert!(db.get_meta(&to_journal_key(3)).unwrap().is_some());
+
+		// the inserted nodes are restored from the journal
+		let restored: RefWindow<u64, H256, TestDb> =
+			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false, Some(2)).unwrap();
+		let checkpoints = restored.checkpoints.as_ref().unwrap();
+		assert_eq!(restored.base, 4);
+		assert_eq!(checkpoints.first_kept, 3);
+		assert_eq!(checkpoints.inserted, pruning.checkpoints.as_ref().unwrap().inserted);
+		assert_eq!(checkpoints.inserted.get(&H256::from_low_u64_be(5)), Some(&1));
+	}
 }
@@ -29,9 +29,12 @@
 //! * For a given account id, there may be at most `max_count` statements with `max_size` total data
 //!   size. To satisfy this, statements for this account ID are removed from the store starting with
 //!   the lowest priority until a constraint is satisfied.
-//! * There may not be more than `MAX_TOTAL_STATEMENTS` total statements with `MAX_TOTAL_SIZE` size.
-//!   To satisfy this, statements are removed from the store starting with the lowest
-//!   `global_priority` until a constraint is satisfied.
+//! * There may not be more than `Options::max_total_statements` total statements with
+//!   `Options::max_total_size` size, and no more than `Options::max_statements_per_topic`
+//!   statements with the same topic. To satisfy this, statements are removed from the store
+//!   according to `Options::eviction_strategy`: by lowest priority, by age, or not at all.
+//!   Statements are always removed from the account holding the most of them, so an account can't
+//!   push out the statements of accounts holding fewer statements than itself.
 //!
 //! When a new statement is inserted that would not satisfy constraints in the first place, no
 //! statements are deleted and `Ignored` result is returned.
@@ -51,8 +54,9 @@ mod metrics;
 
 pub use sp_statement_store::{Error, StatementStore, MAX_TOPICS};
 
+use futures::channel::mpsc;
 use metrics::MetricsLink as PrometheusMetrics;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use sc_keystore::LocalKeystore;
 use sp_api::ProvideRuntimeApi;
@@ -64,7 +68,7 @@ use sp_statement_store::{
 		InvalidStatement, StatementSource, StatementStoreExt, ValidStatement, ValidateStatement,
 	},
 	AccountId, BlockHash, Channel, DecryptionKey, Hash, NetworkPriority, Proof, Result, Statement,
-	SubmitResult, Topic,
+	StatementStream, SubmitResult, Topic,
 };
 use std::{
 	collections::{BTreeMap, HashMap, HashSet},
@@ -80,8 +84,14 @@ const DEFAULT_PURGE_AFTER_SEC: u64 = 2 * 24 * 60 * 60; //48h
 const DEFAULT_MAX_TOTAL_STATEMENTS: usize = 8192;
 const DEFAULT_MAX_TOTAL_SIZE: usize = 64 * 1024 * 1024;
 
+/// Number of new statements buffered for each subscriber before they start being dropped.
+const SUBSCRIPTION_BUFFER_SIZE: usize = 1024;
+
 const MAINTENANCE_PERIOD: std::time::Duration = std::time::Duration::from_secs(30);
 
+/// Number of accounts reported in the per-account metrics, the ones using the most space.
+const MAX_REPORTED_ACCOUNTS: usize = 10;
+
 mod col {
 	pub const META: u8 = 0;
 	pub const STATEMENTS: u8 = 1;
@@ -127,16 +137,37 @@ struct StatementsForAccount {
 	data_size: usize,
 }
 
+/// Strategy used to make room for new statements once a store-wide or per-topic limit is
+/// reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionStrategy {
+	/// Don't evict anything, new statements are ignored until there is room for them.
+	#[default]
+	Reject,
+	/// Evict the statements with the lowest priority, as long as it is lower than the priority of
+	/// the new statement.
+	LowestPriority,
+	/// Evict the statements that were added to the store first, regardless of their priority.
+	///
+	/// Statements loaded from the database on startup count as added at that time.
+	Oldest,
+}
+
 /// Store configuration
 pub struct Options {
-	/// Maximum statement allowed in the store. Once this limit is reached lower-priority
-	/// statements may be evicted.
-	max_total_statements: usize,
-	/// Maximum total data size allowed in the store. Once this limit is reached lower-priority
-	/// statements may be evicted.
-	max_total_size: usize,
+	/// Maximum statement allowed in the store. Once this limit is reached statements are
+	/// evicted according to `eviction_strategy`.
+	pub max_total_statements: usize,
+	/// Maximum total data size allowed in the store. Once this limit is reached statements are
+	/// evicted according to `eviction_strategy`.
+	pub max_total_size: usize,
+	/// Maximum number of statements with the same topic, `None` for no limit. Once this limit is
+	/// reached statements with the topic are evicted according to `eviction_strategy`.
+	pub max_statements_per_topic: Option<usize>,
+	/// Strategy used to evict statements once one of the limits above is reached.
+	pub eviction_strategy: EvictionStrategy,
 	/// Number of seconds for which removed statements won't be allowed to be added back in.
-	purge_after_sec: u64,
+	pub purge_after_sec: u64,
 }
 
 impl Default for Options {
@@ -144,6 +175,8 @@ impl Default for Options {
 		Options {
 			max_total_statements: DEFAULT_MAX_TOTAL_STATEMENTS,
 			max_total_size: DEFAULT_MAX_TOTAL_SIZE,
+			max_statements_per_topic: None,
+			eviction_strategy: EvictionStrategy::Reject,
 			purge_after_sec: DEFAULT_PURGE_AFTER_SEC,
 		}
 	}
@@ -154,7 +187,8 @@ struct Index {
 	by_topic: HashMap<Topic, HashSet<Hash>>,
 	by_dec_key: HashMap<Option<DecryptionKey>, HashSet<Hash>>,
 	topics_and_keys: HashMap<Hash, ([Option<Topic>; MAX_TOPICS], Option<DecryptionKey>)>,
-	entries: HashMap<Hash, (AccountId, Priority, usize)>,
+	// Value is account, priority, data size and the timestamp the statement was added at.
+	entries: HashMap<Hash, (AccountId, Priority, usize, u64)>,
 	expired: HashMap<Hash, u64>, // Value is expiration timestamp.
 	accounts: HashMap<AccountId, StatementsForAccount>,
 	options: Options,
@@ -206,6 +240,7 @@ pub struct Store {
 	// Used for testing
 	time_override: Option<u64>,
 	metrics: PrometheusMetrics,
+	subscribers: Mutex<Vec<mpsc::Sender<(Hash, Arc<Statement>)>>>,
 }
 
 enum IndexQuery {
@@ -224,7 +259,13 @@ impl Index {
 		Index { options, ..Default::default() }
 	}
 
-	fn insert_new(&mut self, hash: Hash, account: AccountId, statement: &Statement) {
+	fn insert_new(
+		&mut self,
+		hash: Hash,
+		account: AccountId,
+		statement: &Statement,
+		current_time: u64,
+	) {
 		let mut all_topics = [None; MAX_TOPICS];
 		let mut nt = 0;
 		while let Some(t) = statement.topic(nt) {
@@ -238,7 +279,8 @@ impl Index {
 			self.topics_and_keys.insert(hash, (all_topics, key));
 		}
 		let priority = Priority(statement.priority().unwrap_or(0));
-		self.entries.insert(hash, (account, priority, statement.data_len()));
+		self.entries
+			.insert(hash, (account, priority, statement.data_len(), current_time));
 		self.total_size += statement.data_len();
 		let account_info = self.accounts.entry(account).or_default();
 		account_info.data_size += statement.data_len();
@@ -321,7 +363,7 @@ impl Index {
 	}
 
 	fn make_expired(&mut self, hash: &Hash, current_time: u64) -> bool {
-		if let Some((account, priority, len)) = self.entries.remove(hash) {
+		if let Some((account, priority, len, _)) = self.entries.remove(hash) {
 			self.total_size -= len;
 			if let Some((topics, key)) = self.topics_and_keys.remove(hash) {
 				for t in topics.into_iter().flatten() {
@@ -452,10 +494,46 @@ impl Index {
 				would_free_size += len;
 			}
 		}
+		// Check per-topic constraints.
+		if let Some(max_per_topic) = self.options.max_statements_per_topic {
+			let mut nt = 0;
+			while let Some(topic) = statement.topic(nt) {
+				nt += 1;
+				let Some(topic_set) = self.by_topic.get(&topic) else { continue };
+				let satisfied = |evicted: &HashSet<Hash>, _: usize| {
+					topic_set.iter().filter(|h| !evicted.contains(*h)).count() < max_per_topic
+				};
+				if !self.select_for_eviction(
+					topic_set.iter(),
+					account,
+					priority,
+					&mut evicted,
+					&mut would_free_size,
+					satisfied,
+				) {
+					log::debug!(
+						target: LOG_TARGET,
+						"Ignored statement {} because topic {} is full",
+						HexDisplay::from(&hash),
+						HexDisplay::from(&topic),
+					);
+					return MaybeInserted::Ignored
+				}
+			}
+		}
 		// Now check global constraints as well.
-		if !((self.total_size - would_free_size + statement_len <= self.options.max_total_size) &&
-			self.entries.len() + 1 - evicted.len() <= self.options.max_total_statements)
-		{
+		let satisfied = |evicted: &HashSet<Hash>, would_free_size: usize| {
+			self.total_size - would_free_size + statement_len <= self.options.max_total_size &&
+				self.entries.len() + 1 - evicted.len() <= self.options.max_total_statements
+		};
+		if !self.select_for_eviction(
+			self.entries.keys(),
+			account,
+			priority,
+			&mut evicted,
+			&mut would_free_size,
+			satisfied,
+		) {
 			log::debug!(
 				target: LOG_TARGET,
 				"Ignored statement {} because the store is full (size={}, count={})",
@@ -469,9 +547,107 @@ impl Index {
 		for h in &evicted {
 			self.make_expired(h, current_time);
 		}
-		self.insert_new(hash, *account, statement);
+		self.insert_new(hash, *account, statement, current_time);
 		MaybeInserted::Inserted(evicted)
 	}
+
+	/// Add statements from `candidates` to `evicted` according to the eviction strategy, until
+	/// `satisfied` returns `true`.
+	///
+	/// Statements are always evicted from the account holding the most candidates, counting the
+	/// new statement for the submitting `account`. Priorities and ages are chosen by the
+	/// submitters, so they are only used to pick a statement within that account and to break
+	/// ties. This way an account can't evict statements of accounts holding fewer statements
+	/// than itself.
+	///
+	/// Returns `false` if the constraint can't be satisfied.
+	fn select_for_eviction<'a>(
+		&self,
+		candidates: impl Iterator<Item = &'a Hash>,
+		account: &AccountId,
+		priority: Priority,
+		evicted: &mut HashSet<Hash>,
+		would_free_size: &mut usize,
+		satisfied: impl Fn(&HashSet<Hash>, usize) -> bool,
+	) -> bool {
+		if satisfied(evicted, *would_free_size) {
+			return true
+		}
+
+		let strategy = self.options.eviction_strategy;
+		if strategy == EvictionStrategy::Reject {
+			return false
+		}
+		// Ordering of the statements of an account, the statement to evict first is the smallest.
+		let eviction_key = |hash: Hash, priority: Priority, added: u64| match strategy {
+			EvictionStrategy::LowestPriority => (priority.0, added, hash),
+			_ => (0, added, hash),
+		};
+
+		let mut by_account = HashMap::<AccountId, Vec<(Hash, Priority, usize, u64)>>::new();
+		for hash in candidates.filter(|hash| !evicted.contains(*hash)) {
+			if let Some((owner, priority, len, added)) = self.entries.get(hash) {
+				by_account.entry(*owner).or_default().push((*hash, *priority, *len, *added));
+			}
+		}
+		for statements in by_account.values_mut() {
+			// Sort descending, so the next statement to evict can be popped off the end.
+			statements.sort_by_key(|(hash, priority, _, added)| {
+				std::cmp::Reverse(eviction_key(*hash, *priority, *added))
+			});
+		}
+
+		loop {
+			// The account with the most statements pays. On a tie, other accounts pay before the
+			// submitter, as the submitter's count already includes the new statement.
+			let victim = by_account
+				.iter()
+				.filter_map(|(owner, statements)| {
+					let (hash, priority, _, added) = statements.last()?;
+					let count = statements.len() + usize::from(owner == account);
+					Some((
+						count,
+						owner != account,
+						std::cmp::Reverse(eviction_key(*hash, *priority, *added)),
+						*owner,
+					))
+				})
+				.max()
+				.map(|(_, _, _, owner)| owner);
+			let Some(statements) = victim.and_then(|victim| by_account.get_mut(&victim)) else {
+				return false
+			};
+			let (hash, evicted_priority, len, _) =
+				statements.pop().expect("Only accounts with statements are selected; qed");
+			if strategy == EvictionStrategy::LowestPriority && evicted_priority >= priority {
+				return false
+			}
+
+			log::trace!(target: LOG_TARGET, "Evicting statement {:?}", HexDisplay::from(&hash));
+			evicted.insert(hash);
+			*would_free_size += len;
+			if satisfied(evicted, *would_free_size) {
+				return true
+			}
+		}
+	}
+
+	/// Number of statements and their total data size for the `count` accounts using the most
+	/// space, largest first.
+	fn largest_accounts(&self, count: usize) -> Vec<(&AccountId, usize, usize)> {
+		let mut accounts = self
+			.accounts
+			.iter()
+			.map(|(account, statements)| {
+				(account, statements.by_priority.len(), statements.data_size)
+			})
+			.collect::<Vec<_>>();
+		accounts.sort_unstable_by(|(a, _, a_size), (b, _, b_size)| {
+			b_size.cmp(a_size).then_with(|| a.cmp(b))
+		});
+		accounts.truncate(count);
+		accounts
+	}
 }
 
 impl Store {
@@ -573,6 +749,7 @@ impl Store {
 			keystore,
 			time_override: None,
 			metrics: PrometheusMetrics::new(prometheus),
+			subscribers: Default::default(),
 		};
 		store.populate()?;
 		Ok(store)
@@ -584,6 +761,7 @@ impl Store {
 	// iterating the index.
 	fn populate(&self) -> Result<()> {
 		{
+			let current_time = self.timestamp();
 			let mut index = self.index.write();
 			self.db
 				.iter_column_while(col::STATEMENTS, |item| {
@@ -596,7 +774,7 @@ impl Store {
 							HexDisplay::from(&hash)
 						);
 						if let Some(account_id) = statement.account_id() {
-							index.insert_new(hash, account_id, &statement);
+							index.insert_new(hash, account_id, &statement, current_time);
 						} else {
 							log::debug!(
 								target: LOG_TARGET,
@@ -680,6 +858,17 @@ impl Store {
 		} else {
 			self.metrics.report(|metrics| metrics.statements_pruned.inc_by(count));
 		}
+		// Only the largest accounts are reported, to keep the number of label values bounded.
+		self.metrics.report(|metrics| {
+			metrics.account_statements.reset();
+			metrics.account_data_size.reset();
+			for (account, count, size) in self.index.read().largest_accounts(MAX_REPORTED_ACCOUNTS)
+			{
+				let account = HexDisplay::from(account).to_string();
+				metrics.account_statements.with_label_values(&[&account]).set(count as u64);
+				metrics.account_data_size.with_label_values(&[&account]).set(size as u64);
+			}
+		});
 		log::trace!(
 			target: LOG_TARGET,
 			"Completed store maintenance. Purged: {}, Active: {}, Expired: {}",
@@ -703,6 +892,29 @@ impl Store {
 		self.time_override = Some(time);
 	}
 
+	/// Notify subscribers about a newly added statement.
+	fn notify_subscribers(&self, hash: Hash, statement: &Statement) {
+		let mut subscribers = self.subscribers.lock();
+		if subscribers.is_empty() {
+			return
+		}
+
+		// The subscribers share the same statement.
+		let statement = Arc::new(statement.clone());
+		subscribers.retain_mut(|sink| match sink.try_send((hash, statement.clone())) {
+			Ok(()) => true,
+			Err(e) if e.is_full() => {
+				log::warn!(
+					target: LOG_TARGET,
+					"Trying to notify a statement subscriber, but the channel is full. Dropping {:?}",
+					HexDisplay::from(&hash),
+				);
+				true
+			},
+			Err(_) => false,
+		});
+	}
+
 	/// Returns `self` as [`StatementStoreExt`].
 	pub fn as_statement_store_ext(self: Arc<Self>) -> StatementStoreExt {
 		StatementStoreExt::new(self)
@@ -814,6 +1026,48 @@ impl StatementStore for Store {
 		})
 	}
 
+	/// Return up to `count` known statements which include all topics and whose decryption key
+	/// is `dest`, ordered by hash and starting after the statement with hash `start_after`.
+	fn matching_statements(
+		&self,
+		match_all_topics: &[Topic],
+		dest: Option<DecryptionKey>,
+		start_after: Option<Hash>,
+		count: usize,
+	) -> Result<(Vec<(Hash, Statement)>, bool)> {
+		let mut hashes = Vec::new();
+		self.index.read().iterate_with(dest, match_all_topics, |hash| {
+			if start_after.map_or(true, |start_after| *hash > start_after) {
+				hashes.push(*hash);
+			}
+			Ok(())
+		})?;
+
+		// Only the hashes of the page are sorted, and only its statements are read.
+		let has_more = hashes.len() > count;
+		if has_more {
+			hashes.select_nth_unstable(count);
+			hashes.truncate(count);
+		}
+		hashes.sort_unstable();
+
+		let mut result = Vec::with_capacity(hashes.len());
+		for hash in hashes {
+			// The statement might have been removed in the meantime.
+			if let Some(statement) = self.statement(&hash)? {
+				result.push((hash, statement));
+			}
+		}
+		Ok((result, has_more))
+	}
+
+	/// Subscribe to statements added to the store from now on.
+	fn subscribe_statements(&self) -> StatementStream {
+		let (sink, stream) = mpsc::channel(SUBSCRIPTION_BUFFER_SIZE);
+		self.subscribers.lock().push(sink);
+		stream
+	}
+
 	/// Submit a statement to the store. Validates the statement and returns validation result.
 	fn submit(&self, statement: Statement, source: StatementSource) -> SubmitResult {
 		let hash = statement.hash();
@@ -872,6 +1126,7 @@ impl StatementStore for Store {
 
 		let current_time = self.timestamp();
 		let mut commit = Vec::new();
+		let num_evicted;
 		{
 			let mut index = self.index.write();
 
@@ -881,6 +1136,7 @@ impl StatementStore for Store {
 					MaybeInserted::Inserted(evicted) => evicted,
 				};
 
+			num_evicted = evicted.len();
 			commit.push((col::STATEMENTS, hash.to_vec(), Some(statement.encode())));
 			for hash in evicted {
 				commit.push((col::STATEMENTS, hash.to_vec(), None));
@@ -896,7 +1152,11 @@ impl StatementStore for Store {
 				return SubmitResult::InternalError(Error::Db(e.to_string()))
 			}
 		} // Release index lock
-		self.metrics.report(|metrics| metrics.submitted_statements.inc());
+		self.metrics.report(|metrics| {
+			metrics.submitted_statements.inc();
+			metrics.statements_evicted.inc_by(num_evicted as u64);
+		});
+		self.notify_subscribers(hash, &statement);
 		let network_priority = NetworkPriority::High;
 		log::trace!(target: LOG_TARGET, "Statement submitted: {:?}", HexDisplay::from(&hash));
 		SubmitResult::New(network_priority)
@@ -929,7 +1189,7 @@ impl StatementStore for Store {
 
 #[cfg(test)]
 mod tests {
-	use crate::Store;
+	use crate::{EvictionStrategy, Store};
 	use sc_keystore::Keystore;
 	use sp_core::Pair;
 	use sp_statement_store::{
@@ -937,6 +1197,7 @@ mod tests {
 		AccountId, Channel, DecryptionKey, NetworkPriority, Proof, SignatureVerificationResult,
 		Statement, StatementSource, StatementStore, SubmitResult, Topic,
 	};
+	use std::sync::Arc;
 
 	type Extrinsic = sp_runtime::OpaqueExtrinsic;
 	type Hash = sp_core::H256;
@@ -1293,4 +1554,177 @@ mod tests {
 		let posted_clear = store.posted_clear(&[], public.into()).unwrap();
 		assert_eq!(posted_clear, vec![plain]);
 	}
+
+	#[test]
+	fn global_eviction_by_priority() {
+		let (store, _temp) = test_store();
+		store.index.write().options.max_total_statements = 2;
+		store.index.write().options.eviction_strategy = EvictionStrategy::LowestPriority;
+		let source = StatementSource::Network;
+		let ok = SubmitResult::New(NetworkPriority::High);
+
+		assert_eq!(store.submit(statement(5, 1, None, 100), source), ok);
+		assert_eq!(store.submit(statement(6, 3, None, 100), source), ok);
+		// Evicts the priority 1 statement.
+		assert_eq!(store.submit(statement(7, 2, None, 100), source), ok);
+		// Nothing with a lower priority left to evict.
+		assert_eq!(store.submit(statement(8, 1, None, 100), source), SubmitResult::Ignored);
+
+		let mut expected_statements =
+			vec![statement(6, 3, None, 100).hash(), statement(7, 2, None, 100).hash()];
+		expected_statements.sort();
+		let mut statements: Vec<_> =
+			store.statements().unwrap().into_iter().map(|(hash, _)| hash).collect();
+		statements.sort();
+		assert_eq!(expected_statements, statements);
+	}
+
+	#[test]
+	fn global_eviction_by_age() {
+		let (mut store, _temp) = test_store();
+		store.index.write().options.max_total_statements = 2;
+		store.index.write().options.eviction_strategy = EvictionStrategy::Oldest;
+		let source = StatementSource::Network;
+		let ok = SubmitResult::New(NetworkPriority::High);
+
+		store.set_time(1);
+		assert_eq!(store.submit(statement(5, 5, None, 100), source), ok);
+		store.set_time(2);
+		assert_eq!(store.submit(statement(6, 1, None, 100), source), ok);
+		store.set_time(3);
+		// Evicts the oldest statement, even though it has the highest priority.
+		assert_eq!(store.submit(statement(7, 1, None, 100), source), ok);
+
+		assert_eq!(store.statement(&statement(5, 5, None, 100).hash()).unwrap(), None);
+		assert_eq!(store.statements().unwrap().len(), 2);
+	}
+
+	#[test]
+	fn eviction_is_capped_to_the_submitters_share() {
+		let (mut store, _temp) = test_store();
+		store.index.write().options.max_total_statements = 3;
+		store.index.write().options.eviction_strategy = EvictionStrategy::Oldest;
+		let source = StatementSource::Network;
+		let ok = SubmitResult::New(NetworkPriority::High);
+
+		store.set_time(1);
+		assert_eq!(store.submit(statement(5, 1, None, 100), source), ok);
+		store.set_time(2);
+		assert_eq!(store.submit(statement(6, 1, None, 100), source), ok);
+		store.set_time(3);
+		assert_eq!(store.submit(statement(7, 1, None, 100), source), ok);
+
+		// Account 7 already holds a statement, so it has to make room by evicting its own ones,
+		// even though the statements of the other accounts are older.
+		store.set_time(4);
+		assert_eq!(store.submit(statement(7, 2, None, 100), source), ok);
+		store.set_time(5);
+		assert_eq!(store.submit(statement(7, 3, None, 100), source), ok);
+
+		assert_eq!(store.statement(&statement(7, 1, None, 100).hash()).unwrap(), None);
+		assert_eq!(store.statement(&statement(7, 2, None, 100).hash()).unwrap(), None);
+		assert!(store.statement(&statement(5, 1, None, 100).hash()).unwrap().is_some());
+		assert!(store.statement(&statement(6, 1, None, 100).hash()).unwrap().is_some());
+
+		// A new account has as many statements as the others once its statement is added, so
+		// the oldest statement of another account is evicted.
+		store.set_time(6);
+		assert_eq!(store.submit(statement(8, 1, None, 100), source), ok);
+		assert_eq!(store.statement(&statement(5, 1, None, 100).hash()).unwrap(), None);
+	}
+
+	#[test]
+	fn largest_accounts_are_reported() {
+		let (store, _temp) = test_store();
+		let source = StatementSource::Network;
+		let ok = SubmitResult::New(NetworkPriority::High);
+
+		assert_eq!(store.submit(statement(1, 1, None, 100), source), ok);
+		assert_eq!(store.submit(statement(2, 1, None, 300), source), ok);
+		assert_eq!(store.submit(statement(3, 1, None, 50), source), ok);
+		assert_eq!(store.submit(statement(3, 2, None, 50), source), ok);
+
+		let index = store.index.read();
+		assert_eq!(index.largest_accounts(2), vec![(&account(2), 1, 300), (&account(1), 1, 100)]);
+		assert_eq!(index.largest_accounts(10).len(), 3);
+	}
+
+	#[test]
+	fn per_topic_limit() {
+		let (store, _temp) = test_store();
+		store.index.write().options.max_statements_per_topic = Some(1);
+		let source = StatementSource::Network;
+		let ok = SubmitResult::New(NetworkPriority::High);
+		let with_topic = |account, priority, t| {
+			let mut statement = statement(account, priority, None, 100);
+			statement.set_topic(0, topic(t));
+			statement
+		};
+
+		assert_eq!(store.submit(with_topic(5, 1, 0), source), ok);
+		assert_eq!(store.submit(with_topic(6, 2, 0), source), SubmitResult::Ignored);
+		assert_eq!(store.submit(with_topic(6, 2, 1), source), ok);
+
+		store.index.write().options.eviction_strategy = EvictionStrategy::LowestPriority;
+		assert_eq!(store.submit(with_topic(7, 2, 0), source), ok);
+		assert_eq!(store.statement(&with_topic(5, 1, 0).hash()).unwrap(), None);
+		assert_eq!(store.broadcasts(&[topic(0)]).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn matching_statements_by_topic_and_key() {
+		let (store, _temp) = test_store();
+		let statement0 = signed_statement_with_topics(0, &[topic(0)], None);
+		let statement1 = signed_statement_with_topics(1, &[topic(0), topic(1)], Some(dec_key(1)));
+		store.submit(statement0.clone(), StatementSource::Network);
+		store.submit(statement1.clone(), StatementSource::Network);
+
+		assert_eq!(
+			store.matching_statements(&[topic(0)], None, None, 10).unwrap(),
+			(vec![(statement0.hash(), statement0)], false)
+		);
+		assert_eq!(
+			store.matching_statements(&[topic(1)], Some(dec_key(1)), None, 10).unwrap(),
+			(vec![(statement1.hash(), statement1)], false)
+		);
+		assert!(store.matching_statements(&[topic(1)], None, None, 10).unwrap().0.is_empty());
+	}
+
+	#[test]
+	fn matching_statements_are_paged_by_hash() {
+		let (store, _temp) = test_store();
+		let mut statements = (0..5)
+			.map(|i| signed_statement_with_topics(i, &[topic(0)], None))
+			.map(|statement| (statement.hash(), statement))
+			.collect::<Vec<_>>();
+		for (_, statement) in &statements {
+			store.submit(statement.clone(), StatementSource::Network);
+		}
+		statements.sort_by_key(|(hash, _)| *hash);
+
+		let (page, has_more) = store.matching_statements(&[topic(0)], None, None, 2).unwrap();
+		assert_eq!((page, has_more), (statements[..2].to_vec(), true));
+
+		let start_after = Some(statements[1].0);
+		let (page, has_more) =
+			store.matching_statements(&[topic(0)], None, start_after, 3).unwrap();
+		assert_eq!((page, has_more), (statements[2..].to_vec(), false));
+	}
+
+	#[test]
+	fn subscribers_are_notified_about_new_statements() {
+		let (store, _temp) = test_store();
+		let mut stream = store.subscribe_statements();
+		let statement0 = signed_statement(0);
+		store.submit(statement0.clone(), StatementSource::Network);
+		// Known statements are not reported again.
+		store.submit(statement0.clone(), StatementSource::Network);
+
+		assert_eq!(stream.try_next().unwrap(), Some((statement0.hash(), Arc::new(statement0))));
+		assert!(stream.try_next().is_err());
+
+		drop(stream);
+		store.submit(signed_statement(1), StatementSource::Network);
+		assert!(store.subscribers.lock().is_empty());
+	}
 }
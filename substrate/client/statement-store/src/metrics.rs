@@ -20,7 +20,7 @@
 
 use std::sync::Arc;
 
-use prometheus_endpoint::{register, Counter, PrometheusError, Registry, U64};
+use prometheus_endpoint::{register, Counter, GaugeVec, Opts, PrometheusError, Registry, U64};
 
 #[derive(Clone, Default)]
 pub struct MetricsLink(Arc<Option<Metrics>>);
@@ -48,6 +48,9 @@ pub struct Metrics {
 	pub submitted_statements: Counter<U64>,
 	pub validations_invalid: Counter<U64>,
 	pub statements_pruned: Counter<U64>,
+	pub statements_evicted: Counter<U64>,
+	pub account_statements: GaugeVec<U64>,
+	pub account_data_size: GaugeVec<U64>,
 }
 
 impl Metrics {
@@ -74,6 +77,33 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			statements_evicted: register(
+				Counter::new(
+					"substrate_sub_statement_store_statements_evicted",
+					"Total number of statements that were evicted to make room for new statements",
+				)?,
+				registry,
+			)?,
+			account_statements: register(
+				GaugeVec::new(
+					Opts::new(
+						"substrate_sub_statement_store_account_statements",
+						"Number of statements in the store of the accounts using the most space",
+					),
+					&["account"],
+				)?,
+				registry,
+			)?,
+			account_data_size: register(
+				GaugeVec::new(
+					Opts::new(
+						"substrate_sub_statement_store_account_data_size",
+						"Total data size of statements in the store of the accounts using the most space",
+					),
+					&["account"],
+				)?,
+				registry,
+			)?,
 		})
 	}
 }
@@ -0,0 +1,218 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Selection of the transaction pool implementation of a full node.
+
+use crate::{
+	graph::{self, ExtrinsicHash, IsValidator},
+	FullChainApi, FullForkAwarePool, FullPool, PolledIterator, ReadyIteratorFor,
+};
+use async_trait::async_trait;
+use prometheus_endpoint::Registry as PrometheusRegistry;
+use sc_transaction_pool_api::{
+	ChainEvent, ImportNotificationStream, LocalTransactionFor, LocalTransactionPool,
+	MaintainedTransactionPool, PoolFuture, PoolStatus, TransactionFor, TransactionPool,
+	TransactionSource, TransactionStatusStreamFor, TxHash,
+};
+use sp_core::traits::SpawnEssentialNamed;
+use sp_runtime::traits::Block as BlockT;
+use std::{collections::HashMap, pin::Pin, sync::Arc};
+
+/// The type of the transaction pool of a full node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransactionPoolType {
+	/// The [`crate::BasicPool`], keeping the transactions valid at the best block only.
+	#[default]
+	SingleState,
+	/// The [`crate::ForkAwareTxPool`], keeping a view of the transactions for every fork.
+	ForkAware,
+}
+
+/// The transaction pool of a full node, of the [`TransactionPoolType`] selected at startup.
+pub enum TransactionPoolHandle<Block, Client>
+where
+	Block: BlockT,
+	FullChainApi<Client, Block>: graph::ChainApi<Block = Block> + 'static,
+{
+	/// The single-state pool.
+	SingleState(Arc<FullPool<Block, Client>>),
+	/// The fork-aware pool.
+	ForkAware(Arc<FullForkAwarePool<Block, Client>>),
+}
+
+impl<Block, Client> TransactionPoolHandle<Block, Client>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sc_client_api::ExecutorProvider<Block>
+		+ sc_client_api::UsageProvider<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	/// Create new transaction pool of the given type for a full node.
+	pub fn new_full(
+		pool_type: TransactionPoolType,
+		options: graph::Options,
+		is_validator: IsValidator,
+		prometheus: Option<&PrometheusRegistry>,
+		spawner: impl SpawnEssentialNamed,
+		client: Arc<Client>,
+	) -> Arc<Self> {
+		Arc::new(match pool_type {
+			TransactionPoolType::SingleState => Self::SingleState(FullPool::new_full(
+				options,
+				is_validator,
+				prometheus,
+				spawner,
+				client,
+			)),
+			TransactionPoolType::ForkAware => Self::ForkAware(FullForkAwarePool::new_full(
+				options,
+				is_validator,
+				prometheus,
+				spawner,
+				client,
+			)),
+		})
+	}
+}
+
+/// Forwards the call to the pool selected at startup.
+macro_rules! delegate {
+	($self:ident, $pool:ident => $call:expr) => {
+		match $self {
+			Self::SingleState($pool) => $call,
+			Self::ForkAware($pool) => $call,
+		}
+	};
+}
+
+impl<Block, Client> TransactionPool for TransactionPoolHandle<Block, Client>
+where
+	Block: BlockT,
+	FullChainApi<Client, Block>: graph::ChainApi<Block = Block> + 'static,
+{
+	type Block = Block;
+	type Hash = ExtrinsicHash<FullChainApi<Client, Block>>;
+	type InPoolTransaction = graph::base_pool::Transaction<TxHash<Self>, TransactionFor<Self>>;
+	type Error = <FullChainApi<Client, Block> as graph::ChainApi>::Error;
+
+	fn submit_at(
+		&self,
+		at: Block::Hash,
+		source: TransactionSource,
+		xts: Vec<TransactionFor<Self>>,
+	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
+		delegate!(self, pool => pool.submit_at(at, source, xts))
+	}
+
+	fn submit_one(
+		&self,
+		at: Block::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<TxHash<Self>, Self::Error> {
+		delegate!(self, pool => pool.submit_one(at, source, xt))
+	}
+
+	fn submit_and_watch(
+		&self,
+		at: Block::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<Pin<Box<TransactionStatusStreamFor<Self>>>, Self::Error> {
+		delegate!(self, pool => pool.submit_and_watch(at, source, xt))
+	}
+
+	fn ready_at(&self, at: Block::Hash) -> PolledIterator<FullChainApi<Client, Block>> {
+		delegate!(self, pool => pool.ready_at(at))
+	}
+
+	fn ready(&self) -> ReadyIteratorFor<FullChainApi<Client, Block>> {
+		delegate!(self, pool => pool.ready())
+	}
+
+	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
+		delegate!(self, pool => pool.remove_invalid(hashes))
+	}
+
+	fn futures(&self) -> Vec<Self::InPoolTransaction> {
+		delegate!(self, pool => pool.futures())
+	}
+
+	fn status(&self) -> PoolStatus {
+		delegate!(self, pool => pool.status())
+	}
+
+	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
+		delegate!(self, pool => pool.import_notification_stream())
+	}
+
+	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>) {
+		delegate!(self, pool => pool.on_broadcasted(propagations))
+	}
+
+	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
+		delegate!(self, pool => pool.hash_of(xt))
+	}
+
+	fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
+		delegate!(self, pool => pool.ready_transaction(hash))
+	}
+}
+
+impl<Block, Client> LocalTransactionPool for TransactionPoolHandle<Block, Client>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>,
+	Client: Send + Sync + 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	type Block = Block;
+	type Hash = ExtrinsicHash<FullChainApi<Client, Block>>;
+	type Error = <FullChainApi<Client, Block> as graph::ChainApi>::Error;
+
+	fn submit_local(
+		&self,
+		at: Block::Hash,
+		xt: LocalTransactionFor<Self>,
+	) -> Result<Self::Hash, Self::Error> {
+		delegate!(self, pool => pool.submit_local(at, xt))
+	}
+}
+
+#[async_trait]
+impl<Block, Client> MaintainedTransactionPool for TransactionPoolHandle<Block, Client>
+where
+	Block: BlockT,
+	FullChainApi<Client, Block>: graph::ChainApi<Block = Block> + 'static,
+{
+	async fn maintain(&self, event: ChainEvent<Self::Block>) {
+		delegate!(self, pool => pool.maintain(event).await)
+	}
+}
@@ -0,0 +1,658 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The fork-aware transaction pool implementation.
+
+use std::{
+	collections::{HashMap, HashSet},
+	pin::Pin,
+	sync::Arc,
+};
+
+use async_trait::async_trait;
+use futures::{
+	channel::mpsc::{channel, Sender},
+	prelude::*,
+};
+use parking_lot::Mutex;
+use prometheus_endpoint::Registry as PrometheusRegistry;
+use sc_transaction_pool_api::{
+	error::Error as TxPoolError, ChainEvent, ImportNotificationStream, MaintainedTransactionPool,
+	PoolFuture, PoolStatus, TransactionFor, TransactionPool, TransactionSource,
+	TransactionStatusStreamFor, TxHash,
+};
+use sp_blockchain::{HashAndNumber, TreeRoute};
+use sp_core::traits::SpawnEssentialNamed;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, NumberFor},
+};
+
+use super::{
+	multi_view_listener::MultiViewListener, revalidation_worker::RevalidationQueue,
+	tx_mem_pool::TxMemPool, view_store::ViewStore,
+};
+use crate::{
+	api::FullChainApi,
+	error,
+	graph::{self, base_pool::Limit, ExtrinsicHash, IsValidator},
+	metrics::MetricsLink as PrometheusMetrics,
+	PolledIterator, ReadyIteratorFor, LOG_TARGET,
+};
+
+/// A fork-aware transaction pool for a full node.
+pub type FullForkAwarePool<Block, Client> = ForkAwareTxPool<FullChainApi<Client, Block>, Block>;
+
+/// Transaction pool keeping a separate view of the transactions for every tracked fork.
+///
+/// See the [module level documentation](super) for details.
+pub struct ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: graph::ChainApi<Block = Block> + 'static,
+{
+	api: Arc<ChainApi>,
+	/// All the transactions submitted to the pool.
+	mempool: Arc<TxMemPool<ChainApi>>,
+	/// The views of the tracked forks.
+	view_store: Arc<ViewStore<ChainApi>>,
+	/// Merges the status streams of watched transactions over all the views.
+	listener: Arc<MultiViewListener<ChainApi>>,
+	/// Sinks of the import notification streams.
+	import_notification_sinks: Arc<Mutex<Vec<Sender<ExtrinsicHash<ChainApi>>>>>,
+	/// Revalidates the views of the new best blocks.
+	revalidation_queue: Arc<RevalidationQueue<ChainApi>>,
+	metrics: PrometheusMetrics,
+}
+
+impl<ChainApi, Block> ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: graph::ChainApi<Block = Block> + 'static,
+{
+	/// Create new fork-aware transaction pool with provided api, for tests.
+	///
+	/// The views are revalidated during maintenance, rather than in a background task.
+	pub fn new_test(
+		pool_api: Arc<ChainApi>,
+		best_block_hash: Block::Hash,
+		options: graph::Options,
+	) -> Self {
+		let revalidation_queue = RevalidationQueue::new(pool_api.clone());
+		Self::new_with_revalidation_queue(
+			options,
+			true.into(),
+			pool_api,
+			None,
+			revalidation_queue,
+			best_block_hash,
+		)
+	}
+
+	/// Create new fork-aware transaction pool with provided api and options.
+	///
+	/// The initial view is created at the given best block. The views are revalidated in a
+	/// background task spawned with the given spawner.
+	pub fn with_options(
+		options: graph::Options,
+		is_validator: IsValidator,
+		pool_api: Arc<ChainApi>,
+		prometheus: Option<&PrometheusRegistry>,
+		spawner: impl SpawnEssentialNamed,
+		best_block_hash: Block::Hash,
+	) -> Self {
+		let (revalidation_queue, background_task) =
+			RevalidationQueue::new_background(pool_api.clone());
+		spawner.spawn_essential("txpool-background", Some("transaction-pool"), background_task);
+
+		Self::new_with_revalidation_queue(
+			options,
+			is_validator,
+			pool_api,
+			prometheus,
+			revalidation_queue,
+			best_block_hash,
+		)
+	}
+
+	fn new_with_revalidation_queue(
+		options: graph::Options,
+		is_validator: IsValidator,
+		pool_api: Arc<ChainApi>,
+		prometheus: Option<&PrometheusRegistry>,
+		revalidation_queue: RevalidationQueue<ChainApi>,
+		best_block_hash: Block::Hash,
+	) -> Self {
+		let mempool_limit = Limit {
+			count: options.ready.count + options.future.count,
+			total_bytes: options.ready.total_bytes + options.future.total_bytes,
+		};
+		let view_store = ViewStore::new(pool_api.clone(), options, is_validator);
+
+		match pool_api.block_id_to_number(&BlockId::Hash(best_block_hash)) {
+			Ok(Some(number)) =>
+				view_store.insert_initial_view(HashAndNumber { hash: best_block_hash, number }),
+			_ => log::warn!(
+				target: LOG_TARGET,
+				"Could not create initial view at {:?}, waiting for the next block",
+				best_block_hash,
+			),
+		}
+
+		Self {
+			mempool: Arc::new(TxMemPool::new(pool_api.clone(), mempool_limit)),
+			api: pool_api,
+			view_store: Arc::new(view_store),
+			listener: Arc::new(MultiViewListener::new()),
+			import_notification_sinks: Default::default(),
+			revalidation_queue: Arc::new(revalidation_queue),
+			metrics: PrometheusMetrics::new(prometheus),
+		}
+	}
+
+	/// Get access to the underlying api
+	pub fn api(&self) -> &ChainApi {
+		&self.api
+	}
+
+	/// Returns the number of active and inactive views.
+	pub fn views_count(&self) -> (usize, usize) {
+		self.view_store.len()
+	}
+
+	/// Returns the number of transactions kept in the mempool.
+	pub fn mempool_len(&self) -> usize {
+		self.mempool.len()
+	}
+
+	/// Returns the status of the view at the given block, if the view exists.
+	pub fn status_at(&self, at: Block::Hash) -> Option<PoolStatus> {
+		self.view_store.get_view_at(at, true).map(|view| view.status())
+	}
+
+	/// Resolves the number of the given block.
+	fn block_hash_and_number(&self, at: Block::Hash) -> Option<HashAndNumber<Block>> {
+		match self.api.block_id_to_number(&BlockId::Hash(at)) {
+			Ok(Some(number)) => Some(HashAndNumber { hash: at, number }),
+			Ok(None) => {
+				log::debug!(target: LOG_TARGET, "Could not find block number for {:?}", at);
+				None
+			},
+			Err(e) => {
+				log::debug!(target: LOG_TARGET, "Error retrieving number of {:?}: {}", at, e);
+				None
+			},
+		}
+	}
+
+	/// Returns the hashes of the transactions included in the given block.
+	async fn block_transactions(&self, at: Block::Hash) -> Vec<ExtrinsicHash<ChainApi>> {
+		self.api
+			.block_body(at)
+			.await
+			.unwrap_or_else(|e| {
+				log::warn!(target: LOG_TARGET, "Failed to fetch block body: {}", e);
+				None
+			})
+			.unwrap_or_default()
+			.iter()
+			.map(|xt| self.api.hash_and_length(xt).0)
+			.collect()
+	}
+
+	/// Handles the new best block: creates the view and notifies about retracted blocks.
+	async fn handle_new_block(&self, hash: Block::Hash, tree_route: Option<Arc<TreeRoute<Block>>>) {
+		let Some(at) = self.block_hash_and_number(hash) else { return };
+
+		// Retracted blocks are reported first, so the watchers see the transactions leaving the
+		// old fork before they are included in the new one.
+		if let Some(tree_route) = tree_route {
+			for retracted in tree_route.retracted() {
+				self.listener.retracted(retracted.hash);
+			}
+		}
+
+		let view = self.view_store.build_view(at, &self.mempool, &self.listener).await;
+		self.view_store.set_most_recent_view(hash);
+
+		self.revalidation_queue.revalidate_later(view).await;
+	}
+
+	/// Handles the finalized block: reports finalized watched transactions and drops stale views
+	/// and transactions.
+	async fn handle_finalized(&self, hash: Block::Hash, tree_route: &[Block::Hash]) {
+		let Some(finalized) = self.block_hash_and_number(hash) else { return };
+
+		let mut pruned = 0;
+		for block in tree_route.iter().chain(std::iter::once(&hash)) {
+			for (index, tx_hash) in self.block_transactions(*block).await.into_iter().enumerate() {
+				if let Some(tx) = self.mempool.remove(&tx_hash) {
+					pruned += 1;
+					if tx.watched {
+						self.listener.finalize_transaction(tx_hash, *block, index);
+					}
+				}
+			}
+		}
+		self.metrics.report(|metrics| metrics.block_transactions_pruned.inc_by(pruned));
+
+		self.view_store.handle_finalized(&finalized);
+
+		if self.view_store.is_empty() {
+			return
+		}
+
+		// Transactions included in the non-finalized blocks of the remaining forks are pruned
+		// from the views, but they are still waiting for finality.
+		let mut visited = HashSet::new();
+		let mut included = HashSet::new();
+		for view in self.view_store.active_views() {
+			let Ok(tree_route) = self.api.tree_route(hash, view.at.hash) else { continue };
+			for block in tree_route.enacted() {
+				if visited.insert(block.hash) {
+					included.extend(self.block_transactions(block.hash).await);
+				}
+			}
+		}
+
+		// Transactions not known to any of the remaining forks are no longer valid.
+		let invalid = self
+			.mempool
+			.retain(|tx_hash| included.contains(tx_hash) || self.view_store.is_imported(tx_hash));
+		if !invalid.is_empty() {
+			log::debug!(target: LOG_TARGET, "Removed from mempool: {:?}", invalid);
+			self.metrics
+				.report(|metrics| metrics.validations_invalid.inc_by(invalid.len() as u64));
+			self.listener.invalidate_transactions(&invalid);
+		}
+	}
+}
+
+/// Notifies the import notification streams about the imported transaction.
+fn notify_imported<H: Copy + std::fmt::Debug>(sinks: &Mutex<Vec<Sender<H>>>, hash: H) {
+	sinks.lock().retain_mut(|sink| match sink.try_send(hash) {
+		Ok(()) => true,
+		Err(e) =>
+			if e.is_full() {
+				log::warn!(
+					target: LOG_TARGET,
+					"[{:?}] Trying to notify an import but the channel is full",
+					hash,
+				);
+				true
+			} else {
+				false
+			},
+	});
+}
+
+impl<ChainApi, Block> TransactionPool for ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: 'static + graph::ChainApi<Block = Block>,
+{
+	type Block = ChainApi::Block;
+	type Hash = ExtrinsicHash<ChainApi>;
+	type InPoolTransaction = graph::base_pool::Transaction<TxHash<Self>, TransactionFor<Self>>;
+	type Error = ChainApi::Error;
+
+	fn submit_at(
+		&self,
+		at: <Self::Block as BlockT>::Hash,
+		source: TransactionSource,
+		xts: Vec<TransactionFor<Self>>,
+	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
+		let view_store = self.view_store.clone();
+		let mempool = self.mempool.clone();
+		let sinks = self.import_notification_sinks.clone();
+
+		self.metrics
+			.report(|metrics| metrics.submitted_transactions.inc_by(xts.len() as u64));
+
+		let mut results = self.mempool.extend_unwatched(source, &xts);
+		let to_submit = xts
+			.into_iter()
+			.zip(results.iter())
+			.filter_map(|(xt, result)| result.is_ok().then_some(xt))
+			.collect::<Vec<_>>();
+
+		async move {
+			if view_store.is_empty() || to_submit.is_empty() {
+				return Ok(results)
+			}
+
+			let submitted = to_submit.len();
+			let mut view_results = view_store.submit_at(source, to_submit).await;
+			let imported_anywhere = (0..submitted)
+				.map(|index| {
+					view_results.values().any(
+						|view_result| matches!(view_result, Ok(results) if results[index].is_ok()),
+					)
+				})
+				.collect::<Vec<_>>();
+
+			// The results reported to the caller are the results at the requested block, or at
+			// the most recent view if there is no view at this block.
+			let reference = view_store
+				.get_view_at(at, false)
+				.or_else(|| view_store.most_recent_view())
+				.map(|view| view.at.hash)
+				.filter(|hash| matches!(view_results.get(hash), Some(Ok(_))))
+				.or_else(|| {
+					view_results.iter().find(|(_, result)| result.is_ok()).map(|(hash, _)| *hash)
+				});
+			let reference_results = match reference.and_then(|hash| view_results.remove(&hash)) {
+				Some(Ok(reference_results)) => reference_results,
+				_ => {
+					for tx_hash in results.iter().filter_map(|result| result.as_ref().ok()) {
+						mempool.remove(tx_hash);
+					}
+					return view_results
+						.into_values()
+						.find_map(|result| result.err())
+						.map_or(Ok(results), Err)
+				},
+			};
+
+			for ((result, reference_result), imported) in results
+				.iter_mut()
+				.filter(|result| result.is_ok())
+				.zip(reference_results)
+				.zip(imported_anywhere)
+			{
+				let tx_hash = *result.as_ref().expect("Filtered for successful results; qed");
+				if imported {
+					notify_imported(&sinks, tx_hash);
+				} else {
+					mempool.remove(&tx_hash);
+				}
+				if reference_result.is_err() {
+					*result = reference_result;
+				}
+			}
+
+			Ok(results)
+		}
+		.boxed()
+	}
+
+	fn submit_one(
+		&self,
+		at: <Self::Block as BlockT>::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<TxHash<Self>, Self::Error> {
+		let results = self.submit_at(at, source, vec![xt]);
+		async move { results.await?.pop().expect("One extrinsic passed; one result returned; qed") }
+			.boxed()
+	}
+
+	fn submit_and_watch(
+		&self,
+		_at: <Self::Block as BlockT>::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<Pin<Box<TransactionStatusStreamFor<Self>>>, Self::Error> {
+		let view_store = self.view_store.clone();
+		let mempool = self.mempool.clone();
+		let listener = self.listener.clone();
+		let sinks = self.import_notification_sinks.clone();
+
+		self.metrics.report(|metrics| metrics.submitted_transactions.inc());
+
+		let tx_hash = match self.mempool.push_watched(source, xt.clone()) {
+			Ok(tx_hash) => tx_hash,
+			Err(e) => return future::ready(Err(e)).boxed(),
+		};
+		let Some(external_watcher) = self.listener.create_external_watcher_for_tx(tx_hash) else {
+			return future::ready(Err(TxPoolError::AlreadyImported(Box::new(tx_hash)).into()))
+				.boxed()
+		};
+
+		async move {
+			let view_results = view_store.submit_and_watch(source, xt).await;
+
+			let mut first_error = None;
+			let mut imported_anywhere = view_results.is_empty();
+			for result in view_results.into_values() {
+				match result {
+					Ok(watcher) => {
+						imported_anywhere = true;
+						listener.add_view_watcher_for_tx(tx_hash, watcher.into_stream().boxed());
+					},
+					Err(e) => {
+						first_error.get_or_insert(e);
+					},
+				}
+			}
+
+			if !imported_anywhere {
+				mempool.remove(&tx_hash);
+				listener.remove_external_watcher(&tx_hash);
+				return Err(first_error.expect("There is at least one view result; qed"))
+			}
+
+			notify_imported(&sinks, tx_hash);
+			Ok(external_watcher)
+		}
+		.boxed()
+	}
+
+	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
+		let removed = self.view_store.remove_invalid(hashes);
+		for hash in hashes {
+			self.mempool.remove(hash);
+		}
+		self.listener.invalidate_transactions(hashes);
+		self.metrics
+			.report(|metrics| metrics.validations_invalid.inc_by(removed.len() as u64));
+		removed
+	}
+
+	fn status(&self) -> PoolStatus {
+		self.view_store
+			.most_recent_view()
+			.map(|view| view.status())
+			.unwrap_or(PoolStatus { ready: 0, ready_bytes: 0, future: 0, future_bytes: 0 })
+	}
+
+	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
+		const CHANNEL_BUFFER_SIZE: usize = 1024;
+
+		let (sink, stream) = channel(CHANNEL_BUFFER_SIZE);
+		self.import_notification_sinks.lock().push(sink);
+		stream
+	}
+
+	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
+		self.api.hash_and_length(xt).0
+	}
+
+	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>) {
+		if let Some(view) = self.view_store.most_recent_view() {
+			view.pool.validated_pool().on_broadcasted(propagations)
+		}
+	}
+
+	fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
+		self.view_store
+			.most_recent_view()
+			.and_then(|view| view.pool.validated_pool().ready_by_hash(hash))
+	}
+
+	fn ready_at(&self, at: <Self::Block as BlockT>::Hash) -> PolledIterator<ChainApi> {
+		if let Some(view) = self.view_store.get_view_at(at, true) {
+			let iterator: ReadyIteratorFor<ChainApi> = Box::new(view.pool.validated_pool().ready());
+			return async move { iterator }.boxed()
+		}
+
+		let Some(at) = self.block_hash_and_number(at) else {
+			return async { Box::new(std::iter::empty()) as Box<_> }.boxed()
+		};
+
+		// There is no view at the requested block yet, so it is built on demand.
+		let view_store = self.view_store.clone();
+		let mempool = self.mempool.clone();
+		let listener = self.listener.clone();
+		async move {
+			let view = view_store.build_view(at, &mempool, &listener).await;
+			Box::new(view.pool.validated_pool().ready()) as ReadyIteratorFor<ChainApi>
+		}
+		.boxed()
+	}
+
+	fn ready(&self) -> ReadyIteratorFor<ChainApi> {
+		match self.view_store.most_recent_view() {
+			Some(view) => Box::new(view.pool.validated_pool().ready()),
+			None => Box::new(std::iter::empty()),
+		}
+	}
+
+	fn futures(&self) -> Vec<Self::InPoolTransaction> {
+		self.view_store
+			.most_recent_view()
+			.map(|view| {
+				let pool = view.pool.validated_pool().pool.read();
+				pool.futures().cloned().collect::<Vec<_>>()
+			})
+			.unwrap_or_default()
+	}
+}
+
+impl<Block, Client> FullForkAwarePool<Block, Client>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sc_client_api::ExecutorProvider<Block>
+		+ sc_client_api::UsageProvider<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	/// Create new fork-aware transaction pool for a full node with the provided api.
+	pub fn new_full(
+		options: graph::Options,
+		is_validator: IsValidator,
+		prometheus: Option<&PrometheusRegistry>,
+		spawner: impl SpawnEssentialNamed,
+		client: Arc<Client>,
+	) -> Arc<Self> {
+		let pool_api = Arc::new(FullChainApi::new(client.clone(), prometheus, &spawner));
+		Arc::new(Self::with_options(
+			options,
+			is_validator,
+			pool_api,
+			prometheus,
+			spawner,
+			client.usage_info().chain.best_hash,
+		))
+	}
+}
+
+impl<Block, Client> sc_transaction_pool_api::LocalTransactionPool
+	for ForkAwareTxPool<FullChainApi<Client, Block>, Block>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>,
+	Client: Send + Sync + 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	type Block = Block;
+	type Hash = ExtrinsicHash<FullChainApi<Client, Block>>;
+	type Error = <FullChainApi<Client, Block> as graph::ChainApi>::Error;
+
+	fn submit_local(
+		&self,
+		at: Block::Hash,
+		xt: sc_transaction_pool_api::LocalTransactionFor<Self>,
+	) -> Result<Self::Hash, Self::Error> {
+		use sp_runtime::{
+			traits::SaturatedConversion, transaction_validity::TransactionValidityError,
+		};
+
+		let validity = self
+			.api
+			.validate_transaction_blocking(at, TransactionSource::Local, xt.clone())?
+			.map_err(|e| {
+				Self::Error::Pool(match e {
+					TransactionValidityError::Invalid(i) => TxPoolError::InvalidTransaction(i),
+					TransactionValidityError::Unknown(u) => TxPoolError::UnknownTransaction(u),
+				})
+			})?;
+
+		let (hash, bytes) = self.api.hash_and_length(&xt);
+		let block_number: NumberFor<Block> = self
+			.api
+			.block_id_to_number(&BlockId::hash(at))?
+			.ok_or_else(|| error::Error::BlockIdConversion(format!("{:?}", at)))?;
+
+		self.mempool
+			.extend_unwatched(TransactionSource::Local, &[xt.clone()])
+			.remove(0)?;
+
+		// The transaction was validated at `at` only, it is imported into all the forks as is.
+		// The views will revalidate it at their own blocks when they are revalidated.
+		let views = self.view_store.active_views();
+		let mut result = Ok(hash);
+		for view in views {
+			let validated = graph::ValidatedTransaction::valid_at(
+				block_number.saturated_into::<u64>(),
+				hash,
+				TransactionSource::Local,
+				xt.clone(),
+				bytes,
+				validity.clone(),
+			);
+			let view_result = view.pool.validated_pool().submit(vec![validated]).remove(0);
+			if view.at.hash == at {
+				result = view_result;
+			}
+		}
+
+		if result.is_ok() {
+			notify_imported(&self.import_notification_sinks, hash);
+		}
+		result
+	}
+}
+
+#[async_trait]
+impl<ChainApi, Block> MaintainedTransactionPool for ForkAwareTxPool<ChainApi, Block>
+where
+	Block: BlockT,
+	ChainApi: 'static + graph::ChainApi<Block = Block>,
+{
+	async fn maintain(&self, event: ChainEvent<Self::Block>) {
+		log::trace!(target: LOG_TARGET, "maintain: {:?}", event);
+		match event {
+			ChainEvent::NewBestBlock { hash, tree_route } =>
+				self.handle_new_block(hash, tree_route).await,
+			ChainEvent::Finalized { hash, tree_route } =>
+				self.handle_finalized(hash, &tree_route).await,
+		}
+	}
+}
@@ -0,0 +1,49 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Fork-aware transaction pool.
+//!
+//! Instead of a single set of ready and future transactions tied to the best block, the
+//! fork-aware pool keeps a _view_ for every tracked fork. A view is a separate `graph::Pool`
+//! holding the transactions valid at its block. All the submitted transactions are additionally
+//! kept in the _mempool_, which is used to populate views created for new blocks.
+//!
+//! - a new view is cloned from the view of the nearest ancestor block, the transactions included in
+//!   the blocks enacted since then are pruned and the missing mempool transactions are submitted,
+//! - views at the tips of the forks are _active_, submitted transactions are validated against all
+//!   of them; views of non-leaf blocks are kept _inactive_ until finalization,
+//! - only the view of the best block is revalidated, once per view, in a background task,
+//! - [`ready_at`](sc_transaction_pool_api::TransactionPool::ready_at) is served for any block,
+//!   building the view on demand,
+//! - upon finalization, views that are not descendants of the finalized block are dropped, and so
+//!   are the mempool transactions which are included or no longer valid in any view.
+//!
+//! The status streams of watched transactions are merged over all the views by the
+//! `MultiViewListener`.
+//!
+//! A full node uses the fork-aware pool when it is built with
+//! [`TransactionPoolType::ForkAware`](crate::TransactionPoolType::ForkAware).
+
+mod fork_aware_txpool;
+mod multi_view_listener;
+mod revalidation_worker;
+mod tx_mem_pool;
+mod view;
+mod view_store;
+
+pub use fork_aware_txpool::{ForkAwareTxPool, FullForkAwarePool};
@@ -0,0 +1,243 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Multi-view listener.
+//!
+//! Every watched transaction is imported into many views. The listener merges the status streams
+//! of all the views into the single stream exposed to the `submit_and_watch` caller, removing the
+//! duplicated events.
+
+use std::{
+	collections::{HashMap, HashSet},
+	pin::Pin,
+};
+
+use futures::{
+	future::Either,
+	stream::{self, SelectAll},
+	StreamExt,
+};
+use parking_lot::RwLock;
+use sc_transaction_pool_api::{TransactionStatus, TransactionStatusStream, TxIndex};
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
+
+use crate::{
+	graph::{self, BlockHash, ExtrinsicHash},
+	LOG_TARGET,
+};
+
+/// The stream of the transaction status events.
+pub(super) type TxStatusStream<ChainApi> =
+	Pin<Box<TransactionStatusStream<ExtrinsicHash<ChainApi>, BlockHash<ChainApi>>>>;
+
+/// Commands sent by the pool to the external watcher of a transaction.
+enum ControllerCommand<ChainApi: graph::ChainApi> {
+	/// Merge the status stream of a new view into the external stream.
+	AddViewStream(TxStatusStream<ChainApi>),
+	/// The given block was retracted.
+	Retracted(BlockHash<ChainApi>),
+	/// The transaction was finalized in the given block.
+	Finalized(BlockHash<ChainApi>, TxIndex),
+	/// The transaction is no longer valid at any of the views.
+	Invalidated,
+}
+
+/// The state of the single external watcher stream.
+struct ExternalWatcherContext<ChainApi: graph::ChainApi> {
+	/// The hash of the watched transaction.
+	tx_hash: ExtrinsicHash<ChainApi>,
+	/// The merged status streams of all the views.
+	fused: SelectAll<TxStatusStream<ChainApi>>,
+	/// The receiver of the commands sent by the pool.
+	controller_receiver: TracingUnboundedReceiver<ControllerCommand<ChainApi>>,
+	/// Was the final event already sent?
+	terminate: bool,
+	/// Was the `Future` event already sent?
+	future_seen: bool,
+	/// Was the `Ready` event already sent?
+	ready_seen: bool,
+	/// The blocks for which the `InBlock` event was already sent.
+	in_block: HashSet<BlockHash<ChainApi>>,
+}
+
+impl<ChainApi: graph::ChainApi> ExternalWatcherContext<ChainApi> {
+	/// Filters out the events which were already sent or are handled by the pool itself.
+	fn handle(
+		&mut self,
+		status: TransactionStatus<ExtrinsicHash<ChainApi>, BlockHash<ChainApi>>,
+	) -> Option<TransactionStatus<ExtrinsicHash<ChainApi>, BlockHash<ChainApi>>> {
+		log::trace!(target: LOG_TARGET, "[{:?}] view status: {:?}", self.tx_hash, status);
+		match status {
+			TransactionStatus::Future if !self.future_seen && !self.ready_seen => {
+				self.future_seen = true;
+				Some(status)
+			},
+			TransactionStatus::Ready if !self.ready_seen => {
+				self.ready_seen = true;
+				Some(status)
+			},
+			TransactionStatus::Broadcast(_) => Some(status),
+			TransactionStatus::InBlock((block, _)) if self.in_block.insert(block) => Some(status),
+			// The final events are decided by the pool, a single view is not authoritative.
+			_ => None,
+		}
+	}
+
+	/// Handles the command sent by the pool.
+	fn handle_command(
+		&mut self,
+		command: ControllerCommand<ChainApi>,
+	) -> Option<TransactionStatus<ExtrinsicHash<ChainApi>, BlockHash<ChainApi>>> {
+		match command {
+			ControllerCommand::AddViewStream(stream) => {
+				self.fused.push(stream);
+				None
+			},
+			ControllerCommand::Retracted(block) =>
+				self.in_block.remove(&block).then_some(TransactionStatus::Retracted(block)),
+			ControllerCommand::Finalized(block, index) => {
+				self.terminate = true;
+				Some(TransactionStatus::Finalized((block, index)))
+			},
+			ControllerCommand::Invalidated => {
+				self.terminate = true;
+				Some(TransactionStatus::Invalid)
+			},
+		}
+	}
+}
+
+/// Merges the status streams of the views into the external streams of watched transactions.
+pub(super) struct MultiViewListener<ChainApi: graph::ChainApi> {
+	/// The controllers of the external watchers, by transaction hash.
+	controllers: RwLock<
+		HashMap<ExtrinsicHash<ChainApi>, TracingUnboundedSender<ControllerCommand<ChainApi>>>,
+	>,
+}
+
+impl<ChainApi: graph::ChainApi + 'static> MultiViewListener<ChainApi> {
+	/// Creates a new, empty listener.
+	pub(super) fn new() -> Self {
+		Self { controllers: Default::default() }
+	}
+
+	/// Creates the external watcher for the given transaction.
+	///
+	/// Returns `None` if the transaction is already watched.
+	pub(super) fn create_external_watcher_for_tx(
+		&self,
+		tx_hash: ExtrinsicHash<ChainApi>,
+	) -> Option<TxStatusStream<ChainApi>> {
+		let mut controllers = self.controllers.write();
+		if controllers.contains_key(&tx_hash) {
+			return None
+		}
+
+		let (sender, receiver) = tracing_unbounded("mpsc_txpool_watcher_controller", 32);
+		controllers.insert(tx_hash, sender);
+
+		let context = ExternalWatcherContext {
+			tx_hash,
+			fused: SelectAll::new(),
+			controller_receiver: receiver,
+			terminate: false,
+			future_seen: false,
+			ready_seen: false,
+			in_block: HashSet::new(),
+		};
+
+		let stream = stream::unfold(context, |mut ctx| async move {
+			if ctx.terminate {
+				return None
+			}
+
+			loop {
+				// Events of the views are handled first, so the final event sent by the pool is
+				// not reported ahead of them.
+				let next = futures::select_biased! {
+					status = ctx.fused.next() => Either::Right(status),
+					command = ctx.controller_receiver.next() => Either::Left(command),
+				};
+
+				match next {
+					Either::Left(Some(command)) =>
+						if let Some(status) = ctx.handle_command(command) {
+							return Some((status, ctx))
+						},
+					// The transaction is no longer tracked by the pool.
+					Either::Left(None) => return None,
+					Either::Right(Some(status)) =>
+						if let Some(status) = ctx.handle(status) {
+							return Some((status, ctx))
+						},
+					// No view streams at the moment, wait for the next command.
+					Either::Right(None) => {},
+				}
+			}
+		});
+
+		Some(stream.boxed())
+	}
+
+	/// Merges the status stream of the view into the external watcher of the transaction.
+	pub(super) fn add_view_watcher_for_tx(
+		&self,
+		tx_hash: ExtrinsicHash<ChainApi>,
+		stream: TxStatusStream<ChainApi>,
+	) {
+		if let Some(controller) = self.controllers.read().get(&tx_hash) {
+			let _ = controller.unbounded_send(ControllerCommand::AddViewStream(stream));
+		}
+	}
+
+	/// Removes the external watcher of the transaction, terminating its stream.
+	pub(super) fn remove_external_watcher(&self, tx_hash: &ExtrinsicHash<ChainApi>) {
+		self.controllers.write().remove(tx_hash);
+	}
+
+	/// Notifies the watchers of transactions included in the retracted block.
+	pub(super) fn retracted(&self, block_hash: BlockHash<ChainApi>) {
+		for controller in self.controllers.read().values() {
+			let _ = controller.unbounded_send(ControllerCommand::Retracted(block_hash));
+		}
+	}
+
+	/// Notifies the watcher that the transaction was finalized.
+	pub(super) fn finalize_transaction(
+		&self,
+		tx_hash: ExtrinsicHash<ChainApi>,
+		block_hash: BlockHash<ChainApi>,
+		index: TxIndex,
+	) {
+		if let Some(controller) = self.controllers.write().remove(&tx_hash) {
+			log::debug!(target: LOG_TARGET, "[{:?}] finalized at {:?}", tx_hash, block_hash);
+			let _ = controller.unbounded_send(ControllerCommand::Finalized(block_hash, index));
+		}
+	}
+
+	/// Notifies the watchers that the transactions are invalid.
+	pub(super) fn invalidate_transactions(&self, tx_hashes: &[ExtrinsicHash<ChainApi>]) {
+		let mut controllers = self.controllers.write();
+		for tx_hash in tx_hashes {
+			if let Some(controller) = controllers.remove(tx_hash) {
+				log::debug!(target: LOG_TARGET, "[{:?}] invalidated", tx_hash);
+				let _ = controller.unbounded_send(ControllerCommand::Invalidated);
+			}
+		}
+	}
+}
@@ -0,0 +1,79 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Revalidation of the views, off the maintenance path.
+
+use std::{pin::Pin, sync::Arc};
+
+use futures::prelude::*;
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
+
+use super::view::View;
+use crate::{graph, LOG_TARGET};
+
+/// Queue of the views waiting to be revalidated.
+pub(super) struct RevalidationQueue<ChainApi: graph::ChainApi> {
+	api: Arc<ChainApi>,
+	/// Sender to the background worker, if there is one.
+	background: Option<TracingUnboundedSender<Arc<View<ChainApi>>>>,
+}
+
+impl<ChainApi> RevalidationQueue<ChainApi>
+where
+	ChainApi: graph::ChainApi + 'static,
+{
+	/// New revalidation queue without a background worker.
+	///
+	/// Views are revalidated when they are queued.
+	pub(super) fn new(api: Arc<ChainApi>) -> Self {
+		Self { api, background: None }
+	}
+
+	/// New revalidation queue with a background worker.
+	///
+	/// The returned future is the worker, and has to be spawned.
+	pub(super) fn new_background(
+		api: Arc<ChainApi>,
+	) -> (Self, Pin<Box<dyn Future<Output = ()> + Send>>) {
+		let (to_worker, from_queue) = tracing_unbounded("mpsc_view_revalidation_queue", 100);
+
+		let worker_api = api.clone();
+		let worker = from_queue
+			.for_each(move |view: Arc<View<ChainApi>>| {
+				let api = worker_api.clone();
+				async move { view.revalidate(api).await }
+			})
+			.boxed();
+
+		(Self { api, background: Some(to_worker) }, worker)
+	}
+
+	/// Queues the view for revalidation.
+	///
+	/// If the queue has a background worker, this returns immediately. Otherwise it resolves once
+	/// the view is revalidated.
+	pub(super) async fn revalidate_later(&self, view: Arc<View<ChainApi>>) {
+		match &self.background {
+			Some(to_worker) =>
+				if let Err(e) = to_worker.unbounded_send(view) {
+					log::warn!(target: LOG_TARGET, "Failed to queue view revalidation: {:?}", e);
+				},
+			None => view.revalidate(self.api.clone()).await,
+		}
+	}
+}
@@ -0,0 +1,184 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transaction memory pool.
+//!
+//! Keeps every transaction submitted to the fork-aware pool, regardless of its validity at any
+//! particular fork. The content of the mempool is used to populate newly created views.
+
+use std::{collections::HashMap, sync::Arc};
+
+use parking_lot::RwLock;
+use sc_transaction_pool_api::{error, TransactionSource};
+
+use crate::graph::{self, base_pool::Limit, ExtrinsicFor, ExtrinsicHash};
+
+/// A transaction stored in the [`TxMemPool`].
+pub(super) struct TxInMemPool<ChainApi: graph::ChainApi> {
+	/// The transaction itself.
+	pub(super) tx: ExtrinsicFor<ChainApi>,
+	/// The source of the transaction.
+	pub(super) source: TransactionSource,
+	/// The encoded size of the transaction.
+	bytes: usize,
+	/// Is the transaction watched by `submit_and_watch`?
+	pub(super) watched: bool,
+}
+
+/// The transactions kept in the [`TxMemPool`].
+struct Transactions<ChainApi: graph::ChainApi> {
+	/// Transactions by hash.
+	by_hash: HashMap<ExtrinsicHash<ChainApi>, Arc<TxInMemPool<ChainApi>>>,
+	/// The total encoded size of the transactions.
+	bytes: usize,
+}
+
+impl<ChainApi: graph::ChainApi> Default for Transactions<ChainApi> {
+	fn default() -> Self {
+		Self { by_hash: Default::default(), bytes: 0 }
+	}
+}
+
+/// Intermediate storage of all the transactions submitted to the fork-aware pool.
+pub(super) struct TxMemPool<ChainApi: graph::ChainApi> {
+	api: Arc<ChainApi>,
+	/// Transactions kept in the mempool.
+	transactions: RwLock<Transactions<ChainApi>>,
+	/// The maximal number and total size of transactions kept.
+	limit: Limit,
+}
+
+impl<ChainApi: graph::ChainApi> TxMemPool<ChainApi> {
+	/// Creates a new, empty mempool with the given limits.
+	pub(super) fn new(api: Arc<ChainApi>, limit: Limit) -> Self {
+		Self { api, transactions: Default::default(), limit }
+	}
+
+	/// Returns the number of transactions kept in the mempool.
+	pub(super) fn len(&self) -> usize {
+		self.transactions.read().by_hash.len()
+	}
+
+	/// Returns the hashes of all the watched transactions.
+	pub(super) fn watched_hashes(&self) -> Vec<ExtrinsicHash<ChainApi>> {
+		self.transactions
+			.read()
+			.by_hash
+			.iter()
+			.filter_map(|(hash, tx)| tx.watched.then_some(*hash))
+			.collect()
+	}
+
+	/// Returns all the transactions kept in the mempool.
+	pub(super) fn clone_transactions(
+		&self,
+	) -> Vec<(ExtrinsicHash<ChainApi>, Arc<TxInMemPool<ChainApi>>)> {
+		self.transactions
+			.read()
+			.by_hash
+			.iter()
+			.map(|(hash, tx)| (*hash, tx.clone()))
+			.collect()
+	}
+
+	/// Inserts the transaction, checking the limits and for duplicates.
+	fn try_insert(
+		&self,
+		transactions: &mut Transactions<ChainApi>,
+		source: TransactionSource,
+		tx: ExtrinsicFor<ChainApi>,
+		watched: bool,
+	) -> Result<ExtrinsicHash<ChainApi>, ChainApi::Error> {
+		let (hash, bytes) = self.api.hash_and_length(&tx);
+
+		if transactions.by_hash.contains_key(&hash) {
+			return Err(error::Error::AlreadyImported(Box::new(hash)).into())
+		}
+
+		if transactions.by_hash.len() >= self.limit.count ||
+			transactions.bytes + bytes > self.limit.total_bytes
+		{
+			return Err(error::Error::ImmediatelyDropped.into())
+		}
+
+		transactions
+			.by_hash
+			.insert(hash, Arc::new(TxInMemPool { tx, source, bytes, watched }));
+		transactions.bytes += bytes;
+		Ok(hash)
+	}
+
+	/// Adds the unwatched transactions to the mempool.
+	///
+	/// Returns the result of insertion for every transaction, in the same order.
+	pub(super) fn extend_unwatched(
+		&self,
+		source: TransactionSource,
+		xts: &[ExtrinsicFor<ChainApi>],
+	) -> Vec<Result<ExtrinsicHash<ChainApi>, ChainApi::Error>> {
+		let mut transactions = self.transactions.write();
+		xts.iter()
+			.map(|xt| self.try_insert(&mut transactions, source, xt.clone(), false))
+			.collect()
+	}
+
+	/// Adds the watched transaction to the mempool.
+	pub(super) fn push_watched(
+		&self,
+		source: TransactionSource,
+		xt: ExtrinsicFor<ChainApi>,
+	) -> Result<ExtrinsicHash<ChainApi>, ChainApi::Error> {
+		let mut transactions = self.transactions.write();
+		self.try_insert(&mut transactions, source, xt, true)
+	}
+
+	/// Removes the transaction with given hash.
+	///
+	/// Returns the removed transaction, if it was kept in the mempool.
+	pub(super) fn remove(
+		&self,
+		hash: &ExtrinsicHash<ChainApi>,
+	) -> Option<Arc<TxInMemPool<ChainApi>>> {
+		let mut transactions = self.transactions.write();
+		let removed = transactions.by_hash.remove(hash)?;
+		transactions.bytes -= removed.bytes;
+		Some(removed)
+	}
+
+	/// Removes all the transactions which do not satisfy the given predicate.
+	///
+	/// Returns the hashes of removed transactions.
+	pub(super) fn retain(
+		&self,
+		mut predicate: impl FnMut(&ExtrinsicHash<ChainApi>) -> bool,
+	) -> Vec<ExtrinsicHash<ChainApi>> {
+		let mut removed = Vec::new();
+		let mut removed_bytes = 0;
+		let mut transactions = self.transactions.write();
+		transactions.by_hash.retain(|hash, tx| {
+			let keep = predicate(hash);
+			if !keep {
+				removed.push(*hash);
+				removed_bytes += tx.bytes;
+			}
+			keep
+		});
+		transactions.bytes -= removed_bytes;
+		removed
+	}
+}
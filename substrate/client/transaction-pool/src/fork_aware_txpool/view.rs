@@ -0,0 +1,142 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transaction pool view.
+//!
+//! A view is the state of the transaction pool at the given block. It contains the transactions
+//! that are valid (ready or future) at that block.
+
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Arc,
+};
+
+use sc_transaction_pool_api::{PoolStatus, TransactionSource};
+use sp_blockchain::HashAndNumber;
+
+use crate::{
+	graph::{self, watcher::Watcher, ExtrinsicFor, ExtrinsicHash, IsValidator},
+	revalidation, LOG_TARGET,
+};
+
+/// The state of the transaction pool at the given block.
+pub(super) struct View<ChainApi: graph::ChainApi> {
+	/// The transaction pool holding the transactions valid at `at`.
+	pub(super) pool: Arc<graph::Pool<ChainApi>>,
+	/// The block at which the view was created.
+	pub(super) at: HashAndNumber<ChainApi::Block>,
+	/// Was this view already revalidated?
+	revalidated: AtomicBool,
+}
+
+impl<ChainApi> View<ChainApi>
+where
+	ChainApi: graph::ChainApi + 'static,
+{
+	/// Creates a new, empty view at the given block.
+	pub(super) fn new(
+		api: Arc<ChainApi>,
+		at: HashAndNumber<ChainApi::Block>,
+		options: graph::Options,
+		is_validator: IsValidator,
+	) -> Self {
+		Self {
+			pool: Arc::new(graph::Pool::new(options, is_validator, api)),
+			at,
+			revalidated: AtomicBool::new(false),
+		}
+	}
+
+	/// Creates a new view at the given block, populated with all the transactions of `self`.
+	///
+	/// Transactions are copied without being revalidated.
+	pub(super) fn new_from_other(
+		&self,
+		api: Arc<ChainApi>,
+		at: HashAndNumber<ChainApi::Block>,
+		options: graph::Options,
+		is_validator: IsValidator,
+	) -> Self {
+		let view = Self::new(api, at, options, is_validator);
+
+		let transactions = {
+			let pool = self.pool.validated_pool().pool.read();
+			pool.ready()
+				.map(|tx| graph::ValidatedTransaction::Valid(tx.duplicate()))
+				.chain(pool.futures().map(|tx| graph::ValidatedTransaction::Valid(tx.duplicate())))
+				.collect::<Vec<_>>()
+		};
+		view.pool.validated_pool().submit(transactions);
+
+		view
+	}
+
+	/// Imports the transactions to the view, validating them at the view's block.
+	pub(super) async fn submit_many(
+		&self,
+		source: TransactionSource,
+		xts: impl IntoIterator<Item = ExtrinsicFor<ChainApi>>,
+	) -> Result<Vec<Result<ExtrinsicHash<ChainApi>, ChainApi::Error>>, ChainApi::Error> {
+		self.pool.submit_at(self.at.hash, source, xts).await
+	}
+
+	/// Imports the transaction to the view and starts watching its progress.
+	pub(super) async fn submit_and_watch(
+		&self,
+		source: TransactionSource,
+		xt: ExtrinsicFor<ChainApi>,
+	) -> Result<Watcher<ExtrinsicHash<ChainApi>, ExtrinsicHash<ChainApi>>, ChainApi::Error> {
+		self.pool.submit_and_watch(self.at.hash, source, xt).await
+	}
+
+	/// Creates a watcher for the transaction, which may not yet be imported to the view.
+	pub(super) fn create_watcher(
+		&self,
+		tx_hash: ExtrinsicHash<ChainApi>,
+	) -> Watcher<ExtrinsicHash<ChainApi>, ExtrinsicHash<ChainApi>> {
+		self.pool.validated_pool().create_watcher(tx_hash)
+	}
+
+	/// Returns true if the transaction is either ready or future in this view.
+	pub(super) fn is_imported(&self, tx_hash: &ExtrinsicHash<ChainApi>) -> bool {
+		self.pool.validated_pool().pool.read().is_imported(tx_hash)
+	}
+
+	/// Returns the status of the view.
+	pub(super) fn status(&self) -> PoolStatus {
+		self.pool.validated_pool().status()
+	}
+
+	/// Revalidates the ready transactions of the view at the view's block.
+	///
+	/// The revalidation is performed only once during the lifetime of the view.
+	pub(super) async fn revalidate(&self, api: Arc<ChainApi>) {
+		if self.revalidated.swap(true, Ordering::Relaxed) {
+			return
+		}
+
+		let hashes = self.pool.validated_pool().ready().map(|tx| tx.hash).collect::<Vec<_>>();
+		log::trace!(
+			target: LOG_TARGET,
+			"[{:?}] revalidating {} transactions in view",
+			self.at.hash,
+			hashes.len(),
+		);
+		revalidation::batch_revalidate(self.pool.clone(), api, self.at.hash, hashes).await;
+	}
+}
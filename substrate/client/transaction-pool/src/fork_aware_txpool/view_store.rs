@@ -0,0 +1,357 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Storage of the views tracked by the fork-aware pool.
+
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
+
+use futures::{future, StreamExt};
+use parking_lot::RwLock;
+use sc_transaction_pool_api::TransactionSource;
+use sp_blockchain::HashAndNumber;
+
+use super::{multi_view_listener::MultiViewListener, tx_mem_pool::TxMemPool, view::View};
+use crate::{
+	graph::{self, watcher::Watcher, BlockHash, ExtrinsicFor, ExtrinsicHash, IsValidator},
+	prune_known_txs_for_block, LOG_TARGET,
+};
+
+/// The results of submission to every view, by the view's block hash.
+type ViewSubmitResults<ChainApi, T> =
+	HashMap<BlockHash<ChainApi>, Result<T, <ChainApi as graph::ChainApi>::Error>>;
+
+/// The collection of views tracked by the fork-aware pool.
+pub(super) struct ViewStore<ChainApi: graph::ChainApi> {
+	api: Arc<ChainApi>,
+	/// The options used to create new views.
+	options: graph::Options,
+	/// Is the local node a validator?
+	is_validator: IsValidator,
+	/// The views at the tips of the forks.
+	active_views: RwLock<HashMap<BlockHash<ChainApi>, Arc<View<ChainApi>>>>,
+	/// The views at non-leaf blocks, kept until they are finalized or discarded.
+	inactive_views: RwLock<HashMap<BlockHash<ChainApi>, Arc<View<ChainApi>>>>,
+	/// The block of the most recently notified best block view.
+	most_recent_view: RwLock<Option<BlockHash<ChainApi>>>,
+}
+
+impl<ChainApi> ViewStore<ChainApi>
+where
+	ChainApi: graph::ChainApi + 'static,
+{
+	/// Creates a new, empty view store.
+	pub(super) fn new(
+		api: Arc<ChainApi>,
+		options: graph::Options,
+		is_validator: IsValidator,
+	) -> Self {
+		Self {
+			api,
+			options,
+			is_validator,
+			active_views: Default::default(),
+			inactive_views: Default::default(),
+			most_recent_view: Default::default(),
+		}
+	}
+
+	/// Returns true if there are no active views.
+	pub(super) fn is_empty(&self) -> bool {
+		self.active_views.read().is_empty()
+	}
+
+	/// Returns the number of active and inactive views.
+	pub(super) fn len(&self) -> (usize, usize) {
+		(self.active_views.read().len(), self.inactive_views.read().len())
+	}
+
+	/// Returns all the active views.
+	pub(super) fn active_views(&self) -> Vec<Arc<View<ChainApi>>> {
+		self.active_views.read().values().cloned().collect()
+	}
+
+	/// Returns the view at the given block.
+	///
+	/// Inactive views are only considered if `allow_inactive` is set.
+	pub(super) fn get_view_at(
+		&self,
+		at: BlockHash<ChainApi>,
+		allow_inactive: bool,
+	) -> Option<Arc<View<ChainApi>>> {
+		if let Some(view) = self.active_views.read().get(&at) {
+			return Some(view.clone())
+		}
+		if allow_inactive {
+			return self.inactive_views.read().get(&at).cloned()
+		}
+		None
+	}
+
+	/// Returns the view of the most recently notified best block.
+	pub(super) fn most_recent_view(&self) -> Option<Arc<View<ChainApi>>> {
+		let at = (*self.most_recent_view.read())?;
+		self.get_view_at(at, true)
+	}
+
+	/// Marks the view at the given block as the most recent one.
+	pub(super) fn set_most_recent_view(&self, at: BlockHash<ChainApi>) {
+		*self.most_recent_view.write() = Some(at);
+	}
+
+	/// Submits the transactions to all the active views.
+	pub(super) async fn submit_at(
+		&self,
+		source: TransactionSource,
+		xts: Vec<ExtrinsicFor<ChainApi>>,
+	) -> ViewSubmitResults<ChainApi, Vec<Result<ExtrinsicHash<ChainApi>, ChainApi::Error>>> {
+		let views = self.active_views();
+		let results = future::join_all(views.iter().map(|view| {
+			let xts = xts.clone();
+			async move { (view.at.hash, view.submit_many(source, xts).await) }
+		}))
+		.await;
+
+		results.into_iter().collect()
+	}
+
+	/// Submits the transaction to all the active views and starts watching it.
+	pub(super) async fn submit_and_watch(
+		&self,
+		source: TransactionSource,
+		xt: ExtrinsicFor<ChainApi>,
+	) -> ViewSubmitResults<ChainApi, Watcher<ExtrinsicHash<ChainApi>, ExtrinsicHash<ChainApi>>> {
+		let views = self.active_views();
+		let results = future::join_all(views.iter().map(|view| {
+			let xt = xt.clone();
+			async move { (view.at.hash, view.submit_and_watch(source, xt).await) }
+		}))
+		.await;
+
+		results.into_iter().collect()
+	}
+
+	/// Finds the view with the highest block number being an ancestor of the given block.
+	fn find_best_ancestor_view(
+		&self,
+		at: BlockHash<ChainApi>,
+	) -> Option<(Arc<View<ChainApi>>, Vec<HashAndNumber<ChainApi::Block>>)> {
+		let views = self
+			.active_views
+			.read()
+			.values()
+			.chain(self.inactive_views.read().values())
+			.cloned()
+			.collect::<Vec<_>>();
+
+		views
+			.into_iter()
+			.filter_map(|view| {
+				let tree_route = self.api.tree_route(view.at.hash, at).ok()?;
+				tree_route.retracted().is_empty().then(|| (view, tree_route.enacted().to_vec()))
+			})
+			.max_by_key(|(view, _)| view.at.number)
+	}
+
+	/// Returns the view at the given block, creating it if needed.
+	///
+	/// A new view is cloned from the nearest ancestor view, if there is any. Transactions
+	/// included in the blocks between the ancestor and `at` are pruned, and the transactions
+	/// from the mempool which are not yet known to the view are submitted to it.
+	pub(super) async fn build_view(
+		&self,
+		at: HashAndNumber<ChainApi::Block>,
+		mempool: &TxMemPool<ChainApi>,
+		listener: &MultiViewListener<ChainApi>,
+	) -> Arc<View<ChainApi>> {
+		if let Some(view) = self.get_view_at(at.hash, true) {
+			return view
+		}
+
+		let origin = self.find_best_ancestor_view(at.hash);
+		log::debug!(
+			target: LOG_TARGET,
+			"Building view at {:?} from {:?}",
+			at,
+			origin.as_ref().map(|(view, _)| view.at.hash),
+		);
+
+		let (view, enacted, origin_hash) = match origin {
+			Some((origin, enacted)) => (
+				origin.new_from_other(
+					self.api.clone(),
+					at.clone(),
+					self.options.clone(),
+					self.is_validator.clone(),
+				),
+				enacted,
+				Some(origin.at.hash),
+			),
+			None => (
+				View::new(
+					self.api.clone(),
+					at.clone(),
+					self.options.clone(),
+					self.is_validator.clone(),
+				),
+				Vec::new(),
+				None,
+			),
+		};
+
+		// Watchers are attached before any further changes, so no event is missed.
+		for tx_hash in mempool.watched_hashes() {
+			listener.add_view_watcher_for_tx(
+				tx_hash,
+				view.create_watcher(tx_hash).into_stream().boxed(),
+			);
+		}
+
+		for block in enacted {
+			prune_known_txs_for_block(block.hash, &*self.api, &view.pool).await;
+		}
+
+		let mut unknown = Vec::<(TransactionSource, Vec<ExtrinsicFor<ChainApi>>)>::new();
+		for (tx_hash, tx) in mempool.clone_transactions() {
+			if view.is_imported(&tx_hash) {
+				continue
+			}
+			match unknown.iter_mut().find(|(source, _)| *source == tx.source) {
+				Some((_, xts)) => xts.push(tx.tx.clone()),
+				None => unknown.push((tx.source, vec![tx.tx.clone()])),
+			}
+		}
+		for (source, xts) in unknown {
+			if let Err(e) = view.submit_many(source, xts).await {
+				log::debug!(target: LOG_TARGET, "Error submitting to view at {:?}: {}", at, e);
+			}
+		}
+
+		self.insert_view(Arc::new(view), origin_hash)
+	}
+
+	/// Inserts the view as an active one, unless there is already a view at the same block.
+	///
+	/// The origin view is no longer a leaf, so it is deactivated.
+	fn insert_view(
+		&self,
+		view: Arc<View<ChainApi>>,
+		origin: Option<BlockHash<ChainApi>>,
+	) -> Arc<View<ChainApi>> {
+		let mut active_views = self.active_views.write();
+		let mut inactive_views = self.inactive_views.write();
+
+		if let Some(existing) =
+			active_views.get(&view.at.hash).or_else(|| inactive_views.get(&view.at.hash))
+		{
+			return existing.clone()
+		}
+
+		if let Some(origin) = origin.and_then(|origin| active_views.remove(&origin)) {
+			inactive_views.insert(origin.at.hash, origin);
+		}
+		active_views.insert(view.at.hash, view.clone());
+		view
+	}
+
+	/// Inserts the initial, empty view at the given block.
+	pub(super) fn insert_initial_view(&self, at: HashAndNumber<ChainApi::Block>) {
+		let view = Arc::new(View::new(
+			self.api.clone(),
+			at.clone(),
+			self.options.clone(),
+			self.is_validator.clone(),
+		));
+		self.insert_view(view, None);
+		self.set_most_recent_view(at.hash);
+	}
+
+	/// Removes the views which are not descendants of the finalized block.
+	///
+	/// The view at the finalized block itself is kept if it is still a leaf.
+	pub(super) fn handle_finalized(&self, finalized: &HashAndNumber<ChainApi::Block>) {
+		let is_kept = |view: &View<ChainApi>| {
+			if view.at.hash == finalized.hash {
+				return true
+			}
+			view.at.number > finalized.number &&
+				self.api
+					.tree_route(finalized.hash, view.at.hash)
+					.map(|tree_route| tree_route.retracted().is_empty())
+					.unwrap_or(false)
+		};
+
+		let mut removed = HashSet::new();
+		{
+			let mut active_views = self.active_views.write();
+			active_views.retain(|hash, view| is_kept(view) || !removed.insert(*hash));
+		}
+		{
+			let mut inactive_views = self.inactive_views.write();
+			inactive_views.retain(|hash, view| {
+				(view.at.hash != finalized.hash && is_kept(view)) || !removed.insert(*hash)
+			});
+		}
+
+		let mut most_recent_view = self.most_recent_view.write();
+		if most_recent_view.map_or(false, |hash| removed.contains(&hash)) {
+			*most_recent_view = None;
+		}
+
+		log::debug!(
+			target: LOG_TARGET,
+			"Finalized {:?}, removed views: {:?}, views left: {:?}",
+			finalized,
+			removed,
+			self.len(),
+		);
+	}
+
+	/// Removes the invalid transactions from all the views.
+	///
+	/// Returns the transactions removed from the most recent view.
+	pub(super) fn remove_invalid(
+		&self,
+		hashes: &[ExtrinsicHash<ChainApi>],
+	) -> Vec<Arc<graph::Transaction<ExtrinsicHash<ChainApi>, ExtrinsicFor<ChainApi>>>> {
+		let most_recent = *self.most_recent_view.read();
+		let views = self
+			.active_views
+			.read()
+			.values()
+			.chain(self.inactive_views.read().values())
+			.cloned()
+			.collect::<Vec<_>>();
+
+		let mut removed = Vec::new();
+		for view in views {
+			let view_removed = view.pool.validated_pool().remove_invalid(hashes);
+			if Some(view.at.hash) == most_recent {
+				removed = view_removed;
+			}
+		}
+		removed
+	}
+
+	/// Returns true if the transaction is imported into any of the active views.
+	pub(super) fn is_imported(&self, tx_hash: &ExtrinsicHash<ChainApi>) -> bool {
+		self.active_views.read().values().any(|view| view.is_imported(tx_hash))
+	}
+}
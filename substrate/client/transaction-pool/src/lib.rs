@@ -23,8 +23,10 @@
 #![warn(unused_extern_crates)]
 
 mod api;
+mod builder;
 mod enactment_state;
 pub mod error;
+mod fork_aware_txpool;
 mod graph;
 mod metrics;
 mod revalidation;
@@ -33,7 +35,9 @@ mod tests;
 
 pub use crate::api::FullChainApi;
 use async_trait::async_trait;
+pub use builder::{TransactionPoolHandle, TransactionPoolType};
 use enactment_state::{EnactmentAction, EnactmentState};
+pub use fork_aware_txpool::{ForkAwareTxPool, FullForkAwarePool};
 use futures::{
 	channel::oneshot,
 	future::{self, ready},
@@ -331,7 +335,12 @@ where
 		self.pool.validated_pool().ready_by_hash(hash)
 	}
 
-	fn ready_at(&self, at: NumberFor<Self::Block>) -> PolledIterator<PoolApi> {
+	fn ready_at(&self, at: <Self::Block as BlockT>::Hash) -> PolledIterator<PoolApi> {
+		let Some(at) = self.api.block_id_to_number(&BlockId::Hash(at)).ok().flatten() else {
+			log::debug!(target: LOG_TARGET, "Could not get block number for {:?}", at);
+			return async { Box::new(std::iter::empty()) as Box<_> }.boxed()
+		};
+
 		let status = self.status();
 		// If there are no transactions in the pool, it is fine to return early.
 		//
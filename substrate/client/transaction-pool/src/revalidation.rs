@@ -63,7 +63,7 @@ impl<Api: ChainApi> Unpin for RevalidationWorker<Api> {}
 ///
 /// Each transaction is validated  against chain, and invalid are
 /// removed from the `pool`, while valid are resubmitted.
-async fn batch_revalidate<Api: ChainApi>(
+pub(crate) async fn batch_revalidate<Api: ChainApi>(
 	pool: Arc<Pool<Api>>,
 	api: Arc<Api>,
 	at: BlockHash<Api>,
@@ -0,0 +1,206 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for the fork-aware transaction pool.
+
+use futures::{executor::block_on, FutureExt};
+use sc_transaction_pool::ForkAwareTxPool;
+use sc_transaction_pool_api::{
+	ChainEvent, MaintainedTransactionPool, TransactionPool, TransactionSource, TransactionStatus,
+};
+use sp_runtime::traits::Block as _;
+use std::sync::Arc;
+use substrate_test_runtime_client::{
+	runtime::{Block, Hash, Header},
+	AccountKeyring::*,
+};
+use substrate_test_runtime_transaction_pool::{uxt, TestApi};
+
+const SOURCE: TransactionSource = TransactionSource::External;
+
+fn pool() -> (ForkAwareTxPool<TestApi, Block>, Arc<TestApi>, Hash) {
+	let api = Arc::new(TestApi::with_alice_nonce(200));
+	let genesis_hash = api.expect_hash_from_number(0);
+	let pool = ForkAwareTxPool::new_test(api.clone(), genesis_hash, Default::default());
+	(pool, api, genesis_hash)
+}
+
+fn new_best_block_event(
+	pool: &ForkAwareTxPool<TestApi, Block>,
+	from: Option<Hash>,
+	to: &Header,
+) -> ChainEvent<Block> {
+	ChainEvent::NewBestBlock {
+		hash: to.hash(),
+		tree_route: from.map(|from| {
+			Arc::new(pool.api().tree_route(from, to.parent_hash).expect("Tree route exists"))
+		}),
+	}
+}
+
+fn finalized_block_event(hash: Hash) -> ChainEvent<Block> {
+	ChainEvent::Finalized { hash, tree_route: Arc::from(vec![]) }
+}
+
+#[test]
+fn fatp_submitted_transaction_is_ready_at_new_block() {
+	let (pool, api, genesis) = pool();
+
+	let xt = uxt(Alice, 200);
+	block_on(pool.submit_one(genesis, SOURCE, xt.clone())).unwrap();
+	assert_eq!(pool.status().ready, 1);
+
+	let header = api.push_block(1, vec![], true);
+	block_on(pool.maintain(new_best_block_event(&pool, None, &header)));
+
+	let ready = block_on(pool.ready_at(header.hash())).map(|tx| tx.hash).collect::<Vec<_>>();
+	assert_eq!(ready, vec![pool.hash_of(&xt)]);
+	assert_eq!(pool.views_count(), (1, 1));
+}
+
+#[test]
+fn fatp_keeps_separate_views_for_forks() {
+	let (pool, api, genesis) = pool();
+
+	let xt = uxt(Alice, 200);
+	block_on(pool.submit_one(genesis, SOURCE, xt.clone())).unwrap();
+
+	let f1 = api.push_block_with_parent(genesis, vec![xt.clone()], true);
+	let f2 = api.push_block_with_parent(genesis, vec![], true);
+
+	block_on(pool.maintain(new_best_block_event(&pool, None, &f1)));
+	assert_eq!(pool.status_at(f1.hash()).unwrap().ready, 0);
+
+	// The view for the second fork is created on demand.
+	assert!(pool.status_at(f2.hash()).is_none());
+	let ready = block_on(pool.ready_at(f2.hash())).map(|tx| tx.hash).collect::<Vec<_>>();
+	assert_eq!(ready, vec![pool.hash_of(&xt)]);
+	assert_eq!(pool.status_at(f2.hash()).unwrap().ready, 1);
+	assert_eq!(pool.views_count(), (2, 1));
+}
+
+#[test]
+fn fatp_ready_at_returns_immediately_for_existing_view() {
+	let (pool, api, genesis) = pool();
+
+	let xt = uxt(Alice, 200);
+	block_on(pool.submit_one(genesis, SOURCE, xt.clone())).unwrap();
+
+	assert!(pool.ready_at(genesis).now_or_never().is_some());
+
+	let header = api.push_block(1, vec![], true);
+	block_on(pool.maintain(new_best_block_event(&pool, None, &header)));
+	let ready = pool.ready_at(header.hash()).now_or_never().expect("View exists");
+	assert_eq!(ready.count(), 1);
+}
+
+#[test]
+fn fatp_watcher_reports_finalization() {
+	let (pool, api, genesis) = pool();
+
+	let xt = uxt(Alice, 200);
+	let watcher = block_on(pool.submit_and_watch(genesis, SOURCE, xt.clone())).unwrap();
+
+	let header = api.push_block(1, vec![xt.clone()], true);
+	block_on(pool.maintain(new_best_block_event(&pool, None, &header)));
+	block_on(pool.maintain(finalized_block_event(header.hash())));
+
+	let mut stream = futures::executor::block_on_stream(watcher);
+	assert_eq!(stream.next(), Some(TransactionStatus::Ready));
+	assert_eq!(stream.next(), Some(TransactionStatus::InBlock((header.hash(), 0))));
+	assert_eq!(stream.next(), Some(TransactionStatus::Finalized((header.hash(), 0))));
+	assert_eq!(stream.next(), None);
+	assert_eq!(pool.mempool_len(), 0);
+}
+
+#[test]
+fn fatp_watcher_reports_retracted_block() {
+	let (pool, api, genesis) = pool();
+
+	let xt = uxt(Alice, 200);
+	let watcher = block_on(pool.submit_and_watch(genesis, SOURCE, xt.clone())).unwrap();
+
+	let f1 = api.push_block_with_parent(genesis, vec![xt.clone()], true);
+	block_on(pool.maintain(new_best_block_event(&pool, None, &f1)));
+
+	let f2 = api.push_block_with_parent(genesis, vec![], true);
+	let f3 = api.push_block_with_parent(f2.hash(), vec![xt.clone()], true);
+	block_on(pool.maintain(new_best_block_event(&pool, Some(f1.hash()), &f3)));
+	block_on(pool.maintain(finalized_block_event(f3.hash())));
+
+	let mut stream = futures::executor::block_on_stream(watcher);
+	assert_eq!(stream.next(), Some(TransactionStatus::Ready));
+	assert_eq!(stream.next(), Some(TransactionStatus::InBlock((f1.hash(), 0))));
+	assert_eq!(stream.next(), Some(TransactionStatus::Retracted(f1.hash())));
+	assert_eq!(stream.next(), Some(TransactionStatus::InBlock((f3.hash(), 0))));
+	assert_eq!(stream.next(), Some(TransactionStatus::Finalized((f3.hash(), 0))));
+	assert_eq!(stream.next(), None);
+}
+
+#[test]
+fn fatp_finalization_drops_stale_views() {
+	let (pool, api, genesis) = pool();
+
+	let f1 = api.push_block_with_parent(genesis, vec![], true);
+	let f2 = api.push_block_with_parent(genesis, vec![], true);
+	block_on(pool.maintain(new_best_block_event(&pool, None, &f1)));
+	block_on(pool.maintain(new_best_block_event(&pool, Some(f1.hash()), &f2)));
+	assert_eq!(pool.views_count(), (2, 1));
+
+	block_on(pool.maintain(finalized_block_event(f2.hash())));
+	assert_eq!(pool.views_count(), (1, 0));
+	assert!(pool.status_at(f1.hash()).is_none());
+}
+
+#[test]
+fn fatp_removes_transactions_invalid_at_all_forks_on_finalization() {
+	let (pool, api, genesis) = pool();
+
+	let xt = uxt(Alice, 200);
+	let watcher = block_on(pool.submit_and_watch(genesis, SOURCE, xt.clone())).unwrap();
+	assert_eq!(pool.mempool_len(), 1);
+
+	api.add_invalid(&xt);
+	let header = api.push_block(1, vec![], true);
+	block_on(pool.maintain(new_best_block_event(&pool, None, &header)));
+	block_on(pool.maintain(finalized_block_event(header.hash())));
+
+	let mut stream = futures::executor::block_on_stream(watcher);
+	assert_eq!(stream.next(), Some(TransactionStatus::Ready));
+	assert_eq!(stream.next(), Some(TransactionStatus::Invalid));
+	assert_eq!(stream.next(), None);
+	assert_eq!(pool.mempool_len(), 0);
+}
+
+#[test]
+fn fatp_remove_invalid_notifies_watcher() {
+	let (pool, _api, genesis) = pool();
+
+	let xt = uxt(Alice, 200);
+	let watcher = block_on(pool.submit_and_watch(genesis, SOURCE, xt.clone())).unwrap();
+
+	let removed = pool.remove_invalid(&[pool.hash_of(&xt)]);
+	assert_eq!(removed.len(), 1);
+	assert_eq!(pool.status().ready, 0);
+	assert_eq!(pool.mempool_len(), 0);
+
+	let mut stream = futures::executor::block_on_stream(watcher);
+	assert_eq!(stream.next(), Some(TransactionStatus::Ready));
+	assert_eq!(stream.next(), Some(TransactionStatus::Invalid));
+	assert_eq!(stream.next(), None);
+}
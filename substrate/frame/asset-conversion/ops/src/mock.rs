@@ -111,9 +111,15 @@ impl pallet_asset_conversion::Config for Test {
 	type PoolSetupFeeTarget = ResolveAssetTo<AssetConversionOrigin, Self::Assets>;
 	type PalletId = AssetConversionPalletId;
 	type WeightInfo = ();
+	type Curve = pallet_asset_conversion::Curve;
 	type LPFee = ConstU32<3>;
 	type LiquidityWithdrawalFee = LiquidityWithdrawalFee;
 	type MaxSwapPathLength = ConstU32<4>;
+	type MaxPriceObservations = ConstU32<16>;
+	type MaxLimitOrders = ConstU32<0>;
+	type MaxLimitOrdersPerAccount = ConstU32<0>;
+	type LimitOrderDeposit = ConstU64<0>;
+	type LimitOrderLifetime = ConstU64<0>;
 	type MintMinLiquidity = ConstU64<100>;
 	#[cfg(feature = "runtime-benchmarks")]
 	type BenchmarkHelper = ();
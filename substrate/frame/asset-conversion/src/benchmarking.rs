@@ -26,6 +26,7 @@ use frame_support::{
 		fungible::NativeOrWithId,
 		fungibles::{Create, Inspect, Mutate, Refund},
 	},
+	weights::Weight,
 };
 use frame_system::RawOrigin as SystemOrigin;
 use sp_core::Get;
@@ -64,6 +65,11 @@ impl<AssetId: From<u32> + Ord> BenchmarkHelper<NativeOrWithId<AssetId>>
 	}
 }
 
+/// The most expensive curve to swap along, which every pool of the benchmarks is created with.
+fn worst_case_curve<T: Config>() -> T::Curve {
+	<T::Curve as PoolCurve<T::Balance, T::HigherPrecisionBalance>>::worst_case()
+}
+
 /// Provides a pair of amounts expected to serve as sufficient initial liquidity for a pool.
 fn valid_liquidity_amount<T: Config>(ed1: T::Balance, ed2: T::Balance) -> (T::Balance, T::Balance)
 where
@@ -156,10 +162,11 @@ where
 
 	mint_setup_fee_asset::<T>(caller, asset1, asset2, &lp_token);
 
-	assert_ok!(AssetConversion::<T>::create_pool(
+	assert_ok!(AssetConversion::<T>::create_pool_with_curve(
 		SystemOrigin::Signed(caller.clone()).into(),
 		Box::new(asset1.clone()),
-		Box::new(asset2.clone())
+		Box::new(asset2.clone()),
+		worst_case_curve::<T>(),
 	));
 
 	(lp_token, liquidity1, liquidity2)
@@ -198,6 +205,35 @@ mod benchmarks {
 		);
 	}
 
+	#[benchmark]
+	fn create_pool_with_curve(n: Linear<0, 2>) {
+		let caller: T::AccountId = whitelisted_caller();
+		let (asset1, asset2) = T::BenchmarkHelper::create_pair(0, 1);
+		create_asset::<T>(&caller, &asset1, T::Assets::minimum_balance(asset1.clone()), true);
+		create_asset::<T>(&caller, &asset2, T::Assets::minimum_balance(asset2.clone()), true);
+
+		let lp_token = AssetConversion::<T>::get_next_pool_asset_id();
+		create_fee_asset::<T>(&caller);
+		mint_setup_fee_asset::<T>(&caller, &asset1, &asset2, &lp_token);
+		let curve =
+			<T::Curve as PoolCurve<T::Balance, T::HigherPrecisionBalance>>::benchmark_curve(n);
+
+		#[extrinsic_call]
+		_(
+			SystemOrigin::Signed(caller.clone()),
+			Box::new(asset1.clone()),
+			Box::new(asset2.clone()),
+			curve.clone(),
+		);
+
+		let pool_id = T::PoolLocator::pool_id(&asset1, &asset2).unwrap();
+		let pool_account = T::PoolLocator::address(&pool_id).unwrap();
+		assert!(PoolCurves::<T>::get(&pool_id).map_or(false, |info| info.curve == curve));
+		assert_last_event::<T>(
+			Event::PoolCreated { creator: caller, pool_account, pool_id, lp_token }.into(),
+		);
+	}
+
 	#[benchmark]
 	fn add_liquidity() {
 		let caller: T::AccountId = whitelisted_caller();
@@ -409,5 +445,80 @@ mod benchmarks {
 		assert_last_event::<T>(Event::Touched { pool_id, who: caller }.into());
 	}
 
+	#[benchmark]
+	fn place_limit_order() -> Result<(), BenchmarkError> {
+		if T::MaxLimitOrders::get() == 0 {
+			return Err(BenchmarkError::Override(BenchmarkResult::from_weight(Weight::MAX)))
+		}
+		let caller: T::AccountId = whitelisted_caller();
+		let (asset1, asset2) = T::BenchmarkHelper::create_pair(0, 1);
+
+		create_fee_asset::<T>(&caller);
+		create_asset_and_pool::<T>(&caller, &asset1, &asset2);
+		let order_id = NextLimitOrderId::<T>::get();
+		let amount_in = T::Assets::minimum_balance(asset1.clone()) + T::Balance::one();
+		assert_ok!(T::Assets::mint_into(asset1.clone(), &caller, amount_in));
+		assert_ok!(T::Assets::mint_into(
+			T::PoolSetupFeeAsset::get(),
+			&caller,
+			T::LimitOrderDeposit::get()
+		));
+
+		#[extrinsic_call]
+		_(
+			SystemOrigin::Signed(caller.clone()),
+			vec![Box::new(asset1), Box::new(asset2)],
+			amount_in,
+			T::Balance::one(),
+		);
+
+		assert_last_event::<T>(
+			Event::LimitOrderPlaced {
+				order_id,
+				who: caller,
+				amount_in,
+				amount_out_min: T::Balance::one(),
+			}
+			.into(),
+		);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn cancel_limit_order() -> Result<(), BenchmarkError> {
+		if T::MaxLimitOrders::get() == 0 {
+			return Err(BenchmarkError::Override(BenchmarkResult::from_weight(Weight::MAX)))
+		}
+		let caller: T::AccountId = whitelisted_caller();
+		let (asset1, asset2) = T::BenchmarkHelper::create_pair(0, 1);
+
+		create_fee_asset::<T>(&caller);
+		create_asset_and_pool::<T>(&caller, &asset1, &asset2);
+		let order_id = NextLimitOrderId::<T>::get();
+		let amount_in = T::Assets::minimum_balance(asset1.clone()) + T::Balance::one();
+		assert_ok!(T::Assets::mint_into(asset1.clone(), &caller, amount_in));
+		assert_ok!(T::Assets::mint_into(
+			T::PoolSetupFeeAsset::get(),
+			&caller,
+			T::LimitOrderDeposit::get()
+		));
+		assert_ok!(AssetConversion::<T>::place_limit_order(
+			SystemOrigin::Signed(caller.clone()).into(),
+			vec![Box::new(asset1.clone()), Box::new(asset2)],
+			amount_in,
+			T::Balance::one(),
+		));
+		// Funds sent to the account of the order are swept to its owner on cancellation.
+		let order_account = AssetConversion::<T>::limit_order_account(order_id);
+		assert_ok!(T::Assets::mint_into(asset1.clone(), &order_account, T::Balance::one()));
+
+		#[extrinsic_call]
+		_(SystemOrigin::Signed(caller.clone()), order_id);
+
+		assert_eq!(T::Assets::balance(asset1, &order_account), T::Balance::zero());
+		assert_last_event::<T>(Event::LimitOrderCancelled { order_id, who: caller }.into());
+		Ok(())
+	}
+
 	impl_benchmark_test_suite!(AssetConversion, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -0,0 +1,887 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pool curves, i.e. the invariants determining the exchange rate between the two reserves of a
+//! liquidity pool.
+
+use super::*;
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_arithmetic::{
+	helpers_128bit::multiply_by_rational_with_rounding,
+	traits::{Bounded, EnsureAdd, EnsureDiv, EnsureMul, EnsureSub, Unsigned},
+	ArithmeticError, FixedPointNumber, FixedU128, PerThing, Permill, Rounding, SignedRounding,
+};
+use sp_runtime::RuntimeDebug;
+
+/// Maximum number of Newton iterations for the StableSwap invariant to converge.
+const MAX_ITERATIONS: u32 = 255;
+
+/// Maximum amplification coefficient of a StableSwap pool.
+pub const MAX_AMPLIFICATION: u32 = 1_000_000;
+
+/// Minimum weight of either asset of a weighted pool.
+pub const MIN_WEIGHT: Permill = Permill::from_percent(2);
+
+/// Number of bits of the fractional part of an exponent taken into account by weighted pools.
+const EXPONENT_PRECISION_BITS: u32 = 48;
+
+/// Error of a [`PoolCurve`] computation.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum CurveError {
+	/// One of the reserves is empty.
+	ZeroLiquidity,
+	/// The requested amount is not less than the reserve.
+	AmountOutTooHigh,
+	/// An overflow happened.
+	Overflow,
+	/// An iterative computation did not converge.
+	NoConvergence,
+}
+
+impl From<ArithmeticError> for CurveError {
+	fn from(_: ArithmeticError) -> Self {
+		CurveError::Overflow
+	}
+}
+
+/// The invariant of a liquidity pool of two assets.
+///
+/// Amounts are computed in the direction of a swap, from the asset going into the pool to the
+/// asset going out of it. Curves which are not symmetric are stored oriented towards the first
+/// asset of the pool and turned around with [`PoolCurve::invert`] for swaps in the other
+/// direction.
+///
+/// `fee` is the liquidity provider fee, in per mille of the amount going into the pool.
+pub trait PoolCurve<Balance, HigherPrecisionBalance>: Sized {
+	/// Whether the parameters of the curve are acceptable for a new pool.
+	fn is_valid(&self) -> bool;
+
+	/// The same curve, seen from the other asset of the pool.
+	fn invert(&self) -> Self;
+
+	/// Given an input amount and the pool reserves, returns the maximum output amount.
+	fn get_amount_out(
+		&self,
+		amount_in: &Balance,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+		fee: u32,
+	) -> Result<Balance, CurveError>;
+
+	/// Given an output amount and the pool reserves, returns the required input amount.
+	fn get_amount_in(
+		&self,
+		amount_out: &Balance,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+		fee: u32,
+	) -> Result<Balance, CurveError>;
+
+	/// The output amount equivalent to `amount` at the current price of the pool, excluding fees.
+	fn quote(
+		&self,
+		amount: &Balance,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+	) -> Result<Balance, CurveError>;
+
+	/// The marginal price of the asset going into the pool, in units of the asset going out of
+	/// it, excluding fees.
+	fn spot_price(
+		&self,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+	) -> Result<FixedU128, CurveError>;
+
+	/// The most expensive curve to swap along, used to benchmark swaps.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn worst_case() -> Self;
+
+	/// The curve to create a pool with for the component `n`, from 0 to 2, used to benchmark
+	/// pool creation with each kind of curve, in increasing cost of storing it. Implementations
+	/// with fewer kinds of curves may repeat them.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_curve(n: u32) -> Self;
+}
+
+/// The curves supported out of the box.
+#[derive(
+	Decode, Encode, Default, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo,
+)]
+pub enum Curve {
+	/// Constant product `x * y = k`, the Uniswap V2 invariant.
+	#[default]
+	ConstantProduct,
+	/// The StableSwap invariant `4A(x + y) + D = 4AD + D^3 / 4xy`, offering low slippage
+	/// between assets of (nearly) the same value.
+	StableSwap {
+		/// The amplification coefficient `A`. The higher, the closer the curve gets to a
+		/// constant sum around the balanced point.
+		amplification: u32,
+	},
+	/// Weighted invariant `x^w1 * y^(1 - w1) = k`, the Balancer invariant.
+	Weighted {
+		/// The weight of the first asset of the pool.
+		weight1: Permill,
+	},
+}
+
+impl<Balance, HigherPrecisionBalance> PoolCurve<Balance, HigherPrecisionBalance> for Curve
+where
+	Balance: frame_support::traits::tokens::Balance,
+	HigherPrecisionBalance: Ensure + Unsigned + Copy + From<u32> + From<Balance> + TryInto<Balance>,
+{
+	fn is_valid(&self) -> bool {
+		match *self {
+			Curve::ConstantProduct => true,
+			Curve::StableSwap { amplification } => (1..=MAX_AMPLIFICATION).contains(&amplification),
+			Curve::Weighted { weight1 } =>
+				weight1 >= MIN_WEIGHT && weight1.left_from_one() >= MIN_WEIGHT,
+		}
+	}
+
+	fn invert(&self) -> Self {
+		match *self {
+			Curve::Weighted { weight1 } => Curve::Weighted { weight1: weight1.left_from_one() },
+			curve => curve,
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn worst_case() -> Self {
+		// The Newton iterations of StableSwap take the longest to converge at the highest
+		// amplification.
+		Curve::StableSwap { amplification: MAX_AMPLIFICATION }
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_curve(n: u32) -> Self {
+		match n {
+			0 => Curve::ConstantProduct,
+			1 => Curve::Weighted { weight1: Permill::from_percent(80) },
+			_ => Curve::StableSwap { amplification: MAX_AMPLIFICATION },
+		}
+	}
+
+	fn get_amount_out(
+		&self,
+		amount_in: &Balance,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+		fee: u32,
+	) -> Result<Balance, CurveError> {
+		if reserve_in.is_zero() || reserve_out.is_zero() {
+			return Err(CurveError::ZeroLiquidity)
+		}
+		match *self {
+			Curve::ConstantProduct =>
+				constant_product::get_amount_out::<_, HigherPrecisionBalance>(
+					amount_in,
+					reserve_in,
+					reserve_out,
+					fee,
+				),
+			Curve::StableSwap { amplification } => {
+				let amount_in = deduct_fee::<_, HigherPrecisionBalance>(amount_in, fee)?;
+				stable_swap::get_amount_out::<_, HigherPrecisionBalance>(
+					amplification,
+					&amount_in,
+					reserve_in,
+					reserve_out,
+				)
+			},
+			Curve::Weighted { weight1 } => {
+				let amount_in = deduct_fee::<_, HigherPrecisionBalance>(amount_in, fee)?;
+				weighted::get_amount_out(weight1, &amount_in, reserve_in, reserve_out)
+			},
+		}
+	}
+
+	fn get_amount_in(
+		&self,
+		amount_out: &Balance,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+		fee: u32,
+	) -> Result<Balance, CurveError> {
+		if reserve_in.is_zero() || reserve_out.is_zero() {
+			return Err(CurveError::ZeroLiquidity)
+		}
+		if amount_out >= reserve_out {
+			return Err(CurveError::AmountOutTooHigh)
+		}
+		match *self {
+			Curve::ConstantProduct => constant_product::get_amount_in::<_, HigherPrecisionBalance>(
+				amount_out,
+				reserve_in,
+				reserve_out,
+				fee,
+			),
+			Curve::StableSwap { amplification } => {
+				let amount_in = stable_swap::get_amount_in::<_, HigherPrecisionBalance>(
+					amplification,
+					amount_out,
+					reserve_in,
+					reserve_out,
+				)?;
+				add_fee::<_, HigherPrecisionBalance>(&amount_in, fee)
+			},
+			Curve::Weighted { weight1 } => {
+				let amount_in =
+					weighted::get_amount_in(weight1, amount_out, reserve_in, reserve_out)?;
+				add_fee::<_, HigherPrecisionBalance>(&amount_in, fee)
+			},
+		}
+	}
+
+	fn quote(
+		&self,
+		amount: &Balance,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+	) -> Result<Balance, CurveError> {
+		match *self {
+			Curve::ConstantProduct =>
+				mul_div::<_, HigherPrecisionBalance>(amount, reserve_out, reserve_in),
+			Curve::StableSwap { .. } =>
+				self.get_amount_out(amount, reserve_in, reserve_out, Zero::zero()),
+			Curve::Weighted { .. } => {
+				let price = self.spot_price(reserve_in, reserve_out)?;
+				weighted::from_u128(
+					price
+						.checked_mul_int(weighted::to_u128(amount)?)
+						.ok_or(CurveError::Overflow)?,
+				)
+			},
+		}
+	}
+
+	fn spot_price(
+		&self,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+	) -> Result<FixedU128, CurveError> {
+		if reserve_in.is_zero() || reserve_out.is_zero() {
+			return Err(CurveError::ZeroLiquidity)
+		}
+		match *self {
+			Curve::ConstantProduct => ratio::<Balance, HigherPrecisionBalance>(
+				HigherPrecisionBalance::from(*reserve_out),
+				HigherPrecisionBalance::from(*reserve_in),
+			),
+			Curve::StableSwap { amplification } => stable_swap::spot_price::<
+				_,
+				HigherPrecisionBalance,
+			>(amplification, reserve_in, reserve_out),
+			Curve::Weighted { weight1 } => weighted::spot_price(weight1, reserve_in, reserve_out),
+		}
+	}
+}
+
+/// `a * b / c`, computed with the higher precision.
+pub(crate) fn mul_div<Balance, HigherPrecisionBalance>(
+	a: &Balance,
+	b: &Balance,
+	c: &Balance,
+) -> Result<Balance, CurveError>
+where
+	Balance: Copy,
+	HigherPrecisionBalance: Ensure + Unsigned + From<Balance> + TryInto<Balance>,
+{
+	let a = HigherPrecisionBalance::from(*a);
+	let b = HigherPrecisionBalance::from(*b);
+	let c = HigherPrecisionBalance::from(*c);
+
+	let result = a
+		.checked_mul(&b)
+		.ok_or(CurveError::Overflow)?
+		.checked_div(&c)
+		.ok_or(CurveError::Overflow)?;
+
+	result.try_into().map_err(|_| CurveError::Overflow)
+}
+
+/// `numerator / denominator` as a [`FixedU128`], both being scaled down to fit a `Balance` if
+/// needed.
+fn ratio<Balance, HigherPrecisionBalance>(
+	numerator: HigherPrecisionBalance,
+	denominator: HigherPrecisionBalance,
+) -> Result<FixedU128, CurveError>
+where
+	Balance: frame_support::traits::tokens::Balance,
+	HigherPrecisionBalance: Ensure + Unsigned + Copy + From<u32> + From<Balance> + TryInto<Balance>,
+{
+	let max = HigherPrecisionBalance::from(Balance::max_value());
+	let larger = if numerator > denominator { numerator } else { denominator };
+	let (numerator, denominator) = if larger > max {
+		let scale = larger.ensure_div(max)?.ensure_add(One::one())?;
+		(numerator.ensure_div(scale)?, denominator.ensure_div(scale)?)
+	} else {
+		(numerator, denominator)
+	};
+	let to_u128 = |amount: HigherPrecisionBalance| -> Result<u128, CurveError> {
+		let amount: Balance = amount.try_into().map_err(|_| CurveError::Overflow)?;
+		weighted::to_u128(&amount)
+	};
+
+	FixedU128::checked_from_rational(to_u128(numerator)?, to_u128(denominator)?)
+		.ok_or(CurveError::Overflow)
+}
+
+/// The part of `amount` remaining once the per mille `fee` is deducted.
+fn deduct_fee<Balance, HigherPrecisionBalance>(
+	amount: &Balance,
+	fee: u32,
+) -> Result<Balance, CurveError>
+where
+	Balance: Copy,
+	HigherPrecisionBalance: Ensure + Unsigned + Copy + From<u32> + From<Balance> + TryInto<Balance>,
+{
+	let amount = HigherPrecisionBalance::from(*amount);
+	let result = amount
+		.ensure_mul(HigherPrecisionBalance::from(1000u32).ensure_sub(fee.into())?)?
+		.ensure_div(1000u32.into())?;
+	result.try_into().map_err(|_| CurveError::Overflow)
+}
+
+/// The amount which is at least `amount` once the per mille `fee` is deducted.
+fn add_fee<Balance, HigherPrecisionBalance>(
+	amount: &Balance,
+	fee: u32,
+) -> Result<Balance, CurveError>
+where
+	Balance: Copy,
+	HigherPrecisionBalance: Ensure + Unsigned + Copy + From<u32> + From<Balance> + TryInto<Balance>,
+{
+	let amount = HigherPrecisionBalance::from(*amount);
+	let result = amount
+		.ensure_mul(1000u32.into())?
+		.ensure_div(HigherPrecisionBalance::from(1000u32).ensure_sub(fee.into())?)?
+		.ensure_add(One::one())?;
+	result.try_into().map_err(|_| CurveError::Overflow)
+}
+
+mod constant_product {
+	use super::*;
+
+	pub(super) fn get_amount_out<Balance, HigherPrecisionBalance>(
+		amount_in: &Balance,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+		fee: u32,
+	) -> Result<Balance, CurveError>
+	where
+		Balance: Copy,
+		HigherPrecisionBalance:
+			Ensure + Unsigned + Copy + From<u32> + From<Balance> + TryInto<Balance>,
+	{
+		let amount_in = HigherPrecisionBalance::from(*amount_in);
+		let reserve_in = HigherPrecisionBalance::from(*reserve_in);
+		let reserve_out = HigherPrecisionBalance::from(*reserve_out);
+
+		let amount_in_with_fee = amount_in
+			.checked_mul(&(HigherPrecisionBalance::from(1000u32) - fee.into()))
+			.ok_or(CurveError::Overflow)?;
+
+		let numerator = amount_in_with_fee.checked_mul(&reserve_out).ok_or(CurveError::Overflow)?;
+
+		let denominator = reserve_in
+			.checked_mul(&1000u32.into())
+			.ok_or(CurveError::Overflow)?
+			.checked_add(&amount_in_with_fee)
+			.ok_or(CurveError::Overflow)?;
+
+		let result = numerator.checked_div(&denominator).ok_or(CurveError::Overflow)?;
+
+		result.try_into().map_err(|_| CurveError::Overflow)
+	}
+
+	pub(super) fn get_amount_in<Balance, HigherPrecisionBalance>(
+		amount_out: &Balance,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+		fee: u32,
+	) -> Result<Balance, CurveError>
+	where
+		Balance: Copy,
+		HigherPrecisionBalance:
+			Ensure + Unsigned + Copy + From<u32> + From<Balance> + TryInto<Balance>,
+	{
+		let amount_out = HigherPrecisionBalance::from(*amount_out);
+		let reserve_in = HigherPrecisionBalance::from(*reserve_in);
+		let reserve_out = HigherPrecisionBalance::from(*reserve_out);
+
+		let numerator = reserve_in
+			.checked_mul(&amount_out)
+			.ok_or(CurveError::Overflow)?
+			.checked_mul(&1000u32.into())
+			.ok_or(CurveError::Overflow)?;
+
+		let denominator = reserve_out
+			.checked_sub(&amount_out)
+			.ok_or(CurveError::Overflow)?
+			.checked_mul(&(HigherPrecisionBalance::from(1000u32) - fee.into()))
+			.ok_or(CurveError::Overflow)?;
+
+		let result = numerator
+			.checked_div(&denominator)
+			.ok_or(CurveError::Overflow)?
+			.checked_add(&One::one())
+			.ok_or(CurveError::Overflow)?;
+
+		result.try_into().map_err(|_| CurveError::Overflow)
+	}
+}
+
+mod stable_swap {
+	use super::*;
+
+	/// `|a - b|`.
+	fn abs_diff<N: Ensure + Unsigned + Copy>(a: N, b: N) -> N {
+		if a > b {
+			a - b
+		} else {
+			b - a
+		}
+	}
+
+	/// The invariant `D` of a pool with reserves `x` and `y`, where `ann` is `A * n^n`.
+	fn invariant<N>(x: N, y: N, ann: N) -> Result<N, CurveError>
+	where
+		N: Ensure + Unsigned + Copy + From<u32>,
+	{
+		let sum = x.ensure_add(y)?;
+		if sum.is_zero() {
+			return Ok(Zero::zero())
+		}
+		let two = N::from(2u32);
+
+		let mut d = sum;
+		for _ in 0..MAX_ITERATIONS {
+			// d_p = D^3 / (4xy)
+			let d_p = d
+				.ensure_mul(d)?
+				.ensure_div(x.ensure_mul(two)?)?
+				.ensure_mul(d)?
+				.ensure_div(y.ensure_mul(two)?)?;
+			let previous = d;
+			let numerator = ann.ensure_mul(sum)?.ensure_add(d_p.ensure_mul(two)?)?.ensure_mul(d)?;
+			let denominator = ann
+				.ensure_sub(One::one())?
+				.ensure_mul(d)?
+				.ensure_add(N::from(3u32).ensure_mul(d_p)?)?;
+			d = numerator.ensure_div(denominator)?;
+			if abs_diff(d, previous) <= One::one() {
+				return Ok(d)
+			}
+		}
+		Err(CurveError::NoConvergence)
+	}
+
+	/// The reserve of one asset which keeps the invariant `d` given the reserve `x` of the other.
+	fn reserve_for<N>(x: N, d: N, ann: N) -> Result<N, CurveError>
+	where
+		N: Ensure + Unsigned + Copy + From<u32>,
+	{
+		let two = N::from(2u32);
+		// c = D^3 / (4x * Ann), b = x + D / Ann
+		let c = d
+			.ensure_mul(d)?
+			.ensure_div(x.ensure_mul(two)?)?
+			.ensure_mul(d)?
+			.ensure_div(ann.ensure_mul(two)?)?;
+		let b = x.ensure_add(d.ensure_div(ann)?)?;
+
+		let mut y = d;
+		for _ in 0..MAX_ITERATIONS {
+			let previous = y;
+			// y = (y^2 + c) / (2y + b - D)
+			y = y
+				.ensure_mul(y)?
+				.ensure_add(c)?
+				.ensure_div(y.ensure_mul(two)?.ensure_add(b)?.ensure_sub(d)?)?;
+			if abs_diff(y, previous) <= One::one() {
+				return Ok(y)
+			}
+		}
+		Err(CurveError::NoConvergence)
+	}
+
+	pub(super) fn get_amount_out<Balance, HigherPrecisionBalance>(
+		amplification: u32,
+		amount_in: &Balance,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+	) -> Result<Balance, CurveError>
+	where
+		Balance: Copy,
+		HigherPrecisionBalance:
+			Ensure + Unsigned + Copy + From<u32> + From<Balance> + TryInto<Balance>,
+	{
+		let amount_in = HigherPrecisionBalance::from(*amount_in);
+		let reserve_in = HigherPrecisionBalance::from(*reserve_in);
+		let reserve_out = HigherPrecisionBalance::from(*reserve_out);
+		let ann = HigherPrecisionBalance::from(amplification).ensure_mul(4u32.into())?;
+
+		let d = invariant(reserve_in, reserve_out, ann)?;
+		let new_reserve_out = reserve_for(reserve_in.ensure_add(amount_in)?, d, ann)?;
+		// Round down in favour of the pool.
+		let result = reserve_out
+			.checked_sub(&new_reserve_out)
+			.and_then(|amount_out| amount_out.checked_sub(&One::one()))
+			.unwrap_or_else(Zero::zero);
+
+		result.try_into().map_err(|_| CurveError::Overflow)
+	}
+
+	pub(super) fn get_amount_in<Balance, HigherPrecisionBalance>(
+		amplification: u32,
+		amount_out: &Balance,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+	) -> Result<Balance, CurveError>
+	where
+		Balance: Copy,
+		HigherPrecisionBalance:
+			Ensure + Unsigned + Copy + From<u32> + From<Balance> + TryInto<Balance>,
+	{
+		let amount_out = HigherPrecisionBalance::from(*amount_out);
+		let reserve_in = HigherPrecisionBalance::from(*reserve_in);
+		let reserve_out = HigherPrecisionBalance::from(*reserve_out);
+		let ann = HigherPrecisionBalance::from(amplification).ensure_mul(4u32.into())?;
+
+		let d = invariant(reserve_in, reserve_out, ann)?;
+		let new_reserve_in = reserve_for(reserve_out.ensure_sub(amount_out)?, d, ann)?;
+		// Round up in favour of the pool, accounting for the tolerance of the iterations.
+		let result = new_reserve_in.ensure_sub(reserve_in)?.ensure_add(2u32.into())?;
+
+		result.try_into().map_err(|_| CurveError::Overflow)
+	}
+
+	pub(super) fn spot_price<Balance, HigherPrecisionBalance>(
+		amplification: u32,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+	) -> Result<FixedU128, CurveError>
+	where
+		Balance: frame_support::traits::tokens::Balance,
+		HigherPrecisionBalance:
+			Ensure + Unsigned + Copy + From<u32> + From<Balance> + TryInto<Balance>,
+	{
+		let x = HigherPrecisionBalance::from(*reserve_in);
+		let y = HigherPrecisionBalance::from(*reserve_out);
+		let two = HigherPrecisionBalance::from(2u32);
+		let ann = HigherPrecisionBalance::from(amplification).ensure_mul(4u32.into())?;
+
+		let d = invariant(x, y, ann)?;
+		// The ratio of the partial derivatives of the invariant, both multiplied by `xy`:
+		// (Ann * xy + D^3 / 4x) / (Ann * xy + D^3 / 4y), with c = D^3 / 4xy.
+		let c = d
+			.ensure_mul(d)?
+			.ensure_div(x.ensure_mul(two)?)?
+			.ensure_mul(d)?
+			.ensure_div(y.ensure_mul(two)?)?;
+		let ann_xy = ann.ensure_mul(x)?.ensure_mul(y)?;
+
+		ratio::<Balance, HigherPrecisionBalance>(
+			ann_xy.ensure_add(c.ensure_mul(y)?)?,
+			ann_xy.ensure_add(c.ensure_mul(x)?)?,
+		)
+	}
+}
+
+mod weighted {
+	use super::*;
+
+	/// `base^(numerator / denominator)`, computed from the binary expansion of the exponent.
+	///
+	/// The result is rounded up, which is in favour of the pool wherever it is used.
+	pub(super) fn pow(
+		base: FixedU128,
+		numerator: u32,
+		denominator: u32,
+	) -> Result<FixedU128, CurveError> {
+		let mul = |a: FixedU128, b: FixedU128| {
+			a.const_checked_mul_with_rounding(b, SignedRounding::Major)
+				.ok_or(CurveError::Overflow)
+		};
+		let mut result = FixedU128::one();
+		for _ in 0..numerator / denominator {
+			result = mul(result, base)?;
+		}
+
+		// Each bit of the fractional part of the exponent contributes a further square root.
+		let mut remainder = numerator % denominator;
+		let mut root = base;
+		for _ in 0..EXPONENT_PRECISION_BITS {
+			if remainder == 0 {
+				break
+			}
+			root = sqrt_up(root)?;
+			remainder *= 2;
+			if remainder >= denominator {
+				remainder -= denominator;
+				result = mul(result, root)?;
+			}
+		}
+		// The bits of the exponent past `EXPONENT_PRECISION_BITS` are dropped, which rounds the
+		// result down for a base above one. Round the exponent up by the last bit instead.
+		if remainder != 0 && base > FixedU128::one() {
+			result = mul(result, root)?;
+		}
+		Ok(result)
+	}
+
+	/// The square root of `x`, rounded up.
+	fn sqrt_up(x: FixedU128) -> Result<FixedU128, CurveError> {
+		let root = x.try_sqrt().ok_or(CurveError::Overflow)?;
+		let squared = root.checked_mul(&root).ok_or(CurveError::Overflow)?;
+		Ok(if squared < x { root.saturating_add(FixedU128::from_inner(1)) } else { root })
+	}
+
+	/// `numerator / denominator`, rounded up.
+	fn ratio_up(numerator: u128, denominator: u128) -> Result<FixedU128, CurveError> {
+		multiply_by_rational_with_rounding(
+			FixedU128::accuracy(),
+			numerator,
+			denominator,
+			Rounding::Up,
+		)
+		.map(FixedU128::from_inner)
+		.ok_or(CurveError::Overflow)
+	}
+
+	pub(super) fn to_u128<Balance: frame_support::traits::tokens::Balance>(
+		amount: &Balance,
+	) -> Result<u128, CurveError> {
+		(*amount).try_into().map_err(|_| CurveError::Overflow)
+	}
+
+	pub(super) fn from_u128<Balance: frame_support::traits::tokens::Balance>(
+		amount: u128,
+	) -> Result<Balance, CurveError> {
+		amount.try_into().map_err(|_| CurveError::Overflow)
+	}
+
+	/// The weights of the asset going into and out of the pool, in parts per million.
+	fn weights(weight_in: Permill) -> (u32, u32) {
+		(weight_in.deconstruct(), weight_in.left_from_one().deconstruct())
+	}
+
+	pub(super) fn get_amount_out<Balance: frame_support::traits::tokens::Balance>(
+		weight_in: Permill,
+		amount_in: &Balance,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+	) -> Result<Balance, CurveError> {
+		let (weight_in, weight_out) = weights(weight_in);
+		let reserve_in = to_u128(reserve_in)?;
+		let new_reserve_in = reserve_in.ensure_add(to_u128(amount_in)?)?;
+
+		// amount_out = reserve_out * (1 - (reserve_in / new_reserve_in)^(weight_in / weight_out))
+		// The share of the reserve kept by the pool is rounded up, so the amount out is rounded
+		// down.
+		let kept = pow(ratio_up(reserve_in, new_reserve_in)?, weight_in, weight_out)?;
+		let result =
+			FixedU128::one().saturating_sub(kept).saturating_mul_int(to_u128(reserve_out)?);
+
+		from_u128(result)
+	}
+
+	pub(super) fn get_amount_in<Balance: frame_support::traits::tokens::Balance>(
+		weight_in: Permill,
+		amount_out: &Balance,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+	) -> Result<Balance, CurveError> {
+		let (weight_in, weight_out) = weights(weight_in);
+		let reserve_out = to_u128(reserve_out)?;
+		let new_reserve_out = reserve_out.ensure_sub(to_u128(amount_out)?)?;
+
+		// amount_in = reserve_in * ((reserve_out / new_reserve_out)^(weight_out / weight_in) - 1)
+		let growth = pow(ratio_up(reserve_out, new_reserve_out)?, weight_out, weight_in)?;
+		let result = growth
+			.saturating_sub(FixedU128::one())
+			.checked_mul_int(to_u128(reserve_in)?)
+			.ok_or(CurveError::Overflow)?
+			// Round up in favour of the pool.
+			.ensure_add(1)?;
+
+		from_u128(result)
+	}
+
+	pub(super) fn spot_price<Balance: frame_support::traits::tokens::Balance>(
+		weight_in: Permill,
+		reserve_in: &Balance,
+		reserve_out: &Balance,
+	) -> Result<FixedU128, CurveError> {
+		let (weight_in, weight_out) = weights(weight_in);
+		// The spot price is `(reserve_out / weight_out) / (reserve_in / weight_in)`.
+		FixedU128::checked_from_rational(to_u128(reserve_out)?, to_u128(reserve_in)?)
+			.ok_or(CurveError::ZeroLiquidity)?
+			.checked_mul(
+				&FixedU128::checked_from_rational(weight_in, weight_out)
+					.ok_or(CurveError::Overflow)?,
+			)
+			.ok_or(CurveError::Overflow)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	type Balance = u128;
+	type HigherPrecisionBalance = sp_core::U256;
+
+	fn amount_out(
+		curve: Curve,
+		amount_in: Balance,
+		reserve_in: Balance,
+		reserve_out: Balance,
+	) -> Balance {
+		PoolCurve::<Balance, HigherPrecisionBalance>::get_amount_out(
+			&curve,
+			&amount_in,
+			&reserve_in,
+			&reserve_out,
+			3,
+		)
+		.unwrap()
+	}
+
+	fn amount_in(
+		curve: Curve,
+		amount_out: Balance,
+		reserve_in: Balance,
+		reserve_out: Balance,
+	) -> Balance {
+		PoolCurve::<Balance, HigherPrecisionBalance>::get_amount_in(
+			&curve,
+			&amount_out,
+			&reserve_in,
+			&reserve_out,
+			3,
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn stable_swap_has_less_slippage_than_constant_product() {
+		let reserve = 1_000_000_000_000;
+		let trade = reserve / 10;
+
+		let constant_product = amount_out(Curve::ConstantProduct, trade, reserve, reserve);
+		let stable = amount_out(Curve::StableSwap { amplification: 100 }, trade, reserve, reserve);
+
+		assert!(stable > constant_product);
+		// Close to one to one, minus the fee.
+		assert!(stable < trade * 997 / 1000);
+		assert!(stable > trade * 995 / 1000);
+	}
+
+	#[test]
+	fn stable_swap_amount_in_covers_amount_out() {
+		let curve = Curve::StableSwap { amplification: 200 };
+		let (reserve_in, reserve_out) = (3_000_000_000, 5_000_000_000);
+
+		let needed = amount_in(curve, 1_000_000_000, reserve_in, reserve_out);
+		assert!(amount_out(curve, needed, reserve_in, reserve_out) >= 1_000_000_000);
+		assert!(amount_out(curve, needed - 1_000, reserve_in, reserve_out) < 1_000_000_000);
+	}
+
+	#[test]
+	fn weighted_pool_prices_by_weight() {
+		let curve = Curve::Weighted { weight1: Permill::from_percent(80) };
+		let quote = |curve: Curve, amount| {
+			PoolCurve::<Balance, HigherPrecisionBalance>::quote(&curve, &amount, &1_000, &1_000)
+				.unwrap()
+		};
+
+		// With equal reserves, the asset with 80% of the weight is worth four times the other.
+		assert_eq!(quote(curve, 100), 400);
+		assert_eq!(quote(PoolCurve::<Balance, HigherPrecisionBalance>::invert(&curve), 400), 100);
+
+		// A 50/50 weighted pool behaves as a constant product pool.
+		let even = Curve::Weighted { weight1: Permill::from_percent(50) };
+		let out = amount_out(even, 1_000, 100_000, 100_000);
+		let expected = amount_out(Curve::ConstantProduct, 1_000, 100_000, 100_000);
+		assert!(out.abs_diff(expected) <= 1);
+	}
+
+	#[test]
+	fn weighted_amount_in_covers_amount_out() {
+		let curve = Curve::Weighted { weight1: Permill::from_percent(20) };
+		let (reserve_in, reserve_out) = (10_000_000_000, 40_000_000_000);
+
+		let needed = amount_in(curve, 1_000_000_000, reserve_in, reserve_out);
+		assert!(amount_out(curve, needed, reserve_in, reserve_out) >= 1_000_000_000);
+	}
+
+	#[test]
+	fn weighted_pow_rounds_truncated_exponents_up() {
+		let close = FixedU128::from_rational(1, 1_000_000_000_000);
+
+		// An exponent of 1/3 doesn't fit in `EXPONENT_PRECISION_BITS`.
+		let root = weighted::pow(FixedU128::from_u32(8), 1, 3).unwrap();
+		assert!(root >= FixedU128::from_u32(2));
+		assert!(root - FixedU128::from_u32(2) < close);
+
+		let root = weighted::pow(FixedU128::from_rational(1, 8), 1, 3).unwrap();
+		assert!(root >= FixedU128::from_rational(1, 2));
+		assert!(root - FixedU128::from_rational(1, 2) < close);
+	}
+
+	#[test]
+	fn spot_prices_follow_reserves() {
+		let spot_price = |curve: Curve, reserve_in: Balance, reserve_out: Balance| {
+			PoolCurve::<Balance, HigherPrecisionBalance>::spot_price(
+				&curve,
+				&reserve_in,
+				&reserve_out,
+			)
+			.unwrap()
+		};
+		let stable = Curve::StableSwap { amplification: 100 };
+
+		assert_eq!(spot_price(Curve::ConstantProduct, 1_000, 3_000), FixedU128::from_u32(3));
+		assert_eq!(
+			spot_price(Curve::Weighted { weight1: Permill::from_percent(80) }, 1_000, 1_000),
+			FixedU128::from_u32(4)
+		);
+		assert_eq!(spot_price(stable, 1_000_000, 1_000_000), FixedU128::one());
+
+		// A scarce asset is worth more, though much less so in a stable pool.
+		let scarce = spot_price(stable, 500_000, 1_500_000);
+		assert!(scarce > FixedU128::one());
+		assert!(scarce < spot_price(Curve::ConstantProduct, 500_000, 1_500_000));
+	}
+
+	#[test]
+	fn curve_parameters_are_validated() {
+		let valid = |curve: Curve| PoolCurve::<Balance, HigherPrecisionBalance>::is_valid(&curve);
+
+		assert!(valid(Curve::ConstantProduct));
+		assert!(valid(Curve::StableSwap { amplification: 100 }));
+		assert!(!valid(Curve::StableSwap { amplification: 0 }));
+		assert!(!valid(Curve::StableSwap { amplification: MAX_AMPLIFICATION + 1 }));
+		assert!(valid(Curve::Weighted { weight1: Permill::from_percent(80) }));
+		assert!(!valid(Curve::Weighted { weight1: Permill::from_percent(1) }));
+		assert!(!valid(Curve::Weighted { weight1: Permill::from_percent(99) }));
+	}
+}
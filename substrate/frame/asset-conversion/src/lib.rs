@@ -24,6 +24,8 @@
 //! This pallet allows you to:
 //!
 //!  - [create a liquidity pool](`Pallet::create_pool()`) for 2 assets
+//!  - [create a liquidity pool with a specific curve](`Pallet::create_pool_with_curve()`), e.g. a
+//!    [StableSwap](`Curve::StableSwap`) or a [weighted](`Curve::Weighted`) pool
 //!  - [provide the liquidity](`Pallet::add_liquidity()`) and receive back an LP token
 //!  - [exchange the LP token back to assets](`Pallet::remove_liquidity()`)
 //!  - [swap a specific amount of assets for another](`Pallet::swap_exact_tokens_for_tokens()`) if
@@ -34,6 +36,25 @@
 //!    a runtime call endpoint
 //!  - [query the size of a liquidity pool](`AssetConversionApi::get_reserves`) via a runtime api
 //!    endpoint.
+//!  - [query a time weighted average price](`AssetConversionApi::quote_price_twap`) via a runtime
+//!    api endpoint
+//!  - [place a limit order](`Pallet::place_limit_order()`) executed once the pools offer the
+//!    desired price, or [cancel it](`Pallet::cancel_limit_order()`), or [reap
+//!    it](`Pallet::reap_limit_order()`) once expired.
+//!
+//! Each pool accumulates the spot prices of its assets over the blocks, in the manner of Uniswap
+//! V2 price oracles, and keeps the latest [`Config::MaxPriceObservations`] of these sums from which
+//! time weighted average prices are derived. The sums are only updated by the calls of this pallet,
+//! not by the swaps of credit through [`SwapCredit`], whose callers don't pay for the update.
+//!
+//! Limit orders rest until the pools along their path offer at least their minimum amount out.
+//! They are checked, and executed when possible, with the weight left at the end of blocks. Each
+//! order holds a [`Config::LimitOrderDeposit`] returned to its owner once it is executed, cancelled
+//! or reaped after [`Config::LimitOrderLifetime`].
+//!
+//! Pools follow the constant product invariant of Uniswap V2 unless created with another
+//! [curve](`PoolCurve`). Swaps along a path may go through pools of different curves, each hop
+//! being priced by the curve of its pool.
 //!
 //! The `quote_price_exact_tokens_for_tokens` and `quote_price_tokens_for_exact_tokens` functions
 //! both take a path parameter of the route to take. If you want to swap from native asset to
@@ -56,6 +77,7 @@
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+mod curve;
 #[cfg(test)]
 mod mock;
 mod swap;
@@ -65,6 +87,7 @@ mod types;
 pub mod weights;
 #[cfg(feature = "runtime-benchmarks")]
 pub use benchmarking::{BenchmarkHelper, NativeOrWithIdFactory};
+pub use curve::*;
 pub use pallet::*;
 pub use swap::*;
 pub use types::*;
@@ -88,10 +111,11 @@ use frame_support::{
 use sp_core::Get;
 use sp_runtime::{
 	traits::{
-		CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Ensure, IntegerSquareRoot, MaybeDisplay,
-		One, TrailingZeroInput, Zero,
+		AccountIdConversion, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Ensure,
+		IntegerSquareRoot, MaybeDisplay, One, TrailingZeroInput, Zero,
 	},
-	DispatchError, Saturating, TokenError, TransactionOutcome,
+	DispatchError, FixedPointNumber, FixedU128, SaturatedConversion, Saturating, TokenError,
+	TransactionOutcome,
 };
 use sp_std::{boxed::Box, collections::btree_set::BTreeSet, vec::Vec};
 
@@ -121,6 +145,7 @@ pub mod pallet {
 			+ One
 			+ Ensure
 			+ Unsigned
+			+ Copy
 			+ From<u32>
 			+ From<Self::Balance>
 			+ TryInto<Self::Balance>;
@@ -156,6 +181,15 @@ pub mod pallet {
 			+ AccountTouch<Self::PoolAssetId, Self::AccountId, Balance = Self::Balance>
 			+ Refund<Self::AccountId, AssetId = Self::PoolAssetId>;
 
+		/// The curves pools can be created with. [`Default`] is the curve of the pools created
+		/// with [`Pallet::create_pool`].
+		///
+		/// Examples: [`crate::curve::Curve`].
+		type Curve: PoolCurve<Self::Balance, Self::HigherPrecisionBalance>
+			+ Parameter
+			+ MaxEncodedLen
+			+ Default;
+
 		/// A % the liquidity providers will take of every swap. Represents 10ths of a percent.
 		#[pallet::constant]
 		type LPFee: Get<u32>;
@@ -183,6 +217,29 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxSwapPathLength: Get<u32>;
 
+		/// The number of price observations kept per pool, bounding the windows time weighted
+		/// average prices can be computed over.
+		#[pallet::constant]
+		type MaxPriceObservations: Get<u32>;
+
+		/// The max number of resting limit orders. Zero disables limit orders.
+		#[pallet::constant]
+		type MaxLimitOrders: Get<u32>;
+
+		/// The max number of resting limit orders of a single account.
+		#[pallet::constant]
+		type MaxLimitOrdersPerAccount: Get<u32>;
+
+		/// The deposit of [`Config::PoolSetupFeeAsset`] held in the account of each limit order
+		/// while it rests. Should be zero or more than the minimum balance of the asset.
+		#[pallet::constant]
+		type LimitOrderDeposit: Get<Self::Balance>;
+
+		/// The number of blocks after which a resting limit order expires and may be reaped by
+		/// anyone, returning its funds and deposit to its owner.
+		#[pallet::constant]
+		type LimitOrderLifetime: Get<BlockNumberFor<Self>>;
+
 		/// The pallet's id, used for deriving its sovereign account ID.
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
@@ -201,6 +258,52 @@ pub mod pallet {
 	pub type Pools<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::PoolId, PoolInfo<T::PoolAssetId>, OptionQuery>;
 
+	/// The curve of each pool created with [`Pallet::create_pool_with_curve`]. Pools without an
+	/// entry follow the default [`Config::Curve`].
+	#[pallet::storage]
+	pub type PoolCurves<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::PoolId, CurveInfo<T::AssetKind, T::Curve>, OptionQuery>;
+
+	/// The price accumulator of each pool, updated before the first change of its reserves in a
+	/// block by the calls of this pallet. Swaps of credit, e.g. for transaction fees, leave it be.
+	#[pallet::storage]
+	pub type PriceAccumulators<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::PoolId,
+		PriceAccumulator<T::AssetKind, BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// The latest observations of the price accumulator of each pool, oldest first.
+	#[pallet::storage]
+	pub type PriceObservations<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::PoolId,
+		BoundedVec<PriceObservation<BlockNumberFor<T>>, T::MaxPriceObservations>,
+		ValueQuery,
+	>;
+
+	/// The resting limit orders.
+	#[pallet::storage]
+	pub type LimitOrders<T: Config> =
+		CountedStorageMap<_, Twox64Concat, u32, LimitOrder<T>, OptionQuery>;
+
+	/// The number of resting limit orders of each account.
+	#[pallet::storage]
+	pub type AccountLimitOrders<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// The id of the next limit order.
+	#[pallet::storage]
+	pub type NextLimitOrderId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The last limit order checked by [`Pallet::execute_limit_orders`], after which the next
+	/// checks resume.
+	#[pallet::storage]
+	pub type LimitOrderCursor<T: Config> = StorageValue<_, u32, OptionQuery>;
+
 	/// Stores the `PoolAssetId` that is going to be used for the next lp token.
 	/// This gets incremented whenever a new lp pool is created.
 	#[pallet::storage]
@@ -293,6 +396,42 @@ pub mod pallet {
 			/// The account initiating the touch.
 			who: T::AccountId,
 		},
+		/// A limit order has been placed.
+		LimitOrderPlaced {
+			/// The id of the order.
+			order_id: u32,
+			/// The account which placed the order.
+			who: T::AccountId,
+			/// The amount of the first asset of the path to sell.
+			amount_in: T::Balance,
+			/// The minimum amount of the last asset of the path to buy.
+			amount_out_min: T::Balance,
+		},
+		/// A limit order has been cancelled and its amount returned to its owner.
+		LimitOrderCancelled {
+			/// The id of the order.
+			order_id: u32,
+			/// The owner of the order.
+			who: T::AccountId,
+		},
+		/// An expired limit order has been reaped and its amount returned to its owner.
+		LimitOrderReaped {
+			/// The id of the order.
+			order_id: u32,
+			/// The owner of the order.
+			who: T::AccountId,
+		},
+		/// A limit order has been executed.
+		LimitOrderExecuted {
+			/// The id of the order.
+			order_id: u32,
+			/// The owner of the order, which received the amount bought.
+			who: T::AccountId,
+			/// The amount of the first asset of the path which was sold.
+			amount_in: T::Balance,
+			/// The amount of the last asset of the path which was bought.
+			amount_out: T::Balance,
+		},
 	}
 
 	#[pallet::error]
@@ -346,6 +485,31 @@ pub mod pallet {
 		IncorrectPoolAssetId,
 		/// The destination account cannot exist with the swapped funds.
 		BelowMinimum,
+		/// The parameters of the curve are not valid.
+		InvalidCurve,
+		/// The computation of the pool's curve did not converge.
+		CurveDidNotConverge,
+		/// The maximum number of resting limit orders has been reached.
+		TooManyLimitOrders,
+		/// The maximum number of resting limit orders of the account has been reached.
+		TooManyAccountLimitOrders,
+		/// The limit order doesn't exist.
+		LimitOrderNotFound,
+		/// The limit order belongs to another account.
+		NotLimitOrderOwner,
+		/// The limit order hasn't expired yet.
+		LimitOrderNotExpired,
+	}
+
+	impl<T> From<CurveError> for Error<T> {
+		fn from(error: CurveError) -> Self {
+			match error {
+				CurveError::ZeroLiquidity => Error::<T>::ZeroLiquidity,
+				CurveError::AmountOutTooHigh => Error::<T>::AmountOutTooHigh,
+				CurveError::Overflow => Error::<T>::Overflow,
+				CurveError::NoConvergence => Error::<T>::CurveDidNotConverge,
+			}
+		}
 	}
 
 	#[pallet::hooks]
@@ -356,6 +520,10 @@ pub mod pallet {
 				"the `MaxSwapPathLength` should be greater than 1",
 			);
 		}
+
+		fn on_idle(_: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::execute_limit_orders(remaining_weight)
+		}
 	}
 
 	/// Pallet's callable functions.
@@ -373,51 +541,7 @@ pub mod pallet {
 			asset2: Box<T::AssetKind>,
 		) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			ensure!(asset1 != asset2, Error::<T>::InvalidAssetPair);
-
-			// prepare pool_id
-			let pool_id = T::PoolLocator::pool_id(&asset1, &asset2)
-				.map_err(|_| Error::<T>::InvalidAssetPair)?;
-			ensure!(!Pools::<T>::contains_key(&pool_id), Error::<T>::PoolExists);
-
-			let pool_account =
-				T::PoolLocator::address(&pool_id).map_err(|_| Error::<T>::InvalidAssetPair)?;
-
-			// pay the setup fee
-			let fee =
-				Self::withdraw(T::PoolSetupFeeAsset::get(), &sender, T::PoolSetupFee::get(), true)?;
-			T::PoolSetupFeeTarget::on_unbalanced(fee);
-
-			if T::Assets::should_touch(*asset1.clone(), &pool_account) {
-				T::Assets::touch(*asset1, &pool_account, &sender)?
-			};
-
-			if T::Assets::should_touch(*asset2.clone(), &pool_account) {
-				T::Assets::touch(*asset2, &pool_account, &sender)?
-			};
-
-			let lp_token = NextPoolAssetId::<T>::get()
-				.or(T::PoolAssetId::initial_value())
-				.ok_or(Error::<T>::IncorrectPoolAssetId)?;
-			let next_lp_token_id = lp_token.increment().ok_or(Error::<T>::IncorrectPoolAssetId)?;
-			NextPoolAssetId::<T>::set(Some(next_lp_token_id));
-
-			T::PoolAssets::create(lp_token.clone(), pool_account.clone(), false, 1u32.into())?;
-			if T::PoolAssets::should_touch(lp_token.clone(), &pool_account) {
-				T::PoolAssets::touch(lp_token.clone(), &pool_account, &sender)?
-			};
-
-			let pool_info = PoolInfo { lp_token: lp_token.clone() };
-			Pools::<T>::insert(pool_id.clone(), pool_info);
-
-			Self::deposit_event(Event::PoolCreated {
-				creator: sender,
-				pool_id,
-				pool_account,
-				lp_token,
-			});
-
-			Ok(())
+			Self::do_create_pool(sender, asset1, asset2, None)
 		}
 
 		/// Provide liquidity into the pool of `asset1` and `asset2`.
@@ -502,6 +626,7 @@ pub mod pallet {
 				Error::<T>::AmountTwoLessThanMinimal
 			);
 
+			Self::update_price_accumulator(&asset1, &asset2)?;
 			T::Assets::transfer(*asset1, &sender, &pool_account, amount1, Preserve)?;
 			T::Assets::transfer(*asset2, &sender, &pool_account, amount2, Preserve)?;
 
@@ -605,6 +730,7 @@ pub mod pallet {
 				Polite,
 			)?;
 
+			Self::update_price_accumulator(&asset1, &asset2)?;
 			T::Assets::transfer(*asset1, &pool_account, &withdraw_to, amount1, Expendable)?;
 			T::Assets::transfer(*asset2, &pool_account, &withdraw_to, amount2, Expendable)?;
 
@@ -720,9 +846,197 @@ pub mod pallet {
 			Self::deposit_event(Event::Touched { pool_id, who });
 			Ok(Some(T::WeightInfo::touch(refunds_number)).into())
 		}
+
+		/// Creates an empty liquidity pool following the given `curve`, and an associated new
+		/// `lp_token` asset (the id of which is returned in the `Event::PoolCreated` event).
+		///
+		/// Curves which are not symmetric, such as weighted ones, are oriented towards `asset1`.
+		///
+		/// Once a pool is created, someone may [`Pallet::add_liquidity`] to it.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::create_pool_with_curve(2))]
+		pub fn create_pool_with_curve(
+			origin: OriginFor<T>,
+			asset1: Box<T::AssetKind>,
+			asset2: Box<T::AssetKind>,
+			curve: T::Curve,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(curve.is_valid(), Error::<T>::InvalidCurve);
+			Self::do_create_pool(sender, asset1, asset2, Some(curve))
+		}
+
+		/// Places a limit order selling exactly `amount_in` of asset `path[0]` for at least
+		/// `amount_out_min` of the last asset of the `path`, to execute once the pools along the
+		/// `path` allow it.
+		///
+		/// The `amount_in` is moved to an account of the order along with the
+		/// [`Config::LimitOrderDeposit`] until it is executed, cancelled or reaped once it
+		/// expires after [`Config::LimitOrderLifetime`]. The amount bought is sent to the caller.
+		///
+		/// Emits `LimitOrderPlaced` event when successful.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::place_limit_order())]
+		pub fn place_limit_order(
+			origin: OriginFor<T>,
+			path: Vec<Box<T::AssetKind>>,
+			amount_in: T::Balance,
+			amount_out_min: T::Balance,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(
+				amount_in > Zero::zero() && amount_out_min > Zero::zero(),
+				Error::<T>::ZeroAmount
+			);
+			ensure!(
+				LimitOrders::<T>::count() < T::MaxLimitOrders::get(),
+				Error::<T>::TooManyLimitOrders
+			);
+			let account_orders = AccountLimitOrders::<T>::get(&sender);
+			ensure!(
+				account_orders < T::MaxLimitOrdersPerAccount::get(),
+				Error::<T>::TooManyAccountLimitOrders
+			);
+
+			let path: Vec<T::AssetKind> = path.into_iter().map(|a| *a).collect();
+			Self::validate_swap_path(&path)?;
+			let path: BoundedVec<_, T::MaxSwapPathLength> =
+				path.try_into().map_err(|_| Error::<T>::InvalidPath)?;
+
+			let order_id = NextLimitOrderId::<T>::get();
+			NextLimitOrderId::<T>::set(order_id.checked_add(1).ok_or(Error::<T>::Overflow)?);
+
+			let asset_in = path[0].clone();
+			let order_account = Self::limit_order_account(order_id);
+			if T::Assets::should_touch(asset_in.clone(), &order_account) {
+				T::Assets::touch(asset_in.clone(), &order_account, &sender)?;
+			}
+			T::Assets::transfer(asset_in, &sender, &order_account, amount_in, Preserve)?;
+			let deposit = T::LimitOrderDeposit::get();
+			if !deposit.is_zero() {
+				T::Assets::transfer(
+					T::PoolSetupFeeAsset::get(),
+					&sender,
+					&order_account,
+					deposit,
+					Preserve,
+				)?;
+			}
+
+			let expiry = frame_system::Pallet::<T>::block_number()
+				.saturating_add(T::LimitOrderLifetime::get());
+			LimitOrders::<T>::insert(
+				order_id,
+				LimitOrder { owner: sender.clone(), path, amount_in, amount_out_min, expiry },
+			);
+			AccountLimitOrders::<T>::insert(&sender, account_orders.saturating_add(1));
+
+			Self::deposit_event(Event::LimitOrderPlaced {
+				order_id,
+				who: sender,
+				amount_in,
+				amount_out_min,
+			});
+			Ok(())
+		}
+
+		/// Cancels a resting limit order of the caller, returning its `amount_in` and deposit
+		/// along with anything else sent to the account of the order.
+		///
+		/// Emits `LimitOrderCancelled` event when successful.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::cancel_limit_order())]
+		pub fn cancel_limit_order(origin: OriginFor<T>, order_id: u32) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let order = LimitOrders::<T>::get(order_id).ok_or(Error::<T>::LimitOrderNotFound)?;
+			ensure!(order.owner == sender, Error::<T>::NotLimitOrderOwner);
+
+			let asset_in = order.path[0].clone();
+			let order_account = Self::limit_order_account(order_id);
+			Self::close_limit_order_account(asset_in, &order_account, &sender)?;
+			Self::remove_limit_order(order_id, &sender);
+
+			Self::deposit_event(Event::LimitOrderCancelled { order_id, who: sender });
+			Ok(())
+		}
+
+		/// Reaps an expired limit order of any account, returning its `amount_in` and deposit
+		/// along with anything else sent to the account of the order to its owner.
+		///
+		/// Emits `LimitOrderReaped` event when successful.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::cancel_limit_order())]
+		pub fn reap_limit_order(origin: OriginFor<T>, order_id: u32) -> DispatchResult {
+			ensure_signed(origin)?;
+			let order = LimitOrders::<T>::get(order_id).ok_or(Error::<T>::LimitOrderNotFound)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() >= order.expiry,
+				Error::<T>::LimitOrderNotExpired
+			);
+			Self::do_reap_limit_order(order_id, order)
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// Creates an empty liquidity pool of `asset1` and `asset2`, following `curve` if given and
+		/// the default [`Config::Curve`] otherwise.
+		pub(crate) fn do_create_pool(
+			sender: T::AccountId,
+			asset1: Box<T::AssetKind>,
+			asset2: Box<T::AssetKind>,
+			curve: Option<T::Curve>,
+		) -> DispatchResult {
+			ensure!(asset1 != asset2, Error::<T>::InvalidAssetPair);
+
+			// prepare pool_id
+			let pool_id = T::PoolLocator::pool_id(&asset1, &asset2)
+				.map_err(|_| Error::<T>::InvalidAssetPair)?;
+			ensure!(!Pools::<T>::contains_key(&pool_id), Error::<T>::PoolExists);
+			let curve_info = curve.map(|curve| CurveInfo { asset1: (*asset1).clone(), curve });
+
+			let pool_account =
+				T::PoolLocator::address(&pool_id).map_err(|_| Error::<T>::InvalidAssetPair)?;
+
+			// pay the setup fee
+			let fee =
+				Self::withdraw(T::PoolSetupFeeAsset::get(), &sender, T::PoolSetupFee::get(), true)?;
+			T::PoolSetupFeeTarget::on_unbalanced(fee);
+
+			if T::Assets::should_touch(*asset1.clone(), &pool_account) {
+				T::Assets::touch(*asset1, &pool_account, &sender)?
+			};
+
+			if T::Assets::should_touch(*asset2.clone(), &pool_account) {
+				T::Assets::touch(*asset2, &pool_account, &sender)?
+			};
+
+			let lp_token = NextPoolAssetId::<T>::get()
+				.or(T::PoolAssetId::initial_value())
+				.ok_or(Error::<T>::IncorrectPoolAssetId)?;
+			let next_lp_token_id = lp_token.increment().ok_or(Error::<T>::IncorrectPoolAssetId)?;
+			NextPoolAssetId::<T>::set(Some(next_lp_token_id));
+
+			T::PoolAssets::create(lp_token.clone(), pool_account.clone(), false, 1u32.into())?;
+			if T::PoolAssets::should_touch(lp_token.clone(), &pool_account) {
+				T::PoolAssets::touch(lp_token.clone(), &pool_account, &sender)?
+			};
+
+			let pool_info = PoolInfo { lp_token: lp_token.clone() };
+			Pools::<T>::insert(pool_id.clone(), pool_info);
+			if let Some(curve_info) = curve_info {
+				PoolCurves::<T>::insert(pool_id.clone(), curve_info);
+			}
+
+			Self::deposit_event(Event::PoolCreated {
+				creator: sender,
+				pool_id,
+				pool_account,
+				lp_token,
+			});
+
+			Ok(())
+		}
+
 		/// Swap exactly `amount_in` of asset `path[0]` for asset `path[1]`.
 		/// If an `amount_out_min` is specified, it will return an error if it is unable to acquire
 		/// the amount desired.
@@ -929,6 +1243,12 @@ pub mod pallet {
 			send_to: &T::AccountId,
 			keep_alive: bool,
 		) -> Result<(), DispatchError> {
+			// Accumulate the prices of all the pools before any of their reserves change.
+			for pair in path.windows(2) {
+				if let [(asset1, _), (asset2, _)] = pair {
+					Self::update_price_accumulator(asset1, asset2)?;
+				}
+			}
 			let (asset_in, amount_in) = path.first().ok_or(Error::<T>::InvalidPath)?;
 			let credit_in = Self::withdraw(asset_in.clone(), sender, *amount_in, keep_alive)?;
 
@@ -1045,6 +1365,118 @@ pub mod pallet {
 			Ok((balance1, balance2))
 		}
 
+		/// Accumulates the spot prices of the pool of `asset1` and `asset2` over the blocks since
+		/// its last update, and records an observation of the sums.
+		///
+		/// Must be called before the reserves of the pool change; only the first call of a block
+		/// has an effect.
+		pub(crate) fn update_price_accumulator(
+			asset1: &T::AssetKind,
+			asset2: &T::AssetKind,
+		) -> Result<(), Error<T>> {
+			let pool_id = T::PoolLocator::pool_id(asset1, asset2)
+				.map_err(|_| Error::<T>::InvalidAssetPair)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let mut accumulator = match PriceAccumulators::<T>::get(&pool_id) {
+				Some(accumulator) if accumulator.last_update == now => return Ok(()),
+				Some(accumulator) => accumulator,
+				None => PriceAccumulator {
+					asset1: asset1.clone(),
+					price1_cumulative: 0,
+					price2_cumulative: 0,
+					last_update: now,
+				},
+			};
+
+			let elapsed: u128 = now.saturating_sub(accumulator.last_update).saturated_into();
+			let other = if accumulator.asset1 == *asset1 { asset2 } else { asset1 };
+			if let Some((price1, price2)) = Self::spot_prices(&accumulator.asset1, other) {
+				accumulator.price1_cumulative = accumulator
+					.price1_cumulative
+					.wrapping_add(price1.into_inner().wrapping_mul(elapsed));
+				accumulator.price2_cumulative = accumulator
+					.price2_cumulative
+					.wrapping_add(price2.into_inner().wrapping_mul(elapsed));
+			}
+			accumulator.last_update = now;
+
+			PriceObservations::<T>::mutate(&pool_id, |observations| {
+				if observations.len() >= T::MaxPriceObservations::get() as usize &&
+					!observations.is_empty()
+				{
+					observations.remove(0);
+				}
+				let _ = observations.try_push(PriceObservation {
+					block: now,
+					price1_cumulative: accumulator.price1_cumulative,
+					price2_cumulative: accumulator.price2_cumulative,
+				});
+			});
+			PriceAccumulators::<T>::insert(pool_id, accumulator);
+
+			Ok(())
+		}
+
+		/// The spot prices of `asset1` in units of `asset2` and of `asset2` in units of `asset1`,
+		/// given by the curve and the reserves of their pool.
+		fn spot_prices(
+			asset1: &T::AssetKind,
+			asset2: &T::AssetKind,
+		) -> Option<(FixedU128, FixedU128)> {
+			let pool_account = T::PoolLocator::pool_address(asset1, asset2).ok()?;
+			let curve = Self::pool_curve(asset1, asset2).ok()?;
+			let reserve1 = Self::get_balance(&pool_account, asset1.clone());
+			let reserve2 = Self::get_balance(&pool_account, asset2.clone());
+
+			Some((
+				curve.spot_price(&reserve1, &reserve2).ok()?,
+				curve.invert().spot_price(&reserve2, &reserve1).ok()?,
+			))
+		}
+
+		/// The amount of `asset2` equivalent to `amount` of `asset1` at the time weighted average
+		/// price of their pool over at least the last `window` blocks.
+		///
+		/// Returns `None` if the pool has no observation old enough.
+		pub fn quote_price_twap(
+			asset1: T::AssetKind,
+			asset2: T::AssetKind,
+			amount: T::Balance,
+			window: BlockNumberFor<T>,
+		) -> Option<T::Balance> {
+			let pool_id = T::PoolLocator::pool_id(&asset1, &asset2).ok()?;
+			let accumulator = PriceAccumulators::<T>::get(&pool_id)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let start = now.checked_sub(&window).filter(|_| !window.is_zero())?;
+			let observation = PriceObservations::<T>::get(&pool_id)
+				.into_iter()
+				.rev()
+				.find(|observation| observation.block <= start)?;
+
+			// Extend the sums up to the current block with the current prices.
+			let (mut price1_cumulative, mut price2_cumulative) =
+				(accumulator.price1_cumulative, accumulator.price2_cumulative);
+			let other = if accumulator.asset1 == asset1 { &asset2 } else { &asset1 };
+			if let Some((price1, price2)) = Self::spot_prices(&accumulator.asset1, other) {
+				let elapsed: u128 = now.saturating_sub(accumulator.last_update).saturated_into();
+				price1_cumulative =
+					price1_cumulative.wrapping_add(price1.into_inner().wrapping_mul(elapsed));
+				price2_cumulative =
+					price2_cumulative.wrapping_add(price2.into_inner().wrapping_mul(elapsed));
+			}
+
+			let (cumulative, observed) = if accumulator.asset1 == asset1 {
+				(price1_cumulative, observation.price1_cumulative)
+			} else {
+				(price2_cumulative, observation.price2_cumulative)
+			};
+			let elapsed: u128 = now.saturating_sub(observation.block).saturated_into();
+			let price = FixedU128::from_inner(cumulative.wrapping_sub(observed) / elapsed);
+
+			let amount: u128 = amount.try_into().ok()?;
+			price.checked_mul_int(amount)?.try_into().ok()
+		}
+
 		/// Leading to an amount at the end of a `path`, get the required amounts in.
 		pub(crate) fn balance_path_from_amount_out(
 			amount_out: T::Balance,
@@ -1063,8 +1495,11 @@ pub mod pallet {
 					},
 				};
 				let (reserve_in, reserve_out) = Self::get_reserves(asset1.clone(), asset2.clone())?;
+				let curve = Self::pool_curve(asset1, &asset2)?;
 				balance_path.push((asset2, amount_in));
-				amount_in = Self::get_amount_in(&amount_in, &reserve_in, &reserve_out)?;
+				amount_in = curve
+					.get_amount_in(&amount_in, &reserve_in, &reserve_out, T::LPFee::get())
+					.map_err(Error::<T>::from)?;
 			}
 			balance_path.reverse();
 
@@ -1089,8 +1524,11 @@ pub mod pallet {
 					},
 				};
 				let (reserve_in, reserve_out) = Self::get_reserves(asset1.clone(), asset2.clone())?;
+				let curve = Self::pool_curve(&asset1, asset2)?;
 				balance_path.push((asset1, amount_out));
-				amount_out = Self::get_amount_out(&amount_out, &reserve_in, &reserve_out)?;
+				amount_out = curve
+					.get_amount_out(&amount_out, &reserve_in, &reserve_out, T::LPFee::get())
+					.map_err(Error::<T>::from)?;
 			}
 			Ok(balance_path)
 		}
@@ -1103,14 +1541,15 @@ pub mod pallet {
 			include_fee: bool,
 		) -> Option<T::Balance> {
 			let pool_account = T::PoolLocator::pool_address(&asset1, &asset2).ok()?;
+			let curve = Self::pool_curve(&asset1, &asset2).ok()?;
 
 			let balance1 = Self::get_balance(&pool_account, asset1);
 			let balance2 = Self::get_balance(&pool_account, asset2);
 			if !balance1.is_zero() {
 				if include_fee {
-					Self::get_amount_out(&amount, &balance1, &balance2).ok()
+					curve.get_amount_out(&amount, &balance1, &balance2, T::LPFee::get()).ok()
 				} else {
-					Self::quote(&amount, &balance1, &balance2).ok()
+					curve.quote(&amount, &balance1, &balance2).ok()
 				}
 			} else {
 				None
@@ -1125,20 +1564,36 @@ pub mod pallet {
 			include_fee: bool,
 		) -> Option<T::Balance> {
 			let pool_account = T::PoolLocator::pool_address(&asset1, &asset2).ok()?;
+			let curve = Self::pool_curve(&asset1, &asset2).ok()?;
 
 			let balance1 = Self::get_balance(&pool_account, asset1);
 			let balance2 = Self::get_balance(&pool_account, asset2);
 			if !balance1.is_zero() {
 				if include_fee {
-					Self::get_amount_in(&amount, &balance1, &balance2).ok()
+					curve.get_amount_in(&amount, &balance1, &balance2, T::LPFee::get()).ok()
 				} else {
-					Self::quote(&amount, &balance2, &balance1).ok()
+					curve.invert().quote(&amount, &balance2, &balance1).ok()
 				}
 			} else {
 				None
 			}
 		}
 
+		/// The curve of the pool of `asset_in` and `asset_out`, oriented from `asset_in` to
+		/// `asset_out`.
+		fn pool_curve(
+			asset_in: &T::AssetKind,
+			asset_out: &T::AssetKind,
+		) -> Result<T::Curve, Error<T>> {
+			let pool_id = T::PoolLocator::pool_id(asset_in, asset_out)
+				.map_err(|_| Error::<T>::InvalidAssetPair)?;
+			Ok(match PoolCurves::<T>::get(&pool_id) {
+				Some(info) if &info.asset1 == asset_in => info.curve,
+				Some(info) => info.curve.invert(),
+				None => T::Curve::default(),
+			})
+		}
+
 		/// Calculates the optimal amount from the reserves.
 		pub fn quote(
 			amount: &T::Balance,
@@ -1167,20 +1622,10 @@ pub mod pallet {
 		}
 
 		fn mul_div(a: &T::Balance, b: &T::Balance, c: &T::Balance) -> Result<T::Balance, Error<T>> {
-			let a = T::HigherPrecisionBalance::from(*a);
-			let b = T::HigherPrecisionBalance::from(*b);
-			let c = T::HigherPrecisionBalance::from(*c);
-
-			let result = a
-				.checked_mul(&b)
-				.ok_or(Error::<T>::Overflow)?
-				.checked_div(&c)
-				.ok_or(Error::<T>::Overflow)?;
-
-			result.try_into().map_err(|_| Error::<T>::Overflow)
+			curve::mul_div::<_, T::HigherPrecisionBalance>(a, b, c).map_err(Into::into)
 		}
 
-		/// Calculates amount out.
+		/// Calculates amount out of a constant product pool.
 		///
 		/// Given an input amount of an asset and pair reserves, returns the maximum output amount
 		/// of the other asset.
@@ -1189,33 +1634,17 @@ pub mod pallet {
 			reserve_in: &T::Balance,
 			reserve_out: &T::Balance,
 		) -> Result<T::Balance, Error<T>> {
-			let amount_in = T::HigherPrecisionBalance::from(*amount_in);
-			let reserve_in = T::HigherPrecisionBalance::from(*reserve_in);
-			let reserve_out = T::HigherPrecisionBalance::from(*reserve_out);
-
-			if reserve_in.is_zero() || reserve_out.is_zero() {
-				return Err(Error::<T>::ZeroLiquidity)
-			}
-
-			let amount_in_with_fee = amount_in
-				.checked_mul(&(T::HigherPrecisionBalance::from(1000u32) - (T::LPFee::get().into())))
-				.ok_or(Error::<T>::Overflow)?;
-
-			let numerator =
-				amount_in_with_fee.checked_mul(&reserve_out).ok_or(Error::<T>::Overflow)?;
-
-			let denominator = reserve_in
-				.checked_mul(&1000u32.into())
-				.ok_or(Error::<T>::Overflow)?
-				.checked_add(&amount_in_with_fee)
-				.ok_or(Error::<T>::Overflow)?;
-
-			let result = numerator.checked_div(&denominator).ok_or(Error::<T>::Overflow)?;
-
-			result.try_into().map_err(|_| Error::<T>::Overflow)
+			PoolCurve::<T::Balance, T::HigherPrecisionBalance>::get_amount_out(
+				&Curve::ConstantProduct,
+				amount_in,
+				reserve_in,
+				reserve_out,
+				T::LPFee::get(),
+			)
+			.map_err(Into::into)
 		}
 
-		/// Calculates amount in.
+		/// Calculates amount in of a constant product pool.
 		///
 		/// Given an output amount of an asset and pair reserves, returns a required input amount
 		/// of the other asset.
@@ -1224,37 +1653,178 @@ pub mod pallet {
 			reserve_in: &T::Balance,
 			reserve_out: &T::Balance,
 		) -> Result<T::Balance, Error<T>> {
-			let amount_out = T::HigherPrecisionBalance::from(*amount_out);
-			let reserve_in = T::HigherPrecisionBalance::from(*reserve_in);
-			let reserve_out = T::HigherPrecisionBalance::from(*reserve_out);
+			PoolCurve::<T::Balance, T::HigherPrecisionBalance>::get_amount_in(
+				&Curve::ConstantProduct,
+				amount_out,
+				reserve_in,
+				reserve_out,
+				T::LPFee::get(),
+			)
+			.map_err(Into::into)
+		}
+
+		/// The account holding the `amount_in` and the deposit of a limit order.
+		pub fn limit_order_account(order_id: u32) -> T::AccountId {
+			T::PalletId::get().into_sub_account_truncating(order_id)
+		}
 
-			if reserve_in.is_zero() || reserve_out.is_zero() {
-				Err(Error::<T>::ZeroLiquidity)?
+		/// Empties the account of a limit order, sending what is left of its asset in and of its
+		/// deposit to the `owner` of the order, and returns the deposit which may have been taken
+		/// from the owner to create the account.
+		///
+		/// Anyone may send funds to the account of an order, which would otherwise keep its
+		/// deposit from being refunded.
+		fn close_limit_order_account(
+			asset_in: T::AssetKind,
+			order_account: &T::AccountId,
+			owner: &T::AccountId,
+		) -> DispatchResult {
+			let deposit_asset = T::PoolSetupFeeAsset::get();
+			// The deposit may be what keeps the account alive, so it is swept last.
+			Self::sweep_limit_order_account(asset_in.clone(), order_account, owner)?;
+			if deposit_asset != asset_in {
+				Self::sweep_limit_order_account(deposit_asset, order_account, owner)?;
 			}
+			Ok(())
+		}
 
-			if amount_out >= reserve_out {
-				Err(Error::<T>::AmountOutTooHigh)?
+		/// Sends the balance of `asset` of the account of a limit order to its `owner`, and
+		/// returns the deposit which may have been taken from the owner to create the account.
+		fn sweep_limit_order_account(
+			asset: T::AssetKind,
+			order_account: &T::AccountId,
+			owner: &T::AccountId,
+		) -> DispatchResult {
+			let remainder = T::Assets::balance(asset.clone(), order_account);
+			if !remainder.is_zero() &&
+				with_storage_layer(|| {
+					T::Assets::transfer(asset.clone(), order_account, owner, remainder, Expendable)
+				})
+				.is_err()
+			{
+				// The owner can't receive the remainder, e.g. as it is below the minimum balance.
+				T::Assets::burn_from(
+					asset.clone(),
+					order_account,
+					remainder,
+					Expendable,
+					Exact,
+					Polite,
+				)?;
 			}
+			if T::Assets::deposit_held(asset.clone(), order_account.clone()).is_some() {
+				T::Assets::refund(asset, order_account.clone())?;
+			}
+			Ok(())
+		}
 
-			let numerator = reserve_in
-				.checked_mul(&amount_out)
-				.ok_or(Error::<T>::Overflow)?
-				.checked_mul(&1000u32.into())
-				.ok_or(Error::<T>::Overflow)?;
+		/// Reaps the limit order `order_id`, returning its funds to its owner.
+		fn do_reap_limit_order(order_id: u32, order: LimitOrder<T>) -> DispatchResult {
+			let order_account = Self::limit_order_account(order_id);
+			Self::close_limit_order_account(order.path[0].clone(), &order_account, &order.owner)?;
+			Self::remove_limit_order(order_id, &order.owner);
 
-			let denominator = reserve_out
-				.checked_sub(&amount_out)
-				.ok_or(Error::<T>::Overflow)?
-				.checked_mul(&(T::HigherPrecisionBalance::from(1000u32) - T::LPFee::get().into()))
-				.ok_or(Error::<T>::Overflow)?;
+			Self::deposit_event(Event::LimitOrderReaped { order_id, who: order.owner });
+			Ok(())
+		}
 
-			let result = numerator
-				.checked_div(&denominator)
-				.ok_or(Error::<T>::Overflow)?
-				.checked_add(&One::one())
-				.ok_or(Error::<T>::Overflow)?;
+		/// Removes the limit order `order_id` of `owner`.
+		fn remove_limit_order(order_id: u32, owner: &T::AccountId) {
+			LimitOrders::<T>::remove(order_id);
+			AccountLimitOrders::<T>::mutate_exists(owner, |count| {
+				*count = count.map(|c| c.saturating_sub(1)).filter(|c| *c > 0);
+			});
+		}
 
-			result.try_into().map_err(|_| Error::<T>::Overflow)
+		/// Executes the resting limit orders for which the pools along their path offer at least
+		/// their minimum amount out, and reaps the expired ones, checking as many orders as the
+		/// `limit` weight allows from where the previous checks stopped.
+		///
+		/// Returns the weight consumed.
+		pub fn execute_limit_orders(limit: Weight) -> Weight {
+			let db_weight = T::DbWeight::get();
+			let mut consumed = db_weight.reads_writes(2, 1);
+			// An order is accounted for as executed, and its owner refunded a deposit.
+			let per_order =
+				T::WeightInfo::swap_exact_tokens_for_tokens(T::MaxSwapPathLength::get())
+					.saturating_add(T::WeightInfo::cancel_limit_order());
+			if consumed.saturating_add(per_order).any_gt(limit) {
+				return Weight::zero()
+			}
+
+			let mut cursor = LimitOrderCursor::<T>::get();
+			let mut orders = match cursor {
+				Some(order_id) =>
+					LimitOrders::<T>::iter_from(LimitOrders::<T>::hashed_key_for(order_id)),
+				None => LimitOrders::<T>::iter(),
+			};
+			let mut wrapped = cursor.is_none();
+			let now = frame_system::Pallet::<T>::block_number();
+			for _ in 0..LimitOrders::<T>::count() {
+				if consumed.saturating_add(per_order).any_gt(limit) {
+					break
+				}
+				let next = match orders.next() {
+					None if !wrapped => {
+						// Start over from the first order.
+						wrapped = true;
+						orders = LimitOrders::<T>::iter();
+						orders.next()
+					},
+					next => next,
+				};
+				let Some((order_id, order)) = next else {
+					cursor = None;
+					break
+				};
+				consumed.saturating_accrue(per_order);
+				cursor = Some(order_id);
+				if now >= order.expiry {
+					let _ = with_storage_layer(|| Self::do_reap_limit_order(order_id, order));
+				} else {
+					Self::try_execute_limit_order(order_id, order);
+				}
+			}
+			LimitOrderCursor::<T>::set(cursor);
+
+			consumed
+		}
+
+		/// Executes the limit order `order_id` if the pools along its path offer at least its
+		/// minimum amount out.
+		fn try_execute_limit_order(order_id: u32, order: LimitOrder<T>) {
+			let path = order.path.into_inner();
+			let amount_out = Self::balance_path_from_amount_in(order.amount_in, path.clone())
+				.ok()
+				.and_then(|balance_path| balance_path.last().map(|(_, amount)| *amount));
+			if amount_out.map_or(true, |amount_out| amount_out < order.amount_out_min) {
+				return
+			}
+
+			let order_account = Self::limit_order_account(order_id);
+			let asset_in = path[0].clone();
+			let executed = with_storage_layer(|| -> Result<T::Balance, DispatchError> {
+				let amount_out = Self::do_swap_exact_tokens_for_tokens(
+					order_account.clone(),
+					path,
+					order.amount_in,
+					Some(order.amount_out_min),
+					order.owner.clone(),
+					false,
+				)?;
+				Self::close_limit_order_account(asset_in, &order_account, &order.owner)?;
+				Self::remove_limit_order(order_id, &order.owner);
+				Ok(amount_out)
+			});
+
+			if let Ok(amount_out) = executed {
+				Self::deposit_event(Event::LimitOrderExecuted {
+					order_id,
+					who: order.owner,
+					amount_in: order.amount_in,
+					amount_out,
+				});
+			}
 		}
 
 		/// Ensure that a path is valid.
@@ -1291,6 +1861,7 @@ pub mod pallet {
 sp_api::decl_runtime_apis! {
 	/// This runtime api allows people to query the size of the liquidity pools
 	/// and quote prices for swaps.
+	#[api_version(2)]
 	pub trait AssetConversionApi<Balance, AssetId>
 	where
 		Balance: frame_support::traits::tokens::Balance + MaybeDisplay,
@@ -1320,6 +1891,17 @@ sp_api::decl_runtime_apis! {
 
 		/// Returns the size of the liquidity pool for the given asset pair.
 		fn get_reserves(asset1: AssetId, asset2: AssetId) -> Option<(Balance, Balance)>;
+
+		/// Provides the amount of `asset2` equivalent to `amount` of `asset1` at the time weighted
+		/// average price of their pool over at least the last `window` blocks.
+		///
+		/// Returns `None` if the pool has not been observed for that long.
+		fn quote_price_twap(
+			asset1: AssetId,
+			asset2: AssetId,
+			amount: Balance,
+			window: u32,
+		) -> Option<Balance>;
 	}
 }
 
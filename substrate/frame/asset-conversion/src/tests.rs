@@ -24,8 +24,9 @@ use frame_support::{
 		fungible::{Inspect as FungibleInspect, NativeOrWithId},
 		fungibles,
 		fungibles::{Inspect, InspectEnumerable},
-		Get,
+		Get, Hooks,
 	},
+	weights::Weight,
 };
 use sp_arithmetic::Permill;
 use sp_runtime::{DispatchError, TokenError};
@@ -2260,6 +2261,51 @@ fn swap_credit_returns_change() {
 	})
 }
 
+#[test]
+fn swap_credit_does_not_update_price_accumulator() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let token_1 = NativeOrWithId::Native;
+		let token_2 = NativeOrWithId::WithId(2);
+		let pool_id = <Test as Config>::PoolLocator::pool_id(&token_1, &token_2).unwrap();
+
+		create_tokens(user, vec![token_2.clone()]);
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone())
+		));
+
+		let ed = get_native_ed();
+		assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), user, 20000 + ed));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(user), 2, user, 1000));
+
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone()),
+			10000,
+			200,
+			1,
+			1,
+			user,
+		));
+		let accumulator = PriceAccumulators::<Test>::get(&pool_id);
+		let observations = PriceObservations::<Test>::get(&pool_id);
+
+		// Swaps of credit, e.g. for transaction fees, are not charged for updating the oracle.
+		System::set_block_number(2);
+		let credit_in = NativeAndAssets::issue(token_1.clone(), 100);
+		assert_ok!(<AssetConversion as SwapCredit<_>>::swap_exact_tokens_for_tokens(
+			vec![token_1.clone(), token_2.clone()],
+			credit_in,
+			None,
+		));
+		assert_eq!(PriceAccumulators::<Test>::get(&pool_id), accumulator);
+		assert_eq!(PriceObservations::<Test>::get(&pool_id), observations);
+	})
+}
+
 #[test]
 fn swap_credit_insufficient_amount_bounds() {
 	new_test_ext().execute_with(|| {
@@ -2493,3 +2539,695 @@ fn swap_credit_invalid_path() {
 		assert_eq!(error, (expected_credit_in, Error::<Test>::InvalidPath.into()));
 	});
 }
+
+#[test]
+fn create_pool_with_curve_validates_curve() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let token_1 = NativeOrWithId::Native;
+		let token_2 = NativeOrWithId::WithId(2);
+		let pool_id = (token_1.clone(), token_2.clone());
+
+		create_tokens(user, vec![token_2.clone()]);
+		assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), user, 1000));
+
+		assert_noop!(
+			AssetConversion::create_pool_with_curve(
+				RuntimeOrigin::signed(user),
+				Box::new(token_1.clone()),
+				Box::new(token_2.clone()),
+				Curve::StableSwap { amplification: 0 },
+			),
+			Error::<Test>::InvalidCurve
+		);
+		assert_noop!(
+			AssetConversion::create_pool_with_curve(
+				RuntimeOrigin::signed(user),
+				Box::new(token_1.clone()),
+				Box::new(token_2.clone()),
+				Curve::Weighted { weight1: Permill::from_percent(100) },
+			),
+			Error::<Test>::InvalidCurve
+		);
+
+		assert_ok!(AssetConversion::create_pool_with_curve(
+			RuntimeOrigin::signed(user),
+			Box::new(token_2.clone()),
+			Box::new(token_1.clone()),
+			Curve::Weighted { weight1: Permill::from_percent(80) },
+		));
+
+		assert_eq!(pools(), vec![pool_id.clone()]);
+		let info = PoolCurves::<Test>::get(&pool_id).unwrap();
+		assert_eq!(info.asset1, token_2);
+		assert_eq!(info.curve, Curve::Weighted { weight1: Permill::from_percent(80) });
+
+		assert_noop!(
+			AssetConversion::create_pool(
+				RuntimeOrigin::signed(user),
+				Box::new(token_1.clone()),
+				Box::new(token_2.clone())
+			),
+			Error::<Test>::PoolExists
+		);
+	});
+}
+
+#[test]
+fn swap_exact_tokens_for_tokens_across_curves() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let token_1 = NativeOrWithId::Native;
+		let token_2 = NativeOrWithId::WithId(2);
+		let token_3 = NativeOrWithId::WithId(3);
+		let stable = Curve::StableSwap { amplification: 100 };
+		let weighted = Curve::Weighted { weight1: Permill::from_percent(80) };
+
+		create_tokens(user, vec![token_2.clone(), token_3.clone()]);
+		let ed = get_native_ed();
+		assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), user, 20_000_000 + ed));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(user), 2, user, 20_000_000));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(user), 3, user, 20_000_000));
+
+		assert_ok!(AssetConversion::create_pool_with_curve(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone()),
+			stable,
+		));
+		// The weighted curve is oriented towards `token_3`, the second asset of the pool.
+		assert_ok!(AssetConversion::create_pool_with_curve(
+			RuntimeOrigin::signed(user),
+			Box::new(token_3.clone()),
+			Box::new(token_1.clone()),
+			weighted,
+		));
+
+		let liquidity = 10_000_000;
+		let weighted_liquidity = 1_000_000;
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone()),
+			liquidity,
+			liquidity,
+			1,
+			1,
+			user,
+		));
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_3.clone()),
+			weighted_liquidity,
+			weighted_liquidity,
+			1,
+			1,
+			user,
+		));
+
+		// With equal reserves, `token_3` holding 80% of the weight is worth four `token_1`.
+		assert_eq!(
+			AssetConversion::quote_price_exact_tokens_for_tokens(
+				token_3.clone(),
+				token_1.clone(),
+				1000,
+				false
+			),
+			Some(4000)
+		);
+		assert_eq!(
+			AssetConversion::quote_price_tokens_for_exact_tokens(
+				token_3.clone(),
+				token_1.clone(),
+				4000,
+				false
+			),
+			Some(1000)
+		);
+
+		let input_amount = 100_000;
+		let expect_out1 =
+			PoolCurve::<u128, <Test as Config>::HigherPrecisionBalance>::get_amount_out(
+				&stable,
+				&input_amount,
+				&liquidity,
+				&liquidity,
+				3,
+			)
+			.unwrap();
+		let expect_out3 =
+			PoolCurve::<u128, <Test as Config>::HigherPrecisionBalance>::get_amount_out(
+				&PoolCurve::<u128, <Test as Config>::HigherPrecisionBalance>::invert(&weighted),
+				&expect_out1,
+				&weighted_liquidity,
+				&weighted_liquidity,
+				3,
+			)
+			.unwrap();
+
+		// The stable pool offers a better rate than a constant product one.
+		assert!(
+			expect_out1 >
+				AssetConversion::get_amount_out(&input_amount, &liquidity, &liquidity).unwrap()
+		);
+		assert_eq!(
+			AssetConversion::quote_price_exact_tokens_for_tokens(
+				token_2.clone(),
+				token_1.clone(),
+				input_amount,
+				true
+			),
+			Some(expect_out1)
+		);
+
+		assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+			RuntimeOrigin::signed(user),
+			bvec![token_2.clone(), token_1.clone(), token_3.clone()],
+			input_amount,
+			1,
+			user,
+			true,
+		));
+
+		let pool_id1 = (token_1.clone(), token_2.clone());
+		let pool_id2 = (token_1.clone(), token_3.clone());
+		let pallet_account1 = <Test as Config>::PoolLocator::address(&pool_id1).unwrap();
+		let pallet_account2 = <Test as Config>::PoolLocator::address(&pool_id2).unwrap();
+
+		assert_eq!(balance(pallet_account1, token_1.clone()), liquidity - expect_out1);
+		assert_eq!(balance(pallet_account1, token_2.clone()), liquidity + input_amount);
+		assert_eq!(balance(pallet_account2, token_1.clone()), weighted_liquidity + expect_out1);
+		assert_eq!(balance(pallet_account2, token_3.clone()), weighted_liquidity - expect_out3);
+		assert_eq!(balance(user, token_3.clone()), 20_000_000 - weighted_liquidity + expect_out3);
+	});
+}
+
+#[test]
+fn quote_price_twap_averages_prices_over_window() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let token_1 = NativeOrWithId::Native;
+		let token_2 = NativeOrWithId::WithId(2);
+
+		create_tokens(user, vec![token_2.clone()]);
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone())
+		));
+
+		let ed = get_native_ed();
+		assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), user, 10000 + ed));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(user), 2, user, 10000));
+
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone()),
+			1000,
+			4000,
+			1,
+			1,
+			user,
+		));
+
+		// no observation precedes the window
+		assert_eq!(
+			AssetConversion::quote_price_twap(token_1.clone(), token_2.clone(), 100, 1),
+			None
+		);
+
+		System::set_block_number(11);
+		assert_eq!(
+			AssetConversion::quote_price_twap(token_1.clone(), token_2.clone(), 100, 10),
+			Some(400)
+		);
+		assert_eq!(
+			AssetConversion::quote_price_twap(token_2.clone(), token_1.clone(), 400, 10),
+			Some(100)
+		);
+		assert_eq!(
+			AssetConversion::quote_price_twap(token_1.clone(), token_2.clone(), 100, 20),
+			None
+		);
+
+		assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+			RuntimeOrigin::signed(user),
+			bvec![token_1.clone(), token_2.clone()],
+			1000,
+			1,
+			user,
+			false,
+		));
+		let pool_account =
+			<Test as Config>::PoolLocator::address(&(token_1.clone(), token_2.clone())).unwrap();
+		let new_price = FixedU128::checked_from_rational(
+			balance(pool_account, token_2.clone()),
+			balance(pool_account, token_1.clone()),
+		)
+		.unwrap();
+
+		System::set_block_number(21);
+		// the swap is not reflected by the average over the blocks preceding it
+		assert_eq!(
+			AssetConversion::quote_price_twap(token_1.clone(), token_2.clone(), 1000, 10),
+			new_price.checked_mul_int(1000)
+		);
+		let average = FixedU128::from_inner(
+			(FixedU128::from_u32(4).into_inner() + new_price.into_inner()) / 2,
+		);
+		assert_eq!(
+			AssetConversion::quote_price_twap(token_1.clone(), token_2.clone(), 1000, 20),
+			average.checked_mul_int(1000)
+		);
+	});
+}
+
+#[test]
+fn price_observations_are_bounded() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let token_1 = NativeOrWithId::Native;
+		let token_2 = NativeOrWithId::WithId(2);
+		let pool_id = (token_1.clone(), token_2.clone());
+
+		create_tokens(user, vec![token_2.clone()]);
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone())
+		));
+
+		let ed = get_native_ed();
+		assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), user, 10000 + ed));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(user), 2, user, 10000));
+
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone()),
+			1000,
+			4000,
+			1,
+			1,
+			user,
+		));
+
+		let max_observations: u32 = <Test as Config>::MaxPriceObservations::get();
+		for block in 2..=(max_observations as u64 + 1) {
+			System::set_block_number(block);
+			assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+				RuntimeOrigin::signed(user),
+				bvec![token_1.clone(), token_2.clone()],
+				10,
+				1,
+				user,
+				false,
+			));
+			// a second swap within the block is not observed
+			assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+				RuntimeOrigin::signed(user),
+				bvec![token_1.clone(), token_2.clone()],
+				10,
+				1,
+				user,
+				false,
+			));
+		}
+
+		let observations = PriceObservations::<Test>::get(&pool_id);
+		assert_eq!(observations.len() as u32, max_observations);
+		assert_eq!(observations.first().map(|o| o.block), Some(2));
+		assert_eq!(PriceAccumulators::<Test>::get(&pool_id).unwrap().last_update, 5);
+
+		// the observation of the first block has been dropped
+		assert_eq!(
+			AssetConversion::quote_price_twap(token_1.clone(), token_2.clone(), 100, 4),
+			None
+		);
+		assert!(
+			AssetConversion::quote_price_twap(token_1.clone(), token_2.clone(), 100, 3).is_some()
+		);
+	});
+}
+
+#[test]
+fn place_and_cancel_limit_order() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let user2 = 2;
+		let token_1 = NativeOrWithId::Native;
+		let token_2 = NativeOrWithId::WithId(2);
+
+		create_tokens(user, vec![token_2.clone()]);
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone())
+		));
+
+		assert_noop!(
+			AssetConversion::place_limit_order(
+				RuntimeOrigin::signed(user2),
+				bvec![token_1.clone(), token_1.clone()],
+				1000,
+				1,
+			),
+			Error::<Test>::InvalidAssetPair
+		);
+		assert_noop!(
+			AssetConversion::place_limit_order(
+				RuntimeOrigin::signed(user2),
+				bvec![token_1.clone(), token_2.clone()],
+				0,
+				1,
+			),
+			Error::<Test>::ZeroAmount
+		);
+
+		assert_ok!(AssetConversion::place_limit_order(
+			RuntimeOrigin::signed(user2),
+			bvec![token_1.clone(), token_2.clone()],
+			1000,
+			5000,
+		));
+		assert!(events().contains(&Event::<Test>::LimitOrderPlaced {
+			order_id: 0,
+			who: user2,
+			amount_in: 1000,
+			amount_out_min: 5000,
+		}));
+		let deposit = <Test as Config>::LimitOrderDeposit::get();
+		let order_account = AssetConversion::limit_order_account(0);
+		assert_eq!(balance(order_account, token_1.clone()), 1000 + deposit);
+		assert_eq!(balance(user2, token_1.clone()), 20000 - 1000 - deposit);
+
+		assert_ok!(AssetConversion::place_limit_order(
+			RuntimeOrigin::signed(user2),
+			bvec![token_1.clone(), token_2.clone()],
+			1000,
+			5000,
+		));
+		assert_noop!(
+			AssetConversion::place_limit_order(
+				RuntimeOrigin::signed(user2),
+				bvec![token_1.clone(), token_2.clone()],
+				1000,
+				5000,
+			),
+			Error::<Test>::TooManyAccountLimitOrders
+		);
+		assert_ok!(AssetConversion::place_limit_order(
+			RuntimeOrigin::signed(user),
+			bvec![token_1.clone(), token_2.clone()],
+			1000,
+			5000,
+		));
+		assert_noop!(
+			AssetConversion::place_limit_order(
+				RuntimeOrigin::signed(user),
+				bvec![token_1.clone(), token_2.clone()],
+				1000,
+				5000,
+			),
+			Error::<Test>::TooManyLimitOrders
+		);
+
+		assert_noop!(
+			AssetConversion::cancel_limit_order(RuntimeOrigin::signed(user), 0),
+			Error::<Test>::NotLimitOrderOwner
+		);
+		assert_noop!(
+			AssetConversion::cancel_limit_order(RuntimeOrigin::signed(user2), 2),
+			Error::<Test>::LimitOrderNotFound
+		);
+
+		assert_ok!(AssetConversion::cancel_limit_order(RuntimeOrigin::signed(user2), 0));
+		assert!(events().contains(&Event::<Test>::LimitOrderCancelled { order_id: 0, who: user2 }));
+		assert_eq!(balance(order_account, token_1.clone()), 0);
+		assert_eq!(balance(user2, token_1.clone()), 20000 - 1000 - deposit);
+		assert!(LimitOrders::<Test>::get(0).is_none());
+		assert_eq!(LimitOrders::<Test>::count(), 2);
+		assert_eq!(AccountLimitOrders::<Test>::get(user2), 1);
+	});
+}
+
+#[test]
+fn cancel_limit_order_sweeps_funds_sent_to_the_order() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let user2 = 2;
+		let token_1 = NativeOrWithId::Native;
+		let token_2 = NativeOrWithId::WithId(2);
+
+		create_tokens(user, vec![token_2.clone()]);
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone())
+		));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(user), 2, user2, 1000));
+
+		// The account of the order is created with a deposit of the owner.
+		assert_ok!(AssetConversion::place_limit_order(
+			RuntimeOrigin::signed(user2),
+			bvec![token_2.clone(), token_1.clone()],
+			500,
+			5000,
+		));
+		let order_account = AssetConversion::limit_order_account(0);
+		let reserved = Balances::reserved_balance(user2);
+		assert!(reserved > 0);
+
+		let deposit = <Test as Config>::LimitOrderDeposit::get();
+		assert_eq!(balance(order_account, token_1.clone()), deposit);
+
+		// Anyone sending funds to the order account doesn't keep the deposit from being refunded.
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(user), 2, order_account, 7));
+
+		assert_ok!(AssetConversion::cancel_limit_order(RuntimeOrigin::signed(user2), 0));
+		assert_eq!(balance(order_account, token_2.clone()), 0);
+		assert_eq!(balance(order_account, token_1.clone()), 0);
+		assert_eq!(balance(user2, token_2.clone()), 1000 + 7);
+		assert_eq!(balance(user2, token_1.clone()), 20000);
+		assert_eq!(Balances::reserved_balance(user2), 0);
+		assert_eq!(AccountLimitOrders::<Test>::get(user2), 0);
+		assert!(!AccountLimitOrders::<Test>::contains_key(user2));
+	});
+}
+
+#[test]
+fn expired_limit_orders_are_reaped() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let user2 = 2;
+		let user3 = 3;
+		let token_1 = NativeOrWithId::Native;
+		let token_2 = NativeOrWithId::WithId(2);
+
+		create_tokens(user, vec![token_2.clone()]);
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone())
+		));
+
+		assert_ok!(AssetConversion::place_limit_order(
+			RuntimeOrigin::signed(user2),
+			bvec![token_1.clone(), token_2.clone()],
+			1000,
+			5000,
+		));
+		assert_ok!(AssetConversion::place_limit_order(
+			RuntimeOrigin::signed(user2),
+			bvec![token_1.clone(), token_2.clone()],
+			1000,
+			5000,
+		));
+		let lifetime = <Test as Config>::LimitOrderLifetime::get();
+		let deposit = <Test as Config>::LimitOrderDeposit::get();
+		assert_eq!(LimitOrders::<Test>::get(0).unwrap().expiry, 1 + lifetime);
+
+		assert_noop!(
+			AssetConversion::reap_limit_order(RuntimeOrigin::signed(user3), 0),
+			Error::<Test>::LimitOrderNotExpired
+		);
+
+		System::set_block_number(1 + lifetime);
+		// anyone may reap an expired order, its funds go to its owner
+		assert_ok!(AssetConversion::reap_limit_order(RuntimeOrigin::signed(user3), 0));
+		assert!(events().contains(&Event::<Test>::LimitOrderReaped { order_id: 0, who: user2 }));
+		assert_eq!(balance(AssetConversion::limit_order_account(0), token_1.clone()), 0);
+		assert_eq!(balance(user2, token_1.clone()), 20000 - 1000 - deposit);
+		assert_eq!(balance(user3, token_1.clone()), 30000);
+		assert_noop!(
+			AssetConversion::reap_limit_order(RuntimeOrigin::signed(user3), 0),
+			Error::<Test>::LimitOrderNotFound
+		);
+
+		// the expired orders are reaped with the weight left at the end of blocks
+		AssetConversion::on_idle(System::block_number(), Weight::MAX);
+		assert!(events().contains(&Event::<Test>::LimitOrderReaped { order_id: 1, who: user2 }));
+		assert_eq!(balance(user2, token_1.clone()), 20000);
+		assert_eq!(LimitOrders::<Test>::count(), 0);
+		assert!(!AccountLimitOrders::<Test>::contains_key(user2));
+	});
+}
+
+#[test]
+fn limit_orders_execute_once_pools_offer_minimum() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let user2 = 2;
+		let token_1 = NativeOrWithId::Native;
+		let token_2 = NativeOrWithId::WithId(2);
+
+		create_tokens(user, vec![token_2.clone()]);
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone())
+		));
+
+		let ed = get_native_ed();
+		assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), user, 20000 + ed));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(user), 2, user, 50000));
+
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(user),
+			Box::new(token_1.clone()),
+			Box::new(token_2.clone()),
+			10000,
+			40000,
+			1,
+			1,
+			user,
+		));
+
+		let amount_in = 1000;
+		let expected_out = AssetConversion::quote_price_exact_tokens_for_tokens(
+			token_1.clone(),
+			token_2.clone(),
+			amount_in,
+			true,
+		)
+		.unwrap();
+
+		assert_ok!(AssetConversion::place_limit_order(
+			RuntimeOrigin::signed(user2),
+			bvec![token_1.clone(), token_2.clone()],
+			amount_in,
+			expected_out + 1,
+		));
+		assert_ok!(AssetConversion::place_limit_order(
+			RuntimeOrigin::signed(user2),
+			bvec![token_1.clone(), token_2.clone()],
+			amount_in,
+			expected_out,
+		));
+		System::reset_events();
+
+		AssetConversion::on_idle(System::block_number(), Weight::MAX);
+
+		assert!(events().contains(&Event::<Test>::LimitOrderExecuted {
+			order_id: 1,
+			who: user2,
+			amount_in,
+			amount_out: expected_out,
+		}));
+		assert_eq!(balance(user2, token_2.clone()), expected_out);
+		assert_eq!(balance(AssetConversion::limit_order_account(1), token_1.clone()), 0);
+		assert!(LimitOrders::<Test>::get(0).is_some());
+		assert!(LimitOrders::<Test>::get(1).is_none());
+
+		// the remaining order does not execute until the price moves in its favour
+		AssetConversion::on_idle(System::block_number(), Weight::MAX);
+		assert!(LimitOrders::<Test>::get(0).is_some());
+
+		assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+			RuntimeOrigin::signed(user),
+			bvec![token_2.clone(), token_1.clone()],
+			10000,
+			1,
+			user,
+			false,
+		));
+
+		AssetConversion::on_idle(System::block_number(), Weight::MAX);
+		assert!(LimitOrders::<Test>::get(0).is_none());
+		assert!(balance(user2, token_2.clone()) >= expected_out * 2 + 1);
+		// the deposits are returned with the execution of the orders
+		assert_eq!(balance(user2, token_1.clone()), 20000 - amount_in * 2);
+		assert_eq!(LimitOrders::<Test>::count(), 0);
+
+		// not enough weight to check an order
+		assert_eq!(AssetConversion::execute_limit_orders(Weight::zero()), Weight::zero());
+	});
+}
+
+#[test]
+fn swap_round_trip_across_curves_is_not_profitable() {
+	new_test_ext().execute_with(|| {
+		let user = 1;
+		let token_1 = NativeOrWithId::Native;
+		let curves = [
+			Curve::ConstantProduct,
+			Curve::StableSwap { amplification: 100 },
+			Curve::Weighted { weight1: Permill::from_percent(80) },
+			Curve::Weighted { weight1: Permill::from_percent(2) },
+		];
+		let tokens: Vec<_> = (2..2 + curves.len() as u32).map(NativeOrWithId::WithId).collect();
+
+		create_tokens(user, tokens.clone());
+		let ed = get_native_ed();
+		assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), user, 100_000_000 + ed));
+
+		let liquidity = 10_000_000;
+		for ((id, token), curve) in (2..).zip(tokens.iter()).zip(curves) {
+			assert_ok!(Assets::mint(RuntimeOrigin::signed(user), id, user, 20_000_000));
+			assert_ok!(AssetConversion::create_pool_with_curve(
+				RuntimeOrigin::signed(user),
+				Box::new(token.clone()),
+				Box::new(token_1.clone()),
+				curve,
+			));
+			assert_ok!(AssetConversion::add_liquidity(
+				RuntimeOrigin::signed(user),
+				Box::new(token_1.clone()),
+				Box::new(token.clone()),
+				liquidity,
+				liquidity,
+				1,
+				1,
+				user,
+			));
+		}
+
+		for token in tokens {
+			for amount_in in [1_000, 123_456, 3_000_000] {
+				let before = balance(user, token.clone());
+				let native_before = balance(user, token_1.clone());
+				assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+					RuntimeOrigin::signed(user),
+					bvec![token.clone(), token_1.clone()],
+					amount_in,
+					1,
+					user,
+					true,
+				));
+				let bought = balance(user, token_1.clone()) - native_before;
+				assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+					RuntimeOrigin::signed(user),
+					bvec![token_1.clone(), token.clone()],
+					bought,
+					1,
+					user,
+					true,
+				));
+				// Rounding never lets a swap there and back return more than was put in.
+				assert!(balance(user, token.clone()) < before);
+			}
+		}
+	});
+}
@@ -18,8 +18,9 @@
 use super::*;
 use codec::{Decode, Encode, MaxEncodedLen};
 use core::marker::PhantomData;
+use frame_support::{BoundedVec, CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound};
 use scale_info::TypeInfo;
-use sp_runtime::traits::TryConvert;
+use sp_runtime::{traits::TryConvert, RuntimeDebug};
 
 /// Represents a swap path with associated asset amounts indicating how much of the asset needs to
 /// be deposited to get the following asset's amount withdrawn (this is inclusive of fees).
@@ -41,6 +42,70 @@ pub struct PoolInfo<PoolAssetId> {
 	pub lp_token: PoolAssetId,
 }
 
+/// Stores the curve of a pool created with a curve other than the default one.
+#[derive(Decode, Encode, Clone, PartialEq, Eq, MaxEncodedLen, TypeInfo)]
+pub struct CurveInfo<AssetKind, Curve> {
+	/// The asset the curve is oriented towards.
+	pub asset1: AssetKind,
+	/// The curve of the pool.
+	pub curve: Curve,
+}
+
+/// The prices of the assets of a pool summed over every block since its first swap or change of
+/// liquidity, from which time weighted average prices are derived.
+///
+/// The sums are of the inner values of [`sp_arithmetic::FixedU128`] prices and wrap around on
+/// overflow, only their differences being meaningful.
+#[derive(Decode, Encode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct PriceAccumulator<AssetKind, BlockNumber> {
+	/// The asset `price1_cumulative` is the price of.
+	pub asset1: AssetKind,
+	/// The sum of the prices of `asset1`, in units of the other asset.
+	pub price1_cumulative: u128,
+	/// The sum of the prices of the other asset, in units of `asset1`.
+	pub price2_cumulative: u128,
+	/// The block the sums were last updated in.
+	pub last_update: BlockNumber,
+}
+
+/// The sums of a [`PriceAccumulator`] as of a given block.
+#[derive(Decode, Encode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct PriceObservation<BlockNumber> {
+	/// The block the sums were recorded in.
+	pub block: BlockNumber,
+	/// The sum of the prices of the first asset of the accumulator.
+	pub price1_cumulative: u128,
+	/// The sum of the prices of the other asset.
+	pub price2_cumulative: u128,
+}
+
+/// A swap of an exact amount, resting until the pools along its path offer at least the minimum
+/// amount out.
+#[derive(
+	Decode,
+	Encode,
+	CloneNoBound,
+	PartialEqNoBound,
+	EqNoBound,
+	RuntimeDebugNoBound,
+	MaxEncodedLen,
+	TypeInfo,
+)]
+#[scale_info(skip_type_params(T))]
+#[codec(mel_bound())]
+pub struct LimitOrder<T: Config> {
+	/// The account which placed the order and receives its proceeds.
+	pub owner: T::AccountId,
+	/// The route of the swap, from the asset sold to the asset bought.
+	pub path: BoundedVec<T::AssetKind, T::MaxSwapPathLength>,
+	/// The amount of the first asset of the `path` to sell.
+	pub amount_in: T::Balance,
+	/// The minimum amount of the last asset of the `path` to buy.
+	pub amount_out_min: T::Balance,
+	/// The block from which the order may be reaped if it hasn't been executed.
+	pub expiry: frame_system::pallet_prelude::BlockNumberFor<T>,
+}
+
 /// Provides means to resolve the `PoolId` and `AccountId` from a pair of assets.
 ///
 /// Resulting `PoolId` remains consistent whether the asset pair is presented as (asset1, asset2)
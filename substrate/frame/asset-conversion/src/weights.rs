@@ -50,11 +50,14 @@ use core::marker::PhantomData;
 /// Weight functions needed for `pallet_asset_conversion`.
 pub trait WeightInfo {
 	fn create_pool() -> Weight;
+	fn create_pool_with_curve(n: u32, ) -> Weight;
 	fn add_liquidity() -> Weight;
 	fn remove_liquidity() -> Weight;
 	fn swap_exact_tokens_for_tokens(n: u32, ) -> Weight;
 	fn swap_tokens_for_exact_tokens(n: u32, ) -> Weight;
 	fn touch(n: u32, ) -> Weight;
+	fn place_limit_order() -> Weight;
+	fn cancel_limit_order() -> Weight;
 }
 
 /// Weights for `pallet_asset_conversion` using the Substrate node and recommended hardware.
@@ -81,6 +84,12 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(8_u64))
 			.saturating_add(T::DbWeight::get().writes(5_u64))
 	}
+	/// Not benchmarked yet: `create_pool` plus the write of `AssetConversion::PoolCurves`.
+	/// The range of component `n` is `[0, 2]`.
+	fn create_pool_with_curve(_n: u32, ) -> Weight {
+		Self::create_pool()
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 	/// Storage: `AssetConversion::Pools` (r:1 w:0)
 	/// Proof: `AssetConversion::Pools` (`max_values`: None, `max_size`: Some(30), added: 2505, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::Asset` (r:2 w:2)
@@ -179,6 +188,18 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(8_u64))
 			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
 	}
+	/// Not benchmarked yet: bounded by `add_liquidity`, which moves two assets to an account
+	/// as well, plus the storage of the order.
+	fn place_limit_order() -> Weight {
+		Self::add_liquidity()
+			.saturating_add(T::DbWeight::get().reads_writes(3, 4))
+	}
+	/// Not benchmarked yet: bounded by `remove_liquidity`, which moves two assets from an
+	/// account as well, plus the storage of the order.
+	fn cancel_limit_order() -> Weight {
+		Self::remove_liquidity()
+			.saturating_add(T::DbWeight::get().reads_writes(3, 4))
+	}
 }
 
 // For backwards compatibility and tests.
@@ -204,6 +225,12 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(8_u64))
 			.saturating_add(RocksDbWeight::get().writes(5_u64))
 	}
+	/// Not benchmarked yet: `create_pool` plus the write of `AssetConversion::PoolCurves`.
+	/// The range of component `n` is `[0, 2]`.
+	fn create_pool_with_curve(_n: u32, ) -> Weight {
+		Self::create_pool()
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 	/// Storage: `AssetConversion::Pools` (r:1 w:0)
 	/// Proof: `AssetConversion::Pools` (`max_values`: None, `max_size`: Some(30), added: 2505, mode: `MaxEncodedLen`)
 	/// Storage: `Assets::Asset` (r:2 w:2)
@@ -302,4 +329,16 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(8_u64))
 			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(n.into())))
 	}
+	/// Not benchmarked yet: bounded by `add_liquidity`, which moves two assets to an account
+	/// as well, plus the storage of the order.
+	fn place_limit_order() -> Weight {
+		Self::add_liquidity()
+			.saturating_add(RocksDbWeight::get().reads_writes(3, 4))
+	}
+	/// Not benchmarked yet: bounded by `remove_liquidity`, which moves two assets from an
+	/// account as well, plus the storage of the order.
+	fn cancel_limit_order() -> Weight {
+		Self::remove_liquidity()
+			.saturating_add(RocksDbWeight::get().reads_writes(3, 4))
+	}
 }
@@ -18,7 +18,11 @@
 //! Functions for the Assets pallet.
 
 use super::*;
-use frame_support::{defensive, traits::Get, BoundedVec};
+use frame_support::{
+	defensive,
+	traits::{Get, VariantCountOf},
+	BoundedVec,
+};
 
 #[must_use]
 pub(super) enum DeadConsequence {
@@ -63,6 +67,28 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		Asset::<T, I>::get(id).map(|x| x.supply)
 	}
 
+	/// Get the amount of asset `id` of `who` which is on hold, for all hold reasons.
+	pub fn total_balance_on_hold(id: T::AssetId, who: &T::AccountId) -> T::Balance {
+		Holds::<T, I>::get(id, who)
+			.iter()
+			.fold(Zero::zero(), |total: T::Balance, hold| total.saturating_add(hold.amount))
+	}
+
+	/// Get the amount of asset `id` of `who` which is frozen, i.e. the largest of its freezes.
+	pub fn total_balance_frozen(id: T::AssetId, who: &T::AccountId) -> T::Balance {
+		Freezes::<T, I>::get(id, who)
+			.iter()
+			.fold(Zero::zero(), |frozen: T::Balance, freeze| frozen.max(freeze.amount))
+	}
+
+	/// The part of the asset `id` balance of `who` which is kept from being reduced by freezes.
+	///
+	/// Freezes apply to the total balance, so funds on hold count towards satisfying them.
+	fn locked_balance(id: T::AssetId, who: &T::AccountId) -> T::Balance {
+		Self::total_balance_frozen(id.clone(), who)
+			.saturating_sub(Self::total_balance_on_hold(id, who))
+	}
+
 	pub(super) fn new_account(
 		who: &T::AccountId,
 		d: &mut AssetDetails<T::Balance, T::AccountId, DepositBalanceOf<T, I>>,
@@ -158,11 +184,14 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 	}
 
 	/// Return the consequence of a withdraw.
+	///
+	/// Freezes are ignored if `force` is `true`; the `T::Freezer` hook is always respected.
 	pub(super) fn can_decrease(
 		id: T::AssetId,
 		who: &T::AccountId,
 		amount: T::Balance,
 		keep_alive: bool,
+		force: bool,
 	) -> WithdrawConsequence<T::Balance> {
 		use WithdrawConsequence::*;
 		let details = match Asset::<T, I>::get(&id) {
@@ -194,6 +223,14 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				}
 			}
 
+			let locked = if force { Zero::zero() } else { Self::locked_balance(id.clone(), who) };
+			if rest < locked {
+				return Frozen
+			}
+			// Funds on hold or frozen keep the account alive.
+			let keep_alive =
+				keep_alive || !locked.is_zero() || !Holds::<T, I>::get(&id, who).is_empty();
+
 			if rest < details.min_balance {
 				if keep_alive {
 					WouldDie
@@ -209,11 +246,12 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 	}
 
 	// Maximum `amount` that can be passed into `can_withdraw` to result in a `WithdrawConsequence`
-	// of `Success`.
+	// of `Success`. Freezes are ignored if `force` is `true`.
 	pub(super) fn reducible_balance(
 		id: T::AssetId,
 		who: &T::AccountId,
 		keep_alive: bool,
+		force: bool,
 	) -> Result<T::Balance, DispatchError> {
 		let details = Asset::<T, I>::get(&id).ok_or(Error::<T, I>::Unknown)?;
 		ensure!(details.status == AssetStatus::Live, Error::<T, I>::AssetNotLive);
@@ -221,20 +259,22 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		let account = Account::<T, I>::get(&id, who).ok_or(Error::<T, I>::NoAccount)?;
 		ensure!(!account.status.is_frozen(), Error::<T, I>::Frozen);
 
-		let amount = if let Some(frozen) = T::Freezer::frozen_balance(id, who) {
+		let locked = if force { Zero::zero() } else { Self::locked_balance(id.clone(), who) };
+		// Funds on hold or frozen keep the account alive.
+		let keep_alive =
+			keep_alive || !locked.is_zero() || !Holds::<T, I>::get(&id, who).is_empty();
+
+		let required = if let Some(frozen) = T::Freezer::frozen_balance(id, who) {
 			// Frozen balance: account CANNOT be deleted
-			let required =
-				frozen.checked_add(&details.min_balance).ok_or(ArithmeticError::Overflow)?;
-			account.balance.saturating_sub(required)
+			frozen.checked_add(&details.min_balance).ok_or(ArithmeticError::Overflow)?
+		} else if keep_alive {
+			// We want to keep the account around.
+			details.min_balance
 		} else {
-			if keep_alive {
-				// We want to keep the account around.
-				account.balance.saturating_sub(details.min_balance)
-			} else {
-				// Don't care if the account dies
-				account.balance
-			}
+			// Don't care if the account dies
+			Zero::zero()
 		};
+		let amount = account.balance.saturating_sub(required.max(locked));
 		Ok(amount.min(details.supply))
 	}
 
@@ -259,10 +299,11 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		amount: T::Balance,
 		f: DebitFlags,
 	) -> Result<T::Balance, DispatchError> {
-		let actual = Self::reducible_balance(id.clone(), target, f.keep_alive)?.min(amount);
+		let actual =
+			Self::reducible_balance(id.clone(), target, f.keep_alive, f.force)?.min(amount);
 		ensure!(f.best_effort || actual >= amount, Error::<T, I>::BalanceLow);
 
-		let conseq = Self::can_decrease(id, target, actual, f.keep_alive);
+		let conseq = Self::can_decrease(id, target, actual, f.keep_alive, f.force);
 		let actual = match conseq.into_result(f.keep_alive) {
 			Ok(dust) => actual.saturating_add(dust), //< guaranteed by reducible_balance
 			Err(e) => {
@@ -304,6 +345,26 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		Ok((credit, maybe_burn))
 	}
 
+	/// Store the `freezes` of `who` on asset `id`.
+	///
+	/// Freezes are only placed on existing accounts; they are dropped once the account dies.
+	pub(super) fn update_freezes(
+		id: T::AssetId,
+		who: &T::AccountId,
+		freezes: BoundedVec<
+			IdAmount<T::RuntimeFreezeReason, T::Balance>,
+			VariantCountOf<T::RuntimeFreezeReason>,
+		>,
+	) -> DispatchResult {
+		if freezes.is_empty() {
+			Freezes::<T, I>::remove(&id, who);
+		} else {
+			ensure!(Account::<T, I>::contains_key(&id, who), Error::<T, I>::NoAccount);
+			Freezes::<T, I>::insert(&id, who, freezes);
+		}
+		Ok(())
+	}
+
 	/// Creates an account for `who` to hold asset `id` with a zero balance and takes a deposit.
 	///
 	/// When `check_depositor` is set to true, the depositor must be either the asset's Admin or
@@ -349,6 +410,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		let mut details = Asset::<T, I>::get(&id).ok_or(Error::<T, I>::Unknown)?;
 		ensure!(matches!(details.status, Live | Frozen), Error::<T, I>::IncorrectStatus);
 		ensure!(account.balance.is_zero() || allow_burn, Error::<T, I>::WouldBurn);
+		ensure!(!Holds::<T, I>::contains_key(&id, &who), Error::<T, I>::ContainsHolds);
 
 		if let Some(deposit) = account.reason.take_deposit() {
 			T::Currency::unreserve(&who, deposit);
@@ -363,6 +425,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 			return Ok(())
 		}
 		Asset::<T, I>::insert(&id, details);
+		Freezes::<T, I>::remove(&id, &who);
 		// Executing a hook here is safe, since it is not in a `mutate`.
 		T::Freezer::died(id, &who);
 		Ok(())
@@ -387,6 +450,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 			ensure!(caller == depositor || caller == details.admin, Error::<T, I>::NoPermission);
 		}
 		ensure!(account.balance.is_zero(), Error::<T, I>::WouldBurn);
+		ensure!(!Holds::<T, I>::contains_key(&id, who), Error::<T, I>::ContainsHolds);
 
 		T::Currency::unreserve(&depositor, deposit);
 
@@ -399,6 +463,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 			return Ok(())
 		}
 		Asset::<T, I>::insert(&id, details);
+		Freezes::<T, I>::remove(&id, &who);
 		// Executing a hook here is safe, since it is not in a `mutate`.
 		T::Freezer::died(id, &who);
 		return Ok(())
@@ -568,6 +633,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
 		// Execute hook outside of `mutate`.
 		if let Some(Remove) = target_died {
+			Freezes::<T, I>::remove(&id, target);
 			T::Freezer::died(id, target);
 		}
 		Ok(actual)
@@ -592,6 +658,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		let (balance, died) =
 			Self::transfer_and_die(id.clone(), source, dest, amount, maybe_need_admin, f)?;
 		if let Some(Remove) = died {
+			Freezes::<T, I>::remove(&id, source);
 			T::Freezer::died(id, source);
 		}
 		Ok(balance)
@@ -774,6 +841,9 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 					}
 					if let Remove = Self::dead_account(&who, &mut details, &v.reason, false) {
 						Account::<T, I>::remove(&id, &who);
+						// The asset is going away, so are any funds on hold and freezes.
+						Holds::<T, I>::remove(&id, &who);
+						Freezes::<T, I>::remove(&id, &who);
 						dead_accounts.push(who);
 					} else {
 						// deposit may have been released, need to update `Account`
@@ -950,6 +1020,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
 		// Execute hook outside of `mutate`.
 		if let Some(Remove) = owner_died {
+			Freezes::<T, I>::remove(&id, owner);
 			T::Freezer::died(id, owner);
 		}
 		Ok(())
@@ -214,7 +214,7 @@ pub mod pallet {
 	use super::*;
 	use frame_support::{
 		pallet_prelude::*,
-		traits::{AccountTouch, ContainsPair},
+		traits::{AccountTouch, ContainsPair, VariantCount, VariantCountOf},
 	};
 	use frame_system::pallet_prelude::*;
 
@@ -249,6 +249,10 @@ pub mod pallet {
 		impl DefaultConfig for TestDefaultConfig {
 			#[inject_runtime_type]
 			type RuntimeEvent = ();
+			#[inject_runtime_type]
+			type RuntimeHoldReason = ();
+			#[inject_runtime_type]
+			type RuntimeFreezeReason = ();
 			type Balance = u64;
 			type RemoveItemsLimit = ConstU32<5>;
 			type AssetId = u32;
@@ -357,6 +361,14 @@ pub mod pallet {
 		#[pallet::no_default]
 		type Freezer: FrozenBalance<Self::AssetId, Self::AccountId, Self::Balance>;
 
+		/// The overarching hold reason, used to place holds on asset balances.
+		#[pallet::no_default_bounds]
+		type RuntimeHoldReason: Parameter + Member + MaxEncodedLen + Copy + VariantCount;
+
+		/// The overarching freeze reason, used to place freezes on asset balances.
+		#[pallet::no_default_bounds]
+		type RuntimeFreezeReason: Parameter + Member + MaxEncodedLen + Copy + VariantCount;
+
 		/// Additional data to be stored with an account's asset balance.
 		type Extra: Member + Parameter + Default + MaxEncodedLen;
 
@@ -405,6 +417,39 @@ pub mod pallet {
 		Approval<T::Balance, DepositBalanceOf<T, I>>,
 	>;
 
+	#[pallet::storage]
+	/// Holds on the balance of a specific account for a specific asset.
+	///
+	/// Held funds are not part of the account's `balance` but still count towards its total
+	/// balance and the asset's supply.
+	pub(super) type Holds<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<
+			IdAmount<T::RuntimeHoldReason, T::Balance>,
+			VariantCountOf<T::RuntimeHoldReason>,
+		>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	/// Freezes on the balance of a specific account for a specific asset.
+	pub(super) type Freezes<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<
+			IdAmount<T::RuntimeFreezeReason, T::Balance>,
+			VariantCountOf<T::RuntimeFreezeReason>,
+		>,
+		ValueQuery,
+	>;
+
 	#[pallet::storage]
 	/// Metadata of an asset.
 	pub(super) type Metadata<T: Config<I>, I: 'static = ()> = StorageMap<
@@ -622,6 +667,12 @@ pub mod pallet {
 		NotFrozen,
 		/// Callback action resulted in error
 		CallbackFailed,
+		/// Number of holds on an asset-account exceeds `VariantCountOf<T::RuntimeHoldReason>`.
+		TooManyHolds,
+		/// Number of freezes on an asset-account exceeds `VariantCountOf<T::RuntimeFreezeReason>`.
+		TooManyFreezes,
+		/// The asset-account still has funds on hold.
+		ContainsHolds,
 	}
 
 	#[pallet::call(weight(<T as Config<I>>::WeightInfo))]
@@ -860,7 +911,7 @@ pub mod pallet {
 			let who = T::Lookup::lookup(who)?;
 			let id: T::AssetId = id.into();
 
-			let f = DebitFlags { keep_alive: false, best_effort: true };
+			let f = DebitFlags { keep_alive: false, best_effort: true, force: false };
 			let _ = Self::do_burn(id, &who, amount, Some(origin), f)?;
 			Ok(())
 		}
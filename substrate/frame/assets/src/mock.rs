@@ -20,13 +20,13 @@
 use super::*;
 use crate as pallet_assets;
 
-use codec::Encode;
+use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
 	construct_runtime, derive_impl, parameter_types,
-	traits::{AsEnsureOriginWithArg, ConstU32, ConstU64},
+	traits::{AsEnsureOriginWithArg, ConstU32, ConstU64, VariantCount},
 };
 use sp_io::storage;
-use sp_runtime::BuildStorage;
+use sp_runtime::{BuildStorage, RuntimeDebug};
 
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -108,12 +108,25 @@ impl AssetsCallbackHandle {
 	}
 }
 
+/// Reasons for holds and freezes placed on asset balances in tests.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub enum TestId {
+	Foo,
+	Bar,
+}
+
+impl VariantCount for TestId {
+	const VARIANT_COUNT: u32 = 2;
+}
+
 #[derive_impl(crate::config_preludes::TestDefaultConfig)]
 impl Config for Test {
 	type Currency = Balances;
 	type CreateOrigin = AsEnsureOriginWithArg<frame_system::EnsureSigned<u64>>;
 	type ForceOrigin = frame_system::EnsureRoot<u64>;
 	type Freezer = TestFreezer;
+	type RuntimeHoldReason = TestId;
+	type RuntimeFreezeReason = TestId;
 	type CallbackHandle = AssetsCallbackHandle;
 }
 
@@ -22,7 +22,15 @@ use crate::{mock::*, Error};
 use frame_support::{
 	assert_noop, assert_ok,
 	dispatch::GetDispatchInfo,
-	traits::{fungibles::InspectEnumerable, tokens::Preservation::Protect, Currency},
+	traits::{
+		fungibles::InspectEnumerable,
+		tokens::{
+			Fortitude::{Force, Polite},
+			Precision::Exact,
+			Preservation::Protect,
+		},
+		Currency,
+	},
 };
 use pallet_balances::Error as BalancesError;
 use sp_io::storage;
@@ -1777,3 +1785,132 @@ fn asset_destroy_refund_existence_deposit() {
 		assert_eq!(Balances::reserved_balance(&admin), 0);
 	});
 }
+
+#[test]
+fn hold_and_release_work() {
+	use frame_support::traits::fungibles::{Inspect, InspectHold, MutateHold};
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(1), 0, 1, 100));
+
+		assert_ok!(<Assets as MutateHold<u64>>::hold(0, &TestId::Foo, &1, 50));
+		assert_eq!(Assets::balance(0, 1), 50);
+		assert_eq!(<Assets as Inspect<u64>>::total_balance(0, &1), 100);
+		assert_eq!(<Assets as InspectHold<u64>>::balance_on_hold(0, &TestId::Foo, &1), 50);
+		assert_eq!(Assets::total_supply(0), 100);
+
+		// The main balance may not drop below the minimum balance.
+		assert_noop!(
+			<Assets as MutateHold<u64>>::hold(0, &TestId::Bar, &1, 45),
+			TokenError::FundsUnavailable
+		);
+		assert_ok!(<Assets as MutateHold<u64>>::hold(0, &TestId::Bar, &1, 40));
+		assert_eq!(Assets::total_balance_on_hold(0, &1), 90);
+
+		assert_ok!(<Assets as MutateHold<u64>>::release(0, &TestId::Foo, &1, 20, Exact));
+		assert_eq!(Assets::balance(0, 1), 30);
+		assert_eq!(<Assets as InspectHold<u64>>::balance_on_hold(0, &TestId::Foo, &1), 30);
+
+		assert_ok!(<Assets as MutateHold<u64>>::burn_held(0, &TestId::Bar, &1, 40, Exact, Polite));
+		assert_eq!(Assets::total_supply(0), 60);
+		assert_eq!(Holds::<Test>::get(0, 1).len(), 1);
+	});
+}
+
+#[test]
+fn holds_keep_the_account_alive() {
+	use frame_support::traits::fungibles::MutateHold;
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(1), 0, 1, 100));
+		assert_ok!(<Assets as MutateHold<u64>>::hold(0, &TestId::Foo, &1, 30));
+
+		// The whole main balance can't be transferred out while funds are on hold.
+		assert_noop!(
+			Assets::transfer(RuntimeOrigin::signed(1), 0, 2, 70),
+			Error::<Test>::BalanceLow
+		);
+		assert_ok!(Assets::transfer(RuntimeOrigin::signed(1), 0, 2, 60));
+		assert_eq!(Assets::balance(0, 1), 10);
+
+		// Nor can the account be refunded.
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), 1, 1, false, 1));
+		Balances::make_free_balance_be(&3, 100);
+		assert_ok!(Assets::touch(RuntimeOrigin::signed(3), 1));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(1), 1, 3, 10));
+		assert_ok!(<Assets as MutateHold<u64>>::hold(1, &TestId::Foo, &3, 5));
+		assert_noop!(
+			Assets::refund(RuntimeOrigin::signed(3), 1, true),
+			Error::<Test>::ContainsHolds
+		);
+
+		assert_ok!(<Assets as MutateHold<u64>>::release(1, &TestId::Foo, &3, 5, Exact));
+		assert_ok!(Assets::refund(RuntimeOrigin::signed(3), 1, true));
+	});
+}
+
+#[test]
+fn freezes_apply_to_the_total_balance() {
+	use frame_support::traits::{
+		fungibles::{Inspect, InspectFreeze, MutateFreeze, MutateHold},
+		tokens::Preservation::Expendable,
+	};
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(1), 0, 1, 100));
+
+		assert_ok!(<Assets as MutateFreeze<u64>>::set_freeze(0, &TestId::Foo, &1, 60));
+		assert_ok!(<Assets as MutateFreeze<u64>>::extend_freeze(0, &TestId::Bar, &1, 50));
+		assert_eq!(<Assets as InspectFreeze<u64>>::balance_frozen(0, &TestId::Foo, &1), 60);
+		assert_eq!(<Assets as Inspect<u64>>::reducible_balance(0, &1, Expendable, Polite), 40);
+		assert_noop!(
+			Assets::transfer(RuntimeOrigin::signed(1), 0, 2, 41),
+			Error::<Test>::BalanceLow
+		);
+
+		// Frozen funds may be put on hold, they still count towards the freeze.
+		assert_ok!(<Assets as MutateHold<u64>>::hold(0, &TestId::Foo, &1, 30));
+		assert_eq!(<Assets as Inspect<u64>>::reducible_balance(0, &1, Expendable, Polite), 40);
+		assert_eq!(<Assets as Inspect<u64>>::reducible_balance(0, &1, Expendable, Force), 60);
+
+		assert_ok!(<Assets as MutateFreeze<u64>>::thaw(0, &TestId::Foo, &1));
+		assert_eq!(<Assets as Inspect<u64>>::reducible_balance(0, &1, Expendable, Polite), 50);
+		assert_ok!(<Assets as MutateFreeze<u64>>::thaw(0, &TestId::Bar, &1));
+		assert!(!Freezes::<Test>::contains_key(0, 1));
+	});
+}
+
+#[test]
+fn destroy_accounts_removes_holds_and_freezes() {
+	use frame_support::traits::fungibles::{MutateFreeze, MutateHold};
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), 0, 1, true, 10));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(1), 0, 1, 100));
+		assert_ok!(<Assets as MutateHold<u64>>::hold(0, &TestId::Foo, &1, 30));
+		assert_ok!(<Assets as MutateFreeze<u64>>::set_freeze(0, &TestId::Foo, &1, 50));
+
+		assert_ok!(Assets::start_destroy(RuntimeOrigin::signed(1), 0));
+		assert_ok!(Assets::destroy_accounts(RuntimeOrigin::signed(1), 0));
+		assert!(!Holds::<Test>::contains_key(0, 1));
+		assert!(!Freezes::<Test>::contains_key(0, 1));
+		assert_ok!(Assets::destroy_approvals(RuntimeOrigin::signed(1), 0));
+		assert_ok!(Assets::finish_destroy(RuntimeOrigin::signed(1), 0));
+	});
+}
+
+#[test]
+fn freezes_require_an_existing_account() {
+	use frame_support::traits::fungibles::{InspectFreeze, MutateFreeze};
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), 0, 1, true, 10));
+		assert!(!<Assets as InspectFreeze<u64>>::can_freeze(0, &TestId::Foo, &2));
+		assert_noop!(
+			<Assets as MutateFreeze<u64>>::set_freeze(0, &TestId::Foo, &2, 10),
+			Error::<Test>::NoAccount
+		);
+
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(1), 0, 2, 100));
+		assert!(<Assets as InspectFreeze<u64>>::can_freeze(0, &TestId::Foo, &2));
+		assert_ok!(<Assets as MutateFreeze<u64>>::set_freeze(0, &TestId::Foo, &2, 10));
+	});
+}
@@ -232,6 +232,15 @@ impl<AssetId, AccountId, Balance> FrozenBalance<AssetId, AccountId, Balance> for
 	fn died(_: AssetId, _: &AccountId) {}
 }
 
+/// An identifier and balance, used for holds and freezes on an asset-account.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct IdAmount<Id, Balance> {
+	/// An identifier for this item.
+	pub id: Id,
+	/// Some amount for this item.
+	pub amount: Balance,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub(super) struct TransferFlags {
 	/// The debited account must stay alive at the end of the operation; an error is returned if
@@ -255,11 +264,14 @@ pub(super) struct DebitFlags {
 	/// successful. If `false`, then the amount debited will always be at least the amount
 	/// specified.
 	pub(super) best_effort: bool,
+	/// Freezes placed on the account through `fungibles::MutateFreeze` may be ignored. The
+	/// `T::Freezer` hook is always respected.
+	pub(super) force: bool,
 }
 
 impl From<TransferFlags> for DebitFlags {
 	fn from(f: TransferFlags) -> Self {
-		Self { keep_alive: f.keep_alive, best_effort: f.best_effort }
+		Self { keep_alive: f.keep_alive, best_effort: f.best_effort, force: false }
 	}
 }
 
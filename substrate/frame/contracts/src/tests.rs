@@ -399,6 +399,7 @@ impl pallet_proxy::Config for Test {
 	type CallHasher = BlakeTwo256;
 	type AnnouncementDepositBase = ConstU64<1>;
 	type AnnouncementDepositFactor = ConstU64<1>;
+	type ProxyLimitsDeposit = ConstU64<1>;
 }
 
 impl pallet_dummy::Config for Test {}
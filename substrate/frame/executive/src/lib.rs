@@ -39,6 +39,15 @@
 //!
 //! The flow of their application in a block is explained in the [block flowchart](block_flowchart).
 //!
+//! Extrinsics are validated and applied through [`Applyable`]. For the extrinsic types of
+//! [`sp_runtime::generic`], this runs the
+//! [`TransactionExtension`](sp_runtime::traits::TransactionExtension) pipeline of the extrinsic,
+//! which determines the origin the call is dispatched with. Extrinsics carrying a
+//! [`SignedExtension`](sp_runtime::traits::SignedExtension) run it through
+//! [`AsTransactionExtension`](sp_runtime::traits::AsTransactionExtension), while runtimes using
+//! [`UncheckedTransaction`](sp_runtime::generic::UncheckedTransaction) as their extrinsic can also
+//! include general transactions, which are authorized by their extension instead of a signature.
+//!
 //! ### Implementations
 //!
 //! The Executive module provides the following implementations:
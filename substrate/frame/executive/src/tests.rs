@@ -502,6 +502,46 @@ fn balance_transfer_dispatch_works() {
 	});
 }
 
+#[test]
+fn signed_extrinsic_fee_is_charged_once() {
+	type Uxt = sp_runtime::generic::UncheckedExtrinsic<
+		u64,
+		RuntimeCall,
+		sp_runtime::testing::TestSignature,
+		SignedExtra,
+	>;
+
+	new_test_ext(1).execute_with(|| {
+		Executive::initialize_block(&Header::new_from_number(1));
+
+		let call = RuntimeCall::Custom(custom::Call::some_function {});
+		let payload = sp_runtime::generic::SignedPayload::new(call.clone(), extra(0, 0)).unwrap();
+		let signature = sp_runtime::testing::TestSignature(1, payload.encode());
+		let uxt = Uxt::new_signed(call, 1, signature, extra(0, 0));
+		let len = uxt.encoded_size();
+
+		let xt = Checkable::check(uxt, &ChainContext::<Runtime>::default()).unwrap();
+		let info = xt.get_dispatch_info();
+		let fee = TransactionPayment::compute_fee(len as u32, &info, 0);
+		assert!(fee > 0);
+
+		assert_ok!(Applyable::apply::<Runtime>(xt, &info, len).unwrap());
+		assert_eq!(<pallet_balances::Pallet<Runtime>>::total_balance(&1), 111 - fee);
+		assert_eq!(
+			System::events()
+				.into_iter()
+				.filter(|r| matches!(
+					r.event,
+					RuntimeEvent::TransactionPayment(
+						pallet_transaction_payment::Event::TransactionFeePaid { .. }
+					)
+				))
+				.count(),
+			1,
+		);
+	});
+}
+
 fn new_test_ext(balance_factor: Balance) -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
 	pallet_balances::GenesisConfig::<Runtime> { balances: vec![(1, 111 * balance_factor)] }
@@ -1387,3 +1427,135 @@ fn is_inherent_works() {
 	let ext = TestXt::new(RuntimeCall::Custom2(custom2::Call::allowed_unsigned {}), None);
 	assert!(!Runtime::is_inherent(&ext), "Unsigned ext are not automatically inherents");
 }
+
+/// A runtime whose transactions are authorized by their extension instead of a signature.
+mod general_transactions {
+	use super::*;
+	use sp_runtime::{
+		generic::UncheckedTransaction,
+		testing::TestSignature,
+		traits::{
+			BlakeTwo256, DispatchInfoOf, Hash, TransactionExtension, TransactionExtensionBase,
+			ValidateResult,
+		},
+	};
+
+	/// Authorizes the transaction on behalf of the given account, unless it is `0` in which case
+	/// the origin is left unchanged.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct AuthorizeAs(u64);
+
+	impl TransactionExtensionBase for AuthorizeAs {
+		const IDENTIFIER: &'static str = "AuthorizeAs";
+		type Implicit = ();
+
+		fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+			Ok(())
+		}
+	}
+
+	impl TransactionExtension<RuntimeCall> for AuthorizeAs {
+		type Val = ();
+		type Pre = ();
+
+		fn validate(
+			&self,
+			origin: RuntimeOrigin,
+			_call: &RuntimeCall,
+			_info: &DispatchInfoOf<RuntimeCall>,
+			_len: usize,
+			_inherited_implication: &impl Encode,
+		) -> ValidateResult<Self::Val, RuntimeCall> {
+			if self.0 == 0 {
+				return Ok((ValidTransaction::default(), (), origin))
+			}
+			Ok((ValidTransaction::default(), (), RuntimeOrigin::signed(self.0)))
+		}
+
+		fn prepare(
+			self,
+			_val: Self::Val,
+			_origin: &RuntimeOrigin,
+			_call: &RuntimeCall,
+			_info: &DispatchInfoOf<RuntimeCall>,
+			_len: usize,
+		) -> Result<Self::Pre, TransactionValidityError> {
+			Ok(())
+		}
+	}
+
+	type Extrinsic = UncheckedTransaction<u64, RuntimeCall, TestSignature, AuthorizeAs>;
+	type TestBlock = Block<Extrinsic>;
+
+	frame_support::construct_runtime!(
+		pub enum Runtime {
+			System: frame_system,
+		}
+	);
+
+	#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+	impl frame_system::Config for Runtime {
+		type Block = TestBlock;
+	}
+
+	type Executive =
+		crate::Executive<Runtime, TestBlock, ChainContext<Runtime>, Runtime, AllPalletsWithSystem>;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::<Runtime>::default()
+			.build_storage()
+			.unwrap()
+			.into()
+	}
+
+	fn remark() -> RuntimeCall {
+		RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![1] })
+	}
+
+	#[test]
+	fn general_transaction_is_dispatched_with_origin_of_extension() {
+		let xt = Extrinsic::new_general(remark(), AuthorizeAs(1));
+		assert!(!Runtime::is_inherent(&xt));
+
+		new_test_ext().execute_with(|| {
+			assert_ok!(Executive::validate_transaction(
+				TransactionSource::External,
+				xt.clone(),
+				H256::random(),
+			));
+		});
+
+		new_test_ext().execute_with(|| {
+			Executive::initialize_block(&Header::new_from_number(1));
+			assert_ok!(Executive::apply_extrinsic(xt).unwrap());
+			System::assert_last_event(
+				frame_system::Event::Remarked { sender: 1, hash: BlakeTwo256::hash(&[1]) }.into(),
+			);
+		});
+	}
+
+	#[test]
+	fn general_transaction_without_authorization_is_invalid() {
+		// Would be dispatched with the `None` origin, as if it was validated as unsigned.
+		let xt = Extrinsic::new_general(remark(), AuthorizeAs(0));
+
+		new_test_ext().execute_with(|| {
+			assert_eq!(
+				Executive::validate_transaction(
+					TransactionSource::External,
+					xt.clone(),
+					H256::random()
+				),
+				Err(InvalidTransaction::UnknownOrigin.into()),
+			);
+		});
+
+		new_test_ext().execute_with(|| {
+			Executive::initialize_block(&Header::new_from_number(1));
+			assert_eq!(
+				Executive::apply_extrinsic(xt),
+				Err(InvalidTransaction::UnknownOrigin.into())
+			);
+		});
+	}
+}
@@ -47,6 +47,30 @@ fn setup_multi<T: Config>(
 	Ok((signatories, Box::new(call)))
 }
 
+fn setup_bounded_call<T: Config>() -> Result<(BoundedCallOf<T>, [u8; 32]), &'static str> {
+	// A remark short enough for the call to be stored inline.
+	let call: <T as Config>::RuntimeCall =
+		frame_system::Call::<T>::remark { remark: vec![0; 100] }.into();
+	let call_hash = call.using_encoded(blake2_256);
+	let inline = call.encode().try_into().map_err(|_| "call should fit inline")?;
+	Ok((frame_support::traits::Bounded::Inline(inline), call_hash))
+}
+
+fn setup_preimage_call<T: Config>() -> Result<(BoundedCallOf<T>, [u8; 32]), &'static str> {
+	// Referencing the call by its preimage is the worst case of proposing it, as the preimage is
+	// checked for and requested, unless the runtime has no preimage provider.
+	let call: <T as Config>::RuntimeCall =
+		frame_system::Call::<T>::remark { remark: vec![0; 100] }.into();
+	match T::Preimages::note(call.encode().into()) {
+		Ok(hash) => {
+			let call_hash = hash.as_ref().try_into().map_err(|_| "hash should be 32 bytes")?;
+			let len = call.encoded_size() as u32;
+			Ok((frame_support::traits::Bounded::Lookup { hash, len }, call_hash))
+		},
+		Err(_) => setup_bounded_call::<T>(),
+	}
+}
+
 benchmarks! {
 	as_multi_threshold_1 {
 		// Transaction Length
@@ -210,5 +234,90 @@ benchmarks! {
 		assert!(!Multisigs::<T>::contains_key(multi_account_id, call_hash));
 	}
 
+	propose_as_multi {
+		// Signatories, need at least 2 people
+		let s in 2 .. T::MaxSignatories::get();
+		let (mut signatories, _) = setup_multi::<T>(s, 0)?;
+		let (call, call_hash) = setup_preimage_call::<T>()?;
+		let multi_account_id = Multisig::<T>::multi_account_id(&signatories, s.try_into().unwrap());
+		// The caller account is not whitelisted, so that the deposit reserve is accounted for.
+		let caller = signatories.pop().ok_or("signatories should have len 2 or more")?;
+	}: _(RawOrigin::Signed(caller), s as u16, signatories, call)
+	verify {
+		assert!(Calls::<T>::contains_key(multi_account_id, call_hash));
+	}
+
+	approve_as_multi_complete {
+		// Signatories, need at least 2 people
+		let s in 2 .. T::MaxSignatories::get();
+		let (mut signatories, _) = setup_multi::<T>(s, 0)?;
+		let (call, call_hash) = setup_bounded_call::<T>()?;
+		let multi_account_id = Multisig::<T>::multi_account_id(&signatories, s.try_into().unwrap());
+		let mut signatories2 = signatories.clone();
+		let caller = signatories.pop().ok_or("signatories should have len 2 or more")?;
+		// before the call, get the timepoint
+		let timepoint = Multisig::<T>::timepoint();
+		// Create the multi, storing the call
+		Multisig::<T>::propose_as_multi(RawOrigin::Signed(caller).into(), s as u16, signatories, call)?;
+		// Everyone except the first person approves
+		for i in 1 .. s - 1 {
+			let mut signatories_loop = signatories2.clone();
+			let caller_loop = signatories_loop.remove(i as usize);
+			let o = RawOrigin::Signed(caller_loop).into();
+			Multisig::<T>::approve_as_multi(o, s as u16, signatories_loop, Some(timepoint), call_hash, Weight::zero())?;
+		}
+		let caller2 = signatories2.remove(0);
+		assert!(Multisigs::<T>::contains_key(&multi_account_id, call_hash));
+		// Whitelist caller account from further DB operations.
+		let caller_key = frame_system::Account::<T>::hashed_key_for(&caller2);
+		frame_benchmarking::benchmarking::add_to_whitelist(caller_key.into());
+	}: approve_as_multi(RawOrigin::Signed(caller2), s as u16, signatories2, Some(timepoint), call_hash, Weight::MAX)
+	verify {
+		assert!(!Multisigs::<T>::contains_key(&multi_account_id, call_hash));
+		assert!(!Calls::<T>::contains_key(&multi_account_id, call_hash));
+	}
+
+	create_managed_multisig {
+		// Signatories, need at least 2 people
+		let s in 2 .. T::MaxSignatories::get();
+		let (mut signatories, _) = setup_multi::<T>(s, 0)?;
+		let caller = signatories.pop().ok_or("signatories should have len 2 or more")?;
+		let multisig = Multisig::<T>::managed_account_id(&caller, 0);
+		// The caller account is not whitelisted, so that the deposit reserve is accounted for.
+	}: _(RawOrigin::Signed(caller), s as u16, signatories, 0)
+	verify {
+		assert!(ManagedMultisigs::<T>::contains_key(multisig));
+	}
+
+	set_managed_multisig {
+		// Signatories, need at least 2 people
+		let s in 2 .. T::MaxSignatories::get();
+		let (mut signatories, _) = setup_multi::<T>(s, 0)?;
+		let new_signatories = signatories.clone();
+		let caller = signatories.pop().ok_or("signatories should have len 2 or more")?;
+		let multisig = Multisig::<T>::managed_account_id(&caller, 0);
+		Multisig::<T>::create_managed_multisig(RawOrigin::Signed(caller).into(), 2, signatories, 0)?;
+		T::Currency::make_free_balance_be(&multisig, BalanceOf::<T>::max_value());
+	}: _(RawOrigin::Signed(multisig.clone()), new_signatories, s as u16)
+	verify {
+		let managed = ManagedMultisigs::<T>::get(&multisig).ok_or("managed multisig not created")?;
+		assert_eq!(managed.threshold, s as u16);
+		assert_eq!(managed.depositor, multisig);
+	}
+
+	dissolve_managed_multisig {
+		// Signatories, need at least 2 people
+		let s in 2 .. T::MaxSignatories::get();
+		let (mut signatories, _) = setup_multi::<T>(s, 0)?;
+		let caller = signatories.pop().ok_or("signatories should have len 2 or more")?;
+		let multisig = Multisig::<T>::managed_account_id(&caller, 0);
+		Multisig::<T>::create_managed_multisig(RawOrigin::Signed(caller.clone()).into(), 2, signatories, 0)?;
+	}: _(RawOrigin::Signed(multisig.clone()))
+	verify {
+		assert!(!ManagedMultisigs::<T>::contains_key(&multisig));
+		assert!(DissolvedManagedMultisigs::<T>::contains_key(&multisig));
+		assert!(T::Currency::reserved_balance(&caller).is_zero());
+	}
+
 	impl_benchmark_test_suite!(Multisig, crate::tests::new_test_ext(), crate::tests::Test);
 }
@@ -31,6 +31,14 @@
 //! operation. This is useful for multisig wallets where cryptographic threshold signatures are
 //! not available or desired.
 //!
+//! An operation may also be proposed together with its call, stored inline or referenced by the
+//! hash of its preimage, so that it is dispatched on the final approval without anyone having to
+//! supply it again.
+//!
+//! Managed multisig accounts have an address which does not depend on their signatories and
+//! threshold. These are kept in storage, and may be changed or dissolved by the managed multisig
+//! itself.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
@@ -39,6 +47,13 @@
 //!   number of signed origins.
 //! * `approve_as_multi` - Approve a call from a composite origin.
 //! * `cancel_as_multi` - Cancel a call from a composite origin.
+//! * `propose_as_multi` - Approve a call from a composite origin, storing it for dispatch on the
+//!   final approval.
+//! * `create_managed_multisig` - Create a managed multisig account.
+//! * `set_managed_multisig` - Change the signatories and threshold of a managed multisig account.
+//! * `dissolve_managed_multisig` - Dissolve a managed multisig account, releasing its deposit.
+//! * `as_managed_multi`, `approve_as_managed_multi`, `cancel_as_managed_multi` and
+//!   `propose_as_managed_multi` - As their counterparts above, for a managed multisig account.
 
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -55,7 +70,7 @@ use frame_support::{
 		PostDispatchInfo,
 	},
 	ensure,
-	traits::{Currency, Get, ReservableCurrency},
+	traits::{Bounded, Currency, Get, QueryPreimage, ReservableCurrency, StorePreimage},
 	weights::Weight,
 	BoundedVec,
 };
@@ -88,6 +103,10 @@ macro_rules! log {
 type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+/// A call of the runtime, stored inline or referenced by the hash of its preimage.
+pub type BoundedCallOf<T> =
+	Bounded<<T as Config>::RuntimeCall, <T as frame_system::Config>::Hashing>;
+
 /// A global extrinsic index, formed as the extrinsic index within a block, together with that
 /// block's height. This allows a transaction in which a multisig operation of a particular
 /// composite was created to be uniquely identified.
@@ -118,6 +137,25 @@ where
 	approvals: BoundedVec<AccountId, MaxApprovals>,
 }
 
+/// A multisig account whose signatories and threshold are kept in storage, rather than being
+/// baked into its address, so that the account itself may change them.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MaxSignatories))]
+pub struct ManagedMultisig<Balance, AccountId, MaxSignatories>
+where
+	MaxSignatories: Get<u32>,
+{
+	/// The accounts which may approve operations of the multisig. Always sorted.
+	signatories: BoundedVec<AccountId, MaxSignatories>,
+	/// The number of approvals of current signatories an operation needs to be executed.
+	threshold: u16,
+	/// The account whose deposit is held for the multisig; its creator until the multisig
+	/// changes itself.
+	depositor: AccountId,
+	/// The amount held in reserve of the `depositor`.
+	deposit: Balance,
+}
+
 type CallHash = [u8; 32];
 
 enum CallOrHash<T: Config> {
@@ -160,10 +198,25 @@ pub mod pallet {
 		#[pallet::constant]
 		type DepositFactor: Get<BalanceOf<Self>>;
 
+		/// The amount of currency burnt from the deposit of a managed multisig when it is
+		/// dissolved, for the storage item kept so that it may not be created anew.
+		///
+		/// This is paid for an additional storage item whose value is empty and whose key size is
+		/// `8 + sizeof(AccountId)` bytes. The deposit of a managed multisig is never less.
+		#[pallet::constant]
+		type DissolvedDeposit: Get<BalanceOf<Self>>;
+
 		/// The maximum amount of signatories allowed in the multisig.
 		#[pallet::constant]
 		type MaxSignatories: Get<u32>;
 
+		/// The provider of preimages, through which stored calls too large to be kept inline are
+		/// looked up.
+		///
+		/// With `()`, only calls small enough to be stored inline may be proposed; calls
+		/// referenced by the hash of a preimage are rejected as unavailable.
+		type Preimages: QueryPreimage<H = Self::Hashing> + StorePreimage;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -186,6 +239,32 @@ pub mod pallet {
 		Multisig<BlockNumberFor<T>, BalanceOf<T>, T::AccountId, T::MaxSignatories>,
 	>;
 
+	/// The calls stored for open multisig operations, dispatched on their final approval.
+	#[pallet::storage]
+	pub type Calls<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		[u8; 32],
+		BoundedCallOf<T>,
+	>;
+
+	/// The signatories and threshold of the managed multisig accounts.
+	#[pallet::storage]
+	pub type ManagedMultisigs<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		ManagedMultisig<BalanceOf<T>, T::AccountId, T::MaxSignatories>,
+	>;
+
+	/// The managed multisig accounts which have been dissolved, and so may not be created anew.
+	///
+	/// Each entry is paid for by burning `DissolvedDeposit` from the deposit of the multisig.
+	#[pallet::storage]
+	pub type DissolvedManagedMultisigs<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, ()>;
+
 	#[pallet::error]
 	pub enum Error<T> {
 		/// Threshold must be 2 or greater.
@@ -216,6 +295,18 @@ pub mod pallet {
 		MaxWeightTooLow,
 		/// The data to be stored is already stored.
 		AlreadyStored,
+		/// The call of the operation is not available or could not be decoded.
+		CallUnavailable,
+		/// Threshold must not exceed the number of signatories.
+		ThresholdTooHigh,
+		/// The account is not a managed multisig.
+		NotManaged,
+		/// The sender is not a signatory of the managed multisig.
+		NotSignatory,
+		/// A managed multisig already exists with the given index.
+		AlreadyExists,
+		/// The managed multisig with the given index has been dissolved.
+		Dissolved,
 	}
 
 	#[pallet::event]
@@ -245,6 +336,12 @@ pub mod pallet {
 			multisig: T::AccountId,
 			call_hash: CallHash,
 		},
+		/// A managed multisig account has been created.
+		ManagedMultisigCreated { creator: T::AccountId, multisig: T::AccountId, threshold: u16 },
+		/// The signatories and threshold of a managed multisig account have been changed.
+		ManagedMultisigChanged { multisig: T::AccountId, threshold: u16 },
+		/// A managed multisig account has been dissolved.
+		ManagedMultisigDissolved { multisig: T::AccountId },
 	}
 
 	#[pallet::hooks]
@@ -398,7 +495,9 @@ pub mod pallet {
 		/// transaction index) of the first approval transaction.
 		/// - `call_hash`: The hash of the call to be executed.
 		///
-		/// NOTE: If this is the final approval, you will want to use `as_multi` instead.
+		/// NOTE: If this is the final approval, you will want to use `as_multi` instead, unless
+		/// the call was stored with `propose_as_multi`, in which case it is dispatched with this
+		/// approval. `max_weight` must then also cover looking up a call stored as a preimage.
 		///
 		/// ## Complexity
 		/// - `O(S)`.
@@ -417,6 +516,7 @@ pub mod pallet {
 
 			T::WeightInfo::approve_as_multi_create(s)
 				.max(T::WeightInfo::approve_as_multi_approve(s))
+				.max(T::WeightInfo::approve_as_multi_complete(s))
 				.saturating_add(*max_weight)
 		})]
 		pub fn approve_as_multi(
@@ -469,30 +569,305 @@ pub mod pallet {
 			call_hash: [u8; 32],
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
-			ensure!(threshold >= 2, Error::<T>::MinimumThreshold);
-			let max_sigs = T::MaxSignatories::get() as usize;
-			ensure!(!other_signatories.is_empty(), Error::<T>::TooFewSignatories);
-			ensure!(other_signatories.len() < max_sigs, Error::<T>::TooManySignatories);
-			let signatories = Self::ensure_sorted_and_insert(other_signatories, who.clone())?;
+			let id = Self::ensure_multi_account_id(&who, threshold, other_signatories)?;
+			Self::cancel(who, id, timepoint, call_hash)
+		}
 
-			let id = Self::multi_account_id(&signatories, threshold);
+		/// Register approval for a dispatch to be made from a deterministic composite account if
+		/// approved by a total of `threshold - 1` of `other_signatories`, storing the call so
+		/// that it is dispatched on the final approval, even if that is given with
+		/// `approve_as_multi`.
+		///
+		/// Payment: `DepositBase` will be reserved, plus `threshold` times `DepositFactor`. It is
+		/// returned once this dispatch happens or is cancelled.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `threshold`: The total number of approvals for this dispatch before it is executed.
+		/// - `other_signatories`: The accounts (other than the sender) who can approve this
+		/// dispatch. May not be empty.
+		/// - `call`: The call to be executed, either inline or referenced by the hash of a
+		/// preimage which has already been noted. The preimage is requested until the operation
+		/// ends.
+		///
+		/// The operation must not be underway yet. Its `call_hash` is the `blake2_256` hash of an
+		/// inline call, or else the hash of the preimage.
+		///
+		/// ## Complexity
+		/// - `O(S)`.
+		/// - One balance-reserve operation.
+		/// - One decode of an inline call, of complexity `O(Z)` where `Z` is its length, which is
+		///   bounded.
+		/// - I/O: 1 read `O(S)`, 2 inserts. Up to one preimage request.
+		/// - One event.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::propose_as_multi(other_signatories.len() as u32))]
+		pub fn propose_as_multi(
+			origin: OriginFor<T>,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			call: BoundedCallOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let id = Self::ensure_multi_account_id(&who, threshold, other_signatories)?;
+			Self::propose(who, id, threshold, call)
+		}
 
-			let m = <Multisigs<T>>::get(&id, call_hash).ok_or(Error::<T>::NotFound)?;
-			ensure!(m.when == timepoint, Error::<T>::WrongTimepoint);
-			ensure!(m.depositor == who, Error::<T>::NotOwner);
+		/// Create a managed multisig account, whose address is derived from the sender and
+		/// `index` rather than from its signatories and threshold.
+		///
+		/// Payment: `DepositBase` will be reserved, plus the number of signatories times
+		/// `DepositFactor`.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `threshold`: The number of approvals an operation of the multisig needs before it
+		/// is executed.
+		/// - `other_signatories`: The accounts (other than the sender) who can approve
+		/// operations of the multisig. May not be empty.
+		/// - `index`: A disambiguation index, in case the sender wants to create several managed
+		/// multisigs. An index whose managed multisig has been dissolved may not be used again.
+		///
+		/// ## Complexity
+		/// - `O(S)`.
+		/// - One balance-reserve operation.
+		/// - I/O: 2 reads, 1 insert `O(S)`.
+		/// - One event.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::create_managed_multisig(other_signatories.len() as u32))]
+		pub fn create_managed_multisig(
+			origin: OriginFor<T>,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			index: u16,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let signatories = Self::ensure_sorted_and_insert(other_signatories, who.clone())?;
+			let signatories = Self::ensure_managed_signatories(signatories, threshold)?;
 
-			let err_amount = T::Currency::unreserve(&m.depositor, m.deposit);
-			debug_assert!(err_amount.is_zero());
-			<Multisigs<T>>::remove(&id, &call_hash);
+			let id = Self::managed_account_id(&who, index);
+			ensure!(!<ManagedMultisigs<T>>::contains_key(&id), Error::<T>::AlreadyExists);
+			ensure!(!<DissolvedManagedMultisigs<T>>::contains_key(&id), Error::<T>::Dissolved);
+
+			let deposit = Self::managed_deposit(signatories.len());
+			T::Currency::reserve(&who, deposit)?;
 
-			Self::deposit_event(Event::MultisigCancelled {
-				cancelling: who,
-				timepoint,
+			<ManagedMultisigs<T>>::insert(
+				&id,
+				ManagedMultisig { signatories, threshold, depositor: who.clone(), deposit },
+			);
+			Self::deposit_event(Event::ManagedMultisigCreated {
+				creator: who,
 				multisig: id,
-				call_hash,
+				threshold,
 			});
 			Ok(())
 		}
+
+		/// Change the signatories and threshold of a managed multisig account.
+		///
+		/// The deposit of the multisig is returned to its depositor, and taken anew from the
+		/// multisig account itself.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the managed multisig account,
+		/// i.e. it must be dispatched by an operation of the multisig.
+		///
+		/// - `signatories`: All the accounts who can approve operations of the multisig, sorted.
+		/// - `threshold`: The number of approvals an operation of the multisig needs before it
+		/// is executed.
+		///
+		/// Operations already underway remain so, but only the approvals of the new signatories
+		/// count towards executing them.
+		///
+		/// ## Complexity
+		/// - `O(S)`.
+		/// - One balance-unreserve and one balance-reserve operation.
+		/// - I/O: 1 read `O(S)`, 1 insert `O(S)`.
+		/// - One event.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::set_managed_multisig(signatories.len() as u32))]
+		pub fn set_managed_multisig(
+			origin: OriginFor<T>,
+			signatories: Vec<T::AccountId>,
+			threshold: u16,
+		) -> DispatchResult {
+			let id = ensure_signed(origin)?;
+			let managed = <ManagedMultisigs<T>>::get(&id).ok_or(Error::<T>::NotManaged)?;
+			let signatories = Self::ensure_managed_signatories(signatories, threshold)?;
+
+			let deposit = Self::managed_deposit(signatories.len());
+			let err_amount = T::Currency::unreserve(&managed.depositor, managed.deposit);
+			debug_assert!(err_amount.is_zero());
+			T::Currency::reserve(&id, deposit)?;
+
+			<ManagedMultisigs<T>>::insert(
+				&id,
+				ManagedMultisig { signatories, threshold, depositor: id.clone(), deposit },
+			);
+			Self::deposit_event(Event::ManagedMultisigChanged { multisig: id, threshold });
+			Ok(())
+		}
+
+		/// Register approval for a dispatch to be made from a managed multisig account, and if
+		/// there are enough approvals of its signatories, dispatch the call.
+		///
+		/// Payment and parameters as for `as_multi`, with the signatories and threshold being
+		/// those of the managed `multisig`, of which the sender must be a signatory.
+		///
+		/// ## Complexity
+		/// As for `as_multi`, with `S` being `MaxSignatories`, plus 1 read.
+		#[pallet::call_index(7)]
+		#[pallet::weight({
+			let s = T::MaxSignatories::get().saturating_sub(1);
+			let z = call.using_encoded(|d| d.len()) as u32;
+
+			T::WeightInfo::as_multi_create(s, z)
+				.max(T::WeightInfo::as_multi_approve(s, z))
+				.max(T::WeightInfo::as_multi_complete(s, z))
+				.saturating_add(T::DbWeight::get().reads(1))
+				.saturating_add(*max_weight)
+		})]
+		pub fn as_managed_multi(
+			origin: OriginFor<T>,
+			multisig: T::AccountId,
+			maybe_timepoint: Option<Timepoint<BlockNumberFor<T>>>,
+			call: Box<<T as Config>::RuntimeCall>,
+			max_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let managed = Self::ensure_managed_signatory(&multisig, &who)?;
+			Self::operate_as(
+				who,
+				multisig,
+				managed.threshold,
+				managed.signatories.len().saturating_sub(1),
+				Some(&managed.signatories),
+				maybe_timepoint,
+				CallOrHash::Call(*call),
+				max_weight,
+			)
+		}
+
+		/// Register approval for a dispatch to be made from a managed multisig account.
+		///
+		/// Payment and parameters as for `approve_as_multi`, with the signatories and threshold
+		/// being those of the managed `multisig`, of which the sender must be a signatory.
+		///
+		/// ## Complexity
+		/// As for `approve_as_multi`, with `S` being `MaxSignatories`, plus 1 read.
+		#[pallet::call_index(8)]
+		#[pallet::weight({
+			let s = T::MaxSignatories::get().saturating_sub(1);
+
+			T::WeightInfo::approve_as_multi_create(s)
+				.max(T::WeightInfo::approve_as_multi_approve(s))
+				.max(T::WeightInfo::approve_as_multi_complete(s))
+				.saturating_add(T::DbWeight::get().reads(1))
+				.saturating_add(*max_weight)
+		})]
+		pub fn approve_as_managed_multi(
+			origin: OriginFor<T>,
+			multisig: T::AccountId,
+			maybe_timepoint: Option<Timepoint<BlockNumberFor<T>>>,
+			call_hash: [u8; 32],
+			max_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let managed = Self::ensure_managed_signatory(&multisig, &who)?;
+			Self::operate_as(
+				who,
+				multisig,
+				managed.threshold,
+				managed.signatories.len().saturating_sub(1),
+				Some(&managed.signatories),
+				maybe_timepoint,
+				CallOrHash::Hash(call_hash),
+				max_weight,
+			)
+		}
+
+		/// Cancel a pre-existing, on-going operation of a managed multisig account. Any deposit
+		/// reserved previously for this operation will be unreserved on success.
+		///
+		/// Parameters as for `cancel_as_multi`, for the managed `multisig`. The sender must have
+		/// opened the operation, but need no longer be a signatory, nor need the multisig still be
+		/// managed, so that operations left underway when it was dissolved can be cancelled.
+		///
+		/// ## Complexity
+		/// As for `cancel_as_multi`, with `S` being `MaxSignatories`.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::cancel_as_multi(T::MaxSignatories::get().saturating_sub(1)))]
+		pub fn cancel_as_managed_multi(
+			origin: OriginFor<T>,
+			multisig: T::AccountId,
+			timepoint: Timepoint<BlockNumberFor<T>>,
+			call_hash: [u8; 32],
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::cancel(who, multisig, timepoint, call_hash)
+		}
+
+		/// Register approval for a dispatch to be made from a managed multisig account, storing
+		/// the call so that it is dispatched on the final approval.
+		///
+		/// Payment and parameters as for `propose_as_multi`, with the signatories and threshold
+		/// being those of the managed `multisig`, of which the sender must be a signatory.
+		///
+		/// ## Complexity
+		/// As for `propose_as_multi`, with `S` being `MaxSignatories`, plus 1 read.
+		#[pallet::call_index(10)]
+		#[pallet::weight(
+			T::WeightInfo::propose_as_multi(T::MaxSignatories::get().saturating_sub(1))
+				.saturating_add(T::DbWeight::get().reads(1))
+		)]
+		pub fn propose_as_managed_multi(
+			origin: OriginFor<T>,
+			multisig: T::AccountId,
+			call: BoundedCallOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let managed = Self::ensure_managed_signatory(&multisig, &who)?;
+			Self::propose(who, multisig, managed.threshold, call)
+		}
+
+		/// Dissolve a managed multisig account, returning its deposit to its depositor less
+		/// `DissolvedDeposit`, which is burnt.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the managed multisig account,
+		/// i.e. it must be dispatched by an operation of the multisig.
+		///
+		/// Operations left underway can no longer be approved, but may still be cancelled with
+		/// `cancel_as_managed_multi` by whoever opened them.
+		///
+		/// WARNING: The account can no longer be operated by anyone once dissolved, as it may
+		/// not be created anew. Any funds left in it should be moved out beforehand, e.g. in a
+		/// batch with this call.
+		///
+		/// ## Complexity
+		/// - `O(S)`.
+		/// - One balance-slash and one balance-unreserve operation.
+		/// - I/O: 1 read `O(S)`, 1 remove, 1 insert.
+		/// - One event.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::dissolve_managed_multisig(T::MaxSignatories::get()))]
+		pub fn dissolve_managed_multisig(origin: OriginFor<T>) -> DispatchResult {
+			let id = ensure_signed(origin)?;
+			let managed = <ManagedMultisigs<T>>::take(&id).ok_or(Error::<T>::NotManaged)?;
+
+			let dissolved_deposit = T::DissolvedDeposit::get().min(managed.deposit);
+			let (_, err_amount) =
+				T::Currency::slash_reserved(&managed.depositor, dissolved_deposit);
+			debug_assert!(err_amount.is_zero());
+			let err_amount = T::Currency::unreserve(
+				&managed.depositor,
+				managed.deposit.saturating_sub(dissolved_deposit),
+			);
+			debug_assert!(err_amount.is_zero());
+			<DissolvedManagedMultisigs<T>>::insert(&id, ());
+
+			Self::deposit_event(Event::ManagedMultisigDissolved { multisig: id });
+			Ok(())
+		}
 	}
 }
 
@@ -507,6 +882,61 @@ impl<T: Config> Pallet<T> {
 			.expect("infinite length input; no invalid inputs for type; qed")
 	}
 
+	/// Derive the account ID of the managed multisig created by `who` with the given `index`.
+	pub fn managed_account_id(who: &T::AccountId, index: u16) -> T::AccountId {
+		let entropy = (b"modlpy/managedms", who, index).using_encoded(blake2_256);
+		Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+			.expect("infinite length input; no invalid inputs for type; qed")
+	}
+
+	/// Check the `threshold` and `other_signatories` of a multisig operation of `who`, and
+	/// derive the multi-account ID they make up.
+	fn ensure_multi_account_id(
+		who: &T::AccountId,
+		threshold: u16,
+		other_signatories: Vec<T::AccountId>,
+	) -> Result<T::AccountId, DispatchError> {
+		ensure!(threshold >= 2, Error::<T>::MinimumThreshold);
+		let max_sigs = T::MaxSignatories::get() as usize;
+		ensure!(!other_signatories.is_empty(), Error::<T>::TooFewSignatories);
+		ensure!(other_signatories.len() < max_sigs, Error::<T>::TooManySignatories);
+		let signatories = Self::ensure_sorted_and_insert(other_signatories, who.clone())?;
+
+		Ok(Self::multi_account_id(&signatories, threshold))
+	}
+
+	/// Check the sorted `signatories` and `threshold` of a managed multisig.
+	fn ensure_managed_signatories(
+		signatories: Vec<T::AccountId>,
+		threshold: u16,
+	) -> Result<BoundedVec<T::AccountId, T::MaxSignatories>, DispatchError> {
+		ensure!(threshold >= 2, Error::<T>::MinimumThreshold);
+		ensure!(signatories.len() >= 2, Error::<T>::TooFewSignatories);
+		ensure!(
+			signatories.windows(2).all(|pair| pair[0] < pair[1]),
+			Error::<T>::SignatoriesOutOfOrder
+		);
+		ensure!(threshold as usize <= signatories.len(), Error::<T>::ThresholdTooHigh);
+		signatories.try_into().map_err(|_| Error::<T>::TooManySignatories.into())
+	}
+
+	/// Get the managed multisig `id`, ensuring `who` is one of its signatories.
+	fn ensure_managed_signatory(
+		id: &T::AccountId,
+		who: &T::AccountId,
+	) -> Result<ManagedMultisig<BalanceOf<T>, T::AccountId, T::MaxSignatories>, DispatchError> {
+		let managed = <ManagedMultisigs<T>>::get(id).ok_or(Error::<T>::NotManaged)?;
+		ensure!(managed.signatories.binary_search(who).is_ok(), Error::<T>::NotSignatory);
+		Ok(managed)
+	}
+
+	/// The deposit held for a managed multisig with `signatories` signatories, which covers the
+	/// `DissolvedDeposit` burnt if it is dissolved.
+	fn managed_deposit(signatories: usize) -> BalanceOf<T> {
+		let deposit = T::DepositBase::get() + T::DepositFactor::get() * (signatories as u32).into();
+		deposit.max(T::DissolvedDeposit::get())
+	}
+
 	fn operate(
 		who: T::AccountId,
 		threshold: u16,
@@ -515,15 +945,35 @@ impl<T: Config> Pallet<T> {
 		call_or_hash: CallOrHash<T>,
 		max_weight: Weight,
 	) -> DispatchResultWithPostInfo {
-		ensure!(threshold >= 2, Error::<T>::MinimumThreshold);
-		let max_sigs = T::MaxSignatories::get() as usize;
-		ensure!(!other_signatories.is_empty(), Error::<T>::TooFewSignatories);
 		let other_signatories_len = other_signatories.len();
-		ensure!(other_signatories_len < max_sigs, Error::<T>::TooManySignatories);
-		let signatories = Self::ensure_sorted_and_insert(other_signatories, who.clone())?;
-
-		let id = Self::multi_account_id(&signatories, threshold);
+		let id = Self::ensure_multi_account_id(&who, threshold, other_signatories)?;
+		Self::operate_as(
+			who,
+			id,
+			threshold,
+			other_signatories_len,
+			None,
+			maybe_timepoint,
+			call_or_hash,
+			max_weight,
+		)
+	}
 
+	/// Approve, and dispatch if it has enough approvals, an operation of the multisig `id`.
+	///
+	/// If `signatories` are given, only their approvals count towards the `threshold`.
+	/// Otherwise all approvals count, as they are of the signatories the multi-account ID was
+	/// derived from.
+	fn operate_as(
+		who: T::AccountId,
+		id: T::AccountId,
+		threshold: u16,
+		other_signatories_len: usize,
+		signatories: Option<&[T::AccountId]>,
+		maybe_timepoint: Option<Timepoint<BlockNumberFor<T>>>,
+		call_or_hash: CallOrHash<T>,
+		max_weight: Weight,
+	) -> DispatchResultWithPostInfo {
 		// Threshold > 1; this means it's a multi-step operation. We extract the `call_hash`.
 		let (call_hash, call_len, maybe_call) = match call_or_hash {
 			CallOrHash::Call(call) => {
@@ -539,6 +989,11 @@ impl<T: Config> Pallet<T> {
 			let timepoint = maybe_timepoint.ok_or(Error::<T>::NoTimepoint)?;
 			ensure!(m.when == timepoint, Error::<T>::WrongTimepoint);
 
+			// Disregard the approvals of those no longer signatories.
+			if let Some(signatories) = signatories {
+				m.approvals.retain(|a| signatories.binary_search(a).is_ok());
+			}
+
 			// Ensure that either we have not yet signed or that it is at threshold.
 			let mut approvals = m.approvals.len() as u16;
 			// We only bother with the approval if we're below threshold.
@@ -548,17 +1003,34 @@ impl<T: Config> Pallet<T> {
 				approvals += 1;
 			}
 
-			// We only bother fetching/decoding call if we know that we're ready to execute.
-			if let Some(call) = maybe_call.filter(|_| approvals >= threshold) {
+			// We only bother fetching/decoding call if we know that we're ready to execute. If
+			// the call is not given, the one stored when the operation was proposed is used.
+			let maybe_call = match maybe_call {
+				Some(call) => Some((
+					call,
+					Weight::zero(),
+					T::WeightInfo::as_multi_complete(other_signatories_len as u32, call_len as u32),
+				)),
+				None if approvals >= threshold =>
+					Self::stored_call(&id, &call_hash, other_signatories_len as u32)?,
+				None => None,
+			};
+			if let Some((call, lookup_weight, complete_weight)) =
+				maybe_call.filter(|_| approvals >= threshold)
+			{
 				// verify weight
 				ensure!(
-					call.get_dispatch_info().weight.all_lte(max_weight),
+					call.get_dispatch_info()
+						.weight
+						.saturating_add(lookup_weight)
+						.all_lte(max_weight),
 					Error::<T>::MaxWeightTooLow
 				);
 
 				// Clean up storage before executing call to avoid an possibility of reentrancy
 				// attack.
 				<Multisigs<T>>::remove(&id, call_hash);
+				Self::clear_call(&id, &call_hash);
 				T::Currency::unreserve(&m.depositor, m.deposit);
 
 				let result = call.dispatch(RawOrigin::Signed(id.clone()).into());
@@ -570,13 +1042,7 @@ impl<T: Config> Pallet<T> {
 					result: result.map(|_| ()).map_err(|e| e.error),
 				});
 				Ok(get_result_weight(result)
-					.map(|actual_weight| {
-						T::WeightInfo::as_multi_complete(
-							other_signatories_len as u32,
-							call_len as u32,
-						)
-						.saturating_add(actual_weight)
-					})
+					.map(|actual_weight| complete_weight.saturating_add(actual_weight))
 					.into())
 			} else {
 				// We cannot dispatch the call now; either it isn't available, or it is, but we
@@ -636,6 +1102,108 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Open an operation of the multisig `id` with the approval of `who`, storing its `call`.
+	fn propose(
+		who: T::AccountId,
+		id: T::AccountId,
+		threshold: u16,
+		call: BoundedCallOf<T>,
+	) -> DispatchResult {
+		let call_hash = match &call {
+			Bounded::Inline(data) => {
+				// Only accept the canonical encoding, so that the operation is that of the call
+				// once decoded.
+				let (decoded, _) =
+					T::Preimages::peek(&call).map_err(|_| Error::<T>::CallUnavailable)?;
+				let call_hash = blake2_256(data);
+				ensure!(
+					decoded.using_encoded(blake2_256) == call_hash,
+					Error::<T>::CallUnavailable
+				);
+				call_hash
+			},
+			Bounded::Lookup { hash, .. } => {
+				ensure!(T::Preimages::have(&call), Error::<T>::CallUnavailable);
+				hash.as_ref().try_into().map_err(|_| Error::<T>::CallUnavailable)?
+			},
+			Bounded::Legacy { .. } => return Err(Error::<T>::CallUnavailable.into()),
+		};
+		ensure!(!<Multisigs<T>>::contains_key(&id, call_hash), Error::<T>::AlreadyStored);
+
+		let deposit = T::DepositBase::get() + T::DepositFactor::get() * threshold.into();
+		T::Currency::reserve(&who, deposit)?;
+
+		let initial_approvals =
+			vec![who.clone()].try_into().map_err(|_| Error::<T>::TooManySignatories)?;
+
+		<Multisigs<T>>::insert(
+			&id,
+			call_hash,
+			Multisig {
+				when: Self::timepoint(),
+				deposit,
+				depositor: who.clone(),
+				approvals: initial_approvals,
+			},
+		);
+		T::Preimages::hold(&call);
+		<Calls<T>>::insert(&id, call_hash, call);
+		Self::deposit_event(Event::NewMultisig { approving: who, multisig: id, call_hash });
+		Ok(())
+	}
+
+	/// Cancel the operation `call_hash` of the multisig `id`, which `who` must have opened.
+	fn cancel(
+		who: T::AccountId,
+		id: T::AccountId,
+		timepoint: Timepoint<BlockNumberFor<T>>,
+		call_hash: CallHash,
+	) -> DispatchResult {
+		let m = <Multisigs<T>>::get(&id, call_hash).ok_or(Error::<T>::NotFound)?;
+		ensure!(m.when == timepoint, Error::<T>::WrongTimepoint);
+		ensure!(m.depositor == who, Error::<T>::NotOwner);
+
+		let err_amount = T::Currency::unreserve(&m.depositor, m.deposit);
+		debug_assert!(err_amount.is_zero());
+		<Multisigs<T>>::remove(&id, &call_hash);
+		Self::clear_call(&id, &call_hash);
+
+		Self::deposit_event(Event::MultisigCancelled {
+			cancelling: who,
+			timepoint,
+			multisig: id,
+			call_hash,
+		});
+		Ok(())
+	}
+
+	/// The call stored for the operation `call_hash` of the multisig `id`, if any, along with
+	/// the weight of looking it up and the weight of completing the operation with it.
+	fn stored_call(
+		id: &T::AccountId,
+		call_hash: &CallHash,
+		other_signatories_len: u32,
+	) -> Result<Option<(<T as Config>::RuntimeCall, Weight, Weight)>, DispatchError> {
+		let Some(bounded) = <Calls<T>>::get(id, call_hash) else { return Ok(None) };
+		let (call, _) = T::Preimages::peek(&bounded).map_err(|_| Error::<T>::CallUnavailable)?;
+
+		// Looking up the call costs about as much as it being given by the final approver.
+		let len = bounded.len().unwrap_or_default();
+		let lookup_weight = T::WeightInfo::as_multi_complete(other_signatories_len, len)
+			.saturating_sub(T::WeightInfo::as_multi_complete(other_signatories_len, 0));
+		let complete_weight = T::WeightInfo::approve_as_multi_complete(other_signatories_len)
+			.saturating_add(lookup_weight);
+		Ok(Some((call, lookup_weight, complete_weight)))
+	}
+
+	/// Remove the call stored for the operation `call_hash` of the multisig `id`, if any, and
+	/// no longer request its preimage.
+	fn clear_call(id: &T::AccountId, call_hash: &CallHash) {
+		if let Some(bounded) = <Calls<T>>::take(id, call_hash) {
+			T::Preimages::drop(&bounded);
+		}
+	}
+
 	/// The current `Timepoint`.
 	pub fn timepoint() -> Timepoint<BlockNumberFor<T>> {
 		Timepoint {
@@ -24,8 +24,9 @@ use super::*;
 use crate as pallet_multisig;
 use frame_support::{
 	assert_noop, assert_ok, derive_impl,
-	traits::{ConstU32, ConstU64, Contains},
+	traits::{ConstU32, ConstU64, Contains, QueryPreimage},
 };
+use frame_system::EnsureRoot;
 use sp_runtime::{BuildStorage, TokenError};
 
 type Block = frame_system::mocking::MockBlockU32<Test>;
@@ -35,6 +36,7 @@ frame_support::construct_runtime!(
 		System: frame_system,
 		Balances: pallet_balances,
 		Multisig: pallet_multisig,
+		Preimage: pallet_preimage,
 	}
 );
 
@@ -52,6 +54,14 @@ impl pallet_balances::Config for Test {
 	type AccountStore = System;
 }
 
+impl pallet_preimage::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<u64>;
+	type Consideration = ();
+}
+
 pub struct TestBaseCallFilter;
 impl Contains<RuntimeCall> for TestBaseCallFilter {
 	fn contains(c: &RuntimeCall) -> bool {
@@ -59,6 +69,8 @@ impl Contains<RuntimeCall> for TestBaseCallFilter {
 			RuntimeCall::Balances(_) => true,
 			// Needed for benchmarking
 			RuntimeCall::System(frame_system::Call::remark { .. }) => true,
+			RuntimeCall::Multisig(Call::set_managed_multisig { .. }) => true,
+			RuntimeCall::Multisig(Call::dissolve_managed_multisig { .. }) => true,
 			_ => false,
 		}
 	}
@@ -69,7 +81,9 @@ impl Config for Test {
 	type Currency = Balances;
 	type DepositBase = ConstU64<1>;
 	type DepositFactor = ConstU64<1>;
+	type DissolvedDeposit = ConstU64<1>;
 	type MaxSignatories = ConstU32<3>;
+	type Preimages = Preimage;
 	type WeightInfo = ();
 }
 
@@ -95,6 +109,15 @@ fn call_transfer(dest: u64, value: u64) -> Box<RuntimeCall> {
 	Box::new(RuntimeCall::Balances(BalancesCall::transfer_allow_death { dest, value }))
 }
 
+fn inline(call: &RuntimeCall) -> BoundedCallOf<Test> {
+	Bounded::Inline(call.encode().try_into().unwrap())
+}
+
+fn lookup(call: &RuntimeCall) -> BoundedCallOf<Test> {
+	let data = call.encode();
+	Bounded::Lookup { hash: blake2_256(&data).into(), len: data.len() as u32 }
+}
+
 #[test]
 fn multisig_deposit_is_taken_and_returned() {
 	new_test_ext().execute_with(|| {
@@ -691,3 +714,393 @@ fn multisig_handles_no_preimage_after_all_approve() {
 		assert_eq!(Balances::free_balance(6), 15);
 	});
 }
+
+#[test]
+fn proposed_call_is_dispatched_on_final_approval() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(3), multi, 5));
+
+		let call = call_transfer(6, 15);
+		let call_weight = call.get_dispatch_info().weight;
+		let hash = blake2_256(&call.encode());
+		assert_ok!(Multisig::propose_as_multi(
+			RuntimeOrigin::signed(1),
+			2,
+			vec![2, 3],
+			inline(&call)
+		));
+		assert_eq!(Balances::reserved_balance(1), 3);
+		assert!(Calls::<Test>::contains_key(multi, hash));
+
+		// The weight of looking up the stored call must be covered too.
+		assert_noop!(
+			Multisig::approve_as_multi(
+				RuntimeOrigin::signed(2),
+				2,
+				vec![1, 3],
+				Some(now()),
+				hash,
+				call_weight
+			),
+			Error::<Test>::MaxWeightTooLow,
+		);
+		assert_ok!(Multisig::approve_as_multi(
+			RuntimeOrigin::signed(2),
+			2,
+			vec![1, 3],
+			Some(now()),
+			hash,
+			Weight::MAX
+		));
+		assert_eq!(Balances::free_balance(6), 15);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(!Multisigs::<Test>::contains_key(multi, hash));
+		assert!(!Calls::<Test>::contains_key(multi, hash));
+	});
+}
+
+#[test]
+fn preimage_stored_call_is_dispatched_on_final_approval() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 3);
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(3), multi, 5));
+
+		let call = call_transfer(6, 15);
+		let hash = blake2_256(&call.encode());
+		assert_ok!(Preimage::note_preimage(RuntimeOrigin::signed(4), call.encode()));
+		assert_ok!(Multisig::propose_as_multi(
+			RuntimeOrigin::signed(1),
+			3,
+			vec![2, 3],
+			lookup(&call)
+		));
+		assert_eq!(Balances::reserved_balance(1), 4);
+		assert!(Calls::<Test>::contains_key(multi, hash));
+		// The preimage is requested for as long as the operation is underway.
+		assert!(Preimage::is_requested(&hash.into()));
+
+		assert_ok!(Multisig::approve_as_multi(
+			RuntimeOrigin::signed(2),
+			3,
+			vec![1, 3],
+			Some(now()),
+			hash,
+			Weight::zero()
+		));
+		assert_eq!(Balances::free_balance(6), 0);
+		assert!(Preimage::is_requested(&hash.into()));
+
+		assert_ok!(Multisig::approve_as_multi(
+			RuntimeOrigin::signed(3),
+			3,
+			vec![1, 2],
+			Some(now()),
+			hash,
+			Weight::MAX
+		));
+		assert_eq!(Balances::free_balance(6), 15);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(!Multisigs::<Test>::contains_key(multi, hash));
+		assert!(!Calls::<Test>::contains_key(multi, hash));
+		assert!(!Preimage::is_requested(&hash.into()));
+	});
+}
+
+#[test]
+fn cancelling_releases_preimage_stored_call() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		let call = call_transfer(6, 15);
+		let hash = blake2_256(&call.encode());
+		assert_ok!(Preimage::note_preimage(RuntimeOrigin::signed(4), call.encode()));
+		assert_ok!(Multisig::propose_as_multi(
+			RuntimeOrigin::signed(1),
+			2,
+			vec![2, 3],
+			lookup(&call)
+		));
+		assert!(Preimage::is_requested(&hash.into()));
+
+		assert_ok!(Multisig::cancel_as_multi(RuntimeOrigin::signed(1), 2, vec![2, 3], now(), hash));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(!Multisigs::<Test>::contains_key(multi, hash));
+		assert!(!Calls::<Test>::contains_key(multi, hash));
+		assert!(!Preimage::is_requested(&hash.into()));
+		// The preimage noted by its owner is left untouched.
+		assert!(<Preimage as QueryPreimage>::have(&lookup(&call)));
+	});
+}
+
+#[test]
+fn propose_as_multi_requires_available_call() {
+	new_test_ext().execute_with(|| {
+		let call = call_transfer(6, 15);
+		let hash = blake2_256(&call.encode());
+
+		// There is no preimage to look the call up from.
+		assert_noop!(
+			Multisig::propose_as_multi(RuntimeOrigin::signed(1), 2, vec![2, 3], lookup(&call)),
+			Error::<Test>::CallUnavailable,
+		);
+		// The call must decode, from its canonical encoding.
+		assert_noop!(
+			Multisig::propose_as_multi(
+				RuntimeOrigin::signed(1),
+				2,
+				vec![2, 3],
+				Bounded::Inline(vec![255; 4].try_into().unwrap())
+			),
+			Error::<Test>::CallUnavailable,
+		);
+		let mut data = call.encode();
+		data.push(0);
+		assert_noop!(
+			Multisig::propose_as_multi(
+				RuntimeOrigin::signed(1),
+				2,
+				vec![2, 3],
+				Bounded::Inline(data.try_into().unwrap())
+			),
+			Error::<Test>::CallUnavailable,
+		);
+
+		assert_ok!(Multisig::propose_as_multi(
+			RuntimeOrigin::signed(1),
+			2,
+			vec![2, 3],
+			inline(&call)
+		));
+		assert_noop!(
+			Multisig::propose_as_multi(RuntimeOrigin::signed(2), 2, vec![1, 3], inline(&call)),
+			Error::<Test>::AlreadyStored,
+		);
+	});
+}
+
+#[test]
+fn cancel_multisig_removes_stored_call() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		let call = call_transfer(6, 15);
+		let hash = blake2_256(&call.encode());
+		assert_ok!(Multisig::propose_as_multi(
+			RuntimeOrigin::signed(1),
+			2,
+			vec![2, 3],
+			inline(&call)
+		));
+		assert_ok!(Multisig::cancel_as_multi(RuntimeOrigin::signed(1), 2, vec![2, 3], now(), hash));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert!(!Calls::<Test>::contains_key(multi, hash));
+	});
+}
+
+#[test]
+fn managed_multisig_can_change_its_signatories() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Multisig::create_managed_multisig(RuntimeOrigin::signed(1), 2, vec![2, 3], 0));
+		let multi = Multisig::managed_account_id(&1, 0);
+		assert_eq!(Balances::reserved_balance(1), 4);
+		assert_noop!(
+			Multisig::create_managed_multisig(RuntimeOrigin::signed(1), 2, vec![2, 3], 0),
+			Error::<Test>::AlreadyExists,
+		);
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(3), multi, 5));
+
+		// Replace signatory 3 with 4, keeping the address.
+		let call = Box::new(RuntimeCall::Multisig(Call::set_managed_multisig {
+			signatories: vec![1, 2, 4],
+			threshold: 2,
+		}));
+		let call_weight = call.get_dispatch_info().weight;
+		assert_ok!(Multisig::as_managed_multi(
+			RuntimeOrigin::signed(1),
+			multi,
+			None,
+			call.clone(),
+			Weight::zero()
+		));
+		assert_ok!(Multisig::as_managed_multi(
+			RuntimeOrigin::signed(3),
+			multi,
+			Some(now()),
+			call,
+			call_weight
+		));
+		System::assert_has_event(
+			Event::ManagedMultisigChanged { multisig: multi, threshold: 2 }.into(),
+		);
+		// The multisig holds its own deposit from now on.
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(multi), 4);
+
+		let call = call_transfer(6, 5);
+		let call_weight = call.get_dispatch_info().weight;
+		assert_ok!(Multisig::as_managed_multi(
+			RuntimeOrigin::signed(4),
+			multi,
+			None,
+			call.clone(),
+			Weight::zero()
+		));
+		assert_noop!(
+			Multisig::as_managed_multi(
+				RuntimeOrigin::signed(3),
+				multi,
+				Some(now()),
+				call.clone(),
+				call_weight
+			),
+			Error::<Test>::NotSignatory,
+		);
+		assert_ok!(Multisig::as_managed_multi(
+			RuntimeOrigin::signed(2),
+			multi,
+			Some(now()),
+			call,
+			call_weight
+		));
+		assert_eq!(Balances::free_balance(6), 5);
+	});
+}
+
+#[test]
+fn managed_multisig_only_counts_approvals_of_current_signatories() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Multisig::create_managed_multisig(RuntimeOrigin::signed(1), 2, vec![2, 3], 0));
+		let multi = Multisig::managed_account_id(&1, 0);
+		assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(2), multi, 10));
+
+		assert_noop!(
+			Multisig::set_managed_multisig(RuntimeOrigin::signed(1), vec![1, 2], 2),
+			Error::<Test>::NotManaged,
+		);
+		assert_noop!(
+			Multisig::set_managed_multisig(RuntimeOrigin::signed(multi), vec![1, 2], 3),
+			Error::<Test>::ThresholdTooHigh,
+		);
+		assert_noop!(
+			Multisig::set_managed_multisig(RuntimeOrigin::signed(multi), vec![2, 1], 2),
+			Error::<Test>::SignatoriesOutOfOrder,
+		);
+		assert_noop!(
+			Multisig::set_managed_multisig(RuntimeOrigin::signed(multi), vec![1, 2, 3, 4], 2),
+			Error::<Test>::TooManySignatories,
+		);
+
+		let call = call_transfer(6, 5);
+		let call_weight = call.get_dispatch_info().weight;
+		let hash = blake2_256(&call.encode());
+		assert_ok!(Multisig::approve_as_managed_multi(
+			RuntimeOrigin::signed(3),
+			multi,
+			None,
+			hash,
+			Weight::zero()
+		));
+		assert_ok!(Multisig::set_managed_multisig(RuntimeOrigin::signed(multi), vec![1, 2, 4], 2));
+
+		// The approval of 3 no longer counts.
+		assert_ok!(Multisig::as_managed_multi(
+			RuntimeOrigin::signed(2),
+			multi,
+			Some(now()),
+			call.clone(),
+			call_weight
+		));
+		assert_eq!(Balances::free_balance(6), 0);
+		assert_ok!(Multisig::as_managed_multi(
+			RuntimeOrigin::signed(4),
+			multi,
+			Some(now()),
+			call,
+			call_weight
+		));
+		assert_eq!(Balances::free_balance(6), 5);
+		// The deposit of the operation is returned to whoever opened it.
+		assert_eq!(Balances::reserved_balance(3), 0);
+
+		// The dissolved multisig may not be created anew by its creator.
+		assert_noop!(
+			Multisig::create_managed_multisig(RuntimeOrigin::signed(1), 2, vec![4, 5], 0),
+			Error::<Test>::Dissolved,
+		);
+		assert_ok!(Multisig::create_managed_multisig(RuntimeOrigin::signed(1), 2, vec![4, 5], 1));
+	});
+}
+
+#[test]
+fn managed_multisig_can_dissolve_itself() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Multisig::create_managed_multisig(RuntimeOrigin::signed(1), 2, vec![2, 3], 0));
+		let multi = Multisig::managed_account_id(&1, 0);
+		assert_eq!(Balances::reserved_balance(1), 4);
+		assert_noop!(
+			Multisig::dissolve_managed_multisig(RuntimeOrigin::signed(1)),
+			Error::<Test>::NotManaged,
+		);
+
+		// An operation left underway by 3.
+		let transfer = call_transfer(6, 5);
+		let transfer_hash = blake2_256(&transfer.encode());
+		assert_ok!(Multisig::approve_as_managed_multi(
+			RuntimeOrigin::signed(3),
+			multi,
+			None,
+			transfer_hash,
+			Weight::zero()
+		));
+		let transfer_timepoint = now();
+		assert_eq!(Balances::reserved_balance(3), 3);
+
+		let call = Box::new(RuntimeCall::Multisig(Call::dissolve_managed_multisig {}));
+		let call_weight = call.get_dispatch_info().weight;
+		assert_ok!(Multisig::as_managed_multi(
+			RuntimeOrigin::signed(1),
+			multi,
+			None,
+			call.clone(),
+			Weight::zero()
+		));
+		assert_ok!(Multisig::as_managed_multi(
+			RuntimeOrigin::signed(2),
+			multi,
+			Some(now()),
+			call,
+			call_weight
+		));
+		System::assert_has_event(Event::ManagedMultisigDissolved { multisig: multi }.into());
+		assert!(!ManagedMultisigs::<Test>::contains_key(multi));
+		// The deposit of the multisig is returned to its creator, less what is burnt for keeping
+		// it dissolved.
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 9);
+		assert_eq!(Balances::total_issuance(), 41);
+		assert!(DissolvedManagedMultisigs::<Test>::contains_key(multi));
+
+		assert_noop!(
+			Multisig::approve_as_managed_multi(
+				RuntimeOrigin::signed(2),
+				multi,
+				Some(transfer_timepoint),
+				transfer_hash,
+				Weight::zero()
+			),
+			Error::<Test>::NotManaged,
+		);
+		// The operation left underway can still be cancelled to release its deposit.
+		assert_ok!(Multisig::cancel_as_managed_multi(
+			RuntimeOrigin::signed(3),
+			multi,
+			transfer_timepoint,
+			transfer_hash
+		));
+		assert_eq!(Balances::reserved_balance(3), 0);
+	});
+}
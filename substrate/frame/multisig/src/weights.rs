@@ -58,6 +58,11 @@ pub trait WeightInfo {
 	fn approve_as_multi_create(s: u32, ) -> Weight;
 	fn approve_as_multi_approve(s: u32, ) -> Weight;
 	fn cancel_as_multi(s: u32, ) -> Weight;
+	fn propose_as_multi(s: u32, ) -> Weight;
+	fn approve_as_multi_complete(s: u32, ) -> Weight;
+	fn create_managed_multisig(s: u32, ) -> Weight;
+	fn set_managed_multisig(s: u32, ) -> Weight;
+	fn dissolve_managed_multisig(s: u32, ) -> Weight;
 }
 
 /// Weights for `pallet_multisig` using the Substrate node and recommended hardware.
@@ -177,6 +182,36 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Not benchmarked yet: `approve_as_multi_create` plus the preimage status and request, the
+	/// stored call and the account of the depositor.
+	fn propose_as_multi(s: u32, ) -> Weight {
+		Self::approve_as_multi_create(s)
+			.saturating_add(T::DbWeight::get().reads_writes(3, 3))
+	}
+	/// Not benchmarked yet: `as_multi_complete` without a call, plus removing the stored call
+	/// and the request of its preimage.
+	fn approve_as_multi_complete(s: u32, ) -> Weight {
+		Self::as_multi_complete(s, 0)
+			.saturating_add(T::DbWeight::get().reads_writes(2, 2))
+	}
+	/// Not benchmarked yet: `approve_as_multi_create` plus the dissolved multisigs and the account
+	/// of the depositor.
+	fn create_managed_multisig(s: u32, ) -> Weight {
+		Self::approve_as_multi_create(s)
+			.saturating_add(T::DbWeight::get().reads_writes(2, 1))
+	}
+	/// Not benchmarked yet: `approve_as_multi_create` plus the accounts of the previous and the
+	/// new depositor.
+	fn set_managed_multisig(s: u32, ) -> Weight {
+		Self::approve_as_multi_create(s)
+			.saturating_add(T::DbWeight::get().reads_writes(2, 2))
+	}
+	/// Not benchmarked yet: `cancel_as_multi` plus the dissolved multisigs, the account of the
+	/// depositor and the total issuance.
+	fn dissolve_managed_multisig(s: u32, ) -> Weight {
+		Self::cancel_as_multi(s)
+			.saturating_add(T::DbWeight::get().reads_writes(2, 3))
+	}
 }
 
 // For backwards compatibility and tests.
@@ -295,4 +330,34 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Not benchmarked yet: `approve_as_multi_create` plus the preimage status and request, the
+	/// stored call and the account of the depositor.
+	fn propose_as_multi(s: u32, ) -> Weight {
+		Self::approve_as_multi_create(s)
+			.saturating_add(RocksDbWeight::get().reads_writes(3, 3))
+	}
+	/// Not benchmarked yet: `as_multi_complete` without a call, plus removing the stored call
+	/// and the request of its preimage.
+	fn approve_as_multi_complete(s: u32, ) -> Weight {
+		Self::as_multi_complete(s, 0)
+			.saturating_add(RocksDbWeight::get().reads_writes(2, 2))
+	}
+	/// Not benchmarked yet: `approve_as_multi_create` plus the dissolved multisigs and the account
+	/// of the depositor.
+	fn create_managed_multisig(s: u32, ) -> Weight {
+		Self::approve_as_multi_create(s)
+			.saturating_add(RocksDbWeight::get().reads_writes(2, 1))
+	}
+	/// Not benchmarked yet: `approve_as_multi_create` plus the accounts of the previous and the
+	/// new depositor.
+	fn set_managed_multisig(s: u32, ) -> Weight {
+		Self::approve_as_multi_create(s)
+			.saturating_add(RocksDbWeight::get().reads_writes(2, 2))
+	}
+	/// Not benchmarked yet: `cancel_as_multi` plus the dissolved multisigs, the account of the
+	/// depositor and the total issuance.
+	fn dissolve_managed_multisig(s: u32, ) -> Weight {
+		Self::cancel_as_multi(s)
+			.saturating_add(RocksDbWeight::get().reads_writes(2, 3))
+	}
 }
@@ -31,17 +31,30 @@ fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
 	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
 }
 
+/// Limits which never run out, so that the proxies they are given to can always be used.
+fn worst_case_limits<T: Config>() -> ProxyLimitsOf<T> {
+	ProxyLimits {
+		expiry: Some(BlockNumberFor::<T>::max_value()),
+		spending_limit: Some(SpendingLimit {
+			amount: BalanceOf::<T>::max_value(),
+			period: 1u32.into(),
+		}),
+	}
+}
+
 fn add_proxies<T: Config>(n: u32, maybe_who: Option<T::AccountId>) -> Result<(), &'static str> {
 	let caller = maybe_who.unwrap_or_else(whitelisted_caller);
 	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
 	for i in 0..n {
 		let real = T::Lookup::unlookup(account("target", i, SEED));
 
-		Proxy::<T>::add_proxy(
+		// Proxies with limits are the worst case for both using and removing them.
+		Proxy::<T>::add_proxy_with_limits(
 			RawOrigin::Signed(caller.clone()).into(),
 			real,
 			T::ProxyType::default(),
 			BlockNumberFor::<T>::zero(),
+			worst_case_limits::<T>(),
 		)?;
 	}
 	Ok(())
@@ -194,6 +207,29 @@ benchmarks! {
 		assert_eq!(proxies.len() as u32, p + 1);
 	}
 
+	add_proxy_with_limits {
+		let p in 1 .. (T::MaxProxies::get() - 1) => add_proxies::<T>(p, None)?;
+		let caller: T::AccountId = whitelisted_caller();
+		let delegate: T::AccountId = account("target", T::MaxProxies::get(), SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let limits = worst_case_limits::<T>();
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		delegate_lookup,
+		T::ProxyType::default(),
+		BlockNumberFor::<T>::zero(),
+		limits
+	)
+	verify {
+		assert_last_event::<T>(Event::ProxyLimitsSet {
+			delegator: caller,
+			delegatee: delegate,
+			proxy_type: T::ProxyType::default(),
+			delay: BlockNumberFor::<T>::zero(),
+			limits,
+		}.into());
+	}
+
 	remove_proxy {
 		let p in 1 .. (T::MaxProxies::get() - 1) => add_proxies::<T>(p, None)?;
 		let caller: T::AccountId = whitelisted_caller();
@@ -207,6 +243,7 @@ benchmarks! {
 	verify {
 		let (proxies, _) = Proxies::<T>::get(caller);
 		assert_eq!(proxies.len() as u32, p - 1);
+		assert_eq!(Limits::<T>::iter().count() as u32, p - 1);
 	}
 
 	remove_proxies {
@@ -216,6 +253,7 @@ benchmarks! {
 	verify {
 		let (proxies, _) = Proxies::<T>::get(caller);
 		assert_eq!(proxies.len() as u32, 0);
+		assert_eq!(Limits::<T>::iter().count(), 0);
 	}
 
 	create_pure {
@@ -258,6 +296,7 @@ benchmarks! {
 	}: _(RawOrigin::Signed(pure_account.clone()), caller_lookup, T::ProxyType::default(), 0, height, ext_index)
 	verify {
 		assert!(!Proxies::<T>::contains_key(&pure_account));
+		assert_eq!(Limits::<T>::iter().count(), 0);
 	}
 
 	impl_benchmark_test_suite!(Proxy, crate::tests::new_test_ext(), crate::tests::Test);
@@ -23,6 +23,16 @@
 //! wish to execute some duration prior to execution happens. In this case, the target account may
 //! reject the announcement and in doing so, veto the execution.
 //!
+//! A proxy may also be given [limits](`ProxyLimits`): an expiry, from which it can no longer be
+//! used and is removed with the weight left at the end of blocks, and a budget of `Currency` it may
+//! spend per period on behalf of the account it acts for. Any decrease of the total balance of that
+//! account while dispatching a call through the proxy counts towards the budget, and a call which
+//! would exceed it is reverted.
+//!
+//! The budget only covers `Currency`, the native balance of the account: transfers of other assets,
+//! such as those of `pallet-assets`, are not metered. A proxy which must not move those should be
+//! given a proxy type whose filter rejects the calls doing so.
+//!
 //! - [`Config`]
 //! - [`Call`]
 
@@ -37,15 +47,19 @@ use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
 	dispatch::GetDispatchInfo,
 	ensure,
+	storage::{with_storage_layer, with_transaction},
 	traits::{Currency, Get, InstanceFilter, IsSubType, IsType, OriginTrait, ReservableCurrency},
+	weights::Weight,
 };
 use frame_system::{self as system, ensure_signed, pallet_prelude::BlockNumberFor};
 pub use pallet::*;
 use scale_info::TypeInfo;
 use sp_io::hashing::blake2_256;
 use sp_runtime::{
-	traits::{Dispatchable, Hash, Saturating, StaticLookup, TrailingZeroInput, Zero},
-	DispatchError, DispatchResult, RuntimeDebug,
+	traits::{
+		AtLeast32BitUnsigned, Dispatchable, Hash, Saturating, StaticLookup, TrailingZeroInput, Zero,
+	},
+	DispatchError, DispatchResult, RuntimeDebug, TransactionOutcome,
 };
 use sp_std::prelude::*;
 pub use weights::WeightInfo;
@@ -57,6 +71,14 @@ type BalanceOf<T> =
 
 type AccountIdLookupOf<T> = <<T as frame_system::Config>::Lookup as StaticLookup>::Source;
 
+type ProxyDefinitionOf<T> = ProxyDefinition<
+	<T as frame_system::Config>::AccountId,
+	<T as Config>::ProxyType,
+	BlockNumberFor<T>,
+>;
+
+type ProxyLimitsOf<T> = ProxyLimits<BalanceOf<T>, BlockNumberFor<T>>;
+
 /// The parameters under which a particular account has a proxy relationship with some other
 /// account.
 #[derive(
@@ -82,6 +104,67 @@ pub struct ProxyDefinition<AccountId, ProxyType, BlockNumber> {
 	pub delay: BlockNumber,
 }
 
+/// An amount of `Currency` that a proxy may spend on behalf of the account it acts for. Other
+/// assets are not covered, see the [pallet documentation](crate).
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct SpendingLimit<Balance, BlockNumber> {
+	/// The amount which may be spent per period.
+	pub amount: Balance,
+	/// The number of blocks after which the amount spent is reset. If zero, it never is.
+	pub period: BlockNumber,
+}
+
+/// The restrictions of a proxy on top of its type and delay.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct ProxyLimits<Balance, BlockNumber> {
+	/// The block from which the proxy may no longer be used.
+	pub expiry: Option<BlockNumber>,
+	/// The amount the proxy may spend.
+	pub spending_limit: Option<SpendingLimit<Balance, BlockNumber>>,
+}
+
+/// The limits of a proxy, together with what it has spent so far and the amount held on deposit
+/// for them.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct ProxyLimitsInfo<Balance, BlockNumber> {
+	/// The limits of the proxy.
+	pub limits: ProxyLimits<Balance, BlockNumber>,
+	/// The amount spent in the current period.
+	pub spent: Balance,
+	/// The height at which the current period started.
+	pub period_start: BlockNumber,
+	/// The amount held on deposit for the limits.
+	pub deposit: Balance,
+}
+
+impl<Balance, BlockNumber> ProxyLimitsInfo<Balance, BlockNumber>
+where
+	Balance: AtLeast32BitUnsigned + Copy,
+	BlockNumber: AtLeast32BitUnsigned + Copy,
+{
+	/// Whether the proxy may no longer be used at height `now`.
+	pub fn is_expired(&self, now: BlockNumber) -> bool {
+		self.limits.expiry.map_or(false, |expiry| now >= expiry)
+	}
+
+	/// Account for `amount` being spent at height `now`, failing if it exceeds the amount left for
+	/// the current period.
+	fn spend(&mut self, amount: Balance, now: BlockNumber) -> Result<(), ()> {
+		let Some(limit) = self.limits.spending_limit else { return Ok(()) };
+		let elapsed = now.saturating_sub(self.period_start);
+		if !limit.period.is_zero() && elapsed >= limit.period {
+			self.spent = Zero::zero();
+			self.period_start = now.saturating_sub(elapsed % limit.period);
+		}
+		let spent = self.spent.saturating_add(amount);
+		if spent > limit.amount {
+			return Err(())
+		}
+		self.spent = spent;
+		Ok(())
+	}
+}
+
 /// Details surrounding a specific instance of an announcement to make a call.
 #[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
 pub struct Announcement<AccountId, Hash, BlockNumber> {
@@ -173,6 +256,21 @@ pub mod pallet {
 		/// into a pre-existing storage value.
 		#[pallet::constant]
 		type AnnouncementDepositFactor: Get<BalanceOf<Self>>;
+
+		/// The amount of currency needed to reserve for giving limits to a proxy.
+		///
+		/// This is held for an additional storage item keyed by an `AccountId` and a proxy
+		/// definition, whose value holds the limits, two `Balance`s and a `BlockNumber` (typically
+		/// 131 bytes in total).
+		#[pallet::constant]
+		type ProxyLimitsDeposit: Get<BalanceOf<Self>>;
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::remove_expired_proxies(now, remaining_weight)
+		}
 	}
 
 	#[pallet::call]
@@ -350,8 +448,11 @@ pub mod pallet {
 			let proxy = Self::pure_account(&spawner, &proxy_type, index, Some(when));
 			ensure!(proxy == who, Error::<T>::NoPermission);
 
-			let (_, deposit) = Proxies::<T>::take(&who);
+			let (proxies, deposit) = Proxies::<T>::take(&who);
 			T::Currency::unreserve(&spawner, deposit);
+			for proxy_def in proxies {
+				Self::remove_limits(&who, proxy_def);
+			}
 
 			Ok(())
 		}
@@ -380,11 +481,7 @@ pub mod pallet {
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			let real = T::Lookup::lookup(real)?;
-			Proxies::<T>::get(&real)
-				.0
-				.into_iter()
-				.find(|x| x.delegate == who)
-				.ok_or(Error::<T>::NotProxy)?;
+			Self::find_proxy(&real, &who, None)?;
 
 			let announcement = Announcement {
 				real: real.clone(),
@@ -512,6 +609,36 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Register a proxy account for the sender that is able to make calls on its behalf,
+		/// within the given `limits`.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Parameters:
+		/// - `delegate`: The account that the `caller` would like to make a proxy.
+		/// - `proxy_type`: The permissions allowed for this proxy account.
+		/// - `delay`: The announcement period required of the initial proxy. Will generally be
+		/// zero.
+		/// - `limits`: The height from which the proxy expires, and the amount of `Currency` it may
+		/// spend on behalf of the sender per period. Only the native balance is metered, so
+		/// `proxy_type` should reject the transfers of other assets if those must be limited too.
+		///
+		/// On top of the deposit for the proxy, `ProxyLimitsDeposit` is held until the proxy is
+		/// removed, which happens automatically once it expires.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::add_proxy_with_limits(T::MaxProxies::get()))]
+		pub fn add_proxy_with_limits(
+			origin: OriginFor<T>,
+			delegate: AccountIdLookupOf<T>,
+			proxy_type: T::ProxyType,
+			delay: BlockNumberFor<T>,
+			limits: ProxyLimitsOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+			Self::add_proxy_delegate_with_limits(&who, delegate, proxy_type, delay, limits)
+		}
 	}
 
 	#[pallet::event]
@@ -543,6 +670,14 @@ pub mod pallet {
 			proxy_type: T::ProxyType,
 			delay: BlockNumberFor<T>,
 		},
+		/// Limits were given to a proxy.
+		ProxyLimitsSet {
+			delegator: T::AccountId,
+			delegatee: T::AccountId,
+			proxy_type: T::ProxyType,
+			delay: BlockNumberFor<T>,
+			limits: ProxyLimitsOf<T>,
+		},
 	}
 
 	#[pallet::error]
@@ -563,6 +698,10 @@ pub mod pallet {
 		Unannounced,
 		/// Cannot add self as proxy.
 		NoSelfProxy,
+		/// The limits of a proxy have none set, or expire already.
+		InvalidLimits,
+		/// The call would have the proxy spend more than it may.
+		SpendingLimitExceeded,
 	}
 
 	/// The set of account proxies. Maps the account which has delegated to the accounts
@@ -596,6 +735,22 @@ pub mod pallet {
 		),
 		ValueQuery,
 	>;
+
+	/// The limits of the proxies which have some, keyed by the account which has delegated and
+	/// the definition of the proxy.
+	#[pallet::storage]
+	pub type Limits<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(T::AccountId, ProxyDefinitionOf<T>),
+		ProxyLimitsInfo<BalanceOf<T>, BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// The last limits checked for expiry, after which the next checks resume.
+	#[pallet::storage]
+	pub type LimitsCursor<T: Config> =
+		StorageValue<_, (T::AccountId, ProxyDefinitionOf<T>), OptionQuery>;
 }
 
 impl<T: Config> Pallet<T> {
@@ -668,6 +823,53 @@ impl<T: Config> Pallet<T> {
 		})
 	}
 
+	/// Register a proxy account for the delegator that is able to make calls on its behalf, within
+	/// the given `limits`.
+	///
+	/// Parameters:
+	/// - `delegator`: The delegator account.
+	/// - `delegatee`: The account that the `delegator` would like to make a proxy.
+	/// - `proxy_type`: The permissions allowed for this proxy account.
+	/// - `delay`: The announcement period required of the initial proxy. Will generally be
+	/// zero.
+	/// - `limits`: The height from which the proxy expires, and the amount it may spend.
+	pub fn add_proxy_delegate_with_limits(
+		delegator: &T::AccountId,
+		delegatee: T::AccountId,
+		proxy_type: T::ProxyType,
+		delay: BlockNumberFor<T>,
+		limits: ProxyLimitsOf<T>,
+	) -> DispatchResult {
+		let now = system::Pallet::<T>::block_number();
+		ensure!(
+			limits.expiry.map_or(limits.spending_limit.is_some(), |expiry| expiry > now),
+			Error::<T>::InvalidLimits
+		);
+		with_storage_layer(|| {
+			Self::add_proxy_delegate(delegator, delegatee.clone(), proxy_type.clone(), delay)?;
+
+			let deposit = T::ProxyLimitsDeposit::get();
+			T::Currency::reserve(delegator, deposit)?;
+			let proxy_def = ProxyDefinition {
+				delegate: delegatee.clone(),
+				proxy_type: proxy_type.clone(),
+				delay,
+			};
+			Limits::<T>::insert(
+				(delegator.clone(), proxy_def),
+				ProxyLimitsInfo { limits, spent: Zero::zero(), period_start: now, deposit },
+			);
+			Self::deposit_event(Event::<T>::ProxyLimitsSet {
+				delegator: delegator.clone(),
+				delegatee,
+				proxy_type,
+				delay,
+				limits,
+			});
+			Ok(())
+		})
+	}
+
 	/// Unregister a proxy account for the delegator.
 	///
 	/// Parameters:
@@ -691,6 +893,7 @@ impl<T: Config> Pallet<T> {
 			};
 			let i = proxies.binary_search(&proxy_def).ok().ok_or(Error::<T>::NotFound)?;
 			proxies.remove(i);
+			Self::remove_limits(delegator, proxy_def);
 			let new_deposit = Self::deposit(proxies.len() as u32);
 			if new_deposit > old_deposit {
 				T::Currency::reserve(delegator, new_deposit - old_deposit)?;
@@ -763,9 +966,12 @@ impl<T: Config> Pallet<T> {
 		delegate: &T::AccountId,
 		force_proxy_type: Option<T::ProxyType>,
 	) -> Result<ProxyDefinition<T::AccountId, T::ProxyType, BlockNumberFor<T>>, DispatchError> {
+		let now = system::Pallet::<T>::block_number();
 		let f = |x: &ProxyDefinition<T::AccountId, T::ProxyType, BlockNumberFor<T>>| -> bool {
 			&x.delegate == delegate &&
-				force_proxy_type.as_ref().map_or(true, |y| &x.proxy_type == y)
+				force_proxy_type.as_ref().map_or(true, |y| &x.proxy_type == y) &&
+				!Limits::<T>::get((real.clone(), x.clone()))
+					.map_or(false, |info| info.is_expired(now))
 		};
 		Ok(Proxies::<T>::get(real).0.into_iter().find(f).ok_or(Error::<T>::NotProxy)?)
 	}
@@ -775,12 +981,24 @@ impl<T: Config> Pallet<T> {
 		real: T::AccountId,
 		call: <T as Config>::RuntimeCall,
 	) {
+		let limits_key = (real.clone(), def.clone());
+		let maybe_limits = Limits::<T>::get(&limits_key);
+		let limited = maybe_limits.is_some();
+
 		// This is a freshly authenticated new account, the origin restrictions doesn't apply.
-		let mut origin: T::RuntimeOrigin = frame_system::RawOrigin::Signed(real).into();
+		let mut origin: T::RuntimeOrigin = frame_system::RawOrigin::Signed(real.clone()).into();
 		origin.add_filter(move |c: &<T as frame_system::Config>::RuntimeCall| {
 			let c = <T as Config>::RuntimeCall::from_ref(c);
 			// We make sure the proxy call does access this pallet to change modify proxies.
 			match c.is_sub_type() {
+				// Proxy call with limits cannot add proxies, which may have no limits, nor act
+				// through the proxies of the account it acts for, which its limits don't apply to.
+				Some(Call::add_proxy { .. }) |
+				Some(Call::add_proxy_with_limits { .. }) |
+				Some(Call::proxy { .. }) |
+				Some(Call::proxy_announced { .. })
+					if limited =>
+					false,
 				// Proxy call cannot add or remove a proxy with more permissions than it already
 				// has.
 				Some(Call::add_proxy { ref proxy_type, .. }) |
@@ -795,8 +1013,23 @@ impl<T: Config> Pallet<T> {
 				_ => def.proxy_type.filter(c),
 			}
 		});
-		let e = call.dispatch(origin);
-		Self::deposit_event(Event::ProxyExecuted { result: e.map(|_| ()).map_err(|e| e.error) });
+		let result = match maybe_limits {
+			Some(mut info) => with_transaction(|| {
+				let balance = T::Currency::total_balance(&real);
+				let result = call.dispatch(origin).map(|_| ()).map_err(|e| e.error);
+				let spent = balance.saturating_sub(T::Currency::total_balance(&real));
+				if info.spend(spent, system::Pallet::<T>::block_number()).is_err() {
+					return TransactionOutcome::Rollback(Ok(Err(
+						Error::<T>::SpendingLimitExceeded.into()
+					)))
+				}
+				Limits::<T>::insert(&limits_key, info);
+				TransactionOutcome::Commit(Ok(result))
+			})
+			.unwrap_or_else(Err),
+			None => call.dispatch(origin).map(|_| ()).map_err(|e| e.error),
+		};
+		Self::deposit_event(Event::ProxyExecuted { result });
 	}
 
 	/// Removes all proxy delegates for a given delegator.
@@ -804,7 +1037,59 @@ impl<T: Config> Pallet<T> {
 	/// Parameters:
 	/// - `delegator`: The delegator account.
 	pub fn remove_all_proxy_delegates(delegator: &T::AccountId) {
-		let (_, old_deposit) = Proxies::<T>::take(&delegator);
+		let (proxies, old_deposit) = Proxies::<T>::take(&delegator);
 		T::Currency::unreserve(&delegator, old_deposit);
+		for proxy_def in proxies {
+			Self::remove_limits(delegator, proxy_def);
+		}
+	}
+
+	/// Removes the limits of a proxy of the delegator, if any, returning their deposit.
+	fn remove_limits(delegator: &T::AccountId, proxy_def: ProxyDefinitionOf<T>) {
+		if let Some(info) = Limits::<T>::take((delegator.clone(), proxy_def)) {
+			T::Currency::unreserve(delegator, info.deposit);
+		}
+	}
+
+	/// Removes the proxies which have expired at height `now`, checking as many proxies with
+	/// limits as the `limit` weight allows from where the previous checks stopped.
+	///
+	/// Returns the weight consumed.
+	pub fn remove_expired_proxies(now: BlockNumberFor<T>, limit: Weight) -> Weight {
+		let db_weight = T::DbWeight::get();
+		let mut consumed = db_weight.reads_writes(1, 1);
+		// A proxy is accounted for as checked and removed.
+		let per_proxy = db_weight
+			.reads(1)
+			.saturating_add(T::WeightInfo::remove_proxy(T::MaxProxies::get()));
+		if consumed.saturating_add(per_proxy).any_gt(limit) {
+			return Weight::zero()
+		}
+
+		let mut cursor = LimitsCursor::<T>::get();
+		let mut limits = match cursor {
+			Some(ref key) => Limits::<T>::iter_from(Limits::<T>::hashed_key_for(key)),
+			None => Limits::<T>::iter(),
+		};
+		while !consumed.saturating_add(per_proxy).any_gt(limit) {
+			let Some(((delegator, proxy_def), info)) = limits.next() else {
+				// Start over from the first proxy next time.
+				cursor = None;
+				break
+			};
+			consumed.saturating_accrue(per_proxy);
+			cursor = Some((delegator.clone(), proxy_def.clone()));
+			if info.is_expired(now) {
+				let _ = Self::remove_proxy_delegate(
+					&delegator,
+					proxy_def.delegate,
+					proxy_def.proxy_type,
+					proxy_def.delay,
+				);
+			}
+		}
+		LimitsCursor::<T>::set(cursor);
+
+		consumed
 	}
 }
@@ -25,7 +25,7 @@ use crate as proxy;
 use codec::{Decode, Encode};
 use frame_support::{
 	assert_noop, assert_ok, derive_impl,
-	traits::{ConstU32, ConstU64, Contains},
+	traits::{ConstU32, ConstU64, Contains, Hooks},
 };
 use sp_core::H256;
 use sp_runtime::{traits::BlakeTwo256, BuildStorage, DispatchError, RuntimeDebug};
@@ -126,6 +126,7 @@ impl Config for Test {
 	type MaxPending = ConstU32<2>;
 	type AnnouncementDepositBase = ConstU64<1>;
 	type AnnouncementDepositFactor = ConstU64<1>;
+	type ProxyLimitsDeposit = ConstU64<1>;
 }
 
 use super::{Call as ProxyCall, Event as ProxyEvent};
@@ -590,3 +591,183 @@ fn pure_works() {
 		);
 	});
 }
+
+#[test]
+fn proxy_limits_restrict_spending() {
+	new_test_ext().execute_with(|| {
+		let limits = ProxyLimits {
+			expiry: None,
+			spending_limit: Some(SpendingLimit { amount: 3, period: 10 }),
+		};
+		assert_ok!(Proxy::add_proxy_with_limits(
+			RuntimeOrigin::signed(1),
+			2,
+			ProxyType::Any,
+			0,
+			limits
+		));
+		System::assert_last_event(
+			ProxyEvent::ProxyLimitsSet {
+				delegator: 1,
+				delegatee: 2,
+				proxy_type: ProxyType::Any,
+				delay: 0,
+				limits,
+			}
+			.into(),
+		);
+		assert_eq!(Balances::reserved_balance(1), 3);
+
+		assert_ok!(Proxy::proxy(RuntimeOrigin::signed(2), 1, None, Box::new(call_transfer(6, 2))));
+		System::assert_last_event(ProxyEvent::ProxyExecuted { result: Ok(()) }.into());
+		assert_eq!(Balances::free_balance(6), 2);
+
+		// the transfer is reverted as it would exceed the amount left
+		assert_ok!(Proxy::proxy(RuntimeOrigin::signed(2), 1, None, Box::new(call_transfer(6, 2))));
+		let de = DispatchError::from(Error::<Test>::SpendingLimitExceeded).stripped();
+		System::assert_last_event(ProxyEvent::ProxyExecuted { result: Err(de) }.into());
+		assert_eq!(Balances::free_balance(6), 2);
+
+		assert_ok!(Proxy::proxy(RuntimeOrigin::signed(2), 1, None, Box::new(call_transfer(6, 1))));
+		System::assert_last_event(ProxyEvent::ProxyExecuted { result: Ok(()) }.into());
+		assert_eq!(Balances::free_balance(6), 3);
+
+		// the limits cannot be escaped by adding another proxy
+		let call = Box::new(RuntimeCall::Proxy(ProxyCall::add_proxy {
+			delegate: 2,
+			proxy_type: ProxyType::JustTransfer,
+			delay: 0,
+		}));
+		assert_ok!(Proxy::proxy(RuntimeOrigin::signed(2), 1, None, call));
+		System::assert_last_event(
+			ProxyEvent::ProxyExecuted { result: Err(SystemError::CallFiltered.into()) }.into(),
+		);
+
+		// the amount spent is reset once the period is over
+		System::set_block_number(11);
+		assert_ok!(Proxy::proxy(RuntimeOrigin::signed(2), 1, None, Box::new(call_transfer(6, 2))));
+		System::assert_last_event(ProxyEvent::ProxyExecuted { result: Ok(()) }.into());
+		assert_eq!(Balances::free_balance(6), 5);
+		assert_eq!(
+			Limits::<Test>::get((
+				1,
+				ProxyDefinition { delegate: 2, proxy_type: ProxyType::Any, delay: 0 }
+			))
+			.map(|info| (info.spent, info.period_start)),
+			Some((2, 11))
+		);
+
+		assert_ok!(Proxy::remove_proxy(RuntimeOrigin::signed(1), 2, ProxyType::Any, 0));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Limits::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn proxy_limits_expire() {
+	new_test_ext().execute_with(|| {
+		let no_limits = ProxyLimits { expiry: None, spending_limit: None };
+		assert_noop!(
+			Proxy::add_proxy_with_limits(RuntimeOrigin::signed(1), 3, ProxyType::Any, 0, no_limits),
+			Error::<Test>::InvalidLimits
+		);
+		let expired = ProxyLimits { expiry: Some(1), spending_limit: None };
+		assert_noop!(
+			Proxy::add_proxy_with_limits(RuntimeOrigin::signed(1), 3, ProxyType::Any, 0, expired),
+			Error::<Test>::InvalidLimits
+		);
+
+		let limits = ProxyLimits { expiry: Some(5), spending_limit: None };
+		assert_ok!(Proxy::add_proxy_with_limits(
+			RuntimeOrigin::signed(1),
+			3,
+			ProxyType::JustTransfer,
+			0,
+			limits
+		));
+		assert_ok!(Proxy::add_proxy(RuntimeOrigin::signed(1), 4, ProxyType::Any, 0));
+		assert_eq!(Balances::reserved_balance(1), 4);
+
+		System::set_block_number(4);
+		assert_ok!(Proxy::proxy(RuntimeOrigin::signed(3), 1, None, Box::new(call_transfer(6, 1))));
+		System::assert_last_event(ProxyEvent::ProxyExecuted { result: Ok(()) }.into());
+		Proxy::on_idle(4, Weight::MAX);
+		assert_eq!(Proxies::<Test>::get(1).0.len(), 2);
+
+		System::set_block_number(5);
+		assert_noop!(
+			Proxy::proxy(RuntimeOrigin::signed(3), 1, None, Box::new(call_transfer(6, 1))),
+			Error::<Test>::NotProxy
+		);
+
+		assert_eq!(Proxy::on_idle(5, Weight::zero()), Weight::zero());
+		assert_eq!(Proxies::<Test>::get(1).0.len(), 2);
+
+		Proxy::on_idle(5, Weight::MAX);
+		System::assert_last_event(
+			ProxyEvent::ProxyRemoved {
+				delegator: 1,
+				delegatee: 3,
+				proxy_type: ProxyType::JustTransfer,
+				delay: 0,
+			}
+			.into(),
+		);
+		assert_eq!(
+			Proxies::<Test>::get(1).0.into_inner(),
+			vec![ProxyDefinition { delegate: 4, proxy_type: ProxyType::Any, delay: 0 }]
+		);
+		assert_eq!(Limits::<Test>::iter().count(), 0);
+		assert_eq!(Balances::reserved_balance(1), 2);
+	});
+}
+
+#[test]
+fn expired_proxies_cannot_announce() {
+	new_test_ext().execute_with(|| {
+		let limits = ProxyLimits { expiry: Some(5), spending_limit: None };
+		assert_ok!(Proxy::add_proxy_with_limits(
+			RuntimeOrigin::signed(1),
+			3,
+			ProxyType::Any,
+			1,
+			limits
+		));
+
+		System::set_block_number(4);
+		assert_ok!(Proxy::announce(RuntimeOrigin::signed(3), 1, [1; 32].into()));
+
+		System::set_block_number(5);
+		assert_noop!(
+			Proxy::announce(RuntimeOrigin::signed(3), 1, [2; 32].into()),
+			Error::<Test>::NotProxy
+		);
+		assert_eq!(Announcements::<Test>::get(3).0.len(), 1);
+	});
+}
+
+#[test]
+fn removing_proxies_returns_limits_deposits() {
+	new_test_ext().execute_with(|| {
+		let limits = ProxyLimits { expiry: Some(10), spending_limit: None };
+		assert_ok!(Proxy::add_proxy_with_limits(
+			RuntimeOrigin::signed(1),
+			2,
+			ProxyType::Any,
+			0,
+			limits
+		));
+		assert_ok!(Proxy::add_proxy_with_limits(
+			RuntimeOrigin::signed(1),
+			3,
+			ProxyType::JustTransfer,
+			0,
+			limits
+		));
+		assert_eq!(Balances::reserved_balance(1), 5);
+
+		assert_ok!(Proxy::remove_proxies(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Limits::<Test>::iter().count(), 0);
+	});
+}
@@ -57,6 +57,7 @@ pub trait WeightInfo {
 	fn reject_announcement(a: u32, p: u32, ) -> Weight;
 	fn announce(a: u32, p: u32, ) -> Weight;
 	fn add_proxy(p: u32, ) -> Weight;
+	fn add_proxy_with_limits(p: u32, ) -> Weight;
 	fn remove_proxy(p: u32, ) -> Weight;
 	fn remove_proxies(p: u32, ) -> Weight;
 	fn create_pure(p: u32, ) -> Weight;
@@ -181,6 +182,12 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Not benchmarked yet: `add_proxy` plus the write of `Proxy::Limits`.
+	/// The range of component `p` is `[1, 31]`.
+	fn add_proxy_with_limits(p: u32, ) -> Weight {
+		Self::add_proxy(p)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 	/// Storage: `Proxy::Proxies` (r:1 w:1)
 	/// Proof: `Proxy::Proxies` (`max_values`: None, `max_size`: Some(1241), added: 3716, mode: `MaxEncodedLen`)
 	/// The range of component `p` is `[1, 31]`.
@@ -356,6 +363,12 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Not benchmarked yet: `add_proxy` plus the write of `Proxy::Limits`.
+	/// The range of component `p` is `[1, 31]`.
+	fn add_proxy_with_limits(p: u32, ) -> Weight {
+		Self::add_proxy(p)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 	/// Storage: `Proxy::Proxies` (r:1 w:1)
 	/// Proof: `Proxy::Proxies` (`max_values`: None, `max_size`: Some(1241), added: 3716, mode: `MaxEncodedLen`)
 	/// The range of component `p` is `[1, 31]`.
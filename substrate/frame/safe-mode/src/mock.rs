@@ -148,6 +148,7 @@ impl pallet_proxy::Config for Test {
 	type MaxPending = ConstU32<2>;
 	type AnnouncementDepositBase = ConstU64<1>;
 	type AnnouncementDepositFactor = ConstU64<1>;
+	type ProxyLimitsDeposit = ConstU64<1>;
 }
 
 /// The calls that can always bypass safe-mode.
@@ -196,6 +196,23 @@ pub fn expand_outer_origin(
 			}
 		}
 
+		impl #scrate::sp_runtime::traits::AsSystemOriginSigner<<#runtime as #system_path::Config>::AccountId>
+			for RuntimeOrigin
+		{
+			fn as_system_origin_signer(&self) -> Option<&<#runtime as #system_path::Config>::AccountId> {
+				match &self.caller {
+					OriginCaller::system(#system_path::Origin::<#runtime>::Signed(who)) => Some(who),
+					_ => None,
+				}
+			}
+		}
+
+		impl #scrate::sp_runtime::traits::AsTransactionAuthorizedOrigin for RuntimeOrigin {
+			fn is_transaction_authorized(&self) -> bool {
+				!matches!(&self.caller, OriginCaller::system(#system_path::Origin::<#runtime>::None))
+			}
+		}
+
 		#[derive(
 			Clone, PartialEq, Eq, #scrate::__private::RuntimeDebug, #scrate::__private::codec::Encode,
 			#scrate::__private::codec::Decode, #scrate::__private::scale_info::TypeInfo, #scrate::__private::codec::MaxEncodedLen,
@@ -0,0 +1,98 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License
+use crate::{construct_runtime::Pallet, pallet::parse::helper::two128_str};
+use core::str::FromStr;
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::quote;
+
+/// Expands the `RuntimeViewFunction` struct dispatching view function queries to the pallets.
+pub fn expand_outer_view_function(
+	runtime_name: &Ident,
+	pallet_decls: &[Pallet],
+	scrate: &TokenStream2,
+) -> TokenStream2 {
+	let prefix_conditionals = pallet_decls
+		.iter()
+		.filter(|decl| decl.find_part("Pallet").is_some())
+		.map(|decl| {
+			let pallet_name = &decl.name;
+			let path = &decl.path;
+			let instance = decl.instance.as_ref().into_iter();
+			let prefix = two128_str(&pallet_name.to_string());
+			let attr = decl.cfg_pattern.iter().fold(TokenStream2::new(), |acc, pattern| {
+				let attr = TokenStream2::from_str(&format!("#[cfg({})]", pattern.original()))
+					.expect("was successfully parsed before; qed");
+				quote! {
+					#acc
+					#attr
+				}
+			});
+
+			quote! {
+				#attr
+				if id.prefix == #prefix {
+					return <
+						#path::Pallet<#runtime_name #(, #path::#instance)*>
+							as #scrate::view_functions::DispatchViewFunction
+					>::dispatch_view_function(id, input, output)
+				}
+			}
+		});
+
+	quote! {
+		/// Runtime query type, dispatching view function queries to the pallets of the runtime.
+		#[derive(
+			Clone, PartialEq, Eq,
+			#scrate::__private::codec::Encode,
+			#scrate::__private::codec::Decode,
+			#scrate::__private::scale_info::TypeInfo,
+			#scrate::__private::RuntimeDebug,
+		)]
+		pub struct RuntimeViewFunction;
+
+		const _: () = {
+			impl #scrate::view_functions::DispatchViewFunction for RuntimeViewFunction {
+				fn dispatch_view_function<O: #scrate::__private::codec::Output>(
+					id: &#scrate::view_functions::ViewFunctionId,
+					input: &mut &[u8],
+					output: &mut O,
+				) -> Result<(), #scrate::view_functions::ViewFunctionDispatchError> {
+					#( #prefix_conditionals )*
+					Err(#scrate::view_functions::ViewFunctionDispatchError::NotFound(id.clone()))
+				}
+			}
+
+			impl #runtime_name {
+				/// Convenience function for view function queries, to be used by the
+				/// `RuntimeViewFunction` runtime API.
+				pub fn execute_view_function(
+					id: #scrate::view_functions::ViewFunctionId,
+					input: #scrate::__private::sp_std::vec::Vec<u8>,
+				) -> Result<
+					#scrate::__private::sp_std::vec::Vec<u8>,
+					#scrate::view_functions::ViewFunctionDispatchError,
+				> {
+					let mut output = #scrate::__private::sp_std::vec![];
+					<
+						RuntimeViewFunction as #scrate::view_functions::DispatchViewFunction
+					>::dispatch_view_function(&id, &mut &input[..], &mut output)?;
+					Ok(output)
+				}
+			}
+		};
+	}
+}
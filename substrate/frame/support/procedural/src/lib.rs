@@ -925,6 +925,15 @@ pub fn extra_constants(_: TokenStream, _: TokenStream) -> TokenStream {
 	pallet_macro_stub()
 }
 
+///
+/// ---
+///
+/// Documentation for this macro can be found at `frame_support::pallet_macros::view_functions`.
+#[proc_macro_attribute]
+pub fn view_functions(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
 ///
 /// ---
 ///
@@ -1208,6 +1217,7 @@ pub fn import_section(attr: TokenStream, tokens: TokenStream) -> TokenStream {
 /// 	  RuntimeSlashReason,
 /// 	  RuntimeLockId,
 /// 	  RuntimeTask,
+/// 	  RuntimeViewFunction,
 ///   )]
 ///   pub struct Runtime;
 ///
@@ -0,0 +1,254 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::pallet::{
+	parse::{helper::bytes_to_array, view_functions::ViewFunctionDef},
+	Def,
+};
+use proc_macro2::TokenStream;
+
+/// Expand the view functions of the pallet:
+/// * a struct holding the arguments of each view function, implementing `ViewFunction`,
+/// * the implementation of `ViewFunctionIdPrefix` and `DispatchViewFunction` for the pallet,
+/// * the `pallet_view_functions_metadata` function.
+pub fn expand_view_functions(def: &Def) -> TokenStream {
+	let view_functions =
+		def.view_functions.as_ref().map(|d| &d.view_functions[..]).unwrap_or_default();
+
+	let view_fn_structs = view_functions.iter().map(|view_fn| expand_view_function(def, view_fn));
+	let impl_prefix = impl_view_function_id_prefix(def);
+	let impl_dispatch = impl_dispatch_view_function(def, view_functions);
+	let impl_metadata = impl_view_functions_metadata(def, view_functions);
+
+	quote::quote! {
+		#( #view_fn_structs )*
+		#impl_prefix
+		#impl_dispatch
+		#impl_metadata
+	}
+}
+
+fn where_clause(def: &Def) -> Option<syn::WhereClause> {
+	let mut where_clauses = vec![&def.config.where_clause];
+	where_clauses.extend(def.view_functions.iter().map(|d| &d.where_clause));
+	super::merge_where_clauses(&where_clauses)
+}
+
+fn expand_view_function(def: &Def, view_fn: &ViewFunctionDef) -> TokenStream {
+	let span = view_fn.name.span();
+	let frame_support = &def.frame_support;
+	let pallet_ident = &def.pallet_struct.pallet;
+	let type_impl_gen = &def.type_impl_generics(span);
+	let type_decl_bounded_gen = &def.type_decl_bounded_generics(span);
+	let type_use_gen = &def.type_use_generics(span);
+	let where_clause = where_clause(def);
+
+	let struct_ident = view_fn.view_function_struct_ident();
+	let view_fn_name = &view_fn.name;
+	let arg_names = view_fn.args.iter().map(|(name, _)| name).collect::<Vec<_>>();
+	let arg_types = view_fn.args.iter().map(|(_, ty)| ty).collect::<Vec<_>>();
+	let return_type = &view_fn.return_type;
+	let docs = &view_fn.docs;
+	let suffix = bytes_to_array(view_fn.view_function_id_suffix_bytes());
+	let capture_docs = if cfg!(feature = "no-metadata-docs") { "never" } else { "always" };
+	let struct_doc = format!(
+		"The arguments of the [`{0}`]({1}::{0}) view function.",
+		view_fn_name,
+		quote::quote!(#pallet_ident),
+	);
+
+	quote::quote_spanned!(span =>
+		#[doc = #struct_doc]
+		#[doc = ""]
+		#( #[doc = #docs] )*
+		#[derive(
+			#frame_support::RuntimeDebugNoBound,
+			#frame_support::CloneNoBound,
+			#frame_support::EqNoBound,
+			#frame_support::PartialEqNoBound,
+			#frame_support::__private::codec::Encode,
+			#frame_support::__private::codec::Decode,
+			#frame_support::__private::scale_info::TypeInfo,
+		)]
+		#[codec(encode_bound())]
+		#[codec(decode_bound())]
+		#[scale_info(skip_type_params(#type_use_gen), capture_docs = #capture_docs)]
+		#[allow(missing_docs)]
+		pub struct #struct_ident<#type_decl_bounded_gen> #where_clause {
+			#( pub #arg_names: #arg_types, )*
+			#[codec(skip)]
+			_marker: ::core::marker::PhantomData<(#type_use_gen,)>,
+		}
+
+		impl<#type_impl_gen> #struct_ident<#type_use_gen> #where_clause {
+			/// Create the arguments of the view function.
+			pub fn new(#( #arg_names: #arg_types, )*) -> Self {
+				Self { #( #arg_names, )* _marker: ::core::marker::PhantomData }
+			}
+		}
+
+		impl<#type_impl_gen> #frame_support::view_functions::ViewFunctionIdSuffix
+			for #struct_ident<#type_use_gen> #where_clause
+		{
+			const SUFFIX: [u8; 16] = #suffix;
+		}
+
+		impl<#type_impl_gen> #frame_support::view_functions::ViewFunction
+			for #struct_ident<#type_use_gen> #where_clause
+		{
+			type ReturnType = #return_type;
+
+			fn id() -> #frame_support::view_functions::ViewFunctionId {
+				#frame_support::view_functions::ViewFunctionId {
+					prefix: <
+						#pallet_ident<#type_use_gen> as #frame_support::view_functions::ViewFunctionIdPrefix
+					>::prefix(),
+					suffix: <
+						Self as #frame_support::view_functions::ViewFunctionIdSuffix
+					>::SUFFIX,
+				}
+			}
+
+			fn invoke(self) -> Self::ReturnType {
+				let Self { #( #arg_names, )* _marker } = self;
+				#pallet_ident::<#type_use_gen>::#view_fn_name(#( #arg_names, )*)
+			}
+		}
+	)
+}
+
+fn impl_view_function_id_prefix(def: &Def) -> TokenStream {
+	let span = def.pallet_struct.attr_span;
+	let frame_support = &def.frame_support;
+	let frame_system = &def.frame_system;
+	let pallet_ident = &def.pallet_struct.pallet;
+	let type_impl_gen = &def.type_impl_generics(span);
+	let type_use_gen = &def.type_use_generics(span);
+	let where_clause = where_clause(def);
+
+	quote::quote_spanned!(span =>
+		impl<#type_impl_gen> #frame_support::view_functions::ViewFunctionIdPrefix
+			for #pallet_ident<#type_use_gen> #where_clause
+		{
+			fn prefix() -> [u8; 16] {
+				let name = <
+					<T as #frame_system::Config>::PalletInfo as #frame_support::traits::PalletInfo
+				>::name::<Self>()
+					.expect("No name found for the pallet in the runtime! This usually means \
+						that the pallet wasn't added to `construct_runtime!`.");
+				#frame_support::__private::hashing::twox_128(name.as_bytes())
+			}
+		}
+	)
+}
+
+fn impl_dispatch_view_function(def: &Def, view_functions: &[ViewFunctionDef]) -> TokenStream {
+	let span = def.pallet_struct.attr_span;
+	let frame_support = &def.frame_support;
+	let pallet_ident = &def.pallet_struct.pallet;
+	let type_impl_gen = &def.type_impl_generics(span);
+	let type_use_gen = &def.type_use_generics(span);
+	let where_clause = where_clause(def);
+
+	let suffixes = view_functions
+		.iter()
+		.map(|view_fn| bytes_to_array(view_fn.view_function_id_suffix_bytes()));
+	let struct_idents = view_functions.iter().map(|view_fn| view_fn.view_function_struct_ident());
+	// Avoid unused variable warnings for pallets without view functions.
+	let (input, output) = if view_functions.is_empty() {
+		(quote::quote!(_input), quote::quote!(_output))
+	} else {
+		(quote::quote!(input), quote::quote!(output))
+	};
+
+	quote::quote_spanned!(span =>
+		impl<#type_impl_gen> #frame_support::view_functions::DispatchViewFunction
+			for #pallet_ident<#type_use_gen> #where_clause
+		{
+			#[deny(unreachable_patterns)]
+			fn dispatch_view_function<O: #frame_support::__private::codec::Output>(
+				id: &#frame_support::view_functions::ViewFunctionId,
+				#input: &mut &[u8],
+				#output: &mut O,
+			) -> Result<(), #frame_support::view_functions::ViewFunctionDispatchError> {
+				match id.suffix {
+					#(
+						#suffixes => <
+							#struct_idents<#type_use_gen> as #frame_support::view_functions::ViewFunction
+						>::execute(#input, #output),
+					)*
+					_ => Err(#frame_support::view_functions::ViewFunctionDispatchError::NotFound(
+						id.clone(),
+					)),
+				}
+			}
+		}
+	)
+}
+
+fn impl_view_functions_metadata(def: &Def, view_functions: &[ViewFunctionDef]) -> TokenStream {
+	let span = def.pallet_struct.attr_span;
+	let frame_support = &def.frame_support;
+	let pallet_ident = &def.pallet_struct.pallet;
+	let type_impl_gen = &def.type_impl_generics(span);
+	let type_use_gen = &def.type_use_generics(span);
+	let where_clause = where_clause(def);
+
+	let view_functions = view_functions.iter().map(|view_fn| {
+		let name = view_fn.name.to_string();
+		let struct_ident = view_fn.view_function_struct_ident();
+		let return_type = &view_fn.return_type;
+
+		let inputs = view_fn.args.iter().map(|(name, ty)| {
+			let name = name.to_string();
+			quote::quote!(
+				#frame_support::__private::metadata_ir::PalletViewFunctionParamMetadataIR {
+					name: #name,
+					ty: #frame_support::__private::scale_info::meta_type::<#ty>(),
+				}
+			)
+		});
+
+		let no_docs = vec![];
+		let docs = if cfg!(feature = "no-metadata-docs") { &no_docs } else { &view_fn.docs };
+
+		quote::quote!(
+			#frame_support::__private::metadata_ir::PalletViewFunctionMetadataIR {
+				name: #name,
+				id: <
+					#struct_ident<#type_use_gen> as #frame_support::view_functions::ViewFunction
+				>::id().into(),
+				inputs: #frame_support::__private::sp_std::vec![ #( #inputs ),* ],
+				output: #frame_support::__private::scale_info::meta_type::<#return_type>(),
+				docs: #frame_support::__private::sp_std::vec![ #( #docs ),* ],
+			}
+		)
+	});
+
+	quote::quote_spanned!(span =>
+		impl<#type_impl_gen> #pallet_ident<#type_use_gen> #where_clause {
+			#[doc(hidden)]
+			pub fn pallet_view_functions_metadata()
+				-> #frame_support::__private::sp_std::vec::Vec<
+					#frame_support::__private::metadata_ir::PalletViewFunctionMetadataIR
+				>
+			{
+				#frame_support::__private::sp_std::vec![ #( #view_functions ),* ]
+			}
+		}
+	)
+}
@@ -176,6 +176,7 @@ fn construct_runtime_final_expansion(
 	let mut slash_reason = None;
 	let mut lock_id = None;
 	let mut task = None;
+	let mut view_functions = None;
 
 	for runtime_type in runtime_types.iter() {
 		match runtime_type {
@@ -218,6 +219,9 @@ fn construct_runtime_final_expansion(
 			RuntimeType::RuntimeTask(_) => {
 				task = Some(expand::expand_outer_task(&name, &pallets, &scrate));
 			},
+			RuntimeType::RuntimeViewFunction(_) => {
+				view_functions = Some(expand::expand_outer_view_function(&name, &pallets, &scrate));
+			},
 		}
 	}
 
@@ -295,6 +299,8 @@ fn construct_runtime_final_expansion(
 
 		#task
 
+		#view_functions
+
 		#metadata
 
 		#outer_config
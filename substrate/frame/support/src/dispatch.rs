@@ -24,8 +24,8 @@ use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use sp_runtime::{
-	generic::{CheckedExtrinsic, UncheckedExtrinsic},
-	traits::SignedExtension,
+	generic::{CheckedExtrinsic, CheckedTransaction, ExtrinsicFormat, UncheckedExtrinsic},
+	traits::{ExtensionPostDispatchWeightHandler, SignedExtension, TransactionExtensionBase},
 	DispatchError, RuntimeDebug,
 };
 use sp_std::fmt;
@@ -323,6 +323,16 @@ impl From<()> for PostDispatchInfo {
 	}
 }
 
+impl ExtensionPostDispatchWeightHandler for PostDispatchInfo {
+	fn add_extension_weight(&mut self, weight: Weight) {
+		// Without an actual weight, the weight of the extensions is already part of the
+		// pre-dispatch weight used in its place.
+		if let Some(actual_weight) = self.actual_weight.as_mut() {
+			*actual_weight = actual_weight.saturating_add(weight);
+		}
+	}
+}
+
 impl sp_runtime::traits::Printable for PostDispatchInfo {
 	fn print(&self) {
 		"actual_weight=".print();
@@ -389,6 +399,23 @@ where
 	}
 }
 
+/// Implementation for checked transaction, accounting for the weight of the extension.
+impl<AccountId, Call, Extension> GetDispatchInfo for CheckedTransaction<AccountId, Call, Extension>
+where
+	Call: GetDispatchInfo,
+	Extension: TransactionExtensionBase,
+{
+	fn get_dispatch_info(&self) -> DispatchInfo {
+		let mut info = self.function.get_dispatch_info();
+		match &self.format {
+			ExtrinsicFormat::Bare => {},
+			ExtrinsicFormat::Signed(_, extension) | ExtrinsicFormat::General(extension) =>
+				info.weight = info.weight.saturating_add(extension.weight()),
+		}
+		info
+	}
+}
+
 /// Implementation for test extrinsic.
 #[cfg(feature = "std")]
 impl<Call: Encode + GetDispatchInfo, Extra: Encode> GetDispatchInfo
@@ -75,6 +75,7 @@ pub mod storage;
 #[cfg(test)]
 mod tests;
 pub mod traits;
+pub mod view_functions;
 pub mod weights;
 #[doc(hidden)]
 pub mod unsigned {
@@ -1416,6 +1417,53 @@ pub mod pallet_macros {
 	/// args, 0 generics, and some return type.
 	pub use frame_support_procedural::extra_constants;
 
+	/// Declares read-only functions of the pallet which can be queried from outside of the
+	/// runtime, without the need of a dedicated runtime API.
+	///
+	/// Must be defined like:
+	///
+	/// ```
+	/// #[frame_support::pallet]
+	/// mod pallet {
+	/// # 	use frame_support::pallet_prelude::*;
+	/// #
+	/// 	#[pallet::pallet]
+	/// 	pub struct Pallet<T>(_);
+	///
+	/// # 	#[pallet::config]
+	/// # 	pub trait Config: frame_system::Config {}
+	/// #
+	/// 	#[pallet::storage]
+	/// 	pub type SomeMap<T> = StorageMap<_, Twox64Concat, u32, u32>;
+	///
+	/// 	#[pallet::view_functions]
+	/// 	impl<T: Config> Pallet<T> // $optional_where_clause
+	/// 	{
+	/// 		/// Some doc
+	/// 		pub fn get_value(key: u32) -> Option<u32> {
+	/// 			SomeMap::<T>::get(key)
+	/// 		}
+	/// 	}
+	/// }
+	/// ```
+	///
+	/// I.e. a regular rust `impl` block with some optional where clause and functions with 0
+	/// generics, arguments which are plain identifiers and some return type. The arguments and
+	/// the return type must implement `Encode`, `Decode` and `TypeInfo`.
+	///
+	/// For each function, a struct named after the function in pascal case with a
+	/// `ViewFunction` suffix (`GetValueViewFunction` in the example above) is generated. It
+	/// holds the arguments of the function and implements
+	/// [`ViewFunction`](crate::view_functions::ViewFunction).
+	///
+	/// The functions are identified by a
+	/// [`ViewFunctionId`](crate::view_functions::ViewFunctionId) made of the `twox_128` hash
+	/// of the pallet name in the runtime and the `twox_128` hash of the signature of the
+	/// function. They are executed through the
+	/// [`RuntimeViewFunction`](crate::view_functions::runtime_api::RuntimeViewFunction)
+	/// runtime API and are listed in the metadata of the pallet.
+	pub use frame_support_procedural::view_functions;
+
 	#[rustfmt::skip]
 	/// Allows bypassing the `frame_system::Config` supertrait check.
 	///
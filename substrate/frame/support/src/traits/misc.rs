@@ -943,6 +943,19 @@ where
 	}
 }
 
+impl<Address, Call, Signature, Extension> ExtrinsicCall
+	for sp_runtime::generic::UncheckedTransaction<Address, Call, Signature, Extension>
+where
+	Address: TypeInfo,
+	Call: TypeInfo,
+	Signature: TypeInfo,
+	Extension: TypeInfo,
+{
+	fn call(&self) -> &Self::Call {
+		&self.function
+	}
+}
+
 /// Something that can estimate the fee of a (frame-based) call.
 ///
 /// Typically, the same pallet that will charge transaction fees will implement this.
@@ -0,0 +1,139 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traits and types for the read-only view functions of pallets.
+//!
+//! View functions are declared in a pallet with
+//! [`pallet::view_functions`](crate::pallet_macros::view_functions) and are exposed by the
+//! runtime through the single [`RuntimeViewFunction`](runtime_api::RuntimeViewFunction) runtime
+//! API. A query is routed to the pallet by the [`ViewFunctionId::prefix`] and to the function
+//! within the pallet by the [`ViewFunctionId::suffix`].
+
+use codec::{Decode, DecodeAll, Encode, Output};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// The unique identifier of a view function.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ViewFunctionId {
+	/// The part of the id used to route the query to the pallet, the `twox_128` hash of the name
+	/// of the pallet in the runtime.
+	pub prefix: [u8; 16],
+	/// The part of the id used to route the query to the function within the pallet, the
+	/// `twox_128` hash of the signature of the function.
+	pub suffix: [u8; 16],
+}
+
+impl From<ViewFunctionId> for [u8; 32] {
+	fn from(value: ViewFunctionId) -> Self {
+		let mut output = [0u8; 32];
+		output[..16].copy_from_slice(&value.prefix);
+		output[16..].copy_from_slice(&value.suffix);
+		output
+	}
+}
+
+/// Error returned when dispatching a view function query.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum ViewFunctionDispatchError {
+	/// View functions are not implemented by this runtime.
+	NotImplemented,
+	/// There is no view function with the given id.
+	NotFound(ViewFunctionId),
+	/// Failed to decode the arguments of the view function.
+	Codec,
+}
+
+impl From<codec::Error> for ViewFunctionDispatchError {
+	fn from(_: codec::Error) -> Self {
+		ViewFunctionDispatchError::Codec
+	}
+}
+
+/// Dispatches a view function query by its id, implemented by pallets and by the runtime.
+pub trait DispatchViewFunction {
+	/// Decodes the arguments of the view function with the given `id` from `input`, executes it
+	/// and writes the encoded result to `output`.
+	fn dispatch_view_function<O: Output>(
+		id: &ViewFunctionId,
+		input: &mut &[u8],
+		output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError>;
+}
+
+impl DispatchViewFunction for () {
+	fn dispatch_view_function<O: Output>(
+		_id: &ViewFunctionId,
+		_input: &mut &[u8],
+		_output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError> {
+		Err(ViewFunctionDispatchError::NotImplemented)
+	}
+}
+
+/// Provides the [`ViewFunctionId::prefix`] of the view functions of a pallet.
+pub trait ViewFunctionIdPrefix {
+	/// The prefix of the view functions of the pallet.
+	fn prefix() -> [u8; 16];
+}
+
+/// Provides the [`ViewFunctionId::suffix`] of a view function.
+pub trait ViewFunctionIdSuffix {
+	/// The suffix of the view function.
+	const SUFFIX: [u8; 16];
+}
+
+/// A view function, holding its arguments.
+pub trait ViewFunction: DecodeAll {
+	/// The type returned by the view function.
+	type ReturnType: Encode;
+
+	/// The id of the view function.
+	fn id() -> ViewFunctionId;
+
+	/// Executes the view function.
+	fn invoke(self) -> Self::ReturnType;
+
+	/// Decodes the view function from `input`, executes it and writes the encoded result to
+	/// `output`.
+	fn execute<O: Output>(
+		input: &mut &[u8],
+		output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError> {
+		let view_function = Self::decode_all(input)?;
+		view_function.invoke().encode_to(output);
+		Ok(())
+	}
+}
+
+/// Runtime API for the view functions of pallets.
+pub mod runtime_api {
+	use super::*;
+
+	sp_api::decl_runtime_apis! {
+		/// Runtime API for executing the view functions of pallets.
+		pub trait RuntimeViewFunction {
+			/// Executes the view function with the given `query_id` and SCALE encoded arguments,
+			/// returning the SCALE encoded result.
+			fn execute_view_function(
+				query_id: ViewFunctionId,
+				input: Vec<u8>,
+			) -> Result<Vec<u8>, ViewFunctionDispatchError>;
+		}
+	}
+}
@@ -0,0 +1,273 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the view functions of pallets, test for:
+//! * dispatching view functions through the runtime
+//! * the ids of the view functions
+//! * the metadata of the view functions
+
+#![recursion_limit = "128"]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	derive_impl,
+	traits::PalletInfoAccess,
+	view_functions::{ViewFunction, ViewFunctionDispatchError, ViewFunctionId},
+};
+use sp_core::sr25519;
+use sp_io::hashing::twox_128;
+use sp_metadata_ir::{PalletViewFunctionMetadataIR, PalletViewFunctionParamMetadataIR};
+use sp_runtime::{generic, traits::BlakeTwo256};
+
+#[frame_support::pallet(dev_mode)]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	pub type SomeValue<T: Config> = StorageValue<_, u32>;
+
+	#[pallet::storage]
+	pub type SomeMap<T: Config> = StorageMap<_, Twox64Concat, u32, u32, OptionQuery>;
+
+	#[pallet::view_functions]
+	impl<T: Config> Pallet<T> {
+		/// Query value no args.
+		pub fn get_value() -> Option<u32> {
+			SomeValue::<T>::get()
+		}
+
+		/// Query value with args.
+		pub fn get_value_with_arg(key: u32) -> Option<u32> {
+			SomeMap::<T>::get(key)
+		}
+	}
+}
+
+#[frame_support::pallet(dev_mode)]
+pub mod pallet_instance {
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::storage]
+	pub type SomeValue<T: Config<I>, I: 'static = ()> = StorageValue<_, u32>;
+
+	#[pallet::storage]
+	pub type SomeMap<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, u32, u32, OptionQuery>;
+
+	#[pallet::view_functions]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Query value no args.
+		pub fn get_value() -> Option<u32> {
+			SomeValue::<T, I>::get()
+		}
+
+		/// Query value with args.
+		pub fn get_value_with_arg(key: u32) -> Option<u32> {
+			SomeMap::<T, I>::get(key)
+		}
+	}
+}
+
+#[frame_support::pallet(dev_mode)]
+pub mod pallet_without_view_functions {
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+}
+
+pub type BlockNumber = u64;
+pub type Signature = sr25519::Signature;
+pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
+pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<u32, RuntimeCall, Signature, ()>;
+pub type Block = generic::Block<Header, UncheckedExtrinsic>;
+
+frame_support::construct_runtime!(
+	pub enum Runtime {
+		System: frame_system,
+		ViewFunctionsExample: pallet,
+		ViewFunctionsInstance: pallet_instance,
+		ViewFunctionsInstance1: pallet_instance::<Instance1>,
+		WithoutViewFunctions: pallet_without_view_functions,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+	type Block = Block;
+}
+
+impl pallet::Config for Runtime {}
+impl pallet_instance::Config for Runtime {}
+impl pallet_instance::Config<pallet_instance::Instance1> for Runtime {}
+impl pallet_without_view_functions::Config for Runtime {}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	frame_system::GenesisConfig::<Runtime>::default()
+		.build_storage()
+		.unwrap()
+		.into()
+}
+
+fn execute_view_function<V: ViewFunction>(view_function: V) -> V::ReturnType
+where
+	V::ReturnType: Decode,
+{
+	let output = Runtime::execute_view_function(V::id(), view_function.encode()).unwrap();
+	V::ReturnType::decode(&mut &output[..]).unwrap()
+}
+
+#[test]
+fn pallet_get_value_query() {
+	new_test_ext().execute_with(|| {
+		let some_value = Some(99);
+		pallet::SomeValue::<Runtime>::set(some_value);
+		assert_eq!(some_value, pallet::Pallet::<Runtime>::get_value());
+
+		let query = pallet::GetValueViewFunction::<Runtime>::new();
+		assert_eq!(some_value, execute_view_function(query));
+	});
+}
+
+#[test]
+fn pallet_get_value_with_arg_query() {
+	new_test_ext().execute_with(|| {
+		let some_key = 1u32;
+		let some_value = Some(123);
+		pallet::SomeMap::<Runtime>::set(some_key, some_value);
+		assert_eq!(some_value, pallet::Pallet::<Runtime>::get_value_with_arg(some_key));
+
+		let query = pallet::GetValueWithArgViewFunction::<Runtime>::new(some_key);
+		assert_eq!(some_value, execute_view_function(query));
+
+		let query = pallet::GetValueWithArgViewFunction::<Runtime>::new(some_key + 1);
+		assert_eq!(None, execute_view_function(query));
+	});
+}
+
+#[test]
+fn pallet_multiple_instances() {
+	use pallet_instance::Instance1;
+
+	new_test_ext().execute_with(|| {
+		let instance_value = Some(123);
+		let instance1_value = Some(456);
+
+		pallet_instance::SomeValue::<Runtime>::set(instance_value);
+		pallet_instance::SomeValue::<Runtime, Instance1>::set(instance1_value);
+
+		let query = pallet_instance::GetValueViewFunction::<Runtime>::new();
+		assert_eq!(instance_value, execute_view_function(query));
+
+		let query = pallet_instance::GetValueViewFunction::<Runtime, Instance1>::new();
+		assert_eq!(instance1_value, execute_view_function(query));
+	});
+}
+
+#[test]
+fn view_function_ids() {
+	let id = pallet::GetValueViewFunction::<Runtime>::id();
+	assert_eq!(id.prefix, twox_128(ViewFunctionsExample::name().as_bytes()));
+	assert_eq!(id.suffix, twox_128(b"get_value()->Option<u32>"));
+
+	let id = pallet::GetValueWithArgViewFunction::<Runtime>::id();
+	assert_eq!(id.suffix, twox_128(b"get_value_with_arg(u32)->Option<u32>"));
+
+	// Instances share the suffix but are routed to different pallets by the prefix.
+	let id = pallet_instance::GetValueViewFunction::<Runtime>::id();
+	let id1 = pallet_instance::GetValueViewFunction::<Runtime, pallet_instance::Instance1>::id();
+	assert_eq!(id.suffix, id1.suffix);
+	assert_eq!(id.prefix, twox_128(b"ViewFunctionsInstance"));
+	assert_eq!(id1.prefix, twox_128(b"ViewFunctionsInstance1"));
+}
+
+#[test]
+fn view_function_dispatch_errors() {
+	new_test_ext().execute_with(|| {
+		let unknown_pallet = ViewFunctionId { prefix: [0; 16], suffix: [0; 16] };
+		assert_eq!(
+			Runtime::execute_view_function(unknown_pallet.clone(), vec![]),
+			Err(ViewFunctionDispatchError::NotFound(unknown_pallet)),
+		);
+
+		let unknown_function = ViewFunctionId {
+			prefix: twox_128(b"WithoutViewFunctions"),
+			suffix: pallet::GetValueViewFunction::<Runtime>::id().suffix,
+		};
+		assert_eq!(
+			Runtime::execute_view_function(unknown_function.clone(), vec![]),
+			Err(ViewFunctionDispatchError::NotFound(unknown_function)),
+		);
+
+		// The argument is missing.
+		assert_eq!(
+			Runtime::execute_view_function(
+				pallet::GetValueWithArgViewFunction::<Runtime>::id(),
+				vec![],
+			),
+			Err(ViewFunctionDispatchError::Codec),
+		);
+	});
+}
+
+#[test]
+fn metadata_ir_definitions() {
+	let metadata = Runtime::metadata_ir();
+	let pallet = |name: &str| metadata.pallets.iter().find(|p| p.name == name).unwrap();
+
+	assert_eq!(
+		pallet("ViewFunctionsExample").view_functions,
+		vec![
+			PalletViewFunctionMetadataIR {
+				name: "get_value",
+				id: pallet::GetValueViewFunction::<Runtime>::id().into(),
+				inputs: vec![],
+				output: scale_info::meta_type::<Option<u32>>(),
+				docs: vec![" Query value no args."],
+			},
+			PalletViewFunctionMetadataIR {
+				name: "get_value_with_arg",
+				id: pallet::GetValueWithArgViewFunction::<Runtime>::id().into(),
+				inputs: vec![PalletViewFunctionParamMetadataIR {
+					name: "key",
+					ty: scale_info::meta_type::<u32>(),
+				}],
+				output: scale_info::meta_type::<Option<u32>>(),
+				docs: vec![" Query value with args."],
+			},
+		]
+	);
+
+	let instance1_id: [u8; 32] =
+		pallet_instance::GetValueViewFunction::<Runtime, pallet_instance::Instance1>::id().into();
+	assert_eq!(pallet("ViewFunctionsInstance1").view_functions[0].id, instance1_id);
+
+	assert!(pallet("WithoutViewFunctions").view_functions.is_empty());
+}
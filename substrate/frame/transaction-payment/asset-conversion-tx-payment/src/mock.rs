@@ -182,6 +182,8 @@ impl pallet_assets::Config for Runtime {
 	type ApprovalDeposit = ConstU64<0>;
 	type StringLimit = ConstU32<20>;
 	type Freezer = ();
+	type RuntimeHoldReason = ();
+	type RuntimeFreezeReason = ();
 	type Extra = ();
 	type CallbackHandle = ();
 	type WeightInfo = ();
@@ -207,6 +209,8 @@ impl pallet_assets::Config<Instance2> for Runtime {
 	type ApprovalDeposit = ConstU64<0>;
 	type StringLimit = ConstU32<50>;
 	type Freezer = ();
+	type RuntimeHoldReason = ();
+	type RuntimeFreezeReason = ();
 	type Extra = ();
 	type WeightInfo = ();
 	type CallbackHandle = ();
@@ -248,9 +252,15 @@ impl pallet_asset_conversion::Config for Runtime {
 	type PoolSetupFeeAsset = Native;
 	type PoolSetupFeeTarget = ResolveAssetTo<AssetConversionOrigin, Self::Assets>;
 	type PalletId = AssetConversionPalletId;
+	type Curve = pallet_asset_conversion::Curve;
 	type LPFee = ConstU32<3>; // means 0.3%
 	type LiquidityWithdrawalFee = LiquidityWithdrawalFee;
 	type MaxSwapPathLength = MaxSwapPathLength;
+	type MaxPriceObservations = ConstU32<16>;
+	type MaxLimitOrders = ConstU32<0>;
+	type MaxLimitOrdersPerAccount = ConstU32<0>;
+	type LimitOrderDeposit = ConstU64<0>;
+	type LimitOrderLifetime = ConstU64<0>;
 	type MintMinLiquidity = ConstU64<100>; // 100 is good enough when the main currency has 12 decimals.
 	type WeightInfo = ();
 	pallet_asset_conversion::runtime_benchmarks_enabled! {
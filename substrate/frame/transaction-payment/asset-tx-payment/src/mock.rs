@@ -142,6 +142,8 @@ impl pallet_assets::Config for Runtime {
 	type ApprovalDeposit = ConstU64<0>;
 	type StringLimit = ConstU32<20>;
 	type Freezer = ();
+	type RuntimeHoldReason = ();
+	type RuntimeFreezeReason = ();
 	type Extra = ();
 	type CallbackHandle = ();
 	type WeightInfo = ();
@@ -54,6 +54,7 @@ use frame_support::{
 	dispatch::{
 		DispatchClass, DispatchInfo, DispatchResult, GetDispatchInfo, Pays, PostDispatchInfo,
 	},
+	storage::{transactional::with_transaction_opaque_err, TransactionOutcome},
 	traits::{Defensive, EstimateCallFee, Get},
 	weights::{Weight, WeightToFee},
 };
@@ -61,11 +62,12 @@ pub use pallet::*;
 pub use payment::*;
 use sp_runtime::{
 	traits::{
-		Convert, DispatchInfoOf, Dispatchable, One, PostDispatchInfoOf, SaturatedConversion,
-		Saturating, SignedExtension, Zero,
+		AsSystemOriginSigner, Convert, DispatchInfoOf, DispatchOriginOf, Dispatchable, One,
+		PostDispatchInfoOf, SaturatedConversion, Saturating, SignedExtension, ValidateResult, Zero,
 	},
 	transaction_validity::{
-		TransactionPriority, TransactionValidity, TransactionValidityError, ValidTransaction,
+		InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError,
+		ValidTransaction,
 	},
 	FixedPointNumber, FixedU128, Perbill, Perquintill, RuntimeDebug,
 };
@@ -731,6 +733,24 @@ where
 		.map(|i| (fee, i))
 	}
 
+	/// Check that the fee can be withdrawn from `who`, without withdrawing it.
+	///
+	/// Returns the fee which would be withdrawn.
+	fn can_withdraw_fee(
+		&self,
+		who: &T::AccountId,
+		call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+	) -> Result<BalanceOf<T>, TransactionValidityError> {
+		with_transaction_opaque_err(|| {
+			TransactionOutcome::Rollback(
+				self.withdraw_fee(who, call, info, len).map(|(fee, _)| fee),
+			)
+		})
+		.map_err(|_| InvalidTransaction::ExhaustsResources)?
+	}
+
 	/// Get an appropriate priority for a transaction with the given `DispatchInfo`, encoded length
 	/// and user-included tip.
 	///
@@ -882,6 +902,81 @@ where
 	}
 }
 
+impl<T: Config> sp_runtime::traits::TransactionExtensionBase for ChargeTransactionPayment<T>
+where
+	BalanceOf<T>: Send + Sync,
+{
+	const IDENTIFIER: &'static str = "ChargeTransactionPayment";
+	type Implicit = ();
+
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+		Ok(())
+	}
+}
+
+/// The fees are paid by the signer of the origin of the transaction, which may have been set by
+/// a previous extension of the pipeline, e.g. to let a sponsor pay for the transaction.
+/// Transactions without a signed origin pay no fees and must be authorized by another extension.
+impl<T: Config> sp_runtime::traits::TransactionExtension<T::RuntimeCall>
+	for ChargeTransactionPayment<T>
+where
+	BalanceOf<T>: Send + Sync + From<u64>,
+	T::RuntimeCall: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	DispatchOriginOf<T::RuntimeCall>: AsSystemOriginSigner<T::AccountId>,
+{
+	type Val = ();
+	type Pre = Option<(
+		// tip
+		BalanceOf<T>,
+		// who paid the fee
+		T::AccountId,
+		// imbalance resulting from withdrawing the fee
+		<<T as Config>::OnChargeTransaction as OnChargeTransaction<T>>::LiquidityInfo,
+	)>;
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<T::RuntimeCall>,
+		call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+		_inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, T::RuntimeCall> {
+		let Some(who) = origin.as_system_origin_signer() else {
+			return Ok((ValidTransaction::default(), (), origin))
+		};
+		let final_fee = self.can_withdraw_fee(who, call, info, len)?;
+		let valid = ValidTransaction {
+			priority: Self::get_priority(info, len, self.0, final_fee),
+			..Default::default()
+		};
+		Ok((valid, (), origin))
+	}
+
+	fn prepare(
+		self,
+		_val: Self::Val,
+		origin: &DispatchOriginOf<T::RuntimeCall>,
+		call: &T::RuntimeCall,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		let Some(who) = origin.as_system_origin_signer() else { return Ok(None) };
+		let (_fee, imbalance) = self.withdraw_fee(who, call, info, len)?;
+		Ok(Some((self.0, who.clone(), imbalance)))
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<T::RuntimeCall>,
+		post_info: &PostDispatchInfoOf<T::RuntimeCall>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		<Self as SignedExtension>::post_dispatch(pre, info, post_info, len, result)
+	}
+}
+
 impl<T: Config, AnyCall: GetDispatchInfo + Encode> EstimateCallFee<AnyCall, BalanceOf<T>>
 	for Pallet<T>
 where
@@ -262,6 +262,61 @@ fn signed_extension_allows_free_transactions() {
 		});
 }
 
+#[test]
+fn transaction_extension_charges_signer_of_origin() {
+	use sp_runtime::traits::TransactionExtension;
+
+	ExtBuilder::default()
+		.balance_factor(10)
+		.base_weight(Weight::from_parts(5, 0))
+		.build()
+		.execute_with(|| {
+			let len = 10;
+			let info = info_from_weight(Weight::from_parts(5, 0));
+			let ext = ChargeTransactionPayment::<Runtime>::from(0);
+
+			// Validation checks the fee can be paid without withdrawing it.
+			let (valid, _, _) =
+				ext.validate_only(RuntimeOrigin::signed(1), CALL, &info, len).unwrap();
+			assert!(valid.priority > 0);
+			assert_eq!(Balances::free_balance(1), 100);
+
+			let (pre, origin) =
+				ext.validate_and_prepare(RuntimeOrigin::signed(1), CALL, &info, len).unwrap();
+			assert_eq!(origin.as_system_origin_signer(), Some(&1));
+			assert_eq!(Balances::free_balance(1), 100 - 5 - 5 - 10);
+
+			assert_ok!(<ChargeTransactionPayment<Runtime> as TransactionExtension<
+				RuntimeCall,
+			>>::post_dispatch(pre, &info, &default_post_info(), len, &Ok(())));
+			assert_eq!(Balances::free_balance(1), 100 - 5 - 5 - 10);
+			assert_eq!(FeeUnbalancedAmount::get(), 5 + 5 + 10);
+			assert_eq!(TipUnbalancedAmount::get(), 0);
+		});
+}
+
+#[test]
+fn transaction_extension_without_signer_pays_no_fee() {
+	use sp_runtime::traits::TransactionExtension;
+
+	ExtBuilder::default().balance_factor(0).build().execute_with(|| {
+		let len = 10;
+		let info = info_from_weight(Weight::from_parts(5, 0));
+		let ext = ChargeTransactionPayment::<Runtime>::from(0);
+
+		// 1 cannot pay for the transaction.
+		assert_eq!(
+			ext.validate_only(RuntimeOrigin::signed(1), CALL, &info, len).map(|_| ()),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Payment)),
+		);
+
+		// Without a signer nobody is charged.
+		let (pre, _) = ext.validate_and_prepare(RuntimeOrigin::none(), CALL, &info, len).unwrap();
+		assert!(pre.is_none());
+		assert_eq!(FeeUnbalancedAmount::get(), 0);
+	});
+}
+
 #[test]
 fn signed_ext_length_fee_is_also_updated_per_congestion() {
 	ExtBuilder::default()
@@ -83,6 +83,7 @@ impl From<PalletMetadataIR> for PalletMetadata {
 			error: ir.error.map(Into::into),
 			index: ir.index,
 			docs: ir.docs,
+			// Note: ir.view_functions not part of v15.
 		}
 	}
 }
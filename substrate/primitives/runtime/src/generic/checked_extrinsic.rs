@@ -20,17 +20,40 @@
 
 use crate::{
 	traits::{
-		self, DispatchInfoOf, Dispatchable, MaybeDisplay, Member, PostDispatchInfoOf,
-		SignedExtension, ValidateUnsigned,
+		self, AsSystemOriginSigner, AsTransactionAuthorizedOrigin, AsTransactionExtension,
+		DispatchInfoOf, Dispatchable, ExtensionPostDispatchWeightHandler, MaybeDisplay, Member,
+		PostDispatchInfoOf, SignedExtension, TransactionExtension, ValidateUnsigned,
+	},
+	transaction_validity::{
+		InvalidTransaction, TransactionSource, TransactionValidity, TransactionValidityError,
 	},
-	transaction_validity::{TransactionSource, TransactionValidity},
 };
+use codec::Encode;
+
+/// The format of an extrinsic, which determines the origin it is dispatched with and the
+/// [`TransactionExtension`] data it carries.
+#[derive(PartialEq, Eq, Clone, sp_core::RuntimeDebug)]
+pub enum ExtrinsicFormat<AccountId, Extension> {
+	/// An extrinsic without origin nor extension data, i.e. an inherent or an unsigned transaction
+	/// validated with [`ValidateUnsigned`]. It is dispatched with the `None` origin.
+	Bare,
+	/// A transaction signed by the given account. The extensions are given the signed origin of
+	/// the account.
+	Signed(AccountId, Extension),
+	/// A transaction without signature. The extensions are given the `None` origin and are
+	/// responsible for authorizing the transaction by changing its origin, otherwise it is
+	/// rejected with [`InvalidTransaction::UnknownOrigin`].
+	General(Extension),
+}
 
 /// Definition of something that the external world might want to say; its existence implies that it
 /// has been checked and is good, particularly with regards to the signature.
 ///
 /// This is typically passed into [`traits::Applyable::apply`], which should execute
 /// [`CheckedExtrinsic::function`], alongside all other bits and bobs.
+///
+/// The [`SignedExtension`] of the extrinsic is run through the [`TransactionExtension`] pipeline
+/// with [`AsTransactionExtension`].
 #[derive(PartialEq, Eq, Clone, sp_core::RuntimeDebug)]
 pub struct CheckedExtrinsic<AccountId, Call, Extra> {
 	/// Who this purports to be from and the number of extrinsics have come before
@@ -45,9 +68,11 @@ impl<AccountId, Call, Extra, RuntimeOrigin> traits::Applyable
 	for CheckedExtrinsic<AccountId, Call, Extra>
 where
 	AccountId: Member + MaybeDisplay,
-	Call: Member + Dispatchable<RuntimeOrigin = RuntimeOrigin>,
+	Call: Member + Encode + Dispatchable<RuntimeOrigin = RuntimeOrigin>,
 	Extra: SignedExtension<AccountId = AccountId, Call = Call>,
-	RuntimeOrigin: From<Option<AccountId>>,
+	RuntimeOrigin:
+		From<Option<AccountId>> + AsSystemOriginSigner<AccountId> + AsTransactionAuthorizedOrigin,
+	PostDispatchInfoOf<Call>: ExtensionPostDispatchWeightHandler,
 {
 	type Call = Call;
 
@@ -59,13 +84,12 @@ where
 		info: &DispatchInfoOf<Self::Call>,
 		len: usize,
 	) -> TransactionValidity {
-		if let Some((ref id, ref extra)) = self.signed {
-			Extra::validate(extra, id, &self.function, info, len)
-		} else {
-			let valid = Extra::validate_unsigned(&self.function, info, len)?;
-			let unsigned_validation = U::validate_unsigned(source, &self.function)?;
-			Ok(valid.combine_with(unsigned_validation))
-		}
+		let format = match self.signed {
+			Some((ref id, ref extra)) =>
+				ExtrinsicFormat::Signed(id.clone(), AsTransactionExtension::from(extra.clone())),
+			None => ExtrinsicFormat::Bare,
+		};
+		validate::<U, _, _, _>(&format, &self.function, source, info, len)
 	}
 
 	fn apply<U: ValidateUnsigned<Call = Self::Call>>(
@@ -73,26 +97,145 @@ where
 		info: &DispatchInfoOf<Self::Call>,
 		len: usize,
 	) -> crate::ApplyExtrinsicResultWithInfo<PostDispatchInfoOf<Self::Call>> {
-		let (maybe_who, maybe_pre) = if let Some((id, extra)) = self.signed {
-			let pre = Extra::pre_dispatch(extra, &id, &self.function, info, len)?;
-			(Some(id), Some(pre))
-		} else {
-			Extra::pre_dispatch_unsigned(&self.function, info, len)?;
-			U::pre_dispatch(&self.function)?;
-			(None, None)
-		};
-		let res = self.function.dispatch(RuntimeOrigin::from(maybe_who));
-		let post_info = match res {
-			Ok(info) => info,
-			Err(err) => err.post_info,
+		traits::Applyable::apply::<U>(CheckedTransaction::from(self), info, len)
+	}
+}
+
+/// An extrinsic that has passed the verification stage, whose [`TransactionExtension`] pipeline
+/// is run for any [`ExtrinsicFormat`].
+#[derive(PartialEq, Eq, Clone, sp_core::RuntimeDebug)]
+pub struct CheckedTransaction<AccountId, Call, Extension> {
+	/// The format of the extrinsic, with the signer and the extension data, if any.
+	pub format: ExtrinsicFormat<AccountId, Extension>,
+
+	/// The function that should be called.
+	pub function: Call,
+}
+
+impl<AccountId, Call, Extra> From<CheckedExtrinsic<AccountId, Call, Extra>>
+	for CheckedTransaction<AccountId, Call, AsTransactionExtension<Extra>>
+where
+	Extra: SignedExtension,
+{
+	fn from(xt: CheckedExtrinsic<AccountId, Call, Extra>) -> Self {
+		let format = match xt.signed {
+			Some((id, extra)) => ExtrinsicFormat::Signed(id, extra.into()),
+			None => ExtrinsicFormat::Bare,
 		};
-		Extra::post_dispatch(
-			maybe_pre,
-			info,
-			&post_info,
-			len,
-			&res.map(|_| ()).map_err(|e| e.error),
-		)?;
-		Ok(res)
+		Self { format, function: xt.function }
+	}
+}
+
+impl<AccountId, Call, Extension, RuntimeOrigin> traits::Applyable
+	for CheckedTransaction<AccountId, Call, Extension>
+where
+	AccountId: Member + MaybeDisplay,
+	Call: Member + Encode + Dispatchable<RuntimeOrigin = RuntimeOrigin>,
+	Extension: TransactionExtension<Call>,
+	RuntimeOrigin: From<Option<AccountId>> + AsTransactionAuthorizedOrigin,
+	PostDispatchInfoOf<Call>: ExtensionPostDispatchWeightHandler,
+{
+	type Call = Call;
+
+	fn validate<U: ValidateUnsigned<Call = Self::Call>>(
+		&self,
+		source: TransactionSource,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		validate::<U, _, _, _>(&self.format, &self.function, source, info, len)
+	}
+
+	fn apply<U: ValidateUnsigned<Call = Self::Call>>(
+		self,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> crate::ApplyExtrinsicResultWithInfo<PostDispatchInfoOf<Self::Call>> {
+		match self.format {
+			ExtrinsicFormat::Bare => {
+				Extension::bare_validate_and_prepare(&self.function, info, len)?;
+				U::pre_dispatch(&self.function)?;
+				let res = self.function.dispatch(None.into());
+				let post_info = match res {
+					Ok(info) => info,
+					Err(err) => err.post_info,
+				};
+				Extension::bare_post_dispatch(
+					info,
+					&post_info,
+					len,
+					&res.map(|_| ()).map_err(|e| e.error),
+				)?;
+				Ok(res)
+			},
+			ExtrinsicFormat::Signed(signer, extension) =>
+				extension.dispatch_transaction(Some(signer).into(), self.function, info, len),
+			ExtrinsicFormat::General(extension) => {
+				// The origin is checked before anything is prepared, e.g. fees withdrawn.
+				let extension_weight = extension.weight();
+				let implicit = extension.implicit()?;
+				let (_, val, origin) = extension.validate_for_apply(
+					None.into(),
+					&self.function,
+					info,
+					len,
+					&(&self.function, &extension, &implicit),
+				)?;
+				ensure_authorized(&origin)?;
+				let pre = extension.prepare(val, &origin, &self.function, info, len)?;
+				Extension::dispatch_prepared(
+					pre,
+					origin,
+					self.function,
+					info,
+					len,
+					extension_weight,
+				)
+			},
+		}
+	}
+}
+
+/// Validate an extrinsic of the given `format` for the transaction queue.
+fn validate<U, AccountId, Call, Extension>(
+	format: &ExtrinsicFormat<AccountId, Extension>,
+	call: &Call,
+	source: TransactionSource,
+	info: &DispatchInfoOf<Call>,
+	len: usize,
+) -> TransactionValidity
+where
+	U: ValidateUnsigned<Call = Call>,
+	AccountId: Clone,
+	Call: Encode + Dispatchable,
+	Call::RuntimeOrigin: From<Option<AccountId>> + AsTransactionAuthorizedOrigin,
+	Extension: TransactionExtension<Call>,
+{
+	match format {
+		ExtrinsicFormat::Bare => {
+			let valid = Extension::bare_validate(call, info, len)?;
+			let unsigned_validation = U::validate_unsigned(source, call)?;
+			Ok(valid.combine_with(unsigned_validation))
+		},
+		ExtrinsicFormat::Signed(signer, extension) => extension
+			.validate_only(Some(signer.clone()).into(), call, info, len)
+			.map(|(valid, _, _)| valid),
+		ExtrinsicFormat::General(extension) => {
+			let (valid, _, origin) = extension.validate_only(None.into(), call, info, len)?;
+			ensure_authorized(&origin)?;
+			Ok(valid)
+		},
+	}
+}
+
+/// Reject a general transaction which none of its extensions authorized, since it would be
+/// dispatched with the `None` origin without going through [`ValidateUnsigned`].
+fn ensure_authorized<Origin: AsTransactionAuthorizedOrigin>(
+	origin: &Origin,
+) -> Result<(), TransactionValidityError> {
+	if origin.is_transaction_authorized() {
+		Ok(())
+	} else {
+		Err(InvalidTransaction::UnknownOrigin.into())
 	}
 }
@@ -26,12 +26,14 @@ mod header;
 #[cfg(test)]
 mod tests;
 mod unchecked_extrinsic;
+mod unchecked_transaction;
 
 pub use self::{
 	block::{Block, BlockId, SignedBlock},
-	checked_extrinsic::CheckedExtrinsic,
+	checked_extrinsic::{CheckedExtrinsic, CheckedTransaction, ExtrinsicFormat},
 	digest::{Digest, DigestItem, DigestItemRef, OpaqueDigestItemId},
 	era::{Era, Phase},
 	header::Header,
 	unchecked_extrinsic::{SignedPayload, UncheckedExtrinsic},
+	unchecked_transaction::{Preamble, SignedTransactionPayload, UncheckedTransaction},
 };
@@ -208,6 +208,10 @@ where
 {
 	const VERSION: u8 = EXTRINSIC_FORMAT_VERSION;
 	type SignedExtensions = Extra;
+
+	fn signed_extensions_metadata() -> Vec<traits::SignedExtensionMetadata> {
+		Extra::metadata()
+	}
 }
 
 /// A payload that has been signed for an unchecked extrinsics.
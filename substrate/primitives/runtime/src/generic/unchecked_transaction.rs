@@ -0,0 +1,536 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generic implementation of an unchecked (pre-verification) extrinsic carrying
+//! [`TransactionExtension`](crate::traits::TransactionExtension) data.
+
+use crate::{
+	generic::{CheckedTransaction, ExtrinsicFormat},
+	traits::{
+		self, Checkable, Extrinsic, ExtrinsicMetadata, IdentifyAccount, MaybeDisplay, Member,
+		SignedExtensionMetadata, TransactionExtensionBase,
+	},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	OpaqueExtrinsic,
+};
+use codec::{Compact, Decode, Encode, EncodeLike, Error, Input};
+use scale_info::{build::Fields, meta_type, Path, StaticTypeInfo, Type, TypeInfo, TypeParameter};
+use sp_io::hashing::blake2_256;
+#[cfg(all(not(feature = "std"), feature = "serde"))]
+use sp_std::alloc::format;
+use sp_std::{fmt, prelude::*};
+
+/// Version of the [`UncheckedTransaction`] encoded format of bare and signed transactions, which
+/// are encoded the same way as an [`UncheckedExtrinsic`](super::UncheckedExtrinsic).
+const LEGACY_EXTRINSIC_FORMAT_VERSION: u8 = 4;
+
+/// Version of the [`UncheckedTransaction`] encoded format of general transactions. Bare
+/// transactions are also accepted in this version.
+const EXTRINSIC_FORMAT_VERSION: u8 = 5;
+
+/// Version of the encoding of the extension data of a general transaction, which follows the
+/// version byte.
+const EXTENSION_VERSION: u8 = 0;
+
+/// Bits of the version byte which tell the format of the transaction apart.
+const FORMAT_MASK: u8 = 0b1100_0000;
+
+/// Format bits of a signed transaction.
+const SIGNED_TRANSACTION: u8 = 0b1000_0000;
+
+/// Format bits of a general transaction.
+const GENERAL_TRANSACTION: u8 = 0b0100_0000;
+
+/// The data an [`UncheckedTransaction`] carries alongside its call, which determines its
+/// [`ExtrinsicFormat`] once checked.
+#[derive(PartialEq, Eq, Clone, sp_core::RuntimeDebug)]
+pub enum Preamble<Address, Signature, Extension> {
+	/// An inherent or an unsigned transaction validated with
+	/// [`ValidateUnsigned`](crate::traits::ValidateUnsigned).
+	Bare,
+	/// A transaction signed by the given address. The signature covers the call, the extension
+	/// and its implicit data, see [`SignedTransactionPayload`].
+	Signed(Address, Signature, Extension),
+	/// A transaction without signature, which the extension is responsible for authorizing.
+	General(Extension),
+}
+
+/// An extrinsic right from the external world, whose [`TransactionExtension`] pipeline is run
+/// for any [`ExtrinsicFormat`].
+///
+/// Unlike an [`UncheckedExtrinsic`](super::UncheckedExtrinsic), it can carry extension data
+/// without a signature, i.e. be a general transaction.
+///
+/// This can be checked using [`Checkable`], yielding a [`CheckedTransaction`].
+///
+/// [`TransactionExtension`]: crate::traits::TransactionExtension
+#[derive(PartialEq, Eq, Clone)]
+pub struct UncheckedTransaction<Address, Call, Signature, Extension> {
+	/// The signature and extension data of the transaction, if any.
+	pub preamble: Preamble<Address, Signature, Extension>,
+	/// The function that should be called.
+	pub function: Call,
+}
+
+/// Manual [`TypeInfo`] implementation because of custom encoding. The data is a valid encoded
+/// `Vec<u8>`, but requires some logic to extract the preamble and payload.
+///
+/// See [`UncheckedTransaction::encode`] and [`UncheckedTransaction::decode`].
+impl<Address, Call, Signature, Extension> TypeInfo
+	for UncheckedTransaction<Address, Call, Signature, Extension>
+where
+	Address: StaticTypeInfo,
+	Call: StaticTypeInfo,
+	Signature: StaticTypeInfo,
+	Extension: StaticTypeInfo,
+{
+	type Identity = UncheckedTransaction<Address, Call, Signature, Extension>;
+
+	fn type_info() -> Type {
+		Type::builder()
+			.path(Path::new("UncheckedTransaction", module_path!()))
+			.type_params(vec![
+				TypeParameter::new("Address", Some(meta_type::<Address>())),
+				TypeParameter::new("Call", Some(meta_type::<Call>())),
+				TypeParameter::new("Signature", Some(meta_type::<Signature>())),
+				TypeParameter::new("Extension", Some(meta_type::<Extension>())),
+			])
+			.docs(&["UncheckedTransaction raw bytes, requires custom decoding routine"])
+			.composite(Fields::unnamed().field(|f| f.ty::<Vec<u8>>()))
+	}
+}
+
+impl<Address, Call, Signature, Extension>
+	UncheckedTransaction<Address, Call, Signature, Extension>
+{
+	/// New instance of a bare extrinsic, i.e. an inherent or an unsigned transaction.
+	pub fn new_bare(function: Call) -> Self {
+		Self { preamble: Preamble::Bare, function }
+	}
+
+	/// New instance of a signed transaction.
+	pub fn new_signed(
+		function: Call,
+		signed: Address,
+		signature: Signature,
+		extension: Extension,
+	) -> Self {
+		Self { preamble: Preamble::Signed(signed, signature, extension), function }
+	}
+
+	/// New instance of a general transaction.
+	pub fn new_general(function: Call, extension: Extension) -> Self {
+		Self { preamble: Preamble::General(extension), function }
+	}
+}
+
+impl<Address: TypeInfo, Call: TypeInfo, Signature: TypeInfo, Extension: TypeInfo> Extrinsic
+	for UncheckedTransaction<Address, Call, Signature, Extension>
+{
+	type Call = Call;
+
+	type SignaturePayload = (Address, Signature, Extension);
+
+	/// General transactions are reported as signed too, they are never inherents.
+	fn is_signed(&self) -> Option<bool> {
+		Some(!matches!(self.preamble, Preamble::Bare))
+	}
+
+	fn new(function: Call, signed_data: Option<Self::SignaturePayload>) -> Option<Self> {
+		Some(match signed_data {
+			Some((address, signature, extension)) =>
+				Self::new_signed(function, address, signature, extension),
+			None => Self::new_bare(function),
+		})
+	}
+}
+
+impl<LookupSource, AccountId, Call, Signature, Extension, Lookup> Checkable<Lookup>
+	for UncheckedTransaction<LookupSource, Call, Signature, Extension>
+where
+	LookupSource: Member + MaybeDisplay,
+	Call: Encode + Member,
+	Signature: Member + traits::Verify,
+	<Signature as traits::Verify>::Signer: IdentifyAccount<AccountId = AccountId>,
+	Extension: TransactionExtensionBase,
+	AccountId: Member + MaybeDisplay,
+	Lookup: traits::Lookup<Source = LookupSource, Target = AccountId>,
+{
+	type Checked = CheckedTransaction<AccountId, Call, Extension>;
+
+	fn check(self, lookup: &Lookup) -> Result<Self::Checked, TransactionValidityError> {
+		let (format, function) = match self.preamble {
+			Preamble::Bare => (ExtrinsicFormat::Bare, self.function),
+			Preamble::Signed(signed, signature, extension) => {
+				let signed = lookup.lookup(signed)?;
+				let raw_payload = SignedTransactionPayload::new(self.function, extension)?;
+				if !raw_payload.using_encoded(|payload| signature.verify(payload, &signed)) {
+					return Err(InvalidTransaction::BadProof.into())
+				}
+
+				let (function, extension, _) = raw_payload.deconstruct();
+				(ExtrinsicFormat::Signed(signed, extension), function)
+			},
+			Preamble::General(extension) => (ExtrinsicFormat::General(extension), self.function),
+		};
+		Ok(CheckedTransaction { format, function })
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn unchecked_into_checked_i_know_what_i_am_doing(
+		self,
+		lookup: &Lookup,
+	) -> Result<Self::Checked, TransactionValidityError> {
+		let format = match self.preamble {
+			Preamble::Bare => ExtrinsicFormat::Bare,
+			Preamble::Signed(signed, _, extension) =>
+				ExtrinsicFormat::Signed(lookup.lookup(signed)?, extension),
+			Preamble::General(extension) => ExtrinsicFormat::General(extension),
+		};
+		Ok(CheckedTransaction { format, function: self.function })
+	}
+}
+
+impl<Address, Call, Signature, Extension> ExtrinsicMetadata
+	for UncheckedTransaction<Address, Call, Signature, Extension>
+where
+	Extension: TransactionExtensionBase,
+{
+	const VERSION: u8 = LEGACY_EXTRINSIC_FORMAT_VERSION;
+	type SignedExtensions = Extension;
+
+	fn signed_extensions_metadata() -> Vec<SignedExtensionMetadata> {
+		Extension::metadata()
+	}
+}
+
+/// A payload that has been signed for a signed [`UncheckedTransaction`].
+///
+/// It is made of the call, the extension and the implicit data of the extension.
+pub struct SignedTransactionPayload<Call, Extension: TransactionExtensionBase>(
+	(Call, Extension, Extension::Implicit),
+);
+
+impl<Call, Extension> SignedTransactionPayload<Call, Extension>
+where
+	Call: Encode,
+	Extension: TransactionExtensionBase,
+{
+	/// Create new `SignedTransactionPayload`.
+	///
+	/// This function may fail if `implicit` of `Extension` is not available.
+	pub fn new(call: Call, extension: Extension) -> Result<Self, TransactionValidityError> {
+		let implicit = extension.implicit()?;
+		Ok(Self((call, extension, implicit)))
+	}
+
+	/// Create new `SignedTransactionPayload` from raw components.
+	pub fn from_raw(call: Call, extension: Extension, implicit: Extension::Implicit) -> Self {
+		Self((call, extension, implicit))
+	}
+
+	/// Deconstruct the payload into it's components.
+	pub fn deconstruct(self) -> (Call, Extension, Extension::Implicit) {
+		self.0
+	}
+}
+
+impl<Call, Extension> Encode for SignedTransactionPayload<Call, Extension>
+where
+	Call: Encode,
+	Extension: TransactionExtensionBase,
+{
+	/// Get an encoded version of this payload.
+	///
+	/// Payloads longer than 256 bytes are going to be `blake2_256`-hashed.
+	fn using_encoded<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> R {
+		self.0.using_encoded(|payload| {
+			if payload.len() > 256 {
+				f(&blake2_256(payload)[..])
+			} else {
+				f(payload)
+			}
+		})
+	}
+}
+
+impl<Call, Extension> EncodeLike for SignedTransactionPayload<Call, Extension>
+where
+	Call: Encode,
+	Extension: TransactionExtensionBase,
+{
+}
+
+impl<Address, Call, Signature, Extension> Decode
+	for UncheckedTransaction<Address, Call, Signature, Extension>
+where
+	Address: Decode,
+	Signature: Decode,
+	Call: Decode,
+	Extension: Decode,
+{
+	fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+		// Same as for `UncheckedExtrinsic`, the binary format must be compatible with SCALE's
+		// generic `Vec<u8>` type.
+		let expected_length: Compact<u32> = Decode::decode(input)?;
+		let before_length = input.remaining_len()?;
+
+		let version = input.read_byte()?;
+		let preamble = match (version & !FORMAT_MASK, version & FORMAT_MASK) {
+			(LEGACY_EXTRINSIC_FORMAT_VERSION | EXTRINSIC_FORMAT_VERSION, 0) => Preamble::Bare,
+			(LEGACY_EXTRINSIC_FORMAT_VERSION, SIGNED_TRANSACTION) => {
+				let (address, signature, extension) = Decode::decode(input)?;
+				Preamble::Signed(address, signature, extension)
+			},
+			(EXTRINSIC_FORMAT_VERSION, GENERAL_TRANSACTION) => {
+				if input.read_byte()? != EXTENSION_VERSION {
+					return Err("Invalid extension version".into())
+				}
+				Preamble::General(Decode::decode(input)?)
+			},
+			(LEGACY_EXTRINSIC_FORMAT_VERSION | EXTRINSIC_FORMAT_VERSION, _) =>
+				return Err("Invalid transaction format".into()),
+			_ => return Err("Invalid transaction version".into()),
+		};
+		let function = Decode::decode(input)?;
+
+		if let Some((before_length, after_length)) =
+			input.remaining_len()?.and_then(|a| before_length.map(|b| (b, a)))
+		{
+			let length = before_length.saturating_sub(after_length);
+
+			if length != expected_length.0 as usize {
+				return Err("Invalid length prefix".into())
+			}
+		}
+
+		Ok(Self { preamble, function })
+	}
+}
+
+impl<Address, Call, Signature, Extension> Encode
+	for UncheckedTransaction<Address, Call, Signature, Extension>
+where
+	Address: Encode,
+	Signature: Encode,
+	Call: Encode,
+	Extension: Encode,
+{
+	fn encode(&self) -> Vec<u8> {
+		let mut tmp = Vec::with_capacity(sp_std::mem::size_of::<Self>());
+
+		// 1 byte version id.
+		match &self.preamble {
+			Preamble::Bare => tmp.push(LEGACY_EXTRINSIC_FORMAT_VERSION),
+			Preamble::Signed(address, signature, extension) => {
+				tmp.push(LEGACY_EXTRINSIC_FORMAT_VERSION | SIGNED_TRANSACTION);
+				(address, signature, extension).encode_to(&mut tmp);
+			},
+			Preamble::General(extension) => {
+				tmp.push(EXTRINSIC_FORMAT_VERSION | GENERAL_TRANSACTION);
+				tmp.push(EXTENSION_VERSION);
+				extension.encode_to(&mut tmp);
+			},
+		}
+		self.function.encode_to(&mut tmp);
+
+		let compact_len = codec::Compact::<u32>(tmp.len() as u32);
+
+		// Allocate the output buffer with the correct length
+		let mut output = Vec::with_capacity(compact_len.size_hint() + tmp.len());
+
+		compact_len.encode_to(&mut output);
+		output.extend(tmp);
+
+		output
+	}
+}
+
+impl<Address, Call, Signature, Extension> EncodeLike
+	for UncheckedTransaction<Address, Call, Signature, Extension>
+where
+	Address: Encode,
+	Signature: Encode,
+	Call: Encode,
+	Extension: Encode,
+{
+}
+
+#[cfg(feature = "serde")]
+impl<Address: Encode, Signature: Encode, Call: Encode, Extension: Encode> serde::Serialize
+	for UncheckedTransaction<Address, Call, Signature, Extension>
+{
+	fn serialize<S>(&self, seq: S) -> Result<S::Ok, S::Error>
+	where
+		S: ::serde::Serializer,
+	{
+		self.using_encoded(|bytes| seq.serialize_bytes(bytes))
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'a, Address: Decode, Signature: Decode, Call: Decode, Extension: Decode> serde::Deserialize<'a>
+	for UncheckedTransaction<Address, Call, Signature, Extension>
+{
+	fn deserialize<D>(de: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'a>,
+	{
+		let r = sp_core::bytes::deserialize(de)?;
+		Decode::decode(&mut &r[..])
+			.map_err(|e| serde::de::Error::custom(format!("Decode error: {}", e)))
+	}
+}
+
+impl<Address, Call, Signature, Extension> fmt::Debug
+	for UncheckedTransaction<Address, Call, Signature, Extension>
+where
+	Address: fmt::Debug,
+	Call: fmt::Debug,
+	Extension: fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let preamble = match &self.preamble {
+			Preamble::Bare => None,
+			Preamble::Signed(address, _, extension) => Some((Some(address), extension)),
+			Preamble::General(extension) => Some((None, extension)),
+		};
+		write!(f, "UncheckedTransaction({:?}, {:?})", preamble, self.function)
+	}
+}
+
+impl<Address, Call, Signature, Extension>
+	From<UncheckedTransaction<Address, Call, Signature, Extension>> for OpaqueExtrinsic
+where
+	Address: Encode,
+	Signature: Encode,
+	Call: Encode,
+	Extension: Encode,
+{
+	fn from(extrinsic: UncheckedTransaction<Address, Call, Signature, Extension>) -> Self {
+		Self::from_bytes(extrinsic.encode().as_slice()).expect(
+			"both OpaqueExtrinsic and UncheckedTransaction have encoding that is compatible with \
+				raw Vec<u8> encoding; qed",
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		generic::UncheckedExtrinsic, testing::TestSignature as TestSig, traits::IdentityLookup,
+	};
+
+	type TestContext = IdentityLookup<u64>;
+	type TestAccountId = u64;
+	type TestCall = Vec<u8>;
+
+	const TEST_ACCOUNT: TestAccountId = 0;
+
+	type Tx = UncheckedTransaction<TestAccountId, TestCall, TestSig, ()>;
+	type Ex = UncheckedExtrinsic<TestAccountId, TestCall, TestSig, ()>;
+	type CTx = CheckedTransaction<TestAccountId, TestCall, ()>;
+
+	#[test]
+	fn bare_and_signed_encoding_matches_unchecked_extrinsic() {
+		let tx = Tx::new_bare(vec![1u8; 3]);
+		assert_eq!(tx.encode(), Ex::new_unsigned(vec![1u8; 3]).encode());
+
+		let signature = TestSig(TEST_ACCOUNT, (vec![1u8; 3], ()).encode());
+		let tx = Tx::new_signed(vec![1u8; 3], TEST_ACCOUNT, signature.clone(), ());
+		let encoded = tx.encode();
+		assert_eq!(encoded, Ex::new_signed(vec![1u8; 3], TEST_ACCOUNT, signature, ()).encode());
+		assert_eq!(Tx::decode(&mut &encoded[..]), Ok(tx));
+	}
+
+	#[test]
+	fn general_codec_should_work() {
+		let tx = Tx::new_general(vec![1u8; 3], ());
+		let encoded = tx.encode();
+		// Length, version 5 with the general bit, extension version, then the call.
+		assert_eq!(encoded, vec![24, 0b0100_0101, 0, 12, 1, 1, 1]);
+		assert_eq!(Tx::decode(&mut &encoded[..]), Ok(tx));
+
+		// Extrinsics which can't carry extension data without a signature don't decode it.
+		assert_eq!(Ex::decode(&mut &encoded[..]), Err("Invalid transaction version".into()));
+	}
+
+	#[test]
+	fn bare_v5_codec_should_work() {
+		let encoded = vec![8, EXTRINSIC_FORMAT_VERSION, 0];
+		assert_eq!(Tx::decode(&mut &encoded[..]), Ok(Tx::new_bare(vec![0u8; 0])));
+	}
+
+	#[test]
+	fn invalid_formats_are_rejected() {
+		let mut encoded = Tx::new_general(vec![0u8; 0], ()).encode();
+		encoded[1] |= SIGNED_TRANSACTION;
+		assert_eq!(Tx::decode(&mut &encoded[..]), Err("Invalid transaction format".into()));
+
+		// Signed transactions only exist in the legacy version.
+		let mut encoded = Tx::new_general(vec![0u8; 0], ()).encode();
+		encoded[1] = EXTRINSIC_FORMAT_VERSION | SIGNED_TRANSACTION;
+		assert_eq!(Tx::decode(&mut &encoded[..]), Err("Invalid transaction format".into()));
+
+		// General transactions only exist in version 5.
+		let mut encoded = Tx::new_general(vec![0u8; 0], ()).encode();
+		encoded[1] = LEGACY_EXTRINSIC_FORMAT_VERSION | GENERAL_TRANSACTION;
+		assert_eq!(Tx::decode(&mut &encoded[..]), Err("Invalid transaction format".into()));
+
+		let mut encoded = Tx::new_general(vec![0u8; 0], ()).encode();
+		encoded[2] = EXTENSION_VERSION + 1;
+		assert_eq!(Tx::decode(&mut &encoded[..]), Err("Invalid extension version".into()));
+	}
+
+	#[test]
+	fn check_should_keep_the_format() {
+		let tx = Tx::new_general(vec![0u8; 0], ());
+		assert!(tx.is_signed().unwrap_or(false));
+		assert_eq!(
+			<Tx as Checkable<TestContext>>::check(tx, &Default::default()),
+			Ok(CTx { format: ExtrinsicFormat::General(()), function: vec![0u8; 0] }),
+		);
+
+		let tx = Tx::new_signed(
+			vec![0u8; 0],
+			TEST_ACCOUNT,
+			TestSig(TEST_ACCOUNT, (vec![0u8; 0], ()).encode()),
+			(),
+		);
+		assert_eq!(
+			<Tx as Checkable<TestContext>>::check(tx, &Default::default()),
+			Ok(CTx { format: ExtrinsicFormat::Signed(TEST_ACCOUNT, ()), function: vec![0u8; 0] }),
+		);
+
+		let tx = Tx::new_bare(vec![0u8; 0]);
+		assert!(!tx.is_signed().unwrap_or(false));
+		assert_eq!(
+			<Tx as Checkable<TestContext>>::check(tx, &Default::default()),
+			Ok(CTx { format: ExtrinsicFormat::Bare, function: vec![0u8; 0] }),
+		);
+	}
+
+	#[test]
+	fn badly_signed_check_should_fail() {
+		let tx = Tx::new_signed(vec![0u8; 0], TEST_ACCOUNT, TestSig(TEST_ACCOUNT, vec![1u8]), ());
+		assert_eq!(
+			<Tx as Checkable<TestContext>>::check(tx, &Default::default()),
+			Err(InvalidTransaction::BadProof.into()),
+		);
+	}
+}
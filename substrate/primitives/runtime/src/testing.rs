@@ -366,6 +366,10 @@ where
 {
 	type SignedExtensions = Extra;
 	const VERSION: u8 = 0u8;
+
+	fn signed_extensions_metadata() -> Vec<traits::SignedExtensionMetadata> {
+		Extra::metadata()
+	}
 }
 
 impl<Origin, Call, Extra> Applyable for TestXt<Call, Extra>
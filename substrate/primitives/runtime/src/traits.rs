@@ -52,6 +52,13 @@ use std::fmt::Display;
 #[cfg(feature = "std")]
 use std::str::FromStr;
 
+mod transaction_extension;
+pub use transaction_extension::{
+	AsSystemOriginSigner, AsTransactionAuthorizedOrigin, AsTransactionExtension, DispatchOriginOf,
+	ExtensionPostDispatchWeightHandler, TransactionExtension, TransactionExtensionBase,
+	ValidateResult,
+};
+
 /// A lazy value.
 pub trait Lazy<T: ?Sized> {
 	/// Get a reference to the underlying value.
@@ -1371,7 +1378,12 @@ pub trait ExtrinsicMetadata {
 	const VERSION: u8;
 
 	/// Signed extensions attached to this `Extrinsic`.
-	type SignedExtensions: SignedExtension;
+	///
+	/// Either [`SignedExtension`]s or [`TransactionExtension`]s, depending on the extrinsic.
+	type SignedExtensions;
+
+	/// Returns the metadata of the [`Self::SignedExtensions`].
+	fn signed_extensions_metadata() -> Vec<SignedExtensionMetadata>;
 }
 
 /// Extract the hashing type for a block.
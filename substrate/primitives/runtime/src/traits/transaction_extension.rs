@@ -0,0 +1,861 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The [`TransactionExtension`] trait, a general pipeline of checks and side effects wrapped
+//! around the dispatch of an extrinsic.
+//!
+//! Unlike [`SignedExtension`], a [`TransactionExtension`] is handed the origin of the extrinsic
+//! and returns the origin which is passed to the following extensions and finally used for the
+//! dispatch. This allows an extension to authorize a transaction which carries no signature, or
+//! to change who is accountable for it, e.g. a sponsor paying the fees.
+
+use crate::{
+	scale_info::{StaticTypeInfo, TypeInfo},
+	traits::{
+		DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SignedExtension, SignedExtensionMetadata,
+	},
+	transaction_validity::{TransactionValidity, TransactionValidityError, ValidTransaction},
+	DispatchResult,
+};
+use codec::{Codec, Decode, Encode};
+use impl_trait_for_tuples::impl_for_tuples;
+use sp_core::RuntimeDebug;
+use sp_std::{fmt::Debug, prelude::*};
+use sp_weights::Weight;
+
+/// Shortcut to reference the `RuntimeOrigin` type of a `Dispatchable`.
+pub type DispatchOriginOf<T> = <T as Dispatchable>::RuntimeOrigin;
+
+/// The result of [`TransactionExtension::validate`].
+///
+/// On success, it holds the validity of the transaction, the value passed on to
+/// [`TransactionExtension::prepare`] and the origin passed on to the following extensions.
+pub type ValidateResult<Val, Call> =
+	Result<(ValidTransaction, Val, DispatchOriginOf<Call>), TransactionValidityError>;
+
+/// Extract the signer of an origin, if it is a signed origin.
+pub trait AsSystemOriginSigner<AccountId> {
+	/// Returns the signer of the origin, or `None` if the origin is not a signed origin.
+	fn as_system_origin_signer(&self) -> Option<&AccountId>;
+}
+
+impl<AccountId> AsSystemOriginSigner<AccountId> for Option<AccountId> {
+	fn as_system_origin_signer(&self) -> Option<&AccountId> {
+		self.as_ref()
+	}
+}
+
+/// Tell whether an origin was authorized to dispatch a transaction.
+pub trait AsTransactionAuthorizedOrigin {
+	/// Returns `false` for the `None` origin, which no signature nor extension authorized.
+	fn is_transaction_authorized(&self) -> bool;
+}
+
+impl<AccountId> AsTransactionAuthorizedOrigin for Option<AccountId> {
+	fn is_transaction_authorized(&self) -> bool {
+		self.is_some()
+	}
+}
+
+/// Post dispatch information of a call, which can account for the weight of the transaction
+/// extensions it was dispatched with.
+pub trait ExtensionPostDispatchWeightHandler {
+	/// Add the weight of the extensions to the actual weight consumed by the dispatch, if known.
+	fn add_extension_weight(&mut self, weight: Weight);
+}
+
+impl ExtensionPostDispatchWeightHandler for () {
+	fn add_extension_weight(&mut self, _weight: Weight) {}
+}
+
+/// The part of a [`TransactionExtension`] which does not depend on the call.
+pub trait TransactionExtensionBase:
+	Codec + Debug + Sync + Send + Clone + Eq + PartialEq + StaticTypeInfo
+{
+	/// Unique identifier of this transaction extension.
+	///
+	/// This will be exposed in the metadata to identify the transaction extension used in an
+	/// extrinsic.
+	const IDENTIFIER: &'static str;
+
+	/// Any additional data which was known at the time of transaction construction and can be
+	/// useful in authenticating the transaction. It is not part of the extrinsic but is implied
+	/// by it, e.g. it goes into the signed payload of a signed transaction.
+	type Implicit: Encode + StaticTypeInfo;
+
+	/// Determine the implicit data of the extension. Can also perform any pre-signature
+	/// verification checks and return an error if needed.
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError>;
+
+	/// The weight consumed by executing this extension instance fully during transaction
+	/// dispatch.
+	fn weight(&self) -> Weight {
+		Weight::zero()
+	}
+
+	/// Returns the metadata for this transaction extension.
+	///
+	/// As a [`TransactionExtension`] can be a tuple of [`TransactionExtension`]s we need to return
+	/// a `Vec` that holds the metadata of each one. Each individual `TransactionExtension` must
+	/// return *exactly* one [`SignedExtensionMetadata`].
+	fn metadata() -> Vec<SignedExtensionMetadata> {
+		sp_std::vec![SignedExtensionMetadata {
+			identifier: Self::IDENTIFIER,
+			ty: scale_info::meta_type::<Self>(),
+			additional_signed: scale_info::meta_type::<Self::Implicit>()
+		}]
+	}
+}
+
+/// A pipeline of checks and side effects wrapped around the dispatch of an extrinsic.
+///
+/// The pipeline is run for every [`ExtrinsicFormat`](crate::generic::ExtrinsicFormat):
+///
+/// - For signed and general extrinsics, [`Self::validate`] is called with the origin of the
+///   extrinsic, which is the signed origin of the signer for a signed extrinsic and the `None`
+///   origin for a general one. It returns the origin passed to the next extension, and the origin
+///   returned by the last extension is the one used for the dispatch. A general extrinsic which no
+///   extension authorized, i.e. whose final origin is still `None`, is rejected. When the
+///   extrinsic is applied, [`Self::validate_for_apply`] and then [`Self::prepare`] are called and,
+///   after the dispatch, [`Self::post_dispatch`] is called with the value returned by
+///   [`Self::prepare`].
+/// - For bare extrinsics, i.e. inherents and unsigned transactions validated with
+///   [`ValidateUnsigned`](crate::traits::ValidateUnsigned), there is no extension data and the
+///   `bare_*` functions are called instead.
+///
+/// Extensions must perform the same checks in [`Self::validate`] as when they are applied,
+/// since only the latter happens when the block is built and imported.
+pub trait TransactionExtension<Call: Dispatchable>: TransactionExtensionBase {
+	/// The type which is passed from [`Self::validate`] to [`Self::prepare`].
+	type Val;
+
+	/// The type which is passed from [`Self::prepare`] to [`Self::post_dispatch`].
+	type Pre;
+
+	/// Validate a transaction for the transaction queue and determine its origin.
+	///
+	/// `inherited_implication` is the data implied by the whole transaction, i.e. its call and
+	/// the explicit and implicit data of all its extensions. It is what an extension authorizing
+	/// the transaction, e.g. by verifying a signature, should check against.
+	///
+	/// This function can be called frequently by the transaction queue to obtain transaction
+	/// validity against current state. It should perform all checks that determine a valid
+	/// transaction, that can pay for its execution and quickly eliminate ones that are stale or
+	/// incorrect.
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+		inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, Call>;
+
+	/// Validate a transaction right before it is prepared for dispatch, i.e. when it is applied.
+	///
+	/// By default this is [`Self::validate`]. Extensions whose [`Self::prepare`] performs the
+	/// checks of [`Self::validate`] itself only need to determine the origin here, so that the
+	/// checks don't run twice.
+	fn validate_for_apply(
+		&self,
+		origin: DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+		inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, Call> {
+		self.validate(origin, call, info, len, inherited_implication)
+	}
+
+	/// Do any pre-flight stuff for a transaction after validation.
+	///
+	/// `origin` is the origin the transaction is dispatched with, as returned by the last
+	/// extension of the pipeline.
+	fn prepare(
+		self,
+		val: Self::Val,
+		origin: &DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError>;
+
+	/// Do any post-flight stuff for a transaction.
+	///
+	/// WARNING: It is dangerous to return an error here. To do so will fundamentally invalidate
+	/// the transaction and any block that it is included in, causing the block author to not be
+	/// compensated for their work in validating the transaction or producing the block so far.
+	fn post_dispatch(
+		_pre: Self::Pre,
+		_info: &DispatchInfoOf<Call>,
+		_post_info: &PostDispatchInfoOf<Call>,
+		_len: usize,
+		_result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	/// Validate a bare extrinsic for the transaction queue.
+	fn bare_validate(
+		_call: &Call,
+		_info: &DispatchInfoOf<Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		Ok(ValidTransaction::default())
+	}
+
+	/// Do any pre-flight stuff for a bare extrinsic.
+	///
+	/// By default this delegates to [`Self::bare_validate`], so that all checks performed for the
+	/// transaction queue are also performed when the extrinsic is applied.
+	fn bare_validate_and_prepare(
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> Result<(), TransactionValidityError> {
+		Self::bare_validate(call, info, len).map(|_| ())
+	}
+
+	/// Do any post-flight stuff for a bare extrinsic.
+	fn bare_post_dispatch(
+		_info: &DispatchInfoOf<Call>,
+		_post_info: &PostDispatchInfoOf<Call>,
+		_len: usize,
+		_result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	/// Validate a transaction with the implication of the whole transaction, as done by the
+	/// top-level extension of an extrinsic.
+	fn validate_only(
+		&self,
+		origin: DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> ValidateResult<Self::Val, Call>
+	where
+		Call: Encode,
+	{
+		let implicit = self.implicit()?;
+		self.validate(origin, call, info, len, &(call, self, &implicit))
+	}
+
+	/// Validate and prepare a transaction, returning the value passed to
+	/// [`Self::post_dispatch`] and the origin to dispatch the call with.
+	fn validate_and_prepare(
+		self,
+		origin: DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> Result<(Self::Pre, DispatchOriginOf<Call>), TransactionValidityError>
+	where
+		Call: Encode,
+	{
+		let implicit = self.implicit()?;
+		let (_, val, origin) =
+			self.validate_for_apply(origin, call, info, len, &(call, &self, &implicit))?;
+		let pre = self.prepare(val, &origin, call, info, len)?;
+		Ok((pre, origin))
+	}
+
+	/// Run the whole pipeline around the dispatch of `call`.
+	///
+	/// The weight of the extensions is added to the actual weight of the dispatch, since it is
+	/// part of the weight `info` the transaction was charged for.
+	fn dispatch_transaction(
+		self,
+		origin: DispatchOriginOf<Call>,
+		call: Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> crate::ApplyExtrinsicResultWithInfo<PostDispatchInfoOf<Call>>
+	where
+		Call: Encode,
+		PostDispatchInfoOf<Call>: ExtensionPostDispatchWeightHandler,
+	{
+		let extension_weight = self.weight();
+		let (pre, origin) = self.validate_and_prepare(origin, &call, info, len)?;
+		Self::dispatch_prepared(pre, origin, call, info, len, extension_weight)
+	}
+
+	/// Dispatch `call` with the `origin` and the value returned by [`Self::prepare`], then run
+	/// [`Self::post_dispatch`].
+	///
+	/// `extension_weight` is the [`TransactionExtensionBase::weight`] of the extension.
+	fn dispatch_prepared(
+		pre: Self::Pre,
+		origin: DispatchOriginOf<Call>,
+		call: Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+		extension_weight: Weight,
+	) -> crate::ApplyExtrinsicResultWithInfo<PostDispatchInfoOf<Call>>
+	where
+		PostDispatchInfoOf<Call>: ExtensionPostDispatchWeightHandler,
+	{
+		let mut res = call.dispatch(origin);
+		match &mut res {
+			Ok(post_info) => post_info.add_extension_weight(extension_weight),
+			Err(err) => err.post_info.add_extension_weight(extension_weight),
+		}
+		let post_info = match res {
+			Ok(info) => info,
+			Err(err) => err.post_info,
+		};
+		Self::post_dispatch(pre, info, &post_info, len, &res.map(|_| ()).map_err(|e| e.error))?;
+		Ok(res)
+	}
+}
+
+#[impl_for_tuples(1, 12)]
+impl TransactionExtensionBase for Tuple {
+	for_tuples!( where #( Tuple: TransactionExtensionBase )* );
+	const IDENTIFIER: &'static str = "You should call `identifier()`!";
+	for_tuples!( type Implicit = ( #( Tuple::Implicit ),* ); );
+
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+		Ok(for_tuples!( ( #( Tuple.implicit()? ),* ) ))
+	}
+
+	fn weight(&self) -> Weight {
+		let mut weight = Weight::zero();
+		for_tuples!( #( weight = weight.saturating_add(Tuple.weight()); )* );
+		weight
+	}
+
+	fn metadata() -> Vec<SignedExtensionMetadata> {
+		let mut ids = Vec::new();
+		for_tuples!( #( ids.extend(Tuple::metadata()); )* );
+		ids
+	}
+}
+
+#[impl_for_tuples(1, 12)]
+impl<Call: Dispatchable> TransactionExtension<Call> for Tuple {
+	for_tuples!( where #( Tuple: TransactionExtension<Call> )* );
+	for_tuples!( type Val = ( #( Tuple::Val ),* ); );
+	for_tuples!( type Pre = ( #( Tuple::Pre ),* ); );
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+		inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, Call> {
+		let mut valid = ValidTransaction::default();
+		let mut origin = origin;
+		// Each extension is given the origin returned by the previous one.
+		let val = for_tuples!( ( #( {
+			let (item_valid, item_val, item_origin) =
+				Tuple.validate(origin, call, info, len, inherited_implication)?;
+			valid = valid.combine_with(item_valid);
+			origin = item_origin;
+			item_val
+		} ),* ) );
+		Ok((valid, val, origin))
+	}
+
+	fn validate_for_apply(
+		&self,
+		origin: DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+		inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, Call> {
+		let mut origin = origin;
+		let val = for_tuples!( ( #( {
+			let (_, item_val, item_origin) =
+				Tuple.validate_for_apply(origin, call, info, len, inherited_implication)?;
+			origin = item_origin;
+			item_val
+		} ),* ) );
+		Ok((ValidTransaction::default(), val, origin))
+	}
+
+	fn prepare(
+		self,
+		val: Self::Val,
+		origin: &DispatchOriginOf<Call>,
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		Ok(
+			for_tuples!( ( #( Tuple::prepare(self.Tuple, val.Tuple, origin, call, info, len)? ),* ) ),
+		)
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Call>,
+		post_info: &PostDispatchInfoOf<Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		for_tuples!( #( Tuple::post_dispatch(pre.Tuple, info, post_info, len, result)?; )* );
+		Ok(())
+	}
+
+	fn bare_validate(call: &Call, info: &DispatchInfoOf<Call>, len: usize) -> TransactionValidity {
+		let valid = ValidTransaction::default();
+		for_tuples!( #( let valid = valid.combine_with(Tuple::bare_validate(call, info, len)?); )* );
+		Ok(valid)
+	}
+
+	fn bare_validate_and_prepare(
+		call: &Call,
+		info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> Result<(), TransactionValidityError> {
+		for_tuples!( #( Tuple::bare_validate_and_prepare(call, info, len)?; )* );
+		Ok(())
+	}
+
+	fn bare_post_dispatch(
+		info: &DispatchInfoOf<Call>,
+		post_info: &PostDispatchInfoOf<Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		for_tuples!( #( Tuple::bare_post_dispatch(info, post_info, len, result)?; )* );
+		Ok(())
+	}
+}
+
+impl TransactionExtensionBase for () {
+	const IDENTIFIER: &'static str = "UnitTransactionExtension";
+	type Implicit = ();
+
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+		Ok(())
+	}
+}
+
+impl<Call: Dispatchable> TransactionExtension<Call> for () {
+	type Val = ();
+	type Pre = ();
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<Call>,
+		_call: &Call,
+		_info: &DispatchInfoOf<Call>,
+		_len: usize,
+		_inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, Call> {
+		Ok((ValidTransaction::default(), (), origin))
+	}
+
+	fn prepare(
+		self,
+		_val: Self::Val,
+		_origin: &DispatchOriginOf<Call>,
+		_call: &Call,
+		_info: &DispatchInfoOf<Call>,
+		_len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		Ok(())
+	}
+}
+
+/// Adapter to use a [`SignedExtension`] as a [`TransactionExtension`].
+///
+/// The adapter is encoded as the wrapped extension. It validates the transaction as signed when
+/// the origin is a signed origin and as unsigned otherwise, and never changes the origin.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct AsTransactionExtension<SE: SignedExtension>(pub SE);
+
+impl<SE: SignedExtension> From<SE> for AsTransactionExtension<SE> {
+	fn from(value: SE) -> Self {
+		Self(value)
+	}
+}
+
+impl<SE: SignedExtension> TransactionExtensionBase for AsTransactionExtension<SE> {
+	const IDENTIFIER: &'static str = SE::IDENTIFIER;
+	type Implicit = SE::AdditionalSigned;
+
+	fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+		self.0.additional_signed()
+	}
+
+	fn metadata() -> Vec<SignedExtensionMetadata> {
+		SE::metadata()
+	}
+}
+
+impl<SE: SignedExtension> TransactionExtension<SE::Call> for AsTransactionExtension<SE>
+where
+	DispatchOriginOf<SE::Call>: AsSystemOriginSigner<SE::AccountId>,
+{
+	type Val = ();
+	type Pre = Option<SE::Pre>;
+
+	fn validate(
+		&self,
+		origin: DispatchOriginOf<SE::Call>,
+		call: &SE::Call,
+		info: &DispatchInfoOf<SE::Call>,
+		len: usize,
+		_inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, SE::Call> {
+		let valid = match origin.as_system_origin_signer() {
+			Some(who) => self.0.validate(who, call, info, len)?,
+			None => SE::validate_unsigned(call, info, len)?,
+		};
+		Ok((valid, (), origin))
+	}
+
+	/// `pre_dispatch` of a signed extension performs the checks of its `validate` itself and may
+	/// have side effects such as withdrawing the fee, so nothing is validated ahead of it.
+	fn validate_for_apply(
+		&self,
+		origin: DispatchOriginOf<SE::Call>,
+		_call: &SE::Call,
+		_info: &DispatchInfoOf<SE::Call>,
+		_len: usize,
+		_inherited_implication: &impl Encode,
+	) -> ValidateResult<Self::Val, SE::Call> {
+		Ok((ValidTransaction::default(), (), origin))
+	}
+
+	fn prepare(
+		self,
+		_val: Self::Val,
+		origin: &DispatchOriginOf<SE::Call>,
+		call: &SE::Call,
+		info: &DispatchInfoOf<SE::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		match origin.as_system_origin_signer() {
+			Some(who) => self.0.pre_dispatch(who, call, info, len).map(Some),
+			None => SE::pre_dispatch_unsigned(call, info, len).map(|_| None),
+		}
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<SE::Call>,
+		post_info: &PostDispatchInfoOf<SE::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		SE::post_dispatch(pre, info, post_info, len, result)
+	}
+
+	/// The wrapped extension doesn't use the implication, and its `additional_signed` is part of
+	/// the signed payload which was already verified, so it isn't computed again here.
+	fn validate_only(
+		&self,
+		origin: DispatchOriginOf<SE::Call>,
+		call: &SE::Call,
+		info: &DispatchInfoOf<SE::Call>,
+		len: usize,
+	) -> ValidateResult<Self::Val, SE::Call> {
+		self.validate(origin, call, info, len, &())
+	}
+
+	/// Like [`Self::validate_only`], the `additional_signed` of the wrapped extension isn't
+	/// computed again.
+	fn validate_and_prepare(
+		self,
+		origin: DispatchOriginOf<SE::Call>,
+		call: &SE::Call,
+		info: &DispatchInfoOf<SE::Call>,
+		len: usize,
+	) -> Result<(Self::Pre, DispatchOriginOf<SE::Call>), TransactionValidityError> {
+		let pre = self.prepare((), &origin, call, info, len)?;
+		Ok((pre, origin))
+	}
+
+	fn bare_validate(
+		call: &SE::Call,
+		info: &DispatchInfoOf<SE::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		SE::validate_unsigned(call, info, len)
+	}
+
+	fn bare_validate_and_prepare(
+		call: &SE::Call,
+		info: &DispatchInfoOf<SE::Call>,
+		len: usize,
+	) -> Result<(), TransactionValidityError> {
+		SE::pre_dispatch_unsigned(call, info, len)
+	}
+
+	fn bare_post_dispatch(
+		info: &DispatchInfoOf<SE::Call>,
+		post_info: &PostDispatchInfoOf<SE::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		SE::post_dispatch(None, info, post_info, len, result)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		transaction_validity::InvalidTransaction, DispatchError, DispatchErrorWithPostInfo,
+		DispatchResultWithInfo,
+	};
+
+	/// A call which can only be dispatched by a signed origin.
+	#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+	struct Call;
+
+	impl Dispatchable for Call {
+		type RuntimeOrigin = Option<u64>;
+		type Config = ();
+		type Info = ();
+		type PostInfo = ();
+
+		fn dispatch(self, origin: Self::RuntimeOrigin) -> DispatchResultWithInfo<()> {
+			match origin {
+				Some(_) => Ok(()),
+				None => Err(DispatchErrorWithPostInfo {
+					post_info: (),
+					error: DispatchError::BadOrigin,
+				}),
+			}
+		}
+	}
+
+	/// Authorizes the transaction on behalf of the given account.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	struct Authorize(u64);
+
+	impl TransactionExtensionBase for Authorize {
+		const IDENTIFIER: &'static str = "Authorize";
+		type Implicit = ();
+
+		fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+			Ok(())
+		}
+
+		fn weight(&self) -> Weight {
+			Weight::from_parts(1, 0)
+		}
+	}
+
+	impl TransactionExtension<Call> for Authorize {
+		type Val = ();
+		type Pre = ();
+
+		fn validate(
+			&self,
+			_origin: Option<u64>,
+			_call: &Call,
+			_info: &(),
+			_len: usize,
+			_inherited_implication: &impl Encode,
+		) -> ValidateResult<Self::Val, Call> {
+			Ok((ValidTransaction::default(), (), Some(self.0)))
+		}
+
+		fn prepare(
+			self,
+			_val: Self::Val,
+			_origin: &Option<u64>,
+			_call: &Call,
+			_info: &(),
+			_len: usize,
+		) -> Result<Self::Pre, TransactionValidityError> {
+			Ok(())
+		}
+	}
+
+	/// Rejects transactions which are not signed, with the priority of the signer.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	struct RequireSigner;
+
+	impl TransactionExtensionBase for RequireSigner {
+		const IDENTIFIER: &'static str = "RequireSigner";
+		type Implicit = ();
+
+		fn implicit(&self) -> Result<Self::Implicit, TransactionValidityError> {
+			Ok(())
+		}
+
+		fn weight(&self) -> Weight {
+			Weight::from_parts(2, 0)
+		}
+	}
+
+	impl TransactionExtension<Call> for RequireSigner {
+		type Val = u64;
+		type Pre = u64;
+
+		fn validate(
+			&self,
+			origin: Option<u64>,
+			_call: &Call,
+			_info: &(),
+			_len: usize,
+			_inherited_implication: &impl Encode,
+		) -> ValidateResult<Self::Val, Call> {
+			let who = *origin.as_system_origin_signer().ok_or(InvalidTransaction::BadSigner)?;
+			Ok((ValidTransaction { priority: who, ..Default::default() }, who, origin))
+		}
+
+		fn prepare(
+			self,
+			val: Self::Val,
+			_origin: &Option<u64>,
+			_call: &Call,
+			_info: &(),
+			_len: usize,
+		) -> Result<Self::Pre, TransactionValidityError> {
+			Ok(val)
+		}
+	}
+
+	/// A signed extension whose `additional_signed` is not available anymore.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	struct Stale;
+
+	impl SignedExtension for Stale {
+		const IDENTIFIER: &'static str = "Stale";
+		type AccountId = u64;
+		type Call = Call;
+		type AdditionalSigned = ();
+		type Pre = ();
+
+		fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+			Err(InvalidTransaction::Stale.into())
+		}
+
+		fn pre_dispatch(
+			self,
+			who: &Self::AccountId,
+			call: &Self::Call,
+			info: &DispatchInfoOf<Self::Call>,
+			len: usize,
+		) -> Result<Self::Pre, TransactionValidityError> {
+			self.validate(who, call, info, len).map(|_| ())
+		}
+	}
+
+	thread_local! {
+		static VALIDATIONS: std::cell::Cell<u32> = std::cell::Cell::new(0);
+	}
+
+	/// A signed extension counting how many times its checks run.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	struct Counted;
+
+	impl SignedExtension for Counted {
+		const IDENTIFIER: &'static str = "Counted";
+		type AccountId = u64;
+		type Call = Call;
+		type AdditionalSigned = ();
+		type Pre = ();
+
+		fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+			Ok(())
+		}
+
+		fn validate(
+			&self,
+			_who: &Self::AccountId,
+			_call: &Self::Call,
+			_info: &DispatchInfoOf<Self::Call>,
+			_len: usize,
+		) -> TransactionValidity {
+			VALIDATIONS.with(|v| v.set(v.get() + 1));
+			Ok(ValidTransaction::default())
+		}
+
+		fn pre_dispatch(
+			self,
+			who: &Self::AccountId,
+			call: &Self::Call,
+			info: &DispatchInfoOf<Self::Call>,
+			len: usize,
+		) -> Result<Self::Pre, TransactionValidityError> {
+			self.validate(who, call, info, len).map(|_| ())
+		}
+	}
+
+	#[test]
+	fn signed_extension_checks_run_once_when_applied_in_tuple() {
+		assert_eq!(
+			(AsTransactionExtension::from(Counted), RequireSigner).dispatch_transaction(
+				Some(1),
+				Call,
+				&(),
+				0
+			),
+			Ok(Ok(())),
+		);
+		assert_eq!(VALIDATIONS.with(|v| v.get()), 1);
+	}
+
+	#[test]
+	fn as_transaction_extension_does_not_recompute_additional_signed() {
+		assert_eq!(
+			AsTransactionExtension::from(Stale).dispatch_transaction(Some(1), Call, &(), 0),
+			Ok(Ok(())),
+		);
+	}
+
+	#[test]
+	fn tuple_passes_origin_through_extensions() {
+		let (valid, val, origin) =
+			(Authorize(7), RequireSigner).validate_only(None, &Call, &(), 0).unwrap();
+		assert_eq!(valid.priority, 7);
+		assert_eq!(val, ((), 7));
+		assert_eq!(origin, Some(7));
+
+		// The origin is only changed for the following extensions.
+		assert_eq!(
+			(RequireSigner, Authorize(7)).validate_only(None, &Call, &(), 0).map(|_| ()),
+			Err(InvalidTransaction::BadSigner.into()),
+		);
+	}
+
+	#[test]
+	fn dispatch_transaction_uses_final_origin() {
+		assert_eq!(RequireSigner.dispatch_transaction(Some(3), Call, &(), 0), Ok(Ok(())));
+		assert_eq!(
+			(Authorize(3), RequireSigner).dispatch_transaction(None, Call, &(), 0),
+			Ok(Ok(())),
+		);
+		assert_eq!(
+			().dispatch_transaction(None, Call, &(), 0).unwrap().map_err(|e| e.error),
+			Err(DispatchError::BadOrigin)
+		);
+	}
+
+	#[test]
+	fn tuple_weight_and_metadata_aggregate_extensions() {
+		assert_eq!((Authorize(1), RequireSigner).weight(), Weight::from_parts(3, 0));
+		let identifiers = <(Authorize, RequireSigner) as TransactionExtensionBase>::metadata()
+			.into_iter()
+			.map(|m| m.identifier)
+			.collect::<Vec<_>>();
+		assert_eq!(identifiers, vec!["Authorize", "RequireSigner"]);
+	}
+}
@@ -82,6 +82,9 @@ pub enum InvalidTransaction {
 	MandatoryValidation,
 	/// The sending address is disabled or known to be invalid.
 	BadSigner,
+	/// No signature nor transaction extension authorized the transaction, i.e. it would be
+	/// dispatched with the `None` origin.
+	UnknownOrigin,
 }
 
 impl InvalidTransaction {
@@ -113,6 +116,7 @@ impl From<InvalidTransaction> for &'static str {
 				"Transaction dispatch is mandatory; transactions must not be validated.",
 			InvalidTransaction::Custom(_) => "InvalidTransaction custom error",
 			InvalidTransaction::BadSigner => "Invalid signing address",
+			InvalidTransaction::UnknownOrigin => "Transaction was not authorized by any origin",
 		}
 	}
 }
@@ -48,7 +48,7 @@ pub const MAX_TOPICS: usize = 4;
 
 #[cfg(feature = "std")]
 pub use store_api::{
-	Error, NetworkPriority, Result, StatementSource, StatementStore, SubmitResult,
+	Error, NetworkPriority, Result, StatementSource, StatementStore, StatementStream, SubmitResult,
 };
 
 #[cfg(feature = "std")]
@@ -393,6 +393,13 @@ impl Statement {
 		self.decryption_key
 	}
 
+	/// Check if the statement includes all of `match_all_topics` and its decryption key is
+	/// `dest`. `None` matches statements with no decryption key.
+	pub fn matches(&self, match_all_topics: &[Topic], dest: Option<DecryptionKey>) -> bool {
+		let topics = &self.topics[..self.num_topics as usize];
+		self.decryption_key == dest && match_all_topics.iter().all(|t| topics.contains(t))
+	}
+
 	/// Convert to internal data.
 	pub fn into_data(self) -> Option<Vec<u8>> {
 		self.data
@@ -16,7 +16,8 @@
 // limitations under the License.
 
 pub use crate::runtime_api::StatementSource;
-use crate::{Hash, Statement, Topic};
+use crate::{DecryptionKey, Hash, Statement, Topic};
+use std::sync::Arc;
 
 /// Statement store error.
 #[derive(Debug, Eq, PartialEq, thiserror::Error)]
@@ -61,6 +62,9 @@ pub enum SubmitResult {
 /// Result type for `Error`
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Stream of statements newly added to the store, along with their hashes.
+pub type StatementStream = futures::channel::mpsc::Receiver<(Hash, Arc<Statement>)>;
+
 /// Statement store API.
 pub trait StatementStore: Send + Sync {
 	/// Return all statements.
@@ -82,6 +86,21 @@ pub trait StatementStore: Send + Sync {
 	/// `dest`. The key must be available to the client.
 	fn posted_clear(&self, match_all_topics: &[Topic], dest: [u8; 32]) -> Result<Vec<Vec<u8>>>;
 
+	/// Return up to `count` known statements which include all topics and whose decryption key
+	/// is `dest`, ordered by hash and starting after the statement with hash `start_after`, and
+	/// whether there are more matching statements. `None` matches statements with no
+	/// `DecryptionKey` field.
+	fn matching_statements(
+		&self,
+		match_all_topics: &[Topic],
+		dest: Option<DecryptionKey>,
+		start_after: Option<Hash>,
+		count: usize,
+	) -> Result<(Vec<(Hash, Statement)>, bool)>;
+
+	/// Subscribe to statements added to the store from now on.
+	fn subscribe_statements(&self) -> StatementStream;
+
 	/// Submit a statement.
 	fn submit(&self, statement: Statement, source: StatementSource) -> SubmitResult;
 